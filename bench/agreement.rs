@@ -22,6 +22,7 @@ use ring::{
 static ALGORITHMS: &[(&str, &agreement::Algorithm)] = &[
     ("p256", &agreement::ECDH_P256),
     ("p384", &&agreement::ECDH_P384),
+    ("p521", &agreement::ECDH_P521),
     ("x25519", &&agreement::X25519),
 ];
 
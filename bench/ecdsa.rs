@@ -30,6 +30,11 @@ static ALGORITHMS: &[(&str, &EcdsaSigningAlgorithm, &EcdsaVerificationAlgorithm)
         &signature::ECDSA_P384_SHA384_ASN1_SIGNING,
         &signature::ECDSA_P384_SHA384_ASN1,
     ),
+    (
+        "p521",
+        &signature::ECDSA_P521_SHA512_ASN1_SIGNING,
+        &signature::ECDSA_P521_SHA512_ASN1,
+    ),
 ];
 
 fn sign(c: &mut Criterion) {
@@ -0,0 +1,78 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+#![allow(missing_docs)]
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ring::{
+    rand,
+    signature::{self, Ed25519BatchItem, Ed25519KeyPair, KeyPair},
+};
+
+static BATCH_SIZES: &[usize] = &[1, 4, 16, 64, 256];
+
+// Returns `(public_key, msg, signature)` for `n` different keys and messages.
+fn signed_messages(n: usize) -> Vec<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+    let rng = rand::SystemRandom::new();
+    (0..n)
+        .map(|i| {
+            let pkcs8_bytes = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+            let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8_bytes.as_ref()).unwrap();
+            let msg = format!("message {i}").into_bytes();
+            let signature = key_pair.sign(&msg).as_ref().to_vec();
+            (key_pair.public_key().as_ref().to_vec(), msg, signature)
+        })
+        .collect()
+}
+
+// Verifies each signature of a batch individually, for comparison with
+// `verify_batch`.
+fn verify(c: &mut Criterion) {
+    for &n in BATCH_SIZES {
+        c.bench_function(&format!("ed25519_verify_{n}"), |b| {
+            let signed = signed_messages(n);
+            b.iter(|| {
+                for (public_key, msg, signature) in &signed {
+                    signature::UnparsedPublicKey::new(&signature::ED25519, public_key)
+                        .verify(black_box(msg), black_box(signature))
+                        .unwrap();
+                }
+            })
+        });
+    }
+}
+
+fn verify_batch(c: &mut Criterion) {
+    for &n in BATCH_SIZES {
+        c.bench_function(&format!("ed25519_verify_batch_{n}"), |b| {
+            let rng = rand::SystemRandom::new();
+            let signed = signed_messages(n);
+            let batch: Vec<_> = signed
+                .iter()
+                .map(|(public_key, msg, signature)| Ed25519BatchItem {
+                    public_key,
+                    msg,
+                    signature,
+                })
+                .collect();
+            b.iter(|| {
+                signature::ED25519
+                    .verify_batch(black_box(&batch), &rng)
+                    .unwrap();
+            })
+        });
+    }
+}
+
+criterion_group!(ed25519, verify, verify_batch);
+criterion_main!(ed25519);
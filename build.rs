@@ -60,6 +60,8 @@ const RING_SRCS: &[(&[&str], &str)] = &[
     (&[], "crypto/fipsmodule/ec/ecp_nistz.c"),
     (&[], "crypto/fipsmodule/ec/gfp_p256.c"),
     (&[], "crypto/fipsmodule/ec/gfp_p384.c"),
+    (&[], "crypto/fipsmodule/ec/gfp_p521.c"),
+    (&[], "crypto/fipsmodule/ec/gfp_secp256k1.c"),
     (&[], "crypto/fipsmodule/ec/p256.c"),
     (&[], "crypto/limbs/limbs.c"),
     (&[], "crypto/mem.c"),
@@ -940,6 +942,22 @@ fn prefix_all_symbols(pp: char, prefix_prefix: &str, prefix: &str) -> String {
         "p384_point_double",
         "p384_point_mul",
         "p384_scalar_mul_mont",
+        "p521_elem_div_by_2",
+        "p521_elem_mul_mont",
+        "p521_elem_neg",
+        "p521_elem_sub",
+        "p521_point_add",
+        "p521_point_double",
+        "p521_point_mul",
+        "p521_scalar_mul_mont",
+        "secp256k1_elem_div_by_2",
+        "secp256k1_elem_mul_mont",
+        "secp256k1_elem_neg",
+        "secp256k1_elem_sub",
+        "secp256k1_point_add",
+        "secp256k1_point_double",
+        "secp256k1_point_mul",
+        "secp256k1_scalar_mul_mont",
         "openssl_poly1305_neon2_addmulmod",
         "openssl_poly1305_neon2_blocks",
         "sha256_block_data_order",
@@ -956,6 +974,8 @@ fn prefix_all_symbols(pp: char, prefix_prefix: &str, prefix: &str) -> String {
         "x25519_fe_tobytes",
         "x25519_ge_double_scalarmult_vartime",
         "x25519_ge_frombytes_vartime",
+        "x25519_ge_multi_scalarmult_vartime",
+        "x25519_ge_p3_dbl",
         "x25519_ge_scalarmult_base",
         "x25519_ge_scalarmult_base_adx",
         "x25519_public_from_private_generic_masked",
@@ -46,6 +46,9 @@ mod digest_shavs {
 
     macro_rules! shavs_tests {
         ( $file_name:ident, $algorithm_name:ident ) => {
+            shavs_tests!("../third_party/NIST/SHAVS/", $file_name, $algorithm_name);
+        };
+        ( $dir:expr, $file_name:ident, $algorithm_name:ident ) => {
             #[allow(non_snake_case)]
             mod $algorithm_name {
                 use super::{run_known_answer_test, run_monte_carlo_test};
@@ -58,11 +61,7 @@ mod digest_shavs {
                 fn short_msg_known_answer_test() {
                     run_known_answer_test(
                         &digest::$algorithm_name,
-                        test_file!(concat!(
-                            "../third_party/NIST/SHAVS/",
-                            stringify!($file_name),
-                            "ShortMsg.rsp"
-                        )),
+                        test_file!(concat!($dir, stringify!($file_name), "ShortMsg.rsp")),
                     );
                 }
 
@@ -70,11 +69,7 @@ mod digest_shavs {
                 fn long_msg_known_answer_test() {
                     run_known_answer_test(
                         &digest::$algorithm_name,
-                        test_file!(concat!(
-                            "../third_party/NIST/SHAVS/",
-                            stringify!($file_name),
-                            "LongMsg.rsp"
-                        )),
+                        test_file!(concat!($dir, stringify!($file_name), "LongMsg.rsp")),
                     );
                 }
 
@@ -82,11 +77,7 @@ mod digest_shavs {
                 fn monte_carlo_test() {
                     run_monte_carlo_test(
                         &digest::$algorithm_name,
-                        test_file!(concat!(
-                            "../third_party/NIST/SHAVS/",
-                            stringify!($file_name),
-                            "Monte.rsp"
-                        )),
+                        test_file!(concat!($dir, stringify!($file_name), "Monte.rsp")),
                     );
                 }
             }
@@ -139,7 +130,11 @@ mod digest_shavs {
     }
 
     shavs_tests!(SHA1, SHA1_FOR_LEGACY_USE_ONLY);
+    shavs_tests!(SHA224, SHA224);
     shavs_tests!(SHA256, SHA256);
     shavs_tests!(SHA384, SHA384);
     shavs_tests!(SHA512, SHA512);
+
+    // NIST's SHA-512/224 files aren't vendored; see the headers of these files.
+    shavs_tests!("sha512_224/", SHA512_224, SHA512_224);
 }
@@ -1916,6 +1916,99 @@ void x25519_ge_double_scalarmult_vartime(ge_p2 *r, const uint8_t *a,
   ge_double_scalarmult_vartime(r, a, A, b);
 }
 
+// r = b * B + sum(a[i] * A[i]) for i < num
+// where each a[i] and b are reduced scalars, encoded as 32 little-endian
+// bytes, and B is the Ed25519 base point (x,4/5) with x positive.
+//
+// This is |ge_double_scalarmult_vartime| with the sliding windows of all the
+// points interleaved (Straus's method), so the doublings are shared. |Ai| and
+// |aslide| are scratch space for 8 * num and 256 * num elements.
+void x25519_ge_multi_scalarmult_vartime(ge_p3 *r, const uint8_t *b,
+                                        const uint8_t *a, const ge_p3 *A,
+                                        size_t num, ge_cached *Ai,
+                                        signed char *aslide) {
+  signed char bslide[256];
+  ge_p2 acc;
+  ge_p1p1 t;
+  ge_p3 u;
+  ge_p3 A2;
+  int i;
+  size_t n;
+  int j;
+
+  slide(bslide, b);
+
+  for (n = 0; n < num; ++n) {
+    slide(&aslide[256 * n], &a[32 * n]);
+
+    // A,3A,5A,7A,9A,11A,13A,15A
+    ge_cached *table = &Ai[8 * n];
+    x25519_ge_p3_to_cached(&table[0], &A[n]);
+    ge_p3_dbl(&t, &A[n]);
+    x25519_ge_p1p1_to_p3(&A2, &t);
+    for (j = 0; j < 7; ++j) {
+      x25519_ge_add(&t, &A2, &table[j]);
+      x25519_ge_p1p1_to_p3(&u, &t);
+      x25519_ge_p3_to_cached(&table[j + 1], &u);
+    }
+  }
+
+  for (i = 255; i >= 0; --i) {
+    if (bslide[i]) {
+      break;
+    }
+    for (n = 0; n < num; ++n) {
+      if (aslide[256 * n + (size_t)i]) {
+        break;
+      }
+    }
+    if (n < num) {
+      break;
+    }
+  }
+
+  if (i < 0) {
+    ge_p3_0(r);
+    return;
+  }
+
+  ge_p2_0(&acc);
+
+  for (; i >= 0; --i) {
+    ge_p2_dbl(&t, &acc);
+
+    for (n = 0; n < num; ++n) {
+      signed char s = aslide[256 * n + (size_t)i];
+      if (s > 0) {
+        x25519_ge_p1p1_to_p3(&u, &t);
+        x25519_ge_add(&t, &u, &Ai[8 * n + (size_t)(s / 2)]);
+      } else if (s < 0) {
+        x25519_ge_p1p1_to_p3(&u, &t);
+        x25519_ge_sub(&t, &u, &Ai[8 * n + (size_t)((-s) / 2)]);
+      }
+    }
+
+    if (bslide[i] > 0) {
+      x25519_ge_p1p1_to_p3(&u, &t);
+      ge_madd(&t, &u, &Bi[bslide[i] / 2]);
+    } else if (bslide[i] < 0) {
+      x25519_ge_p1p1_to_p3(&u, &t);
+      ge_msub(&t, &u, &Bi[(-bslide[i]) / 2]);
+    }
+
+    x25519_ge_p1p1_to_p2(&acc, &t);
+  }
+
+  x25519_ge_p1p1_to_p3(r, &t);
+}
+
+// r = 2 * p
+void x25519_ge_p3_dbl(ge_p3 *r, const ge_p3 *p) {
+  ge_p1p1 t;
+  ge_p3_dbl(&t, p);
+  x25519_ge_p1p1_to_p3(r, &t);
+}
+
 void x25519_sc_mask(uint8_t a[32]) {
   a[0] &= 248;
   a[31] &= 127;
@@ -0,0 +1,37 @@
+/* Copyright (c) 2014, Intel Corporation.
+ *
+ * Permission to use, copy, modify, and/or distribute this software for any
+ * purpose with or without fee is hereby granted, provided that the above
+ * copyright notice and this permission notice appear in all copies.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES
+ * WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+ * MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR ANY
+ * SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+ * WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+ * OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+ * CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE. */
+
+#ifndef OPENSSL_HEADER_EC_ECP_NISTZ521_H
+#define OPENSSL_HEADER_EC_ECP_NISTZ521_H
+
+#include "../../limbs/limbs.h"
+
+/* Elements are 521 bits, but they are stored in 576 bits, a multiple of 64,
+ * so that the Montgomery factor R = 2**576 is the same regardless of whether
+ * limbs are 32 or 64 bits. */
+#define P521_LIMBS (576u / LIMB_BITS)
+
+typedef struct {
+  Limb X[P521_LIMBS];
+  Limb Y[P521_LIMBS];
+  Limb Z[P521_LIMBS];
+} P521_POINT;
+
+typedef struct {
+  Limb X[P521_LIMBS];
+  Limb Y[P521_LIMBS];
+} P521_POINT_AFFINE;
+
+
+#endif // OPENSSL_HEADER_EC_ECP_NISTZ521_H
@@ -28,7 +28,9 @@ use crate::{
 use core::ops::RangeFrom;
 
 pub use self::{
+    aes_ccm::{AES_128_CCM, AES_128_CCM_8, AES_256_CCM, AES_256_CCM_8},
     aes_gcm::{AES_128_GCM, AES_256_GCM},
+    aes_gcm_siv::{AES_128_GCM_SIV, AES_256_GCM_SIV},
     chacha20_poly1305::CHACHA20_POLY1305,
     less_safe_key::LessSafeKey,
     nonce::{Nonce, NONCE_LEN},
@@ -122,7 +124,9 @@ impl<A> Eq for Aad<A> where A: Eq {}
 #[allow(clippy::large_enum_variant, variant_size_differences)]
 #[derive(Clone)]
 enum KeyInner {
+    AesCcm(aes_ccm::Key),
     AesGcm(aes_gcm::Key),
+    AesGcmSiv(aes_gcm_siv::Key),
     ChaCha20Poly1305(chacha20_poly1305::Key),
 }
 
@@ -148,12 +152,14 @@ pub struct Algorithm {
         key: &KeyInner,
         nonce: Nonce,
         aad: Aad<&[u8]>,
+        received_tag: &Tag,
         in_out: &mut [u8],
         src: RangeFrom<usize>,
         cpu_features: cpu::Features,
     ) -> Result<Tag, error::Unspecified>,
 
     key_len: usize,
+    tag_len: usize,
     id: AlgorithmID,
 }
 
@@ -177,7 +183,7 @@ impl Algorithm {
     /// See also `MAX_TAG_LEN`.
     #[inline(always)]
     pub fn tag_len(&self) -> usize {
-        TAG_LEN
+        self.tag_len
     }
 
     /// The length of the nonces.
@@ -191,8 +197,14 @@ derive_debug_via_id!(Algorithm);
 
 #[derive(Debug, Eq, PartialEq)]
 enum AlgorithmID {
+    AES_128_CCM,
+    AES_256_CCM,
+    AES_128_CCM_8,
+    AES_256_CCM_8,
     AES_128_GCM,
     AES_256_GCM,
+    AES_128_GCM_SIV,
+    AES_256_GCM_SIV,
     CHACHA20_POLY1305,
 }
 
@@ -205,53 +217,90 @@ impl PartialEq for Algorithm {
 impl Eq for Algorithm {}
 
 /// A possibly valid authentication tag.
+///
+/// A tag is `MAX_TAG_LEN` bytes long unless it is for an algorithm that uses
+/// truncated tags, like `AES_128_CCM_8`.
 #[must_use]
 #[repr(C)]
 #[derive(Clone, Copy)]
-pub struct Tag([u8; TAG_LEN]);
+pub struct Tag {
+    value: [u8; TAG_LEN],
+    len: usize,
+}
+
+impl Tag {
+    /// Truncates the tag to its first `len` bytes.
+    #[inline]
+    fn truncated(self, len: usize) -> Self {
+        debug_assert!(len <= self.len);
+        Self {
+            value: self.value,
+            len,
+        }
+    }
+}
 
 impl AsRef<[u8]> for Tag {
     fn as_ref(&self) -> &[u8] {
-        self.0.as_ref()
+        &self.value[..self.len]
     }
 }
 
 impl TryFrom<&[u8]> for Tag {
     type Error = error::Unspecified;
 
+    /// Fails if `value` is empty or longer than `MAX_TAG_LEN` bytes.
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
-        let raw_tag: [u8; TAG_LEN] = value.try_into().map_err(|_| error::Unspecified)?;
-        Ok(Self::from(raw_tag))
+        if value.is_empty() {
+            return Err(error::Unspecified);
+        }
+        let mut raw_tag = [0u8; TAG_LEN];
+        raw_tag
+            .get_mut(..value.len())
+            .ok_or(error::Unspecified)?
+            .copy_from_slice(value);
+        Ok(Self::from(raw_tag).truncated(value.len()))
     }
 }
 
 impl From<[u8; TAG_LEN]> for Tag {
     #[inline]
     fn from(value: [u8; TAG_LEN]) -> Self {
-        Self(value)
+        Self {
+            value,
+            len: TAG_LEN,
+        }
     }
 }
 
-const MAX_KEY_LEN: usize = 32;
+pub(crate) const MAX_KEY_LEN: usize = 32;
 
-// All the AEADs we support use 128-bit tags.
+// The length of an untruncated tag. All the AEADs we support use 128-bit tags,
+// though some truncate them.
 const TAG_LEN: usize = 16;
 
 /// The maximum length of a tag for the algorithms in this module.
 pub const MAX_TAG_LEN: usize = TAG_LEN;
 
 mod aes;
+mod aes_ccm;
 mod aes_gcm;
+mod aes_gcm_siv;
 mod block;
 mod chacha;
 mod chacha20_poly1305;
 pub mod chacha20_poly1305_openssh;
 mod gcm;
+pub mod gmac;
 mod less_safe_key;
 mod nonce;
+pub mod one_time_poly1305;
 mod opening_key;
 mod poly1305;
+mod polyval;
 pub mod quic;
 mod sealing_key;
 mod shift;
+pub mod stream;
 mod unbound_key;
+pub mod xchacha20_poly1305;
@@ -309,6 +309,7 @@ pub(super) struct AES_KEY {
 // Keep this in sync with `AES_MAXNR` in aes.h.
 const MAX_ROUNDS: usize = 14;
 
+#[derive(Clone, Copy)]
 pub enum Variant {
     AES_128,
     AES_256,
@@ -324,6 +325,13 @@ impl Counter {
         Self([n0, n1, n2, 1.into()])
     }
 
+    /// Constructs a counter from a complete initial counter block, for modes
+    /// that don't use the `Nonce || 1` layout. Only the last 32 bits of the
+    /// block are incremented.
+    pub fn from_initial_block(block: [u8; BLOCK_LEN]) -> Self {
+        Self(block.array_split_map(BigEndian::<u32>::from))
+    }
+
     pub fn increment(&mut self) -> Iv {
         let iv: [[u8; 4]; 4] = self.0.map(Into::into);
         let iv = Iv(Block::from(iv));
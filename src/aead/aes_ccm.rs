@@ -0,0 +1,330 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! AES-CCM, as described in [NIST SP800-38C] and [RFC 3610].
+//!
+//! Only 96-bit nonces are supported, so the length field (`L` in RFC 3610) is
+//! three bytes long. This is the parameterization used by [RFC 6655] and
+//! [RFC 7251] for TLS.
+//!
+//! [NIST SP800-38C]: https://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38c.pdf
+//! [RFC 3610]: https://tools.ietf.org/html/rfc3610
+//! [RFC 6655]: https://tools.ietf.org/html/rfc6655
+//! [RFC 7251]: https://tools.ietf.org/html/rfc7251
+
+use super::{
+    aes::{self, Counter},
+    block::{Block, BLOCK_LEN},
+    shift, Aad, Nonce, Tag, NONCE_LEN,
+};
+use crate::{aead, cpu, error, polyfill::u64_from_usize};
+use core::ops::RangeFrom;
+
+/// AES-128 in CCM mode with 128-bit tags and 96 bit nonces.
+pub static AES_128_CCM: aead::Algorithm = aead::Algorithm {
+    key_len: 16,
+    tag_len: 16,
+    init: init_128_16,
+    seal: aes_ccm_seal,
+    open: aes_ccm_open,
+    id: aead::AlgorithmID::AES_128_CCM,
+};
+
+/// AES-256 in CCM mode with 128-bit tags and 96 bit nonces.
+pub static AES_256_CCM: aead::Algorithm = aead::Algorithm {
+    key_len: 32,
+    tag_len: 16,
+    init: init_256_16,
+    seal: aes_ccm_seal,
+    open: aes_ccm_open,
+    id: aead::AlgorithmID::AES_256_CCM,
+};
+
+/// AES-128 in CCM mode with 64-bit tags and 96 bit nonces ("CCM_8").
+pub static AES_128_CCM_8: aead::Algorithm = aead::Algorithm {
+    key_len: 16,
+    tag_len: 8,
+    init: init_128_8,
+    seal: aes_ccm_seal,
+    open: aes_ccm_open,
+    id: aead::AlgorithmID::AES_128_CCM_8,
+};
+
+/// AES-256 in CCM mode with 64-bit tags and 96 bit nonces ("CCM_8").
+pub static AES_256_CCM_8: aead::Algorithm = aead::Algorithm {
+    key_len: 32,
+    tag_len: 8,
+    init: init_256_8,
+    seal: aes_ccm_seal,
+    open: aes_ccm_open,
+    id: aead::AlgorithmID::AES_256_CCM_8,
+};
+
+#[derive(Clone)]
+pub struct Key {
+    aes_key: aes::Key,
+    tag_len: usize,
+}
+
+fn init_128_16(
+    key: &[u8],
+    cpu_features: cpu::Features,
+) -> Result<aead::KeyInner, error::Unspecified> {
+    init(key, aes::Variant::AES_128, 16, cpu_features)
+}
+
+fn init_256_16(
+    key: &[u8],
+    cpu_features: cpu::Features,
+) -> Result<aead::KeyInner, error::Unspecified> {
+    init(key, aes::Variant::AES_256, 16, cpu_features)
+}
+
+fn init_128_8(
+    key: &[u8],
+    cpu_features: cpu::Features,
+) -> Result<aead::KeyInner, error::Unspecified> {
+    init(key, aes::Variant::AES_128, 8, cpu_features)
+}
+
+fn init_256_8(
+    key: &[u8],
+    cpu_features: cpu::Features,
+) -> Result<aead::KeyInner, error::Unspecified> {
+    init(key, aes::Variant::AES_256, 8, cpu_features)
+}
+
+fn init(
+    key: &[u8],
+    variant: aes::Variant,
+    tag_len: usize,
+    cpu_features: cpu::Features,
+) -> Result<aead::KeyInner, error::Unspecified> {
+    let aes_key = aes::Key::new(key, variant, cpu_features)?;
+    Ok(aead::KeyInner::AesCcm(Key { aes_key, tag_len }))
+}
+
+// The length of the message length field in the first block, i.e. `L` in
+// RFC 3610.
+const LEN_LEN: usize = BLOCK_LEN - 1 - NONCE_LEN;
+
+// The message length must fit in `LEN_LEN` bytes.
+const MAX_IN_OUT_LEN: u64 = (1 << (8 * LEN_LEN)) - 1;
+
+fn aes_ccm_seal(
+    key: &aead::KeyInner,
+    nonce: Nonce,
+    aad: Aad<&[u8]>,
+    in_out: &mut [u8],
+    cpu_features: cpu::Features,
+) -> Result<Tag, error::Unspecified> {
+    let Key { aes_key, tag_len } = match key {
+        aead::KeyInner::AesCcm(key) => key,
+        _ => unreachable!(),
+    };
+
+    let pre_tag = cbc_mac(aes_key, *tag_len, &nonce, aad, in_out, cpu_features)?;
+
+    let mut ctr = Counter::from_initial_block(initial_counter_block(&nonce));
+    let tag_iv = ctr.increment();
+
+    let (whole, remainder) = {
+        let in_out_len = in_out.len();
+        let whole_len = in_out_len - (in_out_len % BLOCK_LEN);
+        in_out.split_at_mut(whole_len)
+    };
+
+    if !whole.is_empty() {
+        aes_key.ctr32_encrypt_within(whole, 0.., &mut ctr, cpu_features);
+    }
+
+    if !remainder.is_empty() {
+        let mut input = Block::zero();
+        input.overwrite_part_at(0, remainder);
+        let output = aes_key.encrypt_iv_xor_block(ctr.into(), input, cpu_features);
+        remainder.copy_from_slice(&output.as_ref()[..remainder.len()]);
+    }
+
+    Ok(finish(aes_key, *tag_len, pre_tag, tag_iv, cpu_features))
+}
+
+fn aes_ccm_open(
+    key: &aead::KeyInner,
+    nonce: Nonce,
+    aad: Aad<&[u8]>,
+    _received_tag: &Tag,
+    in_out: &mut [u8],
+    src: RangeFrom<usize>,
+    cpu_features: cpu::Features,
+) -> Result<Tag, error::Unspecified> {
+    let Key { aes_key, tag_len } = match key {
+        aead::KeyInner::AesCcm(key) => key,
+        _ => unreachable!(),
+    };
+
+    let unprefixed_len = in_out
+        .len()
+        .checked_sub(src.start)
+        .ok_or(error::Unspecified)?;
+    if u64_from_usize(unprefixed_len) > MAX_IN_OUT_LEN {
+        return Err(error::Unspecified);
+    }
+
+    let mut ctr = Counter::from_initial_block(initial_counter_block(&nonce));
+    let tag_iv = ctr.increment();
+
+    let in_prefix_len = src.start;
+    let whole_len = unprefixed_len - (unprefixed_len % BLOCK_LEN);
+
+    if whole_len > 0 {
+        aes_key.ctr32_encrypt_within(
+            &mut in_out[..(in_prefix_len + whole_len)],
+            src,
+            &mut ctr,
+            cpu_features,
+        );
+    }
+
+    let remainder = &mut in_out[whole_len..];
+    shift::shift_partial((in_prefix_len, remainder), |remainder| {
+        let mut input = Block::zero();
+        input.overwrite_part_at(0, remainder);
+        aes_key.encrypt_iv_xor_block(ctr.into(), input, cpu_features)
+    });
+
+    // CCM authenticates the plaintext, so it can only be authenticated after
+    // it has been decrypted.
+    let pre_tag = cbc_mac(
+        aes_key,
+        *tag_len,
+        &nonce,
+        aad,
+        &in_out[..unprefixed_len],
+        cpu_features,
+    )?;
+
+    Ok(finish(aes_key, *tag_len, pre_tag, tag_iv, cpu_features))
+}
+
+// The counter block `A_0` from RFC 3610 Section 2.3. The counter occupies
+// the last `LEN_LEN` bytes, which `Counter` increments as part of a 32-bit
+// big-endian value; it can't carry into the nonce because the message length
+// is limited to `MAX_IN_OUT_LEN`.
+fn initial_counter_block(nonce: &Nonce) -> [u8; BLOCK_LEN] {
+    let mut block = [0u8; BLOCK_LEN];
+    block[0] = L_PRIME;
+    block[1..][..NONCE_LEN].copy_from_slice(nonce.as_ref());
+    block
+}
+
+// `L'` from RFC 3610 Section 2.2.
+#[allow(clippy::cast_possible_truncation)]
+const L_PRIME: u8 = (LEN_LEN - 1) as u8;
+
+// Computes the CBC-MAC `T` of RFC 3610 Section 2.2.
+fn cbc_mac(
+    aes_key: &aes::Key,
+    tag_len: usize,
+    nonce: &Nonce,
+    aad: Aad<&[u8]>,
+    plaintext: &[u8],
+    cpu_features: cpu::Features,
+) -> Result<Block, error::Unspecified> {
+    let plaintext_len = u64_from_usize(plaintext.len());
+    if plaintext_len > MAX_IN_OUT_LEN {
+        return Err(error::Unspecified);
+    }
+
+    let aad = aad.as_ref();
+
+    let mut b_0 = [0u8; BLOCK_LEN];
+    // RFC 3610 Section 2.2: `Flags = 64*Adata + 8*M' + L'`.
+    #[allow(clippy::cast_possible_truncation)]
+    let m_prime = ((tag_len - 2) / 2) as u8;
+    let adata = u8::from(!aad.is_empty());
+    b_0[0] = (adata << 6) | (m_prime << 3) | L_PRIME;
+    b_0[1..][..NONCE_LEN].copy_from_slice(nonce.as_ref());
+    b_0[(BLOCK_LEN - LEN_LEN)..].copy_from_slice(&plaintext_len.to_be_bytes()[(8 - LEN_LEN)..]);
+
+    let mut mac = CbcMac {
+        aes_key,
+        x: aes_key.encrypt_block(Block::from(&b_0), cpu_features),
+        cpu_features,
+    };
+
+    if !aad.is_empty() {
+        // RFC 3610 Section 2.2: the AAD is prefixed with an encoding of its
+        // length, and then the encoded length and the AAD are padded
+        // together to a multiple of the block length.
+        let aad_len = u64_from_usize(aad.len());
+        let mut encoded_len = [0u8; 10];
+        let encoded_len = if aad_len < 0xff00 {
+            encoded_len[..2].copy_from_slice(&aad_len.to_be_bytes()[6..]);
+            &encoded_len[..2]
+        } else if aad_len <= u64::from(u32::MAX) {
+            encoded_len[..2].copy_from_slice(&[0xff, 0xfe]);
+            encoded_len[2..6].copy_from_slice(&aad_len.to_be_bytes()[4..]);
+            &encoded_len[..6]
+        } else {
+            encoded_len[..2].copy_from_slice(&[0xff, 0xff]);
+            encoded_len[2..].copy_from_slice(&aad_len.to_be_bytes());
+            &encoded_len[..]
+        };
+
+        let mut first = Block::zero();
+        first.overwrite_part_at(0, encoded_len);
+        let first_aad_len = core::cmp::min(aad.len(), BLOCK_LEN - encoded_len.len());
+        let (first_aad, rest) = aad.split_at(first_aad_len);
+        first.overwrite_part_at(encoded_len.len(), first_aad);
+        mac.update_block(first);
+        mac.update_padded(rest);
+    }
+
+    mac.update_padded(plaintext);
+
+    Ok(mac.x)
+}
+
+struct CbcMac<'a> {
+    aes_key: &'a aes::Key,
+    x: Block,
+    cpu_features: cpu::Features,
+}
+
+impl CbcMac<'_> {
+    fn update_block(&mut self, block: Block) {
+        self.x = self
+            .aes_key
+            .encrypt_block(self.x ^ block, self.cpu_features);
+    }
+
+    fn update_padded(&mut self, input: &[u8]) {
+        for chunk in input.chunks(BLOCK_LEN) {
+            let mut block = Block::zero();
+            block.overwrite_part_at(0, chunk);
+            self.update_block(block);
+        }
+    }
+}
+
+fn finish(
+    aes_key: &aes::Key,
+    tag_len: usize,
+    pre_tag: Block,
+    tag_iv: aes::Iv,
+    cpu_features: cpu::Features,
+) -> Tag {
+    let tag = aes_key.encrypt_iv_xor_block(tag_iv, pre_tag, cpu_features);
+    Tag::from(*tag.as_ref()).truncated(tag_len)
+}
@@ -23,6 +23,7 @@ use core::ops::RangeFrom;
 /// AES-128 in GCM mode with 128-bit tags and 96 bit nonces.
 pub static AES_128_GCM: aead::Algorithm = aead::Algorithm {
     key_len: 16,
+    tag_len: super::TAG_LEN,
     init: init_128,
     seal: aes_gcm_seal,
     open: aes_gcm_open,
@@ -32,6 +33,7 @@ pub static AES_128_GCM: aead::Algorithm = aead::Algorithm {
 /// AES-256 in GCM mode with 128-bit tags and 96 bit nonces.
 pub static AES_256_GCM: aead::Algorithm = aead::Algorithm {
     key_len: 32,
+    tag_len: super::TAG_LEN,
     init: init_256,
     seal: aes_gcm_seal,
     open: aes_gcm_open,
@@ -182,6 +184,7 @@ fn aes_gcm_open(
     key: &aead::KeyInner,
     nonce: Nonce,
     aad: Aad<&[u8]>,
+    _received_tag: &Tag,
     in_out: &mut [u8],
     src: RangeFrom<usize>,
     cpu_features: cpu::Features,
@@ -320,7 +323,7 @@ fn finish(aes_key: &aes::Key, gcm_ctx: gcm::Context, tag_iv: aes::Iv) -> Tag {
     gcm_ctx.pre_finish(|pre_tag, cpu_features| {
         let encrypted_iv = aes_key.encrypt_block(tag_iv.into_block_less_safe(), cpu_features);
         let tag = pre_tag ^ encrypted_iv;
-        Tag(*tag.as_ref())
+        Tag::from(*tag.as_ref())
     })
 }
 
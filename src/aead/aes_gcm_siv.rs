@@ -0,0 +1,251 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::{
+    aes,
+    block::{Block, BLOCK_LEN},
+    polyval, shift, Aad, Nonce, Tag, NONCE_LEN,
+};
+use crate::{
+    aead, cpu, error,
+    polyfill::{u64_from_usize, usize_from_u64_saturated},
+};
+use core::ops::RangeFrom;
+
+/// AES-128 in GCM-SIV mode with 128-bit tags and 96 bit nonces, as described
+/// in [RFC 8452].
+///
+/// AES-GCM-SIV is nonce-misuse resistant: reusing a nonce with the same key
+/// only reveals whether the same (AAD, plaintext) pair was sealed twice.
+///
+/// [RFC 8452]: https://tools.ietf.org/html/rfc8452
+pub static AES_128_GCM_SIV: aead::Algorithm = aead::Algorithm {
+    key_len: 16,
+    tag_len: super::TAG_LEN,
+    init: init_128,
+    seal: aes_gcm_siv_seal,
+    open: aes_gcm_siv_open,
+    id: aead::AlgorithmID::AES_128_GCM_SIV,
+};
+
+/// AES-256 in GCM-SIV mode with 128-bit tags and 96 bit nonces, as described
+/// in [RFC 8452].
+///
+/// AES-GCM-SIV is nonce-misuse resistant: reusing a nonce with the same key
+/// only reveals whether the same (AAD, plaintext) pair was sealed twice.
+///
+/// [RFC 8452]: https://tools.ietf.org/html/rfc8452
+pub static AES_256_GCM_SIV: aead::Algorithm = aead::Algorithm {
+    key_len: 32,
+    tag_len: super::TAG_LEN,
+    init: init_256,
+    seal: aes_gcm_siv_seal,
+    open: aes_gcm_siv_open,
+    id: aead::AlgorithmID::AES_256_GCM_SIV,
+};
+
+#[derive(Clone)]
+pub struct Key {
+    key_generating_key: aes::Key,
+    variant: aes::Variant,
+}
+
+fn init_128(key: &[u8], cpu_features: cpu::Features) -> Result<aead::KeyInner, error::Unspecified> {
+    init(key, aes::Variant::AES_128, cpu_features)
+}
+
+fn init_256(key: &[u8], cpu_features: cpu::Features) -> Result<aead::KeyInner, error::Unspecified> {
+    init(key, aes::Variant::AES_256, cpu_features)
+}
+
+fn init(
+    key: &[u8],
+    variant: aes::Variant,
+    cpu_features: cpu::Features,
+) -> Result<aead::KeyInner, error::Unspecified> {
+    let key_generating_key = aes::Key::new(key, variant, cpu_features)?;
+    Ok(aead::KeyInner::AesGcmSiv(Key {
+        key_generating_key,
+        variant,
+    }))
+}
+
+// RFC 8452 Section 6: "The limits on plaintext and AAD are 2^36 bytes."
+const MAX_IN_OUT_LEN: usize = super::max_input_len(BLOCK_LEN, 0);
+const _MAX_IN_OUT_LEN_BOUNDED_BY_RFC: () =
+    assert!(MAX_IN_OUT_LEN == usize_from_u64_saturated(1u64 << 36));
+const MAX_AAD_LEN: usize = MAX_IN_OUT_LEN;
+
+fn aes_gcm_siv_seal(
+    key: &aead::KeyInner,
+    nonce: Nonce,
+    aad: Aad<&[u8]>,
+    in_out: &mut [u8],
+    cpu_features: cpu::Features,
+) -> Result<Tag, error::Unspecified> {
+    let key = match key {
+        aead::KeyInner::AesGcmSiv(key) => key,
+        _ => unreachable!(),
+    };
+
+    if in_out.len() > MAX_IN_OUT_LEN || aad.as_ref().len() > MAX_AAD_LEN {
+        return Err(error::Unspecified);
+    }
+
+    let (auth_key, enc_key) = derive_keys(key, &nonce, cpu_features)?;
+    let tag = calculate_tag(&auth_key, &enc_key, &nonce, aad, in_out, cpu_features)?;
+    ctr32_le_encrypt_within(&enc_key, &tag, in_out, 0.., cpu_features);
+    Ok(tag)
+}
+
+fn aes_gcm_siv_open(
+    key: &aead::KeyInner,
+    nonce: Nonce,
+    aad: Aad<&[u8]>,
+    received_tag: &Tag,
+    in_out: &mut [u8],
+    src: RangeFrom<usize>,
+    cpu_features: cpu::Features,
+) -> Result<Tag, error::Unspecified> {
+    let key = match key {
+        aead::KeyInner::AesGcmSiv(key) => key,
+        _ => unreachable!(),
+    };
+
+    let unprefixed_len = in_out
+        .len()
+        .checked_sub(src.start)
+        .ok_or(error::Unspecified)?;
+    if unprefixed_len > MAX_IN_OUT_LEN || aad.as_ref().len() > MAX_AAD_LEN {
+        return Err(error::Unspecified);
+    }
+
+    // Unlike most AEADs, the plaintext must be decrypted before it can be
+    // authenticated since the tag is computed over the plaintext.
+    let (auth_key, enc_key) = derive_keys(key, &nonce, cpu_features)?;
+    ctr32_le_encrypt_within(&enc_key, received_tag, in_out, src, cpu_features);
+    calculate_tag(
+        &auth_key,
+        &enc_key,
+        &nonce,
+        aad,
+        &in_out[..unprefixed_len],
+        cpu_features,
+    )
+}
+
+// RFC 8452 Section 4: derive the per-nonce message-authentication key and
+// message-encryption key.
+fn derive_keys(
+    key: &Key,
+    nonce: &Nonce,
+    cpu_features: cpu::Features,
+) -> Result<(polyval::Key, aes::Key), error::Unspecified> {
+    const HALF_BLOCK_LEN: usize = BLOCK_LEN / 2;
+
+    let enc_key_len = match key.variant {
+        aes::Variant::AES_128 => 16,
+        aes::Variant::AES_256 => 32,
+    };
+
+    let mut auth_key = [0u8; BLOCK_LEN];
+    let mut enc_key = [0u8; 32];
+    let enc_key = &mut enc_key[..enc_key_len];
+
+    // The nonce is preceded by a 32-bit little-endian counter.
+    let mut nonce_block = [0u8; BLOCK_LEN];
+    nonce_block[(BLOCK_LEN - NONCE_LEN)..].copy_from_slice(nonce.as_ref());
+
+    let derived = auth_key
+        .chunks_exact_mut(HALF_BLOCK_LEN)
+        .chain(enc_key.chunks_exact_mut(HALF_BLOCK_LEN));
+    for (counter, out) in (0u32..).zip(derived) {
+        let mut input = Block::from(&nonce_block);
+        input.overwrite_part_at(0, &u32::to_le_bytes(counter));
+        let output = key.key_generating_key.encrypt_block(input, cpu_features);
+        out.copy_from_slice(&output.as_ref()[..HALF_BLOCK_LEN]);
+    }
+
+    let auth_key = polyval::Key::new(Block::from(&auth_key), cpu_features);
+    let enc_key = aes::Key::new(enc_key, key.variant, cpu_features)?;
+    Ok((auth_key, enc_key))
+}
+
+fn calculate_tag(
+    auth_key: &polyval::Key,
+    enc_key: &aes::Key,
+    nonce: &Nonce,
+    aad: Aad<&[u8]>,
+    plaintext: &[u8],
+    cpu_features: cpu::Features,
+) -> Result<Tag, error::Unspecified> {
+    let mut auth = polyval::Context::new(auth_key, cpu_features)?;
+    auth.update_padded(aad.as_ref());
+    auth.update_padded(plaintext);
+    let lengths: [[u8; 8]; 2] = [aad.as_ref().len(), plaintext.len()]
+        .map(|len| u64_from_usize(len) * 8)
+        .map(u64::to_le_bytes);
+    auth.update_block(Block::from(lengths));
+
+    let mut s = *auth.finish().as_ref();
+    s.iter_mut()
+        .zip(nonce.as_ref().iter())
+        .for_each(|(s, n)| *s ^= *n);
+    s[BLOCK_LEN - 1] &= 0x7f;
+
+    let tag = enc_key.encrypt_block(Block::from(&s), cpu_features);
+    Ok(Tag::from(*tag.as_ref()))
+}
+
+// AES-CTR as used by AES-GCM-SIV: the initial counter block is the tag with
+// the most significant bit of the last byte set, and the counter is the first
+// 32 bits of the block, little-endian, wrapping on overflow.
+fn ctr32_le_encrypt_within(
+    enc_key: &aes::Key,
+    tag: &Tag,
+    in_out: &mut [u8],
+    src: RangeFrom<usize>,
+    cpu_features: cpu::Features,
+) {
+    let mut counter_block = tag.value;
+    counter_block[BLOCK_LEN - 1] |= 0x80;
+    let mut counter = u32::from_le_bytes([
+        counter_block[0],
+        counter_block[1],
+        counter_block[2],
+        counter_block[3],
+    ]);
+    let mut next_key_stream_block = || {
+        counter_block[..4].copy_from_slice(&counter.to_le_bytes());
+        counter = counter.wrapping_add(1);
+        enc_key.encrypt_block(Block::from(&counter_block), cpu_features)
+    };
+
+    let in_prefix_len = src.start;
+    let whole_len = {
+        let in_out_len = in_out.len() - in_prefix_len;
+        in_out_len - (in_out_len % BLOCK_LEN)
+    };
+
+    shift::shift_full_blocks(&mut in_out[..(in_prefix_len + whole_len)], src, |input| {
+        next_key_stream_block() ^ Block::from(input)
+    });
+
+    let remainder = &mut in_out[whole_len..];
+    shift::shift_partial((in_prefix_len, remainder), |remainder| {
+        let mut input = Block::zero();
+        input.overwrite_part_at(0, remainder);
+        next_key_stream_block() ^ input
+    });
+}
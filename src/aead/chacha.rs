@@ -140,6 +140,46 @@ impl Key {
     pub(super) fn words_less_safe(&self) -> &[u32; KEY_LEN / 4] {
         &self.words
     }
+
+    /// HChaCha20, as defined in [draft-irtf-cfrg-xchacha Section 2.2].
+    ///
+    /// HChaCha20 is the ChaCha20 block function without the final addition of
+    /// the input state, keeping only words 0..4 and 12..16. The ChaCha20 block
+    /// is computed with the (possibly accelerated) stream cipher and then the
+    /// known input words are subtracted back out.
+    ///
+    /// [draft-irtf-cfrg-xchacha Section 2.2]:
+    ///     https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-xchacha-03#section-2.2
+    pub(super) fn hchacha20(&self, nonce: &[u8; HCHACHA20_NONCE_LEN]) -> Self {
+        let iv = Iv::assume_unique_for_key(*nonce);
+        let input = iv.0;
+
+        let mut block = [0u8; BLOCK_LEN];
+        // It is safe to use `into_counter_for_single_block_less_safe()`
+        // because `block` is exactly one block long.
+        self.encrypt_less_safe(
+            iv.into_counter_for_single_block_less_safe(),
+            &mut block,
+            0..,
+        );
+
+        let mut words = [0u32; BLOCK_LEN / 4];
+        words
+            .iter_mut()
+            .zip(block.chunks_exact(4))
+            .for_each(|(w, b)| *w = u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+
+        let mut subkey = [0u32; KEY_LEN / 4];
+        let (lo, hi) = subkey.split_at_mut(4);
+        lo.iter_mut()
+            .zip(words[..4].iter().zip(SIGMA.iter()))
+            .for_each(|(r, (w, s))| *r = w.wrapping_sub(*s));
+        hi.iter_mut()
+            .zip(words[12..].iter().zip(input.iter()))
+            .for_each(|(r, (w, n))| *r = w.wrapping_sub(*n));
+
+        Self { words: subkey }
+    }
 }
 
 /// Counter || Nonce, all native endian.
@@ -195,8 +235,17 @@ impl Iv {
 
 pub const KEY_LEN: usize = 32;
 
+pub const HCHACHA20_NONCE_LEN: usize = 16;
+
 const BLOCK_LEN: usize = 64;
 
+const SIGMA: [u32; 4] = [
+    u32::from_le_bytes(*b"expa"),
+    u32::from_le_bytes(*b"nd 3"),
+    u32::from_le_bytes(*b"2-by"),
+    u32::from_le_bytes(*b"te k"),
+];
+
 #[cfg(test)]
 mod tests {
     extern crate alloc;
@@ -229,6 +278,27 @@ mod tests {
         chacha20_test(max_offset, Key::encrypt_within);
     }
 
+    // draft-irtf-cfrg-xchacha Section 2.2.1.
+    #[test]
+    fn hchacha20_test() {
+        let key = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+            0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b,
+            0x1c, 0x1d, 0x1e, 0x1f,
+        ];
+        let nonce = [
+            0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00, 0x31, 0x41,
+            0x59, 0x27,
+        ];
+        let expected: [u8; KEY_LEN] = [
+            0x82, 0x41, 0x3b, 0x42, 0x27, 0xb2, 0x7b, 0xfe, 0xd3, 0x0e, 0x42, 0x50, 0x8a, 0x87,
+            0x7d, 0x73, 0xa0, 0xf9, 0xe4, 0xd5, 0x8a, 0x74, 0xa8, 0x53, 0xc1, 0x2e, 0xc4, 0x13,
+            0x26, 0xd3, 0xec, 0xdc,
+        ];
+        let subkey = Key::new(key).hchacha20(&nonce);
+        assert_eq!(subkey.words, Key::new(expected).words);
+    }
+
     // Smoketest the fallback implementation.
     #[test]
     fn chacha20_test_fallback() {
@@ -15,7 +15,7 @@
 // Adapted from the public domain, estream code by D. Bernstein.
 // Adapted from the BoringSSL crypto/chacha/chacha.c.
 
-use super::{Counter, Key, BLOCK_LEN};
+use super::{Counter, Key, BLOCK_LEN, SIGMA};
 use core::ops::RangeFrom;
 
 pub(super) fn ChaCha20_ctr32(
@@ -24,13 +24,6 @@ pub(super) fn ChaCha20_ctr32(
     in_out: &mut [u8],
     src: RangeFrom<usize>,
 ) {
-    const SIGMA: [u32; 4] = [
-        u32::from_le_bytes(*b"expa"),
-        u32::from_le_bytes(*b"nd 3"),
-        u32::from_le_bytes(*b"2-by"),
-        u32::from_le_bytes(*b"te k"),
-    ];
-
     let key = key.words_less_safe();
     let counter = counter.into_words_less_safe();
 
@@ -29,6 +29,7 @@ use core::ops::RangeFrom;
 /// [RFC 8439]: https://tools.ietf.org/html/rfc8439
 pub static CHACHA20_POLY1305: aead::Algorithm = aead::Algorithm {
     key_len: chacha::KEY_LEN,
+    tag_len: super::TAG_LEN,
     init: chacha20_poly1305_init,
     seal: chacha20_poly1305_seal,
     open: chacha20_poly1305_open,
@@ -49,7 +50,7 @@ fn chacha20_poly1305_init(
     Ok(aead::KeyInner::ChaCha20Poly1305(chacha::Key::new(key)))
 }
 
-fn chacha20_poly1305_seal(
+pub(super) fn chacha20_poly1305_seal(
     key: &aead::KeyInner,
     nonce: Nonce,
     aad: Aad<&[u8]>,
@@ -121,7 +122,7 @@ fn chacha20_poly1305_seal(
             &data.out
         };
 
-        return Ok(Tag(out.tag));
+        return Ok(Tag::from(out.tag));
     }
 
     let mut counter = Counter::zero(nonce);
@@ -136,10 +137,11 @@ fn chacha20_poly1305_seal(
     Ok(finish(auth, aad.as_ref().len(), in_out.len()))
 }
 
-fn chacha20_poly1305_open(
+pub(super) fn chacha20_poly1305_open(
     key: &aead::KeyInner,
     nonce: Nonce,
     aad: Aad<&[u8]>,
+    _received_tag: &Tag,
     in_out: &mut [u8],
     src: RangeFrom<usize>,
     cpu_features: cpu::Features,
@@ -209,7 +211,7 @@ fn chacha20_poly1305_open(
             &data.out
         };
 
-        return Ok(Tag(out.tag));
+        return Ok(Tag::from(out.tag));
     }
 
     let mut counter = Counter::zero(nonce);
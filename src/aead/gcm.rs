@@ -274,6 +274,12 @@ impl Context {
         f(self.inner.Xi.0, self.cpu_features)
     }
 
+    /// Returns the accumulated hash without absorbing the lengths block. Only
+    /// for POLYVAL and GMAC, which encode the lengths themselves.
+    pub(super) fn finish_without_lengths(self) -> Block {
+        self.inner.Xi.0
+    }
+
     #[cfg(target_arch = "x86_64")]
     pub(super) fn is_avx(&self) -> bool {
         match detect_implementation(self.cpu_features) {
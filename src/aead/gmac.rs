@@ -0,0 +1,257 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! GMAC, the authentication-only variant of AES-GCM.
+//!
+//! GMAC is AES-GCM with an empty plaintext, where the data to be
+//! authenticated is the AAD. See [NIST SP800-38D] Section 3.
+//!
+//! Like AES-GCM, GMAC requires that each nonce is used at most once for a
+//! given key.
+//!
+//! [NIST SP800-38D]:
+//!    http://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38d.pdf
+
+use super::{
+    aes::{self, Counter},
+    block::{Block, BLOCK_LEN},
+    gcm, Aad, Nonce, Tag,
+};
+use crate::{constant_time, cpu, error, hkdf, polyfill::u64_from_usize};
+
+/// A key for computing GMAC tags.
+#[derive(Clone)]
+pub struct Key {
+    gcm_key: gcm::Key, // First because it has a large alignment requirement.
+    aes_key: aes::Key,
+    algorithm: &'static Algorithm,
+}
+
+impl From<hkdf::Okm<'_, &'static Algorithm>> for Key {
+    fn from(okm: hkdf::Okm<&'static Algorithm>) -> Self {
+        let mut key_bytes = [0; super::MAX_KEY_LEN];
+        let algorithm = *okm.len();
+        let key_bytes = &mut key_bytes[..algorithm.key_len()];
+        okm.fill(key_bytes).unwrap();
+        Self::new(algorithm, key_bytes).unwrap()
+    }
+}
+
+impl Key {
+    /// Constructs a new GMAC key.
+    ///
+    /// `key_bytes` must be exactly `algorithm.key_len()` bytes long.
+    pub fn new(
+        algorithm: &'static Algorithm,
+        key_bytes: &[u8],
+    ) -> Result<Self, error::Unspecified> {
+        let cpu_features = cpu::features();
+        let aes_key = aes::Key::new(key_bytes, algorithm.variant, cpu_features)?;
+        let gcm_key = gcm::Key::new(
+            aes_key.encrypt_block(Block::zero(), cpu_features),
+            cpu_features,
+        );
+        Ok(Self {
+            gcm_key,
+            aes_key,
+            algorithm,
+        })
+    }
+
+    /// The key's algorithm.
+    #[inline(always)]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+}
+
+impl core::fmt::Debug for Key {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Key")
+            .field("algorithm", &self.algorithm)
+            .finish()
+    }
+}
+
+// The data is authenticated as AAD, which is limited to 2^64 - 1 bits (NIST
+// SP800-38D Section 5.2.1.1).
+const MAX_LEN: u64 = u64::MAX / 8;
+
+/// A context for incrementally computing the GMAC tag of some data.
+pub struct Context {
+    inner: gcm::Context,
+    encrypted_iv: Block,
+    pending: [u8; BLOCK_LEN],
+    num_pending: usize,
+    len: u64,
+}
+
+impl Context {
+    /// Constructs a new context for authenticating data with `key` and
+    /// `nonce`.
+    ///
+    /// `nonce` must be unique for every use of the key.
+    pub fn with_key(key: &Key, nonce: Nonce) -> Self {
+        let cpu_features = cpu::features();
+        let tag_iv = Counter::one(nonce).increment();
+        let encrypted_iv = key
+            .aes_key
+            .encrypt_block(tag_iv.into_block_less_safe(), cpu_features);
+        // The lengths are accounted for in `finish`, so the context is
+        // constructed as though all the input were empty. That never fails.
+        let inner = gcm::Context::new(&key.gcm_key, Aad::from(&[]), 0, cpu_features).unwrap();
+        Self {
+            inner,
+            encrypted_iv,
+            pending: [0u8; BLOCK_LEN],
+            num_pending: 0,
+            len: 0,
+        }
+    }
+
+    /// Updates the GMAC computation with all the data in `data`. `update()`
+    /// may be called zero or more times until `finish()` is called.
+    ///
+    /// Fails, without updating the computation, if the total length of the
+    /// data would exceed the 2^64 - 1 bits that GMAC can authenticate.
+    pub fn update(&mut self, mut data: &[u8]) -> Result<(), error::Unspecified> {
+        self.len = self
+            .len
+            .checked_add(u64_from_usize(data.len()))
+            .filter(|&len| len <= MAX_LEN)
+            .ok_or(error::Unspecified)?;
+
+        if self.num_pending > 0 {
+            let to_copy = core::cmp::min(BLOCK_LEN - self.num_pending, data.len());
+            let (to_copy, rest) = data.split_at(to_copy);
+            self.pending[self.num_pending..][..to_copy.len()].copy_from_slice(to_copy);
+            self.num_pending += to_copy.len();
+            data = rest;
+            if self.num_pending < BLOCK_LEN {
+                return Ok(());
+            }
+            self.inner.update_block(Block::from(&self.pending));
+            self.num_pending = 0;
+        }
+
+        let whole_len = data.len() - (data.len() % BLOCK_LEN);
+        let (whole, remainder) = data.split_at(whole_len);
+        if !whole.is_empty() {
+            self.inner.update_blocks(whole);
+        }
+        self.pending[..remainder.len()].copy_from_slice(remainder);
+        self.num_pending = remainder.len();
+        Ok(())
+    }
+
+    /// Finalizes the GMAC computation and returns the tag.
+    pub fn finish(mut self) -> Tag {
+        if self.num_pending > 0 {
+            let mut block = Block::zero();
+            block.overwrite_part_at(0, &self.pending[..self.num_pending]);
+            self.inner.update_block(block);
+        }
+
+        // The data is authenticated as AAD and the plaintext is empty. `update`
+        // ensures that this doesn't overflow.
+        let aad_bits = self.len * 8;
+        self.inner
+            .update_block(Block::from([aad_bits, 0].map(u64::to_be_bytes)));
+
+        let tag = self.inner.finish_without_lengths() ^ self.encrypted_iv;
+        Tag::from(*tag.as_ref())
+    }
+
+    /// Finalizes the GMAC computation and verifies that the result equals
+    /// `tag`, in constant time.
+    pub fn verify(self, tag: &[u8]) -> Result<(), error::Unspecified> {
+        constant_time::verify_slices_are_equal(self.finish().as_ref(), tag)
+    }
+}
+
+/// A GMAC algorithm.
+pub struct Algorithm {
+    variant: aes::Variant,
+    key_len: usize,
+    id: AlgorithmID,
+}
+
+impl hkdf::KeyType for &'static Algorithm {
+    #[inline]
+    fn len(&self) -> usize {
+        self.key_len()
+    }
+}
+
+impl Algorithm {
+    /// The length of the key.
+    #[inline(always)]
+    pub fn key_len(&self) -> usize {
+        self.key_len
+    }
+
+    /// The length of the tag.
+    #[inline(always)]
+    pub fn tag_len(&self) -> usize {
+        super::TAG_LEN
+    }
+}
+
+derive_debug_via_id!(Algorithm);
+
+#[derive(Debug, Eq, PartialEq)]
+enum AlgorithmID {
+    AES_128,
+    AES_256,
+}
+
+impl PartialEq for Algorithm {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Algorithm {}
+
+/// GMAC using AES-128.
+pub static AES_128: Algorithm = Algorithm {
+    variant: aes::Variant::AES_128,
+    key_len: 16,
+    id: AlgorithmID::AES_128,
+};
+
+/// GMAC using AES-256.
+pub static AES_256: Algorithm = Algorithm {
+    variant: aes::Variant::AES_256,
+    key_len: 32,
+    id: AlgorithmID::AES_256,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gmac_update_too_long() {
+        let key = Key::new(&AES_128, &[0u8; 16]).unwrap();
+        let mut ctx = Context::with_key(&key, Nonce::assume_unique_for_key([0u8; 12]));
+        ctx.len = MAX_LEN - 1;
+        assert!(ctx.update(&[0u8; 2]).is_err());
+        assert_eq!(ctx.len, MAX_LEN - 1);
+        assert!(ctx.update(&[0u8; 1]).is_ok());
+        assert!(ctx.update(&[]).is_ok());
+        assert!(ctx.update(&[0u8; 1]).is_err());
+        let _ = ctx.finish(); // no panic
+    }
+}
@@ -12,7 +12,7 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-use super::{Aad, Algorithm, KeyInner, Nonce, Tag, UnboundKey, TAG_LEN};
+use super::{Aad, Algorithm, KeyInner, Nonce, Tag, UnboundKey};
 use crate::{constant_time, cpu, error};
 use core::ops::RangeFrom;
 
@@ -96,7 +96,7 @@ impl LessSafeKey {
     {
         let tag_offset = in_out
             .len()
-            .checked_sub(TAG_LEN)
+            .checked_sub(self.algorithm.tag_len())
             .ok_or(error::Unspecified)?;
 
         // Split the tag off the end of `in_out`.
@@ -170,21 +170,28 @@ fn open_within_<'in_out>(
 ) -> Result<&'in_out mut [u8], error::Unspecified> {
     let ciphertext_len = in_out.get(src.clone()).ok_or(error::Unspecified)?.len();
 
-    let Tag(calculated_tag) =
-        (key.algorithm.open)(&key.inner, nonce, aad, in_out, src, cpu::features())?;
-
-    // if constant_time::verify_slices_are_equal(calculated_tag.as_ref(), received_tag.as_ref())
-    //     .is_err()
-    // {
-    //     // Zero out the plaintext so that it isn't accidentally leaked or used
-    //     // after verification fails. It would be safest if we could check the
-    //     // tag before decrypting, but some `open` implementations interleave
-    //     // authentication with decryption for performance.
-    //     for b in &mut in_out[..ciphertext_len] {
-    //         *b = 0;
-    //     }
-    //     return Err(error::Unspecified);
-    // }
+    let calculated_tag = (key.algorithm.open)(
+        &key.inner,
+        nonce,
+        aad,
+        &received_tag,
+        in_out,
+        src,
+        cpu::features(),
+    )?;
+
+    if constant_time::verify_slices_are_equal(calculated_tag.as_ref(), received_tag.as_ref())
+        .is_err()
+    {
+        // Zero out the plaintext so that it isn't accidentally leaked or used
+        // after verification fails. It would be safest if we could check the
+        // tag before decrypting, but some `open` implementations interleave
+        // authentication with decryption for performance.
+        for b in &mut in_out[..ciphertext_len] {
+            *b = 0;
+        }
+        return Err(error::Unspecified);
+    }
 
     // `ciphertext_len` is also the plaintext length.
     Ok(&mut in_out[..ciphertext_len])
@@ -0,0 +1,79 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! The one-time Poly1305 authenticator, as described in
+//! [RFC 8439 Section 2.5].
+//!
+//! A Poly1305 key must never be used to authenticate more than one message.
+//! Usually each key is derived from a long-term key and a nonce, as
+//! `CHACHA20_POLY1305` and NaCl's `crypto_secretbox` do. A `Key` can't be
+//! cloned and is consumed by `Context::new`, so it can't be reused by
+//! accident.
+//!
+//! [RFC 8439 Section 2.5]: https://tools.ietf.org/html/rfc8439#section-2.5
+
+use super::{poly1305, Tag};
+use crate::{constant_time, cpu, error};
+
+/// The length of a one-time key.
+pub const KEY_LEN: usize = poly1305::KEY_LEN;
+
+/// A one-time Poly1305 key.
+pub struct Key(poly1305::Key);
+
+impl Key {
+    /// Constructs a new one-time key.
+    pub fn new(key_bytes: &[u8; KEY_LEN]) -> Self {
+        Self(poly1305::Key::new(*key_bytes))
+    }
+}
+
+impl core::fmt::Debug for Key {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Key").finish()
+    }
+}
+
+/// A context for incrementally computing the Poly1305 tag of a message.
+pub struct Context {
+    inner: poly1305::Context,
+}
+
+impl Context {
+    /// Constructs a new context for authenticating a single message with
+    /// `key`.
+    pub fn new(key: Key) -> Self {
+        Self {
+            inner: poly1305::Context::from_key(key.0, cpu::features()),
+        }
+    }
+
+    /// Updates the Poly1305 computation with all the data in `data`.
+    /// `update()` may be called zero or more times until `finish()` is
+    /// called.
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data)
+    }
+
+    /// Finalizes the Poly1305 computation and returns the tag.
+    pub fn finish(self) -> Tag {
+        self.inner.finish()
+    }
+
+    /// Finalizes the Poly1305 computation and verifies that the result equals
+    /// `tag`, in constant time.
+    pub fn verify(self, tag: &[u8]) -> Result<(), error::Unspecified> {
+        constant_time::verify_slices_are_equal(self.finish().as_ref(), tag)
+    }
+}
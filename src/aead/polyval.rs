@@ -0,0 +1,143 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! POLYVAL, as defined in [RFC 8452 Section 3].
+//!
+//! POLYVAL is implemented in terms of GHASH using the identity from
+//! [RFC 8452 Appendix A] so that the CLMUL/AVX/NEON GHASH implementations are
+//! used:
+//!
+//! ```text
+//! POLYVAL(H, X_1, ..., X_n) =
+//!     ByteReverse(GHASH(mulX_GHASH(ByteReverse(H)),
+//!                       ByteReverse(X_1), ..., ByteReverse(X_n)))
+//! ```
+//!
+//! [RFC 8452 Section 3]: https://tools.ietf.org/html/rfc8452#section-3
+//! [RFC 8452 Appendix A]: https://tools.ietf.org/html/rfc8452#appendix-A
+
+use super::{
+    block::{Block, BLOCK_LEN},
+    gcm, Aad,
+};
+use crate::{cpu, error};
+
+#[derive(Clone)]
+pub(super) struct Key(gcm::Key);
+
+impl Key {
+    pub(super) fn new(h: Block, cpu_features: cpu::Features) -> Self {
+        Self(gcm::Key::new(mul_x_ghash(byte_reverse(h)), cpu_features))
+    }
+}
+
+pub(super) struct Context(gcm::Context);
+
+impl Context {
+    pub(super) fn new(key: &Key, cpu_features: cpu::Features) -> Result<Self, error::Unspecified> {
+        // The lengths passed here are never used since we never call
+        // `gcm::Context::pre_finish`.
+        gcm::Context::new(&key.0, Aad::from(&[]), 0, cpu_features).map(Self)
+    }
+
+    /// Hashes `input`, padding the final partial block (if any) with zeros.
+    pub(super) fn update_padded(&mut self, input: &[u8]) {
+        const CHUNK_BLOCKS: usize = 16;
+
+        let mut chunks = input.chunks_exact(CHUNK_BLOCKS * BLOCK_LEN);
+        for chunk in chunks.by_ref() {
+            let mut reversed = [0u8; CHUNK_BLOCKS * BLOCK_LEN];
+            reversed
+                .chunks_exact_mut(BLOCK_LEN)
+                .zip(chunk.chunks_exact(BLOCK_LEN))
+                .for_each(|(out, block)| {
+                    out.iter_mut()
+                        .zip(block.iter().rev())
+                        .for_each(|(out, b)| *out = *b)
+                });
+            self.0.update_blocks(&reversed);
+        }
+
+        for remainder in chunks.remainder().chunks(BLOCK_LEN) {
+            let mut block = Block::zero();
+            block.overwrite_part_at(0, remainder);
+            self.update_block(block);
+        }
+    }
+
+    pub(super) fn update_block(&mut self, block: Block) {
+        self.0.update_block(byte_reverse(block));
+    }
+
+    pub(super) fn finish(self) -> Block {
+        byte_reverse(self.0.finish_without_lengths())
+    }
+}
+
+#[inline]
+fn byte_reverse(block: Block) -> Block {
+    let mut bytes = *block.as_ref();
+    bytes.reverse();
+    Block::from(&bytes)
+}
+
+// mulX_GHASH from RFC 8452 Appendix A: multiplication by x in GHASH's
+// bit-reflected field representation.
+fn mul_x_ghash(block: Block) -> Block {
+    let v = u128::from_be_bytes(*block.as_ref());
+    let carry = 0u128.wrapping_sub(v & 1);
+    let v = (v >> 1) ^ (carry & (0xe1 << 120));
+    Block::from(&v.to_be_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 8452 Appendix A.
+    #[test]
+    fn test_polyval() {
+        let h = Block::from(&[
+            0x25, 0x62, 0x93, 0x47, 0x58, 0x92, 0x42, 0x76, 0x1d, 0x31, 0xf8, 0x26, 0xba, 0x4b,
+            0x75, 0x7b,
+        ]);
+        let x_1 = [
+            0x4f, 0x4f, 0x95, 0x66, 0x8c, 0x83, 0xdf, 0xb6, 0x40, 0x17, 0x62, 0xbb, 0x2d, 0x01,
+            0xa2, 0x62,
+        ];
+        let x_2 = [
+            0xd1, 0xa2, 0x4d, 0xdd, 0x27, 0x21, 0xd0, 0x06, 0xbb, 0xe4, 0x5f, 0x20, 0xd3, 0xc9,
+            0xf3, 0x62,
+        ];
+        let expected = [
+            0xf7, 0xa3, 0xb4, 0x7b, 0x84, 0x61, 0x19, 0xfa, 0xe5, 0xb7, 0x86, 0x6c, 0xf5, 0xe5,
+            0xb7, 0x7e,
+        ];
+
+        let cpu_features = cpu::features();
+        let key = Key::new(h, cpu_features);
+
+        let mut ctx = Context::new(&key, cpu_features).unwrap();
+        ctx.update_block(Block::from(&x_1));
+        ctx.update_block(Block::from(&x_2));
+        assert_eq!(ctx.finish().as_ref(), &expected);
+
+        let mut x = [0u8; 2 * BLOCK_LEN];
+        x[..BLOCK_LEN].copy_from_slice(&x_1);
+        x[BLOCK_LEN..].copy_from_slice(&x_2);
+        let mut ctx = Context::new(&key, cpu_features).unwrap();
+        ctx.update_padded(&x);
+        assert_eq!(ctx.finish().as_ref(), &expected);
+    }
+}
@@ -0,0 +1,205 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Chunked encryption of long messages using the STREAM construction from
+//! [Online Authenticated-Encryption and its Nonce-Reuse Misuse-Resistance].
+//!
+//! A message is split into segments, each of which is sealed separately with
+//! the underlying AEAD. The nonce for each segment is the concatenation of a
+//! `NONCE_PREFIX_LEN`-byte prefix that is fixed for the whole message, a
+//! 32-bit big-endian segment counter, and a one-byte flag that is 1 for the
+//! last segment and 0 for all the others. Consequently a `Decryptor` rejects
+//! segments that have been reordered, dropped, or duplicated, and it can
+//! detect that a message has been truncated because the last segment it
+//! receives won't have been sealed as the last segment.
+//!
+//! The nonce prefix must never be reused for the same key. Since the prefix
+//! is only `NONCE_PREFIX_LEN` bytes long, it isn't safe to choose prefixes
+//! randomly for many messages under the same key; prefer to use a fresh key
+//! for each message.
+//!
+//! [Online Authenticated-Encryption and its Nonce-Reuse Misuse-Resistance]:
+//!     https://eprint.iacr.org/2015/189.pdf
+
+use super::{Aad, Algorithm, LessSafeKey, Nonce, UnboundKey, NONCE_LEN};
+use crate::error;
+
+/// Seals the segments of a message.
+pub struct Encryptor {
+    state: State,
+}
+
+impl Encryptor {
+    /// Constructs a new `Encryptor` for a message.
+    ///
+    /// `nonce_prefix` must be unique for every message sealed with the key.
+    pub fn new(key: UnboundKey, nonce_prefix: [u8; NONCE_PREFIX_LEN]) -> Self {
+        Self {
+            state: State::new(key, nonce_prefix),
+        }
+    }
+
+    /// Seals the next segment of the message, which must not be the last
+    /// one, appending the tag to `in_out`.
+    ///
+    /// Fails if the maximum number of segments has been reached.
+    pub fn seal_in_place_append_tag<A, InOut>(
+        &mut self,
+        aad: Aad<A>,
+        in_out: &mut InOut,
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+        InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    {
+        let nonce = self.state.next_nonce()?;
+        self.state.key.seal_in_place_append_tag(nonce, aad, in_out)
+    }
+
+    /// Seals the last segment of the message, appending the tag to `in_out`.
+    ///
+    /// This consumes the `Encryptor` so that no segments can follow.
+    pub fn seal_last_in_place_append_tag<A, InOut>(
+        self,
+        aad: Aad<A>,
+        in_out: &mut InOut,
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+        InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    {
+        let nonce = self.state.peek_nonce(true)?;
+        self.state.key.seal_in_place_append_tag(nonce, aad, in_out)
+    }
+
+    /// The key's AEAD algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.state.key.algorithm()
+    }
+}
+
+impl core::fmt::Debug for Encryptor {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        self.state.key.fmt_debug("Encryptor", f)
+    }
+}
+
+/// Opens the segments of a message sealed by an `Encryptor`.
+pub struct Decryptor {
+    state: State,
+}
+
+impl Decryptor {
+    /// Constructs a new `Decryptor` for a message.
+    ///
+    /// `nonce_prefix` must be the value that was used to seal the message.
+    pub fn new(key: UnboundKey, nonce_prefix: [u8; NONCE_PREFIX_LEN]) -> Self {
+        Self {
+            state: State::new(key, nonce_prefix),
+        }
+    }
+
+    /// Opens the next segment of the message, which must not be the last
+    /// one.
+    ///
+    /// `in_out` is the segment's ciphertext followed by its tag. On success,
+    /// returns the plaintext. Fails if the segment wasn't sealed as the next
+    /// non-last segment of the message; the `Decryptor` may continue to be
+    /// used after a failure, but doing so is rarely useful.
+    pub fn open_in_place<'in_out, A>(
+        &mut self,
+        aad: Aad<A>,
+        in_out: &'in_out mut [u8],
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        let nonce = self.state.peek_nonce(false)?;
+        let plaintext = self.state.key.open_in_place(nonce, aad, in_out)?;
+        self.state.counter += 1;
+        Ok(plaintext)
+    }
+
+    /// Opens the last segment of the message.
+    ///
+    /// This consumes the `Decryptor`. Fails if the segment wasn't sealed as
+    /// the last segment of the message, which is the case if the message was
+    /// truncated.
+    pub fn open_last_in_place<A>(
+        self,
+        aad: Aad<A>,
+        in_out: &mut [u8],
+    ) -> Result<&mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        let nonce = self.state.peek_nonce(true)?;
+        self.state.key.open_in_place(nonce, aad, in_out)
+    }
+
+    /// The key's AEAD algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.state.key.algorithm()
+    }
+}
+
+impl core::fmt::Debug for Decryptor {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        self.state.key.fmt_debug("Decryptor", f)
+    }
+}
+
+struct State {
+    key: LessSafeKey,
+    nonce_prefix: [u8; NONCE_PREFIX_LEN],
+    counter: u32,
+}
+
+impl State {
+    fn new(key: UnboundKey, nonce_prefix: [u8; NONCE_PREFIX_LEN]) -> Self {
+        Self {
+            key: LessSafeKey::new(key),
+            nonce_prefix,
+            counter: 0,
+        }
+    }
+
+    // Returns the nonce for the current segment without advancing to the
+    // next one. Fails if the counter is exhausted; the last possible counter
+    // value can only be used for the last segment so that a message can
+    // always be finished.
+    fn peek_nonce(&self, last: bool) -> Result<Nonce, error::Unspecified> {
+        if !last && self.counter == u32::MAX {
+            return Err(error::Unspecified);
+        }
+        let mut nonce = [0u8; NONCE_LEN];
+        let (prefix, rest) = nonce.split_at_mut(NONCE_PREFIX_LEN);
+        let (counter, flag) = rest.split_at_mut(4);
+        prefix.copy_from_slice(&self.nonce_prefix);
+        counter.copy_from_slice(&self.counter.to_be_bytes());
+        flag[0] = u8::from(last);
+        Ok(Nonce::assume_unique_for_key(nonce))
+    }
+
+    fn next_nonce(&mut self) -> Result<Nonce, error::Unspecified> {
+        let nonce = self.peek_nonce(false)?;
+        self.counter += 1;
+        Ok(nonce)
+    }
+}
+
+/// The length of the nonce prefix.
+pub const NONCE_PREFIX_LEN: usize = NONCE_LEN - 4 - 1;
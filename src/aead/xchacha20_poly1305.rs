@@ -0,0 +1,231 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! XChaCha20-Poly1305, as described in [draft-irtf-cfrg-xchacha].
+//!
+//! XChaCha20-Poly1305 uses 192-bit nonces, which are large enough that nonces
+//! can be generated randomly (e.g. using `ring::rand::SystemRandom`) for
+//! every message without a meaningful risk of collision. Since the nonce
+//! length differs from `ring::aead::NONCE_LEN`, this has its own `Key` and
+//! `Nonce` types instead of being an `ring::aead::Algorithm`.
+//!
+//! [draft-irtf-cfrg-xchacha]:
+//!     https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-xchacha-03
+
+use super::{
+    chacha::{self, HCHACHA20_NONCE_LEN},
+    chacha20_poly1305, Aad, KeyInner, Tag,
+};
+use crate::{constant_time, cpu, error};
+use core::ops::RangeFrom;
+
+/// A key for sealing and opening with XChaCha20-Poly1305.
+///
+/// The caller must ensure that each nonce is unique for the key; choosing
+/// each nonce randomly is sufficient.
+pub struct Key {
+    key: chacha::Key,
+}
+
+impl Key {
+    /// Constructs a new `Key`.
+    pub fn new(key_material: &[u8; KEY_LEN]) -> Self {
+        Self {
+            key: chacha::Key::new(*key_material),
+        }
+    }
+
+    /// Like [`super::LessSafeKey::seal_in_place_append_tag()`], except it
+    /// takes a 192-bit nonce.
+    pub fn seal_in_place_append_tag<A, InOut>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &mut InOut,
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+        InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    {
+        self.seal_in_place_separate_tag(nonce, aad, in_out.as_mut())
+            .map(|tag| in_out.extend(tag.as_ref()))
+    }
+
+    /// Like [`super::LessSafeKey::seal_in_place_separate_tag()`], except it
+    /// takes a 192-bit nonce.
+    pub fn seal_in_place_separate_tag<A>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &mut [u8],
+    ) -> Result<Tag, error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        let (subkey, nonce) = self.derive(nonce);
+        chacha20_poly1305::chacha20_poly1305_seal(
+            &subkey,
+            nonce,
+            Aad::from(aad.as_ref()),
+            in_out,
+            cpu::features(),
+        )
+    }
+
+    /// Like [`super::LessSafeKey::open_in_place()`], except it takes a
+    /// 192-bit nonce.
+    pub fn open_in_place<'in_out, A>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &'in_out mut [u8],
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        self.open_within(nonce, aad, in_out, 0..)
+    }
+
+    /// Like [`super::LessSafeKey::open_within()`], except it takes a 192-bit
+    /// nonce.
+    pub fn open_within<'in_out, A>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &'in_out mut [u8],
+        ciphertext_and_tag: RangeFrom<usize>,
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        let tag_offset = in_out
+            .len()
+            .checked_sub(TAG_LEN)
+            .ok_or(error::Unspecified)?;
+
+        // Split the tag off the end of `in_out`.
+        let (in_out, received_tag) = in_out.split_at_mut(tag_offset);
+        let received_tag = (*received_tag).try_into()?;
+
+        self.open_in_place_separate_tag(nonce, aad, received_tag, in_out, ciphertext_and_tag)
+    }
+
+    /// Like [`super::LessSafeKey::open_in_place_separate_tag()`], except it
+    /// takes a 192-bit nonce.
+    pub fn open_in_place_separate_tag<'in_out, A>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        tag: Tag,
+        in_out: &'in_out mut [u8],
+        ciphertext: RangeFrom<usize>,
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        let ciphertext_len = in_out
+            .get(ciphertext.clone())
+            .ok_or(error::Unspecified)?
+            .len();
+
+        let (subkey, nonce) = self.derive(nonce);
+        let calculated_tag = chacha20_poly1305::chacha20_poly1305_open(
+            &subkey,
+            nonce,
+            Aad::from(aad.as_ref()),
+            &tag,
+            in_out,
+            ciphertext,
+            cpu::features(),
+        )?;
+
+        if constant_time::verify_slices_are_equal(calculated_tag.as_ref(), tag.as_ref()).is_err() {
+            // Zero out the plaintext so that it isn't accidentally leaked or
+            // used after verification fails.
+            for b in &mut in_out[..ciphertext_len] {
+                *b = 0;
+            }
+            return Err(error::Unspecified);
+        }
+
+        // `ciphertext_len` is also the plaintext length.
+        Ok(&mut in_out[..ciphertext_len])
+    }
+
+    // draft-irtf-cfrg-xchacha Section 2.3: the subkey is HChaCha20 of the
+    // first 128 bits of the nonce, and the ChaCha20-Poly1305 nonce is four
+    // zero bytes followed by the last 64 bits of the nonce.
+    fn derive(&self, Nonce(nonce): Nonce) -> (KeyInner, super::Nonce) {
+        let (hchacha20_nonce, remainder) = nonce.split_at(HCHACHA20_NONCE_LEN);
+        let subkey = self.key.hchacha20(hchacha20_nonce.try_into().unwrap());
+
+        let mut chacha20_nonce = [0u8; super::NONCE_LEN];
+        chacha20_nonce[(super::NONCE_LEN - remainder.len())..].copy_from_slice(remainder);
+
+        (
+            KeyInner::ChaCha20Poly1305(subkey),
+            super::Nonce::assume_unique_for_key(chacha20_nonce),
+        )
+    }
+}
+
+impl core::fmt::Debug for Key {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Key").finish()
+    }
+}
+
+/// A 192-bit nonce for a single XChaCha20-Poly1305 sealing or opening
+/// operation.
+///
+/// The user must ensure, for a particular key, that each nonce is unique.
+/// Because the nonce is so large, generating each nonce randomly is safe.
+///
+/// `Nonce` intentionally doesn't implement `Clone` to ensure that each one is
+/// consumed at most once.
+pub struct Nonce([u8; NONCE_LEN]);
+
+impl Nonce {
+    /// Constructs a `Nonce` with the given value, assuming that the value is
+    /// unique for the lifetime of the key it is being used with.
+    ///
+    /// Fails if `value` isn't `NONCE_LEN` bytes long.
+    #[inline]
+    pub fn try_assume_unique_for_key(value: &[u8]) -> Result<Self, error::Unspecified> {
+        let value: &[u8; NONCE_LEN] = value.try_into()?;
+        Ok(Self::assume_unique_for_key(*value))
+    }
+
+    /// Constructs a `Nonce` with the given value, assuming that the value is
+    /// unique for the lifetime of the key it is being used with.
+    #[inline]
+    pub fn assume_unique_for_key(value: [u8; NONCE_LEN]) -> Self {
+        Self(value)
+    }
+}
+
+impl AsRef<[u8; NONCE_LEN]> for Nonce {
+    fn as_ref(&self) -> &[u8; NONCE_LEN] {
+        &self.0
+    }
+}
+
+/// The length of a key.
+pub const KEY_LEN: usize = chacha::KEY_LEN;
+
+/// The length of a nonce.
+pub const NONCE_LEN: usize = 192 / 8;
+
+/// The length of a tag.
+pub const TAG_LEN: usize = super::TAG_LEN;
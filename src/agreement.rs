@@ -12,13 +12,14 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-//! Key Agreement: ECDH, including X25519.
+//! Key Agreement: ECDH, including X25519 and X448.
 //!
 //! # Example
 //!
-//! Note that this example uses X25519, but ECDH using NIST P-256/P-384 is done
-//! exactly the same way, just substituting
-//! `agreement::ECDH_P256`/`agreement::ECDH_P384` for `agreement::X25519`.
+//! Note that this example uses X25519, but ECDH using NIST P-256/P-384/P-521 is
+//! done exactly the same way, just substituting
+//! `agreement::ECDH_P256`/`agreement::ECDH_P384`/`agreement::ECDH_P521` for
+//! `agreement::X25519`.
 //!
 //! ```
 //! use ring::{agreement, rand};
@@ -56,15 +57,23 @@
 //!
 //! # Ok::<(), ring::error::Unspecified>(())
 //! ```
+//!
+//! # Static keys
+//!
+//! An `EphemeralPrivateKey` can be used for only one key agreement. Protocols
+//! with long-term Diffie-Hellman keys can use a `StaticPrivateKey`, which can
+//! be used with `agree_static` any number of times. X25519, P-256, and P-384
+//! static keys can be stored as PKCS#8.
 
 // The "NSA Guide" steps here are from from section 3.1, "Ephemeral Unified
 // Model."
 
-use crate::{cpu, debug, ec, error, rand};
+use crate::{cpu, debug, ec, error, pkcs8, rand};
 
 pub use crate::ec::{
     curve25519::x25519::X25519,
-    suite_b::ecdh::{ECDH_P256, ECDH_P384},
+    curve448::x448::X448,
+    suite_b::ecdh::{ECDH_P256, ECDH_P384, ECDH_P521},
 };
 
 /// A key agreement algorithm.
@@ -147,6 +156,147 @@ impl EphemeralPrivateKey {
     }
 }
 
+/// A static private key, for use with `agree_static`.
+///
+/// Unlike an `EphemeralPrivateKey`, a `StaticPrivateKey` can be used for any
+/// number of key agreements, as needed by protocols with long-term
+/// Diffie-Hellman keys. It can be serialized and loaded again using the raw
+/// private key bytes, and for `X25519`, `ECDH_P256`, and `ECDH_P384`, using
+/// PKCS#8.
+pub struct StaticPrivateKey {
+    private_key: ec::Seed,
+    public_key: PublicKey,
+}
+
+derive_debug_via_field!(StaticPrivateKey, stringify!(StaticPrivateKey), public_key);
+
+impl StaticPrivateKey {
+    /// Generates a new static private key for the given algorithm.
+    pub fn generate(
+        alg: &'static Algorithm,
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<Self, error::Unspecified> {
+        let private_key = ec::Seed::generate(alg.curve, rng, cpu::features())?;
+        let key_pair = ec::KeyPair::derive(private_key)?;
+        Ok(Self::from_key_pair(alg, key_pair))
+    }
+
+    /// Generates a new static private key for the given algorithm and returns
+    /// it serialized as a PKCS#8 document.
+    ///
+    /// For X25519 the document is a v2 `OneAsymmetricKey`, as described in
+    /// [RFC 8410]. For P-256 and P-384 it is a v1 document wrapping an
+    /// `ECPrivateKey`, as described in [RFC 5915], like the ones generated by
+    /// `EcdsaKeyPair::generate_pkcs8`. In both cases the document contains
+    /// the public key. Other algorithms aren't supported.
+    ///
+    /// [RFC 8410]: https://tools.ietf.org/html/rfc8410
+    /// [RFC 5915]: https://tools.ietf.org/html/rfc5915
+    pub fn generate_pkcs8(
+        alg: &'static Algorithm,
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<pkcs8::Document, error::Unspecified> {
+        let key = Self::generate(alg, rng)?;
+        key.to_pkcs8()
+    }
+
+    /// Constructs a static private key by parsing an unencrypted PKCS#8
+    /// document in the form produced by `generate_pkcs8`.
+    ///
+    /// X25519 keys may also be in PKCS#8 v1 form, without the public key, as
+    /// generated by `openssl genpkey`. When the public key is present it must
+    /// be consistent with the private key.
+    pub fn from_pkcs8(alg: &'static Algorithm, pkcs8: &[u8]) -> Result<Self, error::KeyRejected> {
+        let template = pkcs8_template(alg).ok_or_else(error::KeyRejected::wrong_algorithm)?;
+        let input = untrusted::Input::from(pkcs8);
+        let key_pair = if alg.curve.id == ec::CurveID::Curve25519 {
+            ec::key_pair_from_rfc8410_pkcs8(alg.curve, template, input, cpu::features())
+        } else {
+            ec::suite_b::key_pair_from_pkcs8(alg.curve, template, input, cpu::features())
+        }?;
+        Ok(Self::from_key_pair(alg, key_pair))
+    }
+
+    /// Constructs a static private key from its raw encoding.
+    ///
+    /// For X25519 and X448 this is the encoding of RFC 7748. For the NIST
+    /// curves it is the big-endian encoding of the private scalar, padded to
+    /// the length of the curve order; it is rejected if it is not in the range
+    /// [1, n).
+    pub fn from_private_key_bytes(
+        alg: &'static Algorithm,
+        bytes: &[u8],
+    ) -> Result<Self, error::KeyRejected> {
+        let private_key =
+            ec::Seed::from_bytes(alg.curve, untrusted::Input::from(bytes), cpu::features())
+                .map_err(|error::Unspecified| error::KeyRejected::invalid_component())?;
+        let key_pair = ec::KeyPair::derive(private_key)
+            .map_err(|error::Unspecified| error::KeyRejected::unexpected_error())?;
+        Ok(Self::from_key_pair(alg, key_pair))
+    }
+
+    fn from_key_pair(alg: &'static Algorithm, key_pair: ec::KeyPair) -> Self {
+        let (private_key, public_key) = key_pair.split();
+        Self {
+            private_key,
+            public_key: PublicKey {
+                algorithm: alg,
+                bytes: public_key,
+            },
+        }
+    }
+
+    /// Serializes the key as a PKCS#8 document, in the form produced by
+    /// `generate_pkcs8`.
+    ///
+    /// Fails if the algorithm isn't one that `generate_pkcs8` supports.
+    pub fn to_pkcs8(&self) -> Result<pkcs8::Document, error::Unspecified> {
+        let template = pkcs8_template(self.algorithm()).ok_or(error::Unspecified)?;
+        Ok(pkcs8::wrap_key(
+            template,
+            self.private_key.bytes_less_safe(),
+            self.public_key.as_ref(),
+        ))
+    }
+
+    /// The raw encoding of the private key, in the form accepted by
+    /// `from_private_key_bytes`.
+    ///
+    /// This exposes the secret key material; prefer `to_pkcs8` for storage.
+    pub fn private_key_bytes_less_safe(&self) -> &[u8] {
+        self.private_key.bytes_less_safe()
+    }
+
+    /// The public key for this private key.
+    #[inline]
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// The algorithm for the private key.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.public_key.algorithm
+    }
+}
+
+// PKCS#8 serialization of static keys is only supported for the algorithms
+// that protocols commonly use static keys with. Static keys for the other
+// algorithms, which `hpke` needs, can still be constructed from their raw
+// private key bytes.
+fn pkcs8_template(alg: &Algorithm) -> Option<&'static pkcs8::Template> {
+    match alg.curve.id {
+        ec::CurveID::Curve25519 => Some(&ec::curve25519::x25519::PKCS8_TEMPLATE),
+        ec::CurveID::P256 => {
+            Some(&ec::suite_b::ecdsa::signing::EC_PUBLIC_KEY_P256_PKCS8_V1_TEMPLATE)
+        }
+        ec::CurveID::P384 => {
+            Some(&ec::suite_b::ecdsa::signing::EC_PUBLIC_KEY_P384_PKCS8_V1_TEMPLATE)
+        }
+        _ => None,
+    }
+}
+
 /// A public key for key agreement.
 #[derive(Clone)]
 pub struct PublicKey {
@@ -254,22 +404,59 @@ pub fn agree_ephemeral<B: AsRef<[u8]>, R>(
     agree_ephemeral_(my_private_key, peer_public_key, kdf)
 }
 
+/// Performs a key agreement with a static private key and the given public
+/// key.
+///
+/// `my_private_key` is borrowed, so it can be used for any number of key
+/// agreements. Otherwise `agree_static` works exactly like
+/// `agree_ephemeral`: `peer_public_key` is validated the same way, and `kdf`
+/// is called with the raw key material.
+#[inline]
+pub fn agree_static<B: AsRef<[u8]>, R>(
+    my_private_key: &StaticPrivateKey,
+    peer_public_key: &UnparsedPublicKey<B>,
+    kdf: impl FnOnce(&[u8]) -> R,
+) -> Result<R, error::Unspecified> {
+    let peer_public_key = UnparsedPublicKey {
+        algorithm: peer_public_key.algorithm,
+        bytes: peer_public_key.bytes.as_ref(),
+    };
+    agree_(
+        &my_private_key.private_key,
+        my_private_key.algorithm(),
+        peer_public_key,
+        kdf,
+    )
+}
+
 fn agree_ephemeral_<R>(
     my_private_key: EphemeralPrivateKey,
     peer_public_key: UnparsedPublicKey<&[u8]>,
     kdf: impl FnOnce(&[u8]) -> R,
+) -> Result<R, error::Unspecified> {
+    agree_(
+        &my_private_key.private_key,
+        my_private_key.algorithm,
+        peer_public_key,
+        kdf,
+    )
+}
+
+fn agree_<R>(
+    my_private_key: &ec::Seed,
+    alg: &'static Algorithm,
+    peer_public_key: UnparsedPublicKey<&[u8]>,
+    kdf: impl FnOnce(&[u8]) -> R,
 ) -> Result<R, error::Unspecified> {
     // NSA Guide Prerequisite 1.
     //
     // The domain parameters are hard-coded. This check verifies that the
     // peer's public key's domain parameters match the domain parameters of
     // this private key.
-    if peer_public_key.algorithm != my_private_key.algorithm {
+    if peer_public_key.algorithm != alg {
         return Err(error::Unspecified);
     }
 
-    let alg = &my_private_key.algorithm;
-
     // NSA Guide Prerequisite 2, regarding which KDFs are allowed, is delegated
     // to the caller.
 
@@ -278,7 +465,8 @@ fn agree_ephemeral_<R>(
     // during the key-agreement scheme," is delegated to the caller.
 
     // NSA Guide Step 1 is handled by `EphemeralPrivateKey::generate()` and
-    // `EphemeralPrivateKey::compute_public_key()`.
+    // `EphemeralPrivateKey::compute_public_key()`, or by the constructors of
+    // `StaticPrivateKey`.
 
     let mut shared_key = [0u8; ec::ELEM_MAX_BYTES];
     let shared_key = &mut shared_key[..alg.curve.elem_scalar_seed_len];
@@ -289,7 +477,7 @@ fn agree_ephemeral_<R>(
     // that doesn't meet the NSA requirement to "zeroize."
     (alg.ecdh)(
         shared_key,
-        &my_private_key.private_key,
+        my_private_key,
         untrusted::Input::from(peer_public_key.bytes),
     )?;
 
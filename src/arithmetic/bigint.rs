@@ -676,6 +676,92 @@ pub fn elem_exp_consttime<M>(
     Ok(from_montgomery_amm(r_amm, m))
 }
 
+/// Returns `a**-1 (mod m)` for odd `m`, or an error if `a` isn't invertible.
+///
+/// This uses the binary extended Euclidean algorithm and is *not*
+/// constant-time; callers must blind `a` if it is secret.
+pub fn elem_inverse_vartime<M>(
+    a: &Elem<M, Unencoded>,
+    m: &Modulus<M>,
+) -> Result<Elem<M, Unencoded>, error::Unspecified> {
+    let m_limbs = m.limbs();
+
+    // Maintain the invariants `u == x1 * a (mod m)` and `v == x2 * a (mod m)`.
+    // Start with `v == m - a` and `x2 == -1` instead of `v == m` and `x2 == 0`
+    // so that all the values are always less than `m`.
+    let mut u = a.limbs.clone();
+    let mut x1 = m.zero::<Unencoded>().limbs;
+    x1[0] = 1;
+    let mut v = m.zero::<Unencoded>().limbs;
+    limb::limbs_sub_assign_mod(&mut v, &u, m_limbs);
+    let mut x2 = m.zero::<Unencoded>().limbs;
+    limb::limbs_sub_assign_mod(&mut x2, &x1, m_limbs);
+
+    loop {
+        // When the GCD isn't 1, eventually `u == v` and then one becomes zero.
+        if u.is_zero() || v.is_zero() {
+            return Err(error::Unspecified);
+        }
+        while u[0] & 1 == 0 {
+            limbs_shr1(&mut u, 0);
+            limbs_half_mod_vartime(&mut x1, m_limbs);
+        }
+        while v[0] & 1 == 0 {
+            limbs_shr1(&mut v, 0);
+            limbs_half_mod_vartime(&mut x2, m_limbs);
+        }
+        if limbs_are_one_vartime(&u) {
+            return Ok(Elem {
+                limbs: x1,
+                encoding: PhantomData,
+            });
+        }
+        if limbs_are_one_vartime(&v) {
+            return Ok(Elem {
+                limbs: x2,
+                encoding: PhantomData,
+            });
+        }
+        // Since both values are less than `m`, the modular subtraction of the
+        // smaller from the larger is an ordinary subtraction.
+        if limb::limbs_less_than_limbs_vartime(&u, &v) {
+            limb::limbs_sub_assign_mod(&mut v, &u, m_limbs);
+            limb::limbs_sub_assign_mod(&mut x2, &x1, m_limbs);
+        } else {
+            limb::limbs_sub_assign_mod(&mut u, &v, m_limbs);
+            limb::limbs_sub_assign_mod(&mut x1, &x2, m_limbs);
+        }
+    }
+}
+
+fn limbs_are_one_vartime(a: &[Limb]) -> bool {
+    a[0] == 1 && a[1..].iter().all(|&limb| limb == 0)
+}
+
+// Shifts `a` right one bit, shifting `high_bit` into the most significant bit.
+fn limbs_shr1(a: &mut [Limb], high_bit: Limb) {
+    let mut carry = high_bit;
+    for limb in a.iter_mut().rev() {
+        let new_carry = *limb & 1;
+        *limb = (*limb >> 1) | (carry << (LIMB_BITS - 1));
+        carry = new_carry;
+    }
+}
+
+// Sets `a = a / 2 (mod m)` for odd `m`.
+fn limbs_half_mod_vartime(a: &mut [Limb], m: &[Limb]) {
+    let mut carry = 0;
+    if a[0] & 1 == 1 {
+        for (a, &m) in a.iter_mut().zip(m) {
+            let (sum, overflow1) = a.overflowing_add(m);
+            let (sum, overflow2) = sum.overflowing_add(carry);
+            *a = sum;
+            carry = Limb::from(overflow1 | overflow2);
+        }
+    }
+    limbs_shr1(a, carry);
+}
+
 /// Verified a == b**-1 (mod m), i.e. a**-1 == b (mod m).
 pub fn verify_inverses_consttime<M>(
     a: &Elem<M, R>,
@@ -840,6 +926,34 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_elem_inverse_vartime() {
+        let cpu_features = cpu::features();
+        test::run(
+            test_file!("bigint_elem_inverse_vartime_tests.txt"),
+            |section, test_case| {
+                assert_eq!(section, "");
+
+                let m = consume_modulus::<M>(test_case, "M");
+                let m = m.modulus(cpu_features);
+                let a = consume_elem(test_case, "A", &m);
+                let expected_result = test_case
+                    .consume_optional_bytes("ModInv")
+                    .map(|value| Elem::from_be_bytes_padded(untrusted::Input::from(&value), &m));
+
+                match (elem_inverse_vartime(&a, &m), expected_result) {
+                    (Ok(actual_result), Some(Ok(expected_result))) => {
+                        assert_elem_eq(&actual_result, &expected_result)
+                    }
+                    (Err(_), None) => {}
+                    _ => panic!("Unexpected result"),
+                }
+
+                Ok(())
+            },
+        )
+    }
+
     fn consume_elem<M>(
         test_case: &mut test::TestCase,
         name: &str,
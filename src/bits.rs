@@ -72,7 +72,6 @@ impl BitLength<usize> {
     }
 
     /// The bit length, rounded up to a whole number of bytes.
-    #[cfg(any(target_arch = "aarch64", feature = "alloc"))]
     #[inline]
     pub fn as_usize_bytes_rounded_up(&self) -> usize {
         // Equivalent to (self.0 + 7) / 8, except with no potential for
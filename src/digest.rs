@@ -12,7 +12,8 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-//! SHA-2 and the legacy SHA-1 digest algorithm.
+//! SHA-2, SHA-3, BLAKE2, and the legacy SHA-1 digest algorithms, and the
+//! SHAKE extendable-output functions.
 //!
 //! If all the data is available in a single contiguous slice then the `digest`
 //! function should be used. Otherwise, the digest can be calculated in
@@ -24,11 +25,19 @@
 // The goal for this implementation is to drive the overhead as close to zero
 // as possible.
 
-use crate::{c, cpu, debug, polyfill};
+use crate::{c, cpu, debug, error, polyfill};
 use core::num::Wrapping;
 
+mod batch;
+mod blake2;
 mod sha1;
 mod sha2;
+mod sha3;
+
+pub use self::{
+    batch::sha256_batch,
+    sha3::{XofAlgorithm, XofContext, XofReader, SHAKE128, SHAKE256},
+};
 
 #[derive(Clone)]
 pub(crate) struct BlockContext {
@@ -73,11 +82,43 @@ impl BlockContext {
         assert_eq!(pending.len(), block_len);
         assert!(num_pending <= pending.len());
 
+        match self.algorithm.padding {
+            Padding::MerkleDamgard { len_len } => {
+                self.finish_merkle_damgard(pending, num_pending, len_len);
+            }
+            Padding::Keccak { domain } => {
+                sha3::absorb_final_block(
+                    unsafe { &mut self.state.keccak },
+                    pending,
+                    num_pending,
+                    domain,
+                );
+            }
+            Padding::Blake2b => {
+                let _ = self.completed_data_bits(num_pending);
+                blake2::finish(unsafe { &mut self.state.blake2b }, pending, num_pending);
+            }
+            Padding::Blake2s => {
+                let _ = self.completed_data_bits(num_pending);
+                blake2::finish(unsafe { &mut self.state.blake2s }, pending, num_pending);
+            }
+        }
+
+        Digest {
+            algorithm: self.algorithm,
+            value: (self.algorithm.format_output)(self.state),
+        }
+    }
+
+    // FIPS 180-4 Section 5.1.
+    fn finish_merkle_damgard(&mut self, pending: &mut [u8], num_pending: usize, len_len: usize) {
+        let block_len = self.algorithm.block_len;
+
         let mut padding_pos = num_pending;
         pending[padding_pos] = 0x80;
         padding_pos += 1;
 
-        if padding_pos > block_len - self.algorithm.len_len {
+        if padding_pos > block_len - len_len {
             pending[padding_pos..block_len].fill(0);
             unsafe { self.block_data_order(pending.as_ptr(), 1, cpu::features()) };
             // We don't increase |self.completed_data_blocks| because the
@@ -88,24 +129,104 @@ impl BlockContext {
         pending[padding_pos..(block_len - 8)].fill(0);
 
         // Output the length, in bits, in big endian order.
-        let completed_data_bits = self
-            .completed_data_blocks
-            .checked_mul(polyfill::u64_from_usize(block_len))
-            .unwrap()
-            .checked_add(polyfill::u64_from_usize(num_pending))
-            .unwrap()
-            .checked_mul(8)
-            .unwrap();
+        let completed_data_bits = self.completed_data_bits(num_pending);
         pending[(block_len - 8)..block_len].copy_from_slice(&u64::to_be_bytes(completed_data_bits));
 
         unsafe { self.block_data_order(pending.as_ptr(), 1, cpu::features()) };
+    }
 
-        Digest {
-            algorithm: self.algorithm,
-            value: (self.algorithm.format_output)(self.state),
+    // The length of the chaining state as written by `write_state`.
+    fn state_len(&self) -> usize {
+        match self.algorithm.padding {
+            // SHA-1, SHA-224, and SHA-256 use 32-bit words and the SHA-512
+            // family uses 64-bit words. Unused words are written too, for
+            // simplicity.
+            Padding::MerkleDamgard { len_len } if len_len == SHA512_LEN_LEN => {
+                sha2::CHAINING_WORDS * 8
+            }
+            Padding::MerkleDamgard { .. } => sha2::CHAINING_WORDS * 4,
+            Padding::Keccak { .. } => sha3::STATE_WORDS * 8,
+            Padding::Blake2b => blake2::State::<u64>::SERIALIZED_LEN,
+            Padding::Blake2s => blake2::State::<u32>::SERIALIZED_LEN,
         }
     }
 
+    // Writes the chaining state, which must be `self.state_len()` bytes long,
+    // to `out` in a platform-independent format.
+    fn write_state(&self, out: &mut [u8]) {
+        match self.algorithm.padding {
+            Padding::MerkleDamgard { len_len } if len_len == SHA512_LEN_LEN => {
+                let words = unsafe { &self.state.as64 };
+                out.chunks_mut(8)
+                    .zip(words.iter())
+                    .for_each(|(out, Wrapping(w))| out.copy_from_slice(&w.to_be_bytes()));
+            }
+            Padding::MerkleDamgard { .. } => {
+                let words = unsafe { &self.state.as32 };
+                out.chunks_mut(4)
+                    .zip(words.iter())
+                    .for_each(|(out, Wrapping(w))| out.copy_from_slice(&w.to_be_bytes()));
+            }
+            Padding::Keccak { .. } => {
+                let lanes = unsafe { &self.state.keccak };
+                out.chunks_mut(8)
+                    .zip(lanes.iter())
+                    .for_each(|(out, w)| out.copy_from_slice(&w.to_le_bytes()));
+            }
+            Padding::Blake2b => unsafe { self.state.blake2b.write(out) },
+            Padding::Blake2s => unsafe { self.state.blake2s.write(out) },
+        }
+    }
+
+    // Reads a chaining state written by `write_state`. `bytes` must be
+    // `self.state_len()` bytes long.
+    fn read_state(&mut self, bytes: &[u8]) {
+        match self.algorithm.padding {
+            Padding::MerkleDamgard { len_len } if len_len == SHA512_LEN_LEN => {
+                let words = unsafe { &mut self.state.as64 };
+                words
+                    .iter_mut()
+                    .zip(bytes.chunks_exact(8))
+                    .for_each(|(w, bytes)| {
+                        *w = Wrapping(u64::from_be_bytes(bytes.try_into().unwrap()));
+                    });
+            }
+            Padding::MerkleDamgard { .. } => {
+                let words = unsafe { &mut self.state.as32 };
+                words
+                    .iter_mut()
+                    .zip(bytes.chunks_exact(4))
+                    .for_each(|(w, bytes)| {
+                        *w = Wrapping(u32::from_be_bytes(bytes.try_into().unwrap()));
+                    });
+            }
+            Padding::Keccak { .. } => {
+                let lanes = unsafe { &mut self.state.keccak };
+                lanes
+                    .iter_mut()
+                    .zip(bytes.chunks_exact(8))
+                    .for_each(|(w, bytes)| {
+                        *w = u64::from_le_bytes(bytes.try_into().unwrap());
+                    });
+            }
+            Padding::Blake2b => unsafe { self.state.blake2b.read(bytes) },
+            Padding::Blake2s => unsafe { self.state.blake2s.read(bytes) },
+        }
+    }
+
+    // Panics if the input is longer than the 2^64-1 bits that this
+    // implementation supports.
+    fn completed_data_bits(&self, num_pending: usize) -> u64 {
+        self.checked_completed_data_bits(num_pending).unwrap()
+    }
+
+    fn checked_completed_data_bits(&self, num_pending: usize) -> Option<u64> {
+        self.completed_data_blocks
+            .checked_mul(polyfill::u64_from_usize(self.algorithm.block_len))?
+            .checked_add(polyfill::u64_from_usize(num_pending))?
+            .checked_mul(8)
+    }
+
     unsafe fn block_data_order(
         &mut self,
         pending: *const u8,
@@ -140,7 +261,7 @@ impl BlockContext {
 pub struct Context {
     block: BlockContext,
     // TODO: More explicitly force 64-bit alignment for |pending|.
-    pending: [u8; MAX_BLOCK_LEN],
+    pending: [u8; MAX_ANY_BLOCK_LEN],
     num_pending: usize,
 }
 
@@ -149,15 +270,57 @@ impl Context {
     pub fn new(algorithm: &'static Algorithm) -> Self {
         Self {
             block: BlockContext::new(algorithm),
-            pending: [0u8; MAX_BLOCK_LEN],
+            pending: [0u8; MAX_ANY_BLOCK_LEN],
             num_pending: 0,
         }
     }
 
+    /// Constructs a new context for computing a keyed digest, i.e. a MAC, of
+    /// some data.
+    ///
+    /// Only the BLAKE2 algorithms support keying, as specified in
+    /// [RFC 7693 Section 2.5]. `key` must be at least one byte long and no
+    /// longer than 64 bytes for BLAKE2b or 32 bytes for BLAKE2s. Use
+    /// `ring::hmac` to compute MACs with the other algorithms.
+    ///
+    /// A keyed digest should be verified with
+    /// `ring::constant_time::verify_slices_are_equal`.
+    ///
+    /// [`Self::export_state`] exposes the key of a keyed context.
+    ///
+    /// [RFC 7693 Section 2.5]: https://tools.ietf.org/html/rfc7693#section-2.5
+    pub fn new_keyed(
+        algorithm: &'static Algorithm,
+        key: &[u8],
+    ) -> Result<Self, error::Unspecified> {
+        let max_key_len = match algorithm.padding {
+            Padding::Blake2b => blake2::BLAKE2B_MAX_KEY_LEN,
+            Padding::Blake2s => blake2::BLAKE2S_MAX_KEY_LEN,
+            Padding::MerkleDamgard { .. } | Padding::Keccak { .. } => {
+                return Err(error::Unspecified);
+            }
+        };
+        if key.is_empty() || key.len() > max_key_len {
+            return Err(error::Unspecified);
+        }
+
+        let mut ctx = Self::new(algorithm);
+        match algorithm.padding {
+            Padding::Blake2b => unsafe { ctx.block.state.blake2b.set_key_len(key.len()) },
+            _ => unsafe { ctx.block.state.blake2s.set_key_len(key.len()) },
+        }
+        // The key, padded with zeros to a full block, is the first block of
+        // input. It is held back like any other last block so that a MAC of
+        // empty data is computed correctly.
+        ctx.pending[..key.len()].copy_from_slice(key);
+        ctx.num_pending = algorithm.block_len;
+        Ok(ctx)
+    }
+
     pub(crate) fn clone_from(block: &BlockContext) -> Self {
         Self {
             block: block.clone(),
-            pending: [0u8; MAX_BLOCK_LEN],
+            pending: [0u8; MAX_ANY_BLOCK_LEN],
             num_pending: 0,
         }
     }
@@ -165,7 +328,13 @@ impl Context {
     /// Updates the digest with all the data in `data`.
     pub fn update(&mut self, data: &[u8]) {
         let block_len = self.block.algorithm.block_len;
-        if data.len() < block_len - self.num_pending {
+
+        // BLAKE2 needs to know which block is the last one when it compresses
+        // it, so the last block is always kept pending, even if it is full.
+        let hold_last_block = self.block.algorithm.padding.holds_last_block();
+        if data.len() < block_len - self.num_pending
+            || (hold_last_block && data.len() == block_len - self.num_pending)
+        {
             self.pending[self.num_pending..(self.num_pending + data.len())].copy_from_slice(data);
             self.num_pending += data.len();
             return;
@@ -180,8 +349,13 @@ impl Context {
             self.num_pending = 0;
         }
 
-        let num_blocks = remaining.len() / block_len;
-        let num_to_save_for_later = remaining.len() % block_len;
+        let mut num_blocks = remaining.len() / block_len;
+        let mut num_to_save_for_later = remaining.len() % block_len;
+        if hold_last_block && num_to_save_for_later == 0 {
+            // `remaining` isn't empty, since `data` didn't fit in `pending`.
+            num_blocks -= 1;
+            num_to_save_for_later = block_len;
+        }
         self.block.update(&remaining[..(num_blocks * block_len)]);
         if num_to_save_for_later > 0 {
             self.pending[..num_to_save_for_later]
@@ -190,6 +364,104 @@ impl Context {
         }
     }
 
+    /// Exports the state of the digest calculation, so that it can be resumed
+    /// later, possibly in another process, with [`Self::restore`].
+    ///
+    /// The exported state consists of the chaining state, the pending partial
+    /// block, and the number of blocks processed so far. It does not identify
+    /// the algorithm, which must be supplied separately to `restore`. The
+    /// format may change between versions of *ring*.
+    ///
+    /// The exported state reveals as much about the input as the input
+    /// itself does in many cases, e.g. the pending partial block is exported
+    /// as-is, so it must be protected accordingly.
+    ///
+    /// For a keyed context (see [`Self::new_keyed`]) the exported state
+    /// exposes the key: the key block is exported as-is until more input has
+    /// been added, and after that the chaining state is enough to compute
+    /// MACs of any extension of the input. Protect it like the key itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ring::digest;
+    ///
+    /// let mut ctx = digest::Context::new(&digest::SHA256);
+    /// ctx.update(b"hello");
+    /// let state = ctx.export_state();
+    ///
+    /// let mut ctx = digest::Context::restore(&digest::SHA256, state.as_ref())?;
+    /// ctx.update(b", world");
+    ///
+    /// assert_eq!(
+    ///     ctx.finish().as_ref(),
+    ///     digest::digest(&digest::SHA256, b"hello, world").as_ref()
+    /// );
+    /// # Ok::<(), ring::error::Unspecified>(())
+    /// ```
+    pub fn export_state(&self) -> ExportedState {
+        let block_len = self.block.algorithm.block_len;
+        let state_len = self.block.state_len();
+
+        let mut exported = ExportedState {
+            bytes: [0; MAX_EXPORTED_STATE_LEN],
+            len: EXPORTED_HEADER_LEN + state_len + self.num_pending,
+        };
+        let (header, rest) = exported.bytes.split_at_mut(EXPORTED_HEADER_LEN);
+        let (state, pending) = rest.split_at_mut(state_len);
+
+        header[..8].copy_from_slice(&self.block.completed_data_blocks.to_be_bytes());
+        header[8..].copy_from_slice(&u16::to_be_bytes(self.num_pending.try_into().unwrap()));
+        self.block.write_state(state);
+        debug_assert!(self.num_pending <= block_len);
+        pending[..self.num_pending].copy_from_slice(&self.pending[..self.num_pending]);
+
+        exported
+    }
+
+    /// Restores a context from a state exported by [`Self::export_state`].
+    ///
+    /// `algorithm` must be the algorithm of the context that exported the
+    /// state. Fails if `state` is malformed, including if it records more than
+    /// the 2^64 - 1 bits of input that this implementation supports. Not all
+    /// states exported for other algorithms are detected as malformed, and a
+    /// state that has been tampered with won't necessarily be detected either.
+    pub fn restore(
+        algorithm: &'static Algorithm,
+        state: &[u8],
+    ) -> Result<Self, error::Unspecified> {
+        let mut ctx = Self::new(algorithm);
+        let state_len = ctx.block.state_len();
+        if state.len() < EXPORTED_HEADER_LEN + state_len {
+            return Err(error::Unspecified);
+        }
+        let (header, rest) = state.split_at(EXPORTED_HEADER_LEN);
+        let (state, pending) = rest.split_at(state_len);
+
+        let completed_data_blocks = u64::from_be_bytes(header[..8].try_into().unwrap());
+        let num_pending = usize::from(u16::from_be_bytes(header[8..].try_into().unwrap()));
+
+        // Merkle-Damgård and Keccak compress a block as soon as it is full,
+        // whereas BLAKE2 always holds back the last block.
+        let max_pending = if algorithm.padding.holds_last_block() {
+            algorithm.block_len
+        } else {
+            algorithm.block_len - 1
+        };
+        if num_pending > max_pending || pending.len() != num_pending {
+            return Err(error::Unspecified);
+        }
+
+        ctx.block.read_state(state);
+        ctx.block.completed_data_blocks = completed_data_blocks;
+        if ctx.block.checked_completed_data_bits(num_pending).is_none() {
+            return Err(error::Unspecified);
+        }
+        ctx.pending[..num_pending].copy_from_slice(pending);
+        ctx.num_pending = num_pending;
+        Ok(ctx)
+    }
+
     /// Finalizes the digest calculation and returns the digest value.
     ///
     /// `finish` consumes the context so it cannot be (mis-)used after `finish`
@@ -228,6 +500,35 @@ pub fn digest(algorithm: &'static Algorithm, data: &[u8]) -> Digest {
     ctx.finish()
 }
 
+/// The exported state of a [`Context`].
+///
+/// Use [`Self::as_ref`] to get the state as a `&[u8]`.
+#[derive(Clone)]
+pub struct ExportedState {
+    bytes: [u8; MAX_EXPORTED_STATE_LEN],
+    len: usize,
+}
+
+impl AsRef<[u8]> for ExportedState {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl core::fmt::Debug for ExportedState {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("ExportedState").finish()
+    }
+}
+
+// The number of completed blocks as a big-endian `u64`, followed by the
+// number of pending bytes as a big-endian `u16`.
+const EXPORTED_HEADER_LEN: usize = 8 + 2;
+
+const MAX_EXPORTED_STATE_LEN: usize =
+    EXPORTED_HEADER_LEN + (sha3::STATE_WORDS * 8) + MAX_ANY_BLOCK_LEN;
+
 /// A calculated digest value.
 ///
 /// Use [`Self::as_ref`] to get the value as a `&[u8]`.
@@ -265,8 +566,7 @@ pub struct Algorithm {
     chaining_len: usize,
     block_len: usize,
 
-    /// The length of the length in the padding.
-    len_len: usize,
+    padding: Padding,
 
     block_data_order: unsafe extern "C" fn(state: &mut State, data: *const u8, num: c::size_t),
     format_output: fn(input: State) -> Output,
@@ -276,13 +576,44 @@ pub struct Algorithm {
     id: AlgorithmID,
 }
 
+#[allow(variant_size_differences)]
+#[derive(Clone, Copy)]
+enum Padding {
+    /// Merkle-Damgård strengthening, with a big-endian bit length that is
+    /// `len_len` bytes long.
+    MerkleDamgard { len_len: usize },
+
+    /// Keccak's `pad10*1`, preceded by the given domain separation bits.
+    Keccak { domain: u8 },
+
+    /// BLAKE2b's zero padding, with the last block flagged as such.
+    Blake2b,
+
+    /// BLAKE2s's zero padding, with the last block flagged as such.
+    Blake2s,
+}
+
+impl Padding {
+    fn holds_last_block(self) -> bool {
+        matches!(self, Self::Blake2b | Self::Blake2s)
+    }
+}
+
 #[derive(Debug, Eq, PartialEq)]
 enum AlgorithmID {
     SHA1,
+    SHA224,
     SHA256,
     SHA384,
     SHA512,
+    SHA512_224,
     SHA512_256,
+    SHA3_256,
+    SHA3_384,
+    SHA3_512,
+    BLAKE2B_256,
+    BLAKE2B_512,
+    BLAKE2S_256,
 }
 
 impl PartialEq for Algorithm {
@@ -325,7 +656,7 @@ pub static SHA1_FOR_LEGACY_USE_ONLY: Algorithm = Algorithm {
     output_len: sha1::OUTPUT_LEN,
     chaining_len: sha1::CHAINING_LEN,
     block_len: sha1::BLOCK_LEN,
-    len_len: 64 / 8,
+    padding: Padding::MerkleDamgard { len_len: 64 / 8 },
     block_data_order: sha1::block_data_order,
     format_output: sha256_format_output,
     initial_state: State {
@@ -343,6 +674,31 @@ pub static SHA1_FOR_LEGACY_USE_ONLY: Algorithm = Algorithm {
     id: AlgorithmID::SHA1,
 };
 
+/// SHA-224 as specified in [FIPS 180-4].
+///
+/// [FIPS 180-4]: http://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf
+pub static SHA224: Algorithm = Algorithm {
+    output_len: SHA224_OUTPUT_LEN,
+    chaining_len: SHA256_OUTPUT_LEN,
+    block_len: 512 / 8,
+    padding: Padding::MerkleDamgard { len_len: 64 / 8 },
+    block_data_order: sha2::sha256_block_data_order,
+    format_output: sha256_format_output,
+    initial_state: State {
+        as32: [
+            Wrapping(0xc1059ed8u32),
+            Wrapping(0x367cd507u32),
+            Wrapping(0x3070dd17u32),
+            Wrapping(0xf70e5939u32),
+            Wrapping(0xffc00b31u32),
+            Wrapping(0x68581511u32),
+            Wrapping(0x64f98fa7u32),
+            Wrapping(0xbefa4fa4u32),
+        ],
+    },
+    id: AlgorithmID::SHA224,
+};
+
 /// SHA-256 as specified in [FIPS 180-4].
 ///
 /// [FIPS 180-4]: http://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf
@@ -350,7 +706,7 @@ pub static SHA256: Algorithm = Algorithm {
     output_len: SHA256_OUTPUT_LEN,
     chaining_len: SHA256_OUTPUT_LEN,
     block_len: 512 / 8,
-    len_len: 64 / 8,
+    padding: Padding::MerkleDamgard { len_len: 64 / 8 },
     block_data_order: sha2::sha256_block_data_order,
     format_output: sha256_format_output,
     initial_state: State {
@@ -375,7 +731,9 @@ pub static SHA384: Algorithm = Algorithm {
     output_len: SHA384_OUTPUT_LEN,
     chaining_len: SHA512_OUTPUT_LEN,
     block_len: SHA512_BLOCK_LEN,
-    len_len: SHA512_LEN_LEN,
+    padding: Padding::MerkleDamgard {
+        len_len: SHA512_LEN_LEN,
+    },
     block_data_order: sha2::sha512_block_data_order,
     format_output: sha512_format_output,
     initial_state: State {
@@ -400,7 +758,9 @@ pub static SHA512: Algorithm = Algorithm {
     output_len: SHA512_OUTPUT_LEN,
     chaining_len: SHA512_OUTPUT_LEN,
     block_len: SHA512_BLOCK_LEN,
-    len_len: SHA512_LEN_LEN,
+    padding: Padding::MerkleDamgard {
+        len_len: SHA512_LEN_LEN,
+    },
     block_data_order: sha2::sha512_block_data_order,
     format_output: sha512_format_output,
     initial_state: State {
@@ -418,6 +778,37 @@ pub static SHA512: Algorithm = Algorithm {
     id: AlgorithmID::SHA512,
 };
 
+/// SHA-512/224 as specified in [FIPS 180-4].
+///
+/// This is *not* the same as just truncating the output of SHA-512, as
+/// SHA-512/224 has its own initial state distinct from SHA-512's initial
+/// state.
+///
+/// [FIPS 180-4]: http://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf
+pub static SHA512_224: Algorithm = Algorithm {
+    output_len: SHA512_224_OUTPUT_LEN,
+    chaining_len: SHA512_OUTPUT_LEN,
+    block_len: SHA512_BLOCK_LEN,
+    padding: Padding::MerkleDamgard {
+        len_len: SHA512_LEN_LEN,
+    },
+    block_data_order: sha2::sha512_block_data_order,
+    format_output: sha512_format_output,
+    initial_state: State {
+        as64: [
+            Wrapping(0x8c3d37c819544da2),
+            Wrapping(0x73e1996689dcd4d6),
+            Wrapping(0x1dfab7ae32ff9c82),
+            Wrapping(0x679dd514582f9fcf),
+            Wrapping(0x0f6d2b697bd44da8),
+            Wrapping(0x77e36f7304c48942),
+            Wrapping(0x3f9d85a86a1d36c8),
+            Wrapping(0x1112e6ad91d692a1),
+        ],
+    },
+    id: AlgorithmID::SHA512_224,
+};
+
 /// SHA-512/256 as specified in [FIPS 180-4].
 ///
 /// This is *not* the same as just truncating the output of SHA-512, as
@@ -429,7 +820,9 @@ pub static SHA512_256: Algorithm = Algorithm {
     output_len: SHA512_256_OUTPUT_LEN,
     chaining_len: SHA512_OUTPUT_LEN,
     block_len: SHA512_BLOCK_LEN,
-    len_len: SHA512_LEN_LEN,
+    padding: Padding::MerkleDamgard {
+        len_len: SHA512_LEN_LEN,
+    },
     block_data_order: sha2::sha512_block_data_order,
     format_output: sha512_format_output,
     initial_state: State {
@@ -447,19 +840,135 @@ pub static SHA512_256: Algorithm = Algorithm {
     id: AlgorithmID::SHA512_256,
 };
 
+/// SHA3-256 as specified in [FIPS 202].
+///
+/// [FIPS 202]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf
+pub static SHA3_256: Algorithm = Algorithm {
+    output_len: SHA3_256_OUTPUT_LEN,
+    chaining_len: SHA3_256_OUTPUT_LEN,
+    block_len: sha3::SHA3_256_RATE,
+    padding: Padding::Keccak {
+        domain: sha3::SHA3_DOMAIN,
+    },
+    block_data_order: sha3::block_data_order::<{ sha3::SHA3_256_RATE }>,
+    format_output: sha3_format_output,
+    initial_state: State {
+        keccak: [0; sha3::STATE_WORDS],
+    },
+    id: AlgorithmID::SHA3_256,
+};
+
+/// SHA3-384 as specified in [FIPS 202].
+///
+/// [FIPS 202]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf
+pub static SHA3_384: Algorithm = Algorithm {
+    output_len: SHA3_384_OUTPUT_LEN,
+    chaining_len: SHA3_384_OUTPUT_LEN,
+    block_len: sha3::SHA3_384_RATE,
+    padding: Padding::Keccak {
+        domain: sha3::SHA3_DOMAIN,
+    },
+    block_data_order: sha3::block_data_order::<{ sha3::SHA3_384_RATE }>,
+    format_output: sha3_format_output,
+    initial_state: State {
+        keccak: [0; sha3::STATE_WORDS],
+    },
+    id: AlgorithmID::SHA3_384,
+};
+
+/// SHA3-512 as specified in [FIPS 202].
+///
+/// [FIPS 202]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf
+pub static SHA3_512: Algorithm = Algorithm {
+    output_len: SHA3_512_OUTPUT_LEN,
+    chaining_len: SHA3_512_OUTPUT_LEN,
+    block_len: sha3::SHA3_512_RATE,
+    padding: Padding::Keccak {
+        domain: sha3::SHA3_DOMAIN,
+    },
+    block_data_order: sha3::block_data_order::<{ sha3::SHA3_512_RATE }>,
+    format_output: sha3_format_output,
+    initial_state: State {
+        keccak: [0; sha3::STATE_WORDS],
+    },
+    id: AlgorithmID::SHA3_512,
+};
+
+/// BLAKE2b-256, i.e. BLAKE2b with a 256-bit output, as specified in
+/// [RFC 7693].
+///
+/// This is *not* the same as truncating the output of BLAKE2b-512, as the
+/// output length is mixed into BLAKE2b's initial state.
+///
+/// [RFC 7693]: https://tools.ietf.org/html/rfc7693
+pub static BLAKE2B_256: Algorithm = Algorithm {
+    output_len: BLAKE2B_256_OUTPUT_LEN,
+    chaining_len: BLAKE2B_512_OUTPUT_LEN,
+    block_len: blake2::BLAKE2B_BLOCK_LEN,
+    padding: Padding::Blake2b,
+    block_data_order: blake2::blake2b_block_data_order,
+    format_output: blake2::blake2b_format_output,
+    initial_state: State {
+        blake2b: blake2::State::blake2b(BLAKE2B_256_OUTPUT_LEN),
+    },
+    id: AlgorithmID::BLAKE2B_256,
+};
+
+/// BLAKE2b-512 as specified in [RFC 7693].
+///
+/// [RFC 7693]: https://tools.ietf.org/html/rfc7693
+pub static BLAKE2B_512: Algorithm = Algorithm {
+    output_len: BLAKE2B_512_OUTPUT_LEN,
+    chaining_len: BLAKE2B_512_OUTPUT_LEN,
+    block_len: blake2::BLAKE2B_BLOCK_LEN,
+    padding: Padding::Blake2b,
+    block_data_order: blake2::blake2b_block_data_order,
+    format_output: blake2::blake2b_format_output,
+    initial_state: State {
+        blake2b: blake2::State::blake2b(BLAKE2B_512_OUTPUT_LEN),
+    },
+    id: AlgorithmID::BLAKE2B_512,
+};
+
+/// BLAKE2s-256 as specified in [RFC 7693].
+///
+/// [RFC 7693]: https://tools.ietf.org/html/rfc7693
+pub static BLAKE2S_256: Algorithm = Algorithm {
+    output_len: BLAKE2S_256_OUTPUT_LEN,
+    chaining_len: BLAKE2S_256_OUTPUT_LEN,
+    block_len: blake2::BLAKE2S_BLOCK_LEN,
+    padding: Padding::Blake2s,
+    block_data_order: blake2::blake2s_block_data_order,
+    format_output: blake2::blake2s_format_output,
+    initial_state: State {
+        blake2s: blake2::State::blake2s(BLAKE2S_256_OUTPUT_LEN),
+    },
+    id: AlgorithmID::BLAKE2S_256,
+};
+
 #[derive(Clone, Copy)] // XXX: Why do we need to be `Copy`?
 #[repr(C)]
 union State {
     as64: [Wrapping<u64>; sha2::CHAINING_WORDS],
     as32: [Wrapping<u32>; sha2::CHAINING_WORDS],
+    keccak: sha3::State,
+    blake2b: blake2::State<u64>,
+    blake2s: blake2::State<u32>,
 }
 
 #[derive(Clone, Copy)]
 struct Output([u8; MAX_OUTPUT_LEN]);
 
+/// The maximum block length ([`Algorithm::block_len()`]) of the SHA-1 and
+/// SHA-2 algorithms in this module.
+///
+/// The SHA-3 algorithms have larger block lengths, so use
+/// [`Algorithm::block_len()`] to size a buffer for an arbitrary algorithm.
+pub const MAX_BLOCK_LEN: usize = 1024 / 8;
+
 /// The maximum block length ([`Algorithm::block_len()`]) of all the algorithms
 /// in this module.
-pub const MAX_BLOCK_LEN: usize = 1024 / 8;
+pub(crate) const MAX_ANY_BLOCK_LEN: usize = sha3::SHA3_256_RATE;
 
 /// The maximum output length ([`Algorithm::output_len()`]) of all the
 /// algorithms in this module.
@@ -479,6 +988,17 @@ fn sha512_format_output(input: State) -> Output {
     format_output::<_, _, { core::mem::size_of::<u64>() }>(input, u64::to_be_bytes)
 }
 
+fn sha3_format_output(input: State) -> Output {
+    let input = unsafe { input.keccak };
+    let mut output = Output([0; MAX_OUTPUT_LEN]);
+    output
+        .0
+        .chunks_mut(8)
+        .zip(input.iter())
+        .for_each(|(o, i)| o.copy_from_slice(&i.to_le_bytes()));
+    output
+}
+
 #[inline]
 fn format_output<T, F, const N: usize>(input: [Wrapping<T>; sha2::CHAINING_WORDS], f: F) -> Output
 where
@@ -499,6 +1019,9 @@ where
 /// The length of the output of SHA-1, in bytes.
 pub const SHA1_OUTPUT_LEN: usize = sha1::OUTPUT_LEN;
 
+/// The length of the output of SHA-224, in bytes.
+pub const SHA224_OUTPUT_LEN: usize = 224 / 8;
+
 /// The length of the output of SHA-256, in bytes.
 pub const SHA256_OUTPUT_LEN: usize = 256 / 8;
 
@@ -508,9 +1031,30 @@ pub const SHA384_OUTPUT_LEN: usize = 384 / 8;
 /// The length of the output of SHA-512, in bytes.
 pub const SHA512_OUTPUT_LEN: usize = 512 / 8;
 
+/// The length of the output of SHA-512/224, in bytes.
+pub const SHA512_224_OUTPUT_LEN: usize = 224 / 8;
+
 /// The length of the output of SHA-512/256, in bytes.
 pub const SHA512_256_OUTPUT_LEN: usize = 256 / 8;
 
+/// The length of the output of SHA3-256, in bytes.
+pub const SHA3_256_OUTPUT_LEN: usize = 256 / 8;
+
+/// The length of the output of SHA3-384, in bytes.
+pub const SHA3_384_OUTPUT_LEN: usize = 384 / 8;
+
+/// The length of the output of SHA3-512, in bytes.
+pub const SHA3_512_OUTPUT_LEN: usize = 512 / 8;
+
+/// The length of the output of BLAKE2b-256, in bytes.
+pub const BLAKE2B_256_OUTPUT_LEN: usize = 256 / 8;
+
+/// The length of the output of BLAKE2b-512, in bytes.
+pub const BLAKE2B_512_OUTPUT_LEN: usize = 512 / 8;
+
+/// The length of the output of BLAKE2s-256, in bytes.
+pub const BLAKE2S_256_OUTPUT_LEN: usize = 256 / 8;
+
 /// The length of a block for SHA-512-based algorithms, in bytes.
 const SHA512_BLOCK_LEN: usize = 1024 / 8;
 
@@ -584,14 +1128,17 @@ mod tests {
                     completed_data_blocks: max_blocks - 1,
                     algorithm: alg,
                 },
-                pending: [0u8; digest::MAX_BLOCK_LEN],
+                pending: [0u8; digest::MAX_ANY_BLOCK_LEN],
                 num_pending: 0,
             }
         }
 
         max_input_tests!(SHA1_FOR_LEGACY_USE_ONLY);
+        max_input_tests!(SHA224);
         max_input_tests!(SHA256);
         max_input_tests!(SHA384);
         max_input_tests!(SHA512);
+        max_input_tests!(BLAKE2B_512);
+        max_input_tests!(BLAKE2S_256);
     }
 }
@@ -0,0 +1,510 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Multi-buffer hashing: computing the digests of many independent messages
+//! at once, with the messages interleaved across SIMD lanes.
+//!
+//! The SHA-256 compression function in `sha2` is generic over the word type,
+//! so it is reused here with a word type that holds the corresponding words
+//! of several messages, one per lane, and that implements every operation
+//! lane-wise with SIMD instructions.
+
+use super::{
+    sha2::{self, Sha2, Word},
+    SHA256, SHA256_OUTPUT_LEN,
+};
+use crate::{error, polyfill};
+use core::num::Wrapping;
+
+const BLOCK_LEN: usize = 512 / 8;
+
+/// Computes the SHA-256 digests of `messages`, writing the digest of
+/// `messages[i]` to `digests[i]`.
+///
+/// All the messages must have the same length, and `digests` must be exactly
+/// as long as `messages`. When there are many short messages, e.g. when
+/// building a Merkle tree, this is usually faster than hashing each message
+/// separately with `digest::digest`, especially on CPUs that don't have
+/// SHA-256 instructions.
+///
+/// # Examples
+///
+/// ```
+/// use ring::digest;
+///
+/// let messages: [&[u8]; 3] = [b"abc", b"def", b"ghi"];
+/// let mut digests = [[0u8; digest::SHA256_OUTPUT_LEN]; 3];
+/// digest::sha256_batch(&messages, &mut digests)?;
+///
+/// for (message, digest) in messages.iter().zip(digests.iter()) {
+///     assert_eq!(digest, digest::digest(&digest::SHA256, message).as_ref());
+/// }
+/// # Ok::<(), ring::error::Unspecified>(())
+/// ```
+pub fn sha256_batch(
+    messages: &[&[u8]],
+    digests: &mut [[u8; SHA256_OUTPUT_LEN]],
+) -> Result<(), error::Unspecified> {
+    if messages.len() != digests.len() {
+        return Err(error::Unspecified);
+    }
+    let len = match messages.first() {
+        Some(first) => first.len(),
+        None => return Ok(()),
+    };
+    if messages.iter().any(|message| message.len() != len) {
+        return Err(error::Unspecified);
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        if x86_64::AVX2.available(crate::cpu::features()) {
+            return unsafe { x86_64::sha256_batch_avx2(messages, digests, len) };
+        }
+        sha256_batch_lanes::<x86_64::Sse2, 4>(messages, digests, len)
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        sha256_batch_lanes::<aarch64::Neon, 4>(messages, digests, len)
+    }
+
+    #[cfg(not(any(target_arch = "aarch64", target_arch = "x86_64")))]
+    {
+        sha256_batch_lanes::<Portable, 4>(messages, digests, len)
+    }
+}
+
+/// `N` SHA-256 words, one for each of `N` independent messages.
+trait Lanes<const N: usize>: Sha2<InputBytes = [[u8; 4]; N]> {
+    fn splat(word: u32) -> Self;
+    fn to_array(self) -> [u32; N];
+}
+
+#[inline(always)]
+fn sha256_batch_lanes<L: Lanes<N>, const N: usize>(
+    messages: &[&[u8]],
+    digests: &mut [[u8; SHA256_OUTPUT_LEN]],
+    len: usize,
+) -> Result<(), error::Unspecified> {
+    // FIPS 180-4 Section 5.1.1: the message is followed by a 0x80 byte and
+    // its length in bits as a 64-bit big-endian value.
+    let len_bits = polyfill::u64_from_usize(len)
+        .checked_mul(8)
+        .ok_or(error::Unspecified)?;
+    let num_pending = len % BLOCK_LEN;
+    let num_padding_blocks = if num_pending + 1 + 8 > BLOCK_LEN {
+        2
+    } else {
+        1
+    };
+
+    let initial_state = unsafe { SHA256.initial_state.as32 };
+
+    for (messages, digests) in messages.chunks(N).zip(digests.chunks_mut(N)) {
+        // The unused lanes of the last group hash the first message again;
+        // their results are discarded.
+        let message = |lane: usize| *messages.get(lane).unwrap_or(&messages[0]);
+
+        let mut state = initial_state.map(|Wrapping(w)| L::splat(w));
+
+        for offset in (0..(len - num_pending)).step_by(BLOCK_LEN) {
+            let block = interleave::<N>(|lane| &message(lane)[offset..][..BLOCK_LEN]);
+            state = sha2::block_data_order(state, block.as_ptr().cast(), 1);
+        }
+
+        let mut padded = [[0u8; 2 * BLOCK_LEN]; N];
+        for (lane, padded) in padded.iter_mut().enumerate() {
+            let padded = &mut padded[..(num_padding_blocks * BLOCK_LEN)];
+            padded[..num_pending].copy_from_slice(&message(lane)[(len - num_pending)..]);
+            padded[num_pending] = 0x80;
+            let padded_len = padded.len();
+            padded[(padded_len - 8)..].copy_from_slice(&len_bits.to_be_bytes());
+        }
+        for offset in (0..(num_padding_blocks * BLOCK_LEN)).step_by(BLOCK_LEN) {
+            let block = interleave::<N>(|lane| &padded[lane][offset..][..BLOCK_LEN]);
+            state = sha2::block_data_order(state, block.as_ptr().cast(), 1);
+        }
+
+        let state = state.map(L::to_array);
+        for (lane, digest) in digests.iter_mut().enumerate() {
+            digest
+                .chunks_mut(4)
+                .zip(state.iter())
+                .for_each(|(out, word)| out.copy_from_slice(&word[lane].to_be_bytes()));
+        }
+    }
+
+    Ok(())
+}
+
+// Transposes a block of each of the `N` messages so that each word of the
+// result holds the corresponding words of every message.
+#[inline(always)]
+fn interleave<'a, const N: usize>(block: impl Fn(usize) -> &'a [u8]) -> [[[u8; 4]; N]; 16] {
+    let mut interleaved = [[[0u8; 4]; N]; 16];
+    for lane in 0..N {
+        let block = block(lane);
+        for (word, bytes) in interleaved.iter_mut().zip(block.chunks_exact(4)) {
+            word[lane].copy_from_slice(bytes);
+        }
+    }
+    interleaved
+}
+
+/// Implements `Lanes` for `$name`, a wrapper around `$raw` that holds `$n`
+/// words, given lane-wise implementations of the primitive operations.
+macro_rules! impl_lanes {
+    (
+        $name:ident, $raw:ty, $n:expr,
+        add: $add:path,
+        and: $and:path,
+        or: $or:path,
+        xor: $xor:path,
+        shr: $shr:path,
+        shl: $shl:path $(,)?
+    ) => {
+        impl Lanes<$n> for $name {
+            #[inline(always)]
+            fn splat(word: u32) -> Self {
+                Self(unsafe { core::mem::transmute::<[u32; $n], $raw>([word; $n]) })
+            }
+
+            #[inline(always)]
+            fn to_array(self) -> [u32; $n] {
+                unsafe { core::mem::transmute::<$raw, [u32; $n]>(self.0) }
+            }
+        }
+
+        impl core::ops::Add for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn add(self, rhs: Self) -> Self {
+                Self($add(self.0, rhs.0))
+            }
+        }
+
+        impl core::ops::AddAssign for $name {
+            #[inline(always)]
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl core::ops::BitAnd for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn bitand(self, rhs: Self) -> Self {
+                Self($and(self.0, rhs.0))
+            }
+        }
+
+        impl core::ops::BitOr for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn bitor(self, rhs: Self) -> Self {
+                Self($or(self.0, rhs.0))
+            }
+        }
+
+        impl core::ops::BitXor for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn bitxor(self, rhs: Self) -> Self {
+                Self($xor(self.0, rhs.0))
+            }
+        }
+
+        impl core::ops::Not for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn not(self) -> Self {
+                self ^ Self::splat(u32::MAX)
+            }
+        }
+
+        impl core::ops::Shr<usize> for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn shr(self, count: usize) -> Self {
+                #[allow(clippy::cast_possible_truncation)]
+                Self($shr(self.0, count as u32))
+            }
+        }
+
+        impl Word for $name {
+            const ZERO: Self = Self(unsafe { core::mem::transmute::<[u32; $n], $raw>([0; $n]) });
+
+            type InputBytes = [[u8; 4]; $n];
+
+            #[inline(always)]
+            fn from_be_bytes(input: Self::InputBytes) -> Self {
+                let words = input.map(u32::from_be_bytes);
+                Self(unsafe { core::mem::transmute::<[u32; $n], $raw>(words) })
+            }
+
+            #[inline(always)]
+            fn rotr(self, count: u32) -> Self {
+                Self($or($shr(self.0, count), $shl(self.0, 32 - count)))
+            }
+        }
+
+        impl Sha2 for $name {
+            const BIG_SIGMA_0: (u32, u32, u32) = <core::num::Wrapping<u32> as Sha2>::BIG_SIGMA_0;
+            const BIG_SIGMA_1: (u32, u32, u32) = <core::num::Wrapping<u32> as Sha2>::BIG_SIGMA_1;
+            const SMALL_SIGMA_0: (u32, u32, usize) =
+                <core::num::Wrapping<u32> as Sha2>::SMALL_SIGMA_0;
+            const SMALL_SIGMA_1: (u32, u32, usize) =
+                <core::num::Wrapping<u32> as Sha2>::SMALL_SIGMA_1;
+
+            // FIPS 180-4 4.2.2, with each constant in every lane.
+            const K: &'static [Self] = &{
+                let k = <core::num::Wrapping<u32> as Sha2>::K;
+                let mut splatted = [Self::ZERO; 64];
+                let mut i = 0;
+                while i < splatted.len() {
+                    let core::num::Wrapping(k) = k[i];
+                    splatted[i] = Self(unsafe { core::mem::transmute::<[u32; $n], $raw>([k; $n]) });
+                    i += 1;
+                }
+                splatted
+            };
+        }
+    };
+}
+
+#[cfg(not(any(target_arch = "aarch64", target_arch = "x86_64")))]
+use portable::Portable;
+
+#[cfg(not(any(target_arch = "aarch64", target_arch = "x86_64")))]
+mod portable {
+    use super::{Lanes, Sha2, Word};
+
+    /// Four words, operated on one at a time.
+    #[derive(Clone, Copy)]
+    pub(super) struct Portable([u32; 4]);
+
+    macro_rules! lane_wise {
+        ( $name:ident, $f:expr ) => {
+            #[inline(always)]
+            fn $name(a: [u32; 4], b: [u32; 4]) -> [u32; 4] {
+                [
+                    $f(a[0], b[0]),
+                    $f(a[1], b[1]),
+                    $f(a[2], b[2]),
+                    $f(a[3], b[3]),
+                ]
+            }
+        };
+    }
+
+    lane_wise!(add, u32::wrapping_add);
+    lane_wise!(and, |a, b| a & b);
+    lane_wise!(or, |a, b| a | b);
+    lane_wise!(xor, |a, b| a ^ b);
+
+    #[inline(always)]
+    fn shr(a: [u32; 4], count: u32) -> [u32; 4] {
+        a.map(|a| a >> count)
+    }
+
+    #[inline(always)]
+    fn shl(a: [u32; 4], count: u32) -> [u32; 4] {
+        a.map(|a| a << count)
+    }
+
+    impl_lanes!(Portable, [u32; 4], 4,
+        add: add, and: and, or: or, xor: xor, shr: shr, shl: shl);
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86_64 {
+    use super::{sha256_batch_lanes, Lanes, Sha2, Word, SHA256_OUTPUT_LEN};
+    use crate::error;
+    use core::arch::x86_64::*;
+
+    pub(super) use crate::cpu::intel::AVX2;
+
+    /// Four words in an SSE2 register. SSE2 is always available on x86_64.
+    #[derive(Clone, Copy)]
+    pub(super) struct Sse2(__m128i);
+
+    #[inline(always)]
+    fn sse2_add(a: __m128i, b: __m128i) -> __m128i {
+        unsafe { _mm_add_epi32(a, b) }
+    }
+
+    #[inline(always)]
+    fn sse2_and(a: __m128i, b: __m128i) -> __m128i {
+        unsafe { _mm_and_si128(a, b) }
+    }
+
+    #[inline(always)]
+    fn sse2_or(a: __m128i, b: __m128i) -> __m128i {
+        unsafe { _mm_or_si128(a, b) }
+    }
+
+    #[inline(always)]
+    fn sse2_xor(a: __m128i, b: __m128i) -> __m128i {
+        unsafe { _mm_xor_si128(a, b) }
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[inline(always)]
+    fn sse2_shr(a: __m128i, count: u32) -> __m128i {
+        unsafe { _mm_srl_epi32(a, _mm_cvtsi32_si128(count as i32)) }
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[inline(always)]
+    fn sse2_shl(a: __m128i, count: u32) -> __m128i {
+        unsafe { _mm_sll_epi32(a, _mm_cvtsi32_si128(count as i32)) }
+    }
+
+    impl_lanes!(Sse2, __m128i, 4,
+        add: sse2_add, and: sse2_and, or: sse2_or, xor: sse2_xor,
+        shr: sse2_shr, shl: sse2_shl);
+
+    /// Eight words in an AVX2 register.
+    ///
+    /// The operations on `Avx2` must only be used within functions that
+    /// enable the "avx2" target feature, into which they are always inlined,
+    /// and which must only be called when AVX2 is available.
+    #[derive(Clone, Copy)]
+    struct Avx2(__m256i);
+
+    #[inline(always)]
+    fn avx2_add(a: __m256i, b: __m256i) -> __m256i {
+        unsafe { _mm256_add_epi32(a, b) }
+    }
+
+    #[inline(always)]
+    fn avx2_and(a: __m256i, b: __m256i) -> __m256i {
+        unsafe { _mm256_and_si256(a, b) }
+    }
+
+    #[inline(always)]
+    fn avx2_or(a: __m256i, b: __m256i) -> __m256i {
+        unsafe { _mm256_or_si256(a, b) }
+    }
+
+    #[inline(always)]
+    fn avx2_xor(a: __m256i, b: __m256i) -> __m256i {
+        unsafe { _mm256_xor_si256(a, b) }
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[inline(always)]
+    fn avx2_shr(a: __m256i, count: u32) -> __m256i {
+        unsafe { _mm256_srl_epi32(a, _mm_cvtsi32_si128(count as i32)) }
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[inline(always)]
+    fn avx2_shl(a: __m256i, count: u32) -> __m256i {
+        unsafe { _mm256_sll_epi32(a, _mm_cvtsi32_si128(count as i32)) }
+    }
+
+    impl_lanes!(Avx2, __m256i, 8,
+        add: avx2_add, and: avx2_and, or: avx2_or, xor: avx2_xor,
+        shr: avx2_shr, shl: avx2_shl);
+
+    /// # Safety
+    ///
+    /// AVX2 must be available.
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn sha256_batch_avx2(
+        messages: &[&[u8]],
+        digests: &mut [[u8; SHA256_OUTPUT_LEN]],
+        len: usize,
+    ) -> Result<(), error::Unspecified> {
+        sha256_batch_lanes::<Avx2, 8>(messages, digests, len)
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64 {
+    use super::{Lanes, Sha2, Word};
+    use core::arch::aarch64::*;
+
+    /// Four words in a NEON register. NEON is always available on AArch64.
+    #[derive(Clone, Copy)]
+    pub(super) struct Neon(uint32x4_t);
+
+    #[inline(always)]
+    fn neon_add(a: uint32x4_t, b: uint32x4_t) -> uint32x4_t {
+        unsafe { vaddq_u32(a, b) }
+    }
+
+    #[inline(always)]
+    fn neon_and(a: uint32x4_t, b: uint32x4_t) -> uint32x4_t {
+        unsafe { vandq_u32(a, b) }
+    }
+
+    #[inline(always)]
+    fn neon_or(a: uint32x4_t, b: uint32x4_t) -> uint32x4_t {
+        unsafe { vorrq_u32(a, b) }
+    }
+
+    #[inline(always)]
+    fn neon_xor(a: uint32x4_t, b: uint32x4_t) -> uint32x4_t {
+        unsafe { veorq_u32(a, b) }
+    }
+
+    // `vshlq_u32` shifts right when the count is negative.
+    #[allow(clippy::cast_possible_wrap)]
+    #[inline(always)]
+    fn neon_shr(a: uint32x4_t, count: u32) -> uint32x4_t {
+        unsafe { vshlq_u32(a, vdupq_n_s32(-(count as i32))) }
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[inline(always)]
+    fn neon_shl(a: uint32x4_t, count: u32) -> uint32x4_t {
+        unsafe { vshlq_u32(a, vdupq_n_s32(count as i32)) }
+    }
+
+    impl_lanes!(Neon, uint32x4_t, 4,
+        add: neon_add, and: neon_and, or: neon_or, xor: neon_xor,
+        shr: neon_shr, shl: neon_shl);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest;
+
+    // `sha256_batch` doesn't use SSE2 when AVX2 is available, so test it
+    // directly.
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn test_sha256_batch_sse2() {
+        let data = [0x5au8; 3 * BLOCK_LEN];
+        for len in [0, 55, 56, BLOCK_LEN, data.len()] {
+            let messages = [&data[..len]; 5];
+            let mut digests = [[0u8; SHA256_OUTPUT_LEN]; 5];
+            sha256_batch_lanes::<x86_64::Sse2, 4>(&messages, &mut digests, len).unwrap();
+            let expected = digest::digest(&digest::SHA256, &data[..len]);
+            for actual in digests.iter() {
+                assert_eq!(expected.as_ref(), &actual[..]);
+            }
+        }
+    }
+}
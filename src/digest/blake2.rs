@@ -0,0 +1,317 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! BLAKE2b and BLAKE2s, as specified in [RFC 7693].
+//!
+//! Unlike the Merkle-Damgård hash functions, BLAKE2 marks the last block when
+//! compressing it, so `digest::Context` always holds back the last block of
+//! input, even if it is a full block, until `finish` is called.
+//!
+//! [RFC 7693]: https://tools.ietf.org/html/rfc7693
+
+use crate::c;
+use core::ops::{BitXor, Not};
+
+pub(super) const BLAKE2B_BLOCK_LEN: usize = 1024 / 8;
+pub(super) const BLAKE2S_BLOCK_LEN: usize = 512 / 8;
+
+/// The maximum length of a BLAKE2b key.
+pub(super) const BLAKE2B_MAX_KEY_LEN: usize = 512 / 8;
+
+/// The maximum length of a BLAKE2s key.
+pub(super) const BLAKE2S_MAX_KEY_LEN: usize = 256 / 8;
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub(super) struct State<W> {
+    h: [W; 8],
+    // The number of bytes compressed so far, as a double-word counter.
+    t: [W; 2],
+}
+
+// RFC 7693 Section 3.3: the parameter block for sequential hashing, with no
+// key, salt, or personalization, is mixed into the first word of the IV. Its
+// first word is the digest length, with a fanout and depth of 1.
+impl State<u64> {
+    #[allow(clippy::cast_possible_truncation)]
+    pub(super) const fn blake2b(output_len: usize) -> Self {
+        let iv = BLAKE2B_IV;
+        Self {
+            h: [
+                iv[0] ^ 0x0101_0000 ^ (output_len as u64),
+                iv[1],
+                iv[2],
+                iv[3],
+                iv[4],
+                iv[5],
+                iv[6],
+                iv[7],
+            ],
+            t: [0, 0],
+        }
+    }
+}
+
+impl State<u32> {
+    #[allow(clippy::cast_possible_truncation)]
+    pub(super) const fn blake2s(output_len: usize) -> Self {
+        let iv = BLAKE2S_IV;
+        Self {
+            h: [
+                iv[0] ^ 0x0101_0000 ^ (output_len as u32),
+                iv[1],
+                iv[2],
+                iv[3],
+                iv[4],
+                iv[5],
+                iv[6],
+                iv[7],
+            ],
+            t: [0, 0],
+        }
+    }
+}
+
+impl<W: Word> State<W> {
+    /// Adjusts the initial state for a key of `key_len` bytes, which is
+    /// recorded in the second byte of the parameter block.
+    pub(super) fn set_key_len(&mut self, key_len: usize) {
+        self.h[0] = self.h[0] ^ W::from_usize(key_len << 8);
+    }
+
+    /// The length of the state when written by `write`.
+    pub(super) const SERIALIZED_LEN: usize = 10 * W::LEN;
+
+    /// Writes the chaining value followed by the byte counter, as
+    /// little-endian words.
+    pub(super) fn write(&self, out: &mut [u8]) {
+        out.chunks_mut(W::LEN)
+            .zip(self.h.iter().chain(self.t.iter()))
+            .for_each(|(out, w)| w.write_le(out));
+    }
+
+    /// Reads a state written by `write`.
+    pub(super) fn read(&mut self, bytes: &[u8]) {
+        self.h
+            .iter_mut()
+            .chain(self.t.iter_mut())
+            .zip(bytes.chunks_exact(W::LEN))
+            .for_each(|(w, bytes)| *w = W::from_le(bytes));
+    }
+
+    fn update(&mut self, block: &[u8], len: usize, last: bool) {
+        // RFC 7693 Section 3.3: `t` is the total number of bytes processed,
+        // including those in this block but excluding its padding.
+        let len = W::from_usize(len);
+        self.t[0] = self.t[0].wrapping_add(len);
+        if self.t[0] < len {
+            self.t[1] = self.t[1].wrapping_add(W::from_usize(1));
+        }
+        compress(&mut self.h, block, self.t, last);
+    }
+}
+
+pub(super) fn blake2b_format_output(input: super::State) -> super::Output {
+    format_output(unsafe { &input.blake2b })
+}
+
+pub(super) fn blake2s_format_output(input: super::State) -> super::Output {
+    format_output(unsafe { &input.blake2s })
+}
+
+fn format_output<W: Word>(state: &State<W>) -> super::Output {
+    let mut output = super::Output([0; super::MAX_OUTPUT_LEN]);
+    output
+        .0
+        .chunks_mut(W::LEN)
+        .zip(state.h.iter())
+        .for_each(|(o, h)| h.write_le(o));
+    output
+}
+
+pub(super) extern "C" fn blake2b_block_data_order(
+    state: &mut super::State,
+    data: *const u8,
+    num: c::size_t,
+) {
+    let state = unsafe { &mut state.blake2b };
+    block_data_order(state, data, num, BLAKE2B_BLOCK_LEN);
+}
+
+pub(super) extern "C" fn blake2s_block_data_order(
+    state: &mut super::State,
+    data: *const u8,
+    num: c::size_t,
+) {
+    let state = unsafe { &mut state.blake2s };
+    block_data_order(state, data, num, BLAKE2S_BLOCK_LEN);
+}
+
+fn block_data_order<W: Word>(state: &mut State<W>, data: *const u8, num: usize, block_len: usize) {
+    let data = unsafe { core::slice::from_raw_parts(data, num * block_len) };
+    data.chunks_exact(block_len)
+        .for_each(|block| state.update(block, block_len, false));
+}
+
+/// Pads and compresses the last block, `pending[..num_pending]`. `pending`
+/// must be exactly one block long.
+pub(super) fn finish<W: Word>(state: &mut State<W>, pending: &mut [u8], num_pending: usize) {
+    pending[num_pending..].fill(0);
+    state.update(pending, num_pending, true);
+}
+
+// RFC 7693 Section 3.2.
+fn compress<W: Word>(h: &mut [W; 8], block: &[u8], t: [W; 2], last: bool) {
+    let mut m = [W::ZERO; 16];
+    m.iter_mut()
+        .zip(block.chunks_exact(W::LEN))
+        .for_each(|(m, bytes)| *m = W::from_le(bytes));
+
+    let iv = W::IV;
+    let mut v = [
+        h[0],
+        h[1],
+        h[2],
+        h[3],
+        h[4],
+        h[5],
+        h[6],
+        h[7],
+        iv[0],
+        iv[1],
+        iv[2],
+        iv[3],
+        iv[4] ^ t[0],
+        iv[5] ^ t[1],
+        if last { !iv[6] } else { iv[6] },
+        iv[7],
+    ];
+
+    for s in SIGMA.iter().cycle().take(W::ROUNDS) {
+        g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+        g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+        g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+        g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+        g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+        g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+        g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+        g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+
+    for i in 0..8 {
+        h[i] = h[i] ^ v[i] ^ v[i + 8];
+    }
+}
+
+// RFC 7693 Section 3.1.
+#[inline(always)]
+fn g<W: Word>(v: &mut [W; 16], a: usize, b: usize, c: usize, d: usize, x: W, y: W) {
+    let [r1, r2, r3, r4] = W::ROTATIONS;
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(r1);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(r2);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(r3);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(r4);
+}
+
+// RFC 7693 Section 2.7.
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+pub(super) trait Word:
+    Copy + PartialOrd + BitXor<Output = Self> + Not<Output = Self>
+{
+    const ZERO: Self;
+    const LEN: usize;
+    const IV: [Self; 8];
+    const ROUNDS: usize;
+    const ROTATIONS: [u32; 4];
+
+    fn from_usize(value: usize) -> Self;
+    fn from_le(bytes: &[u8]) -> Self;
+    fn write_le(&self, out: &mut [u8]);
+    fn wrapping_add(self, other: Self) -> Self;
+    fn rotate_right(self, n: u32) -> Self;
+}
+
+macro_rules! impl_word {
+    ( $W:ty, $rounds:expr, $rotations:expr, $iv:expr ) => {
+        impl Word for $W {
+            const ZERO: Self = 0;
+            const LEN: usize = core::mem::size_of::<Self>();
+            const IV: [Self; 8] = $iv;
+            const ROUNDS: usize = $rounds;
+            const ROTATIONS: [u32; 4] = $rotations;
+
+            #[allow(clippy::cast_possible_truncation)]
+            #[inline(always)]
+            fn from_usize(value: usize) -> Self {
+                value as Self
+            }
+
+            #[inline(always)]
+            fn from_le(bytes: &[u8]) -> Self {
+                Self::from_le_bytes(bytes.try_into().unwrap())
+            }
+
+            #[inline(always)]
+            fn write_le(&self, out: &mut [u8]) {
+                out.copy_from_slice(&self.to_le_bytes());
+            }
+
+            #[inline(always)]
+            fn wrapping_add(self, other: Self) -> Self {
+                <$W>::wrapping_add(self, other)
+            }
+
+            #[inline(always)]
+            fn rotate_right(self, n: u32) -> Self {
+                <$W>::rotate_right(self, n)
+            }
+        }
+    };
+}
+
+impl_word!(u64, 12, [32, 24, 16, 63], BLAKE2B_IV);
+impl_word!(u32, 10, [16, 12, 8, 7], BLAKE2S_IV);
+
+// RFC 7693 Section 2.6. The BLAKE2b IV is the same as SHA-512's initial state
+// and the BLAKE2s IV is the same as SHA-256's.
+const BLAKE2B_IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+const BLAKE2S_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
@@ -38,12 +38,10 @@ pub(super) extern "C" fn sha512_block_data_order(
     *state = block_data_order(*state, data, num)
 }
 
-#[cfg_attr(
-    any(target_arch = "aarch64", target_arch = "arm", target_arch = "x86_64"),
-    allow(dead_code)
-)]
-#[inline]
-fn block_data_order<S: Sha2>(
+// This is always inlined so that, when it is used by the multi-buffer
+// implementation, it is compiled with the target features of the caller.
+#[inline(always)]
+pub(super) fn block_data_order<S: Sha2>(
     mut H: [S; CHAINING_WORDS],
     M: *const u8,
     num: c::size_t,
@@ -158,7 +156,7 @@ pub(super) trait Word:
 }
 
 /// A SHA-2 input word.
-trait Sha2: Word + BitXor<Output = Self> + Shr<usize, Output = Self> {
+pub(super) trait Sha2: Word + BitXor<Output = Self> + Shr<usize, Output = Self> {
     const BIG_SIGMA_0: (u32, u32, u32);
     const BIG_SIGMA_1: (u32, u32, u32);
     const SMALL_SIGMA_0: (u32, u32, usize);
@@ -0,0 +1,339 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! The Keccak sponge construction, as used by SHA-3 and SHAKE in [FIPS 202].
+//!
+//! [FIPS 202]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf
+
+use crate::c;
+
+/// The number of 64-bit lanes in the Keccak-f[1600] state.
+pub(super) const STATE_WORDS: usize = 25;
+
+pub(super) type State = [u64; STATE_WORDS];
+
+pub(super) const SHA3_256_RATE: usize = (1600 - 2 * 256) / 8;
+pub(super) const SHA3_384_RATE: usize = (1600 - 2 * 384) / 8;
+pub(super) const SHA3_512_RATE: usize = (1600 - 2 * 512) / 8;
+const SHAKE128_RATE: usize = (1600 - 2 * 128) / 8;
+const SHAKE256_RATE: usize = (1600 - 2 * 256) / 8;
+
+const MAX_RATE: usize = SHAKE128_RATE;
+
+// FIPS 202 Section 6.1: SHA-3 appends the bits 01 to the message and SHAKE
+// appends 1111, before the first bit of the `pad10*1` padding. The bytes
+// below are those suffixes followed by the first padding bit, in Keccak's
+// little-endian bit order.
+pub(super) const SHA3_DOMAIN: u8 = 0x06;
+const SHAKE_DOMAIN: u8 = 0x1f;
+
+pub(super) extern "C" fn block_data_order<const RATE: usize>(
+    state: &mut super::State,
+    data: *const u8,
+    num: c::size_t,
+) {
+    let state = unsafe { &mut state.keccak };
+    let data = unsafe { core::slice::from_raw_parts(data, num * RATE) };
+    data.chunks_exact(RATE)
+        .for_each(|block| absorb_block(state, block));
+}
+
+/// Pads the final, partial, block `pending[..num_pending]` according to FIPS
+/// 202 Section 5.1 and absorbs it. `pending` must be exactly one block long.
+pub(super) fn absorb_final_block(
+    state: &mut State,
+    pending: &mut [u8],
+    num_pending: usize,
+    domain: u8,
+) {
+    debug_assert!(num_pending < pending.len());
+    pending[num_pending] = domain;
+    pending[(num_pending + 1)..].fill(0);
+    *pending.last_mut().unwrap() |= 0x80;
+    absorb_block(state, pending);
+}
+
+fn absorb_block(state: &mut State, block: &[u8]) {
+    state
+        .iter_mut()
+        .zip(block.chunks_exact(8))
+        .for_each(|(lane, bytes)| *lane ^= u64::from_le_bytes(bytes.try_into().unwrap()));
+    keccak_f(state);
+}
+
+// FIPS 202 Section 3.3, Keccak-p[1600, 24].
+#[allow(clippy::needless_range_loop)]
+fn keccak_f(a: &mut State) {
+    for &rc in ROUND_CONSTANTS.iter() {
+        // θ
+        let mut c = [0u64; 5];
+        for x in 0..5 {
+            c[x] = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[(5 * y) + x] ^= d;
+            }
+        }
+
+        // ρ and π
+        let mut last = a[1];
+        for (&pi, &rho) in PI.iter().zip(RHO.iter()) {
+            let tmp = a[pi];
+            a[pi] = last.rotate_left(rho);
+            last = tmp;
+        }
+
+        // χ
+        for y in 0..5 {
+            let row = [
+                a[5 * y],
+                a[5 * y + 1],
+                a[5 * y + 2],
+                a[5 * y + 3],
+                a[5 * y + 4],
+            ];
+            for x in 0..5 {
+                a[(5 * y) + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+
+        // ι
+        a[0] ^= rc;
+    }
+}
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+// The rotation offsets of ρ, in the order in which π visits the lanes.
+const RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+// The lane visited at each step of π, starting from lane 1.
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// An extendable-output function (XOF) algorithm.
+pub struct XofAlgorithm {
+    rate: usize,
+    id: XofAlgorithmID,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum XofAlgorithmID {
+    SHAKE128,
+    SHAKE256,
+}
+
+impl PartialEq for XofAlgorithm {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for XofAlgorithm {}
+
+derive_debug_via_id!(XofAlgorithm);
+
+impl XofAlgorithm {
+    /// The internal block length, i.e. the rate of the sponge.
+    pub fn block_len(&self) -> usize {
+        self.rate
+    }
+}
+
+/// SHAKE128 as specified in [FIPS 202].
+///
+/// [FIPS 202]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf
+pub static SHAKE128: XofAlgorithm = XofAlgorithm {
+    rate: SHAKE128_RATE,
+    id: XofAlgorithmID::SHAKE128,
+};
+
+/// SHAKE256 as specified in [FIPS 202].
+///
+/// [FIPS 202]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf
+pub static SHAKE256: XofAlgorithm = XofAlgorithm {
+    rate: SHAKE256_RATE,
+    id: XofAlgorithmID::SHAKE256,
+};
+
+/// A context for absorbing the input of an extendable-output function.
+///
+/// # Examples
+///
+/// ```
+/// use ring::digest;
+///
+/// let mut ctx = digest::XofContext::new(&digest::SHAKE128);
+/// ctx.update(b"hello, ");
+/// ctx.update(b"world");
+/// let mut reader = ctx.finalize();
+///
+/// let mut output = [0u8; 100];
+/// reader.squeeze(&mut output[..10]);
+/// reader.squeeze(&mut output[10..]);
+/// ```
+#[derive(Clone)]
+pub struct XofContext {
+    state: State,
+    pending: [u8; MAX_RATE],
+    num_pending: usize,
+    algorithm: &'static XofAlgorithm,
+}
+
+impl XofContext {
+    /// Constructs a new context.
+    pub fn new(algorithm: &'static XofAlgorithm) -> Self {
+        Self {
+            state: [0; STATE_WORDS],
+            pending: [0; MAX_RATE],
+            num_pending: 0,
+            algorithm,
+        }
+    }
+
+    /// Updates the state with all the data in `data`.
+    pub fn update(&mut self, mut data: &[u8]) {
+        let rate = self.algorithm.rate;
+        while !data.is_empty() {
+            let to_copy = core::cmp::min(rate - self.num_pending, data.len());
+            let (to_copy, rest) = data.split_at(to_copy);
+            self.pending[self.num_pending..][..to_copy.len()].copy_from_slice(to_copy);
+            self.num_pending += to_copy.len();
+            data = rest;
+            if self.num_pending == rate {
+                absorb_block(&mut self.state, &self.pending[..rate]);
+                self.num_pending = 0;
+            }
+        }
+    }
+
+    /// Finishes absorbing the input and returns a reader for the output.
+    ///
+    /// `finalize` consumes the context so that no more input can be absorbed.
+    pub fn finalize(mut self) -> XofReader {
+        let rate = self.algorithm.rate;
+        absorb_final_block(
+            &mut self.state,
+            &mut self.pending[..rate],
+            self.num_pending,
+            SHAKE_DOMAIN,
+        );
+        XofReader {
+            state: self.state,
+            available: rate,
+            algorithm: self.algorithm,
+        }
+    }
+
+    /// The algorithm that this context is using.
+    #[inline(always)]
+    pub fn algorithm(&self) -> &'static XofAlgorithm {
+        self.algorithm
+    }
+}
+
+impl core::fmt::Debug for XofContext {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("XofContext")
+            .field("algorithm", &self.algorithm)
+            .finish()
+    }
+}
+
+/// Reads the output of an extendable-output function.
+pub struct XofReader {
+    state: State,
+    // The number of bytes of the current output block that haven't been
+    // returned yet.
+    available: usize,
+    algorithm: &'static XofAlgorithm,
+}
+
+impl XofReader {
+    /// Fills `out` with the next `out.len()` bytes of output.
+    ///
+    /// The output is the same regardless of how it is split across calls.
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        let rate = self.algorithm.rate;
+        for b in out {
+            if self.available == 0 {
+                keccak_f(&mut self.state);
+                self.available = rate;
+            }
+            let offset = rate - self.available;
+            *b = self.state[offset / 8].to_le_bytes()[offset % 8];
+            self.available -= 1;
+        }
+    }
+
+    /// The algorithm that produced this output.
+    #[inline(always)]
+    pub fn algorithm(&self) -> &'static XofAlgorithm {
+        self.algorithm
+    }
+}
+
+impl core::fmt::Debug for XofReader {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("XofReader")
+            .field("algorithm", &self.algorithm)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The Keccak-f[1600] permutation of the all-zero state, from the Keccak
+    // team's KeccakF-1600-IntermediateValues.txt.
+    #[test]
+    fn test_keccak_f_zero() {
+        let mut state = [0u64; STATE_WORDS];
+        keccak_f(&mut state);
+        assert_eq!(state[0], 0xf1258f7940e1dde7);
+        assert_eq!(state[1], 0x84d5ccf933c0478a);
+        assert_eq!(state[24], 0xeaf1ff7b5ceca249);
+    }
+}
@@ -15,7 +15,7 @@
 //! EdDSA Signatures.
 
 use super::ops::ELEM_LEN;
-use crate::digest;
+use crate::{digest, error};
 
 pub mod signing;
 pub mod verification;
@@ -23,8 +23,61 @@ pub mod verification;
 /// The length of an Ed25519 public key.
 pub const ED25519_PUBLIC_KEY_LEN: usize = ELEM_LEN;
 
-pub fn eddsa_digest(signature_r: &[u8], public_key: &[u8], msg: &[u8]) -> digest::Digest {
+/// The maximum length of the context of an Ed25519ctx or Ed25519ph
+/// signature.
+pub const ED25519_MAX_CONTEXT_LEN: usize = 255;
+
+// The `dom2(phflag, context)` prefix of RFC 8032 Section 5.1, which is used
+// for Ed25519ctx and Ed25519ph but not for pure Ed25519.
+#[derive(Clone, Copy)]
+pub struct Dom2<'a> {
+    phflag: u8,
+    context: &'a [u8],
+}
+
+impl<'a> Dom2<'a> {
+    // Ed25519ctx. RFC 8032 Section 8.3 says the context SHOULD NOT be empty;
+    // we require it not be empty.
+    pub fn ctx(context: &'a [u8]) -> Result<Self, error::Unspecified> {
+        if context.is_empty() {
+            return Err(error::Unspecified);
+        }
+        Self::new(0, context)
+    }
+
+    // Ed25519ph.
+    pub fn ph(context: &'a [u8]) -> Result<Self, error::Unspecified> {
+        Self::new(1, context)
+    }
+
+    fn new(phflag: u8, context: &'a [u8]) -> Result<Self, error::Unspecified> {
+        if context.len() > ED25519_MAX_CONTEXT_LEN {
+            return Err(error::Unspecified);
+        }
+        Ok(Self { phflag, context })
+    }
+
+    pub fn update(&self, ctx: &mut digest::Context) {
+        #[allow(clippy::cast_possible_truncation)]
+        let context_len = self.context.len() as u8;
+        ctx.update(b"SigEd25519 no Ed25519 collisions");
+        ctx.update(&[self.phflag, context_len]);
+        ctx.update(self.context);
+    }
+}
+
+// `msg` is PH(M) from RFC 8032 Section 5.1, i.e. the message itself except
+// for Ed25519ph, where it is the SHA-512 digest of the message.
+pub fn eddsa_digest(
+    dom2: Option<Dom2>,
+    signature_r: &[u8],
+    public_key: &[u8],
+    msg: &[u8],
+) -> digest::Digest {
     let mut ctx = digest::Context::new(&digest::SHA512);
+    if let Some(dom2) = dom2 {
+        dom2.update(&mut ctx);
+    }
     ctx.update(signature_r);
     ctx.update(public_key);
     ctx.update(msg);
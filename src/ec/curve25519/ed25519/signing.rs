@@ -14,7 +14,7 @@
 
 //! EdDSA Signatures.
 
-use super::{super::ops::*, eddsa_digest, ED25519_PUBLIC_KEY_LEN};
+use super::{super::ops::*, eddsa_digest, Dom2, ED25519_PUBLIC_KEY_LEN};
 use crate::{
     cpu, digest, error,
     io::der,
@@ -187,20 +187,68 @@ impl Ed25519KeyPair {
 
     /// Returns the signature of the message `msg`.
     pub fn sign(&self, msg: &[u8]) -> signature::Signature {
-        signature::Signature::new(|signature_bytes| {
-            prefixed_extern! {
-                fn x25519_sc_muladd(
-                    s: &mut [u8; SCALAR_LEN],
-                    a: &Scalar,
-                    b: &Scalar,
-                    c: &Scalar,
-                );
-            }
+        self.sign_(None, msg)
+    }
+
+    /// Returns the Ed25519ctx signature of the message `msg` with the
+    /// context `context`, as described in [RFC 8032 Section 5.1].
+    ///
+    /// `context` must be between 1 and `ED25519_MAX_CONTEXT_LEN` bytes long;
+    /// otherwise an error is returned.
+    ///
+    /// [RFC 8032 Section 5.1]: https://tools.ietf.org/html/rfc8032#section-5.1
+    pub fn sign_ctx(
+        &self,
+        context: &[u8],
+        msg: &[u8],
+    ) -> Result<signature::Signature, error::Unspecified> {
+        let dom2 = Dom2::ctx(context)?;
+        Ok(self.sign_(Some(dom2), msg))
+    }
 
+    /// Returns the Ed25519ph signature of the message `msg` with the context
+    /// `context`, as described in [RFC 8032 Section 5.1].
+    ///
+    /// `context` may be empty, and must be no more than
+    /// `ED25519_MAX_CONTEXT_LEN` bytes long; otherwise an error is returned.
+    ///
+    /// [RFC 8032 Section 5.1]: https://tools.ietf.org/html/rfc8032#section-5.1
+    pub fn sign_ph(
+        &self,
+        context: &[u8],
+        msg: &[u8],
+    ) -> Result<signature::Signature, error::Unspecified> {
+        self.sign_ph_digest(context, digest::digest(&digest::SHA512, msg))
+    }
+
+    /// Returns the Ed25519ph signature of the message that was digested to
+    /// `digest`, with the context `context`.
+    ///
+    /// This is for use when the message was digested elsewhere. `digest` must
+    /// have been computed using SHA-512; otherwise an error is returned.
+    /// The requirements on `context` are the same as for [`Self::sign_ph`].
+    pub fn sign_ph_digest(
+        &self,
+        context: &[u8],
+        digest: digest::Digest,
+    ) -> Result<signature::Signature, error::Unspecified> {
+        if digest.algorithm() != &digest::SHA512 {
+            return Err(error::Unspecified);
+        }
+        let dom2 = Dom2::ph(context)?;
+        Ok(self.sign_(Some(dom2), digest.as_ref()))
+    }
+
+    // `msg` is PH(M) from RFC 8032 Section 5.1.
+    fn sign_(&self, dom2: Option<Dom2>, msg: &[u8]) -> signature::Signature {
+        signature::Signature::new(|signature_bytes| {
             let (signature_bytes, _unused) = signature_bytes.split_at_mut(ELEM_LEN + SCALAR_LEN);
             let (signature_r, signature_s) = signature_bytes.split_at_mut(ELEM_LEN);
             let nonce = {
                 let mut ctx = digest::Context::new(&digest::SHA512);
+                if let Some(dom2) = dom2 {
+                    dom2.update(&mut ctx);
+                }
                 ctx.update(&self.private_prefix);
                 ctx.update(msg);
                 ctx.finish()
@@ -209,16 +257,14 @@ impl Ed25519KeyPair {
 
             let r = ExtPoint::from_scalarmult_base_consttime(&nonce, cpu::features());
             signature_r.copy_from_slice(&r.into_encoded_point());
-            let hram_digest = eddsa_digest(signature_r, self.public_key.as_ref(), msg);
+            let hram_digest = eddsa_digest(dom2, signature_r, self.public_key.as_ref(), msg);
             let hram = Scalar::from_sha512_digest_reduced(hram_digest);
-            unsafe {
-                x25519_sc_muladd(
-                    signature_s.try_into().unwrap(),
-                    &hram,
-                    &self.private_scalar,
-                    &nonce,
-                );
-            }
+            scalar_muladd(
+                signature_s.try_into().unwrap(),
+                &hram,
+                &self.private_scalar,
+                &nonce,
+            );
 
             SIGNATURE_LEN
         })
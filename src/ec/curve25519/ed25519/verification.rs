@@ -14,8 +14,16 @@
 
 //! EdDSA Signatures.
 
-use super::{super::ops::*, eddsa_digest};
-use crate::{error, sealed, signature};
+#[cfg(feature = "std")]
+extern crate std;
+
+use super::{super::ops::*, eddsa_digest, Dom2};
+use crate::{digest, error, sealed, signature};
+
+#[cfg(feature = "alloc")]
+use crate::rand;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
 /// Parameters for EdDSA signing and verification.
 pub struct EdDSAParameters;
@@ -33,6 +41,23 @@ impl core::fmt::Debug for EdDSAParameters {
 /// [Ed25519]: https://ed25519.cr.yp.to/
 pub static ED25519: EdDSAParameters = EdDSAParameters {};
 
+impl EdDSAParameters {
+    /// Verifies the Ed25519ctx signature `signature` of the message `msg`,
+    /// with the context `context`, using the public key `public_key`.
+    ///
+    /// `context` must not be empty and must be no more than
+    /// `ED25519_MAX_CONTEXT_LEN` bytes long.
+    pub fn verify_with_context(
+        &self,
+        public_key: &[u8],
+        context: &[u8],
+        msg: &[u8],
+        signature: &[u8],
+    ) -> Result<(), error::Unspecified> {
+        verify(Some(Dom2::ctx(context)?), public_key, msg, signature)
+    }
+}
+
 impl signature::VerificationAlgorithm for EdDSAParameters {
     fn verify(
         &self,
@@ -40,8 +65,130 @@ impl signature::VerificationAlgorithm for EdDSAParameters {
         msg: untrusted::Input,
         signature: untrusted::Input,
     ) -> Result<(), error::Unspecified> {
-        let public_key: &[u8; ELEM_LEN] = public_key.as_slice_less_safe().try_into()?;
-        let (signature_r, signature_s) = signature.read_all(error::Unspecified, |input| {
+        verify(
+            None,
+            public_key.as_slice_less_safe(),
+            msg.as_slice_less_safe(),
+            signature.as_slice_less_safe(),
+        )
+    }
+
+    fn verify_digest(
+        &self,
+        _public_key: untrusted::Input,
+        _digest: digest::Digest,
+        _signature: untrusted::Input,
+    ) -> Result<(), error::Unspecified> {
+        // Ed25519 signs the message itself, not a digest of it.
+        Err(error::Unspecified)
+    }
+}
+
+impl sealed::Sealed for EdDSAParameters {}
+
+/// Parameters for Ed25519ph verification.
+pub struct Ed25519phParameters(());
+
+impl core::fmt::Debug for Ed25519phParameters {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        write!(f, "ring::signature::ED25519PH")
+    }
+}
+
+/// Verification of Ed25519ph signatures, as described in
+/// [RFC 8032 Section 5.1], with an empty context.
+///
+/// Ed25519ph signs the SHA-512 digest of the message, so `verify_digest` can
+/// be used with a SHA-512 digest of the message. Use `verify_with_context`
+/// to verify signatures with a non-empty context.
+///
+/// [RFC 8032 Section 5.1]: https://tools.ietf.org/html/rfc8032#section-5.1
+pub static ED25519PH: Ed25519phParameters = Ed25519phParameters(());
+
+impl Ed25519phParameters {
+    /// Verifies the Ed25519ph signature `signature` of the message `msg`,
+    /// with the context `context`, using the public key `public_key`.
+    ///
+    /// `context` may be empty and must be no more than
+    /// `ED25519_MAX_CONTEXT_LEN` bytes long.
+    pub fn verify_with_context(
+        &self,
+        public_key: &[u8],
+        context: &[u8],
+        msg: &[u8],
+        signature: &[u8],
+    ) -> Result<(), error::Unspecified> {
+        let digest = digest::digest(&digest::SHA512, msg);
+        self.verify_digest_with_context(public_key, context, digest, signature)
+    }
+
+    /// Verifies the Ed25519ph signature `signature` of the message that was
+    /// digested to `digest`, with the context `context`, using the public key
+    /// `public_key`.
+    ///
+    /// This is for use when the message was digested elsewhere. `digest` must
+    /// have been computed using SHA-512.
+    pub fn verify_digest_with_context(
+        &self,
+        public_key: &[u8],
+        context: &[u8],
+        digest: digest::Digest,
+        signature: &[u8],
+    ) -> Result<(), error::Unspecified> {
+        if digest.algorithm() != &digest::SHA512 {
+            return Err(error::Unspecified);
+        }
+        verify(
+            Some(Dom2::ph(context)?),
+            public_key,
+            digest.as_ref(),
+            signature,
+        )
+    }
+}
+
+impl signature::VerificationAlgorithm for Ed25519phParameters {
+    fn verify(
+        &self,
+        public_key: untrusted::Input,
+        msg: untrusted::Input,
+        signature: untrusted::Input,
+    ) -> Result<(), error::Unspecified> {
+        self.verify_with_context(
+            public_key.as_slice_less_safe(),
+            &[],
+            msg.as_slice_less_safe(),
+            signature.as_slice_less_safe(),
+        )
+    }
+
+    fn verify_digest(
+        &self,
+        public_key: untrusted::Input,
+        digest: digest::Digest,
+        signature: untrusted::Input,
+    ) -> Result<(), error::Unspecified> {
+        self.verify_digest_with_context(
+            public_key.as_slice_less_safe(),
+            &[],
+            digest,
+            signature.as_slice_less_safe(),
+        )
+    }
+}
+
+impl sealed::Sealed for Ed25519phParameters {}
+
+// `msg` is PH(M) from RFC 8032 Section 5.1.
+fn verify(
+    dom2: Option<Dom2>,
+    public_key: &[u8],
+    msg: &[u8],
+    signature: &[u8],
+) -> Result<(), error::Unspecified> {
+    let public_key: &[u8; ELEM_LEN] = public_key.try_into()?;
+    let (signature_r, signature_s) =
+        untrusted::Input::from(signature).read_all(error::Unspecified, |input| {
             let signature_r: &[u8; ELEM_LEN] = input
                 .read_bytes(ELEM_LEN)?
                 .as_slice_less_safe()
@@ -53,25 +200,188 @@ impl signature::VerificationAlgorithm for EdDSAParameters {
             Ok((signature_r, signature_s))
         })?;
 
-        let signature_s = Scalar::from_bytes_checked(*signature_s)?;
+    let signature_s = Scalar::from_bytes_checked(*signature_s)?;
+
+    let mut a = ExtPoint::from_encoded_point_vartime(public_key)?;
+    a.invert_vartime();
+
+    let h_digest = eddsa_digest(dom2, signature_r, public_key, msg);
+    let h = Scalar::from_sha512_digest_reduced(h_digest);
+
+    let mut r = Point::new_at_infinity();
+    unsafe { x25519_ge_double_scalarmult_vartime(&mut r, &h, &a, &signature_s) };
+    let r_check = r.into_encoded_point();
+    if *signature_r != r_check {
+        return Err(error::Unspecified);
+    }
+    Ok(())
+}
+
+/// A public key, message, and signature to be verified as part of a batch by
+/// [`EdDSAParameters::verify_batch`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug)]
+pub struct Ed25519BatchItem<'a> {
+    /// The public key, encoded as described in [RFC 8032 Section 5.1.5].
+    ///
+    /// [RFC 8032 Section 5.1.5]: https://tools.ietf.org/html/rfc8032#section-5.1.5
+    pub public_key: &'a [u8],
+
+    /// The signed message.
+    pub msg: &'a [u8],
+
+    /// The signature.
+    pub signature: &'a [u8],
+}
+
+/// The error returned by [`EdDSAParameters::verify_batch`] when one or more
+/// signatures in the batch are invalid.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchVerificationError {
+    invalid: Vec<usize>,
+}
+
+#[cfg(feature = "alloc")]
+impl BatchVerificationError {
+    /// The indices, in ascending order, of the items in the batch whose
+    /// signatures are invalid.
+    pub fn invalid_indices(&self) -> &[usize] {
+        &self.invalid
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Display for BatchVerificationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("ring::signature::BatchVerificationError")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BatchVerificationError {}
+
+#[cfg(feature = "alloc")]
+impl EdDSAParameters {
+    /// Verifies a batch of Ed25519 signatures.
+    ///
+    /// The batch is checked all at once by verifying a random linear
+    /// combination of the signature verification equations with a single
+    /// multi-scalar multiplication, which is faster than verifying each
+    /// signature on its own. If that check fails then each signature is
+    /// checked individually to find the invalid ones, which are reported in
+    /// the returned error.
+    ///
+    /// A signature is valid according to the rules of [ZIP 215]:
+    ///
+    /// * `S` must be less than the order of the base point.
+    /// * The public key `A` and `R` must decode to points on the curve, but
+    ///   their encodings don't need to be canonical.
+    /// * The cofactored verification equation `[8][S]B = [8]R + [8][k]A` of
+    ///   [RFC 8032 Section 5.1.7] must hold.
+    ///
+    /// [`signature::VerificationAlgorithm::verify`] instead requires the
+    /// cofactorless equation `[S]B = R + [k]A` to hold with `R` encoded
+    /// canonically. Both accept every signature generated as specified in
+    /// RFC 8032, but `verify_batch` also accepts some maliciously constructed
+    /// signatures that `verify` rejects, e.g. ones where `A` or `R` has a
+    /// small-order component. The cofactored equation is used because, unlike
+    /// the cofactorless one, checking it for a random linear combination of
+    /// signatures agrees with checking it for each signature, so the result
+    /// doesn't depend on how signatures are batched.
+    ///
+    /// `rng` is used to generate the coefficients of the random linear
+    /// combination.
+    ///
+    /// [ZIP 215]: https://zips.z.cash/zip-0215
+    /// [RFC 8032 Section 5.1.7]: https://tools.ietf.org/html/rfc8032#section-5.1.7
+    pub fn verify_batch(
+        &self,
+        batch: &[Ed25519BatchItem],
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<(), BatchVerificationError> {
+        let mut random_coefficient = || {
+            let mut z = [0; SCALAR_LEN];
+            rng.fill(&mut z[..16])?;
+            Scalar::from_bytes_checked(z)
+        };
+        if verify_cofactored(batch, &mut random_coefficient).is_ok() {
+            return Ok(());
+        }
+
+        // A single equation doesn't need a random coefficient.
+        let mut one = || {
+            let mut one = [0; SCALAR_LEN];
+            one[0] = 1;
+            Scalar::from_bytes_checked(one)
+        };
+        let invalid: Vec<usize> = batch
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| verify_cofactored(core::slice::from_ref(*item), &mut one).is_err())
+            .map(|(i, _)| i)
+            .collect();
+        if invalid.is_empty() {
+            return Ok(());
+        }
+        Err(BatchVerificationError { invalid })
+    }
+}
+
+// Verifies that [8]([sum(z_i * S_i)]B - sum([z_i]R_i) - sum([z_i * k_i]A_i))
+// is the identity, where each z_i is generated by `coefficient`.
+#[cfg(feature = "alloc")]
+fn verify_cofactored(
+    batch: &[Ed25519BatchItem],
+    coefficient: &mut dyn FnMut() -> Result<Scalar, error::Unspecified>,
+) -> Result<(), error::Unspecified> {
+    let zero = Scalar::from_bytes_checked([0; SCALAR_LEN])?;
+    let mut b_coeff = [0; SCALAR_LEN];
+
+    let mut coeffs = Vec::with_capacity(2 * batch.len());
+    let mut points = Vec::with_capacity(2 * batch.len());
+    for item in batch {
+        let public_key: &[u8; ELEM_LEN] = item.public_key.try_into()?;
+        if item.signature.len() != ELEM_LEN + SCALAR_LEN {
+            return Err(error::Unspecified);
+        }
+        let (signature_r, signature_s) = item.signature.split_at(ELEM_LEN);
+        let signature_r: &[u8; ELEM_LEN] = signature_r.try_into()?;
+        let signature_s = Scalar::from_bytes_checked(signature_s.try_into()?)?;
 
+        let mut r = ExtPoint::from_encoded_point_vartime(signature_r)?;
+        r.invert_vartime();
         let mut a = ExtPoint::from_encoded_point_vartime(public_key)?;
         a.invert_vartime();
 
-        let h_digest = eddsa_digest(signature_r, public_key, msg.as_slice_less_safe());
+        let h_digest = eddsa_digest(None, signature_r, public_key, item.msg);
         let h = Scalar::from_sha512_digest_reduced(h_digest);
 
-        let mut r = Point::new_at_infinity();
-        unsafe { x25519_ge_double_scalarmult_vartime(&mut r, &h, &a, &signature_s) };
-        let r_check = r.into_encoded_point();
-        if *signature_r != r_check {
-            return Err(error::Unspecified);
-        }
-        Ok(())
+        let z = coefficient()?;
+        let mut z_h = [0; SCALAR_LEN];
+        scalar_muladd(&mut z_h, &z, &h, &zero);
+        let b_coeff_prev = Scalar::from_bytes_checked(b_coeff)?;
+        scalar_muladd(&mut b_coeff, &z, &signature_s, &b_coeff_prev);
+
+        coeffs.push(z);
+        points.push(r);
+        coeffs.push(Scalar::from_bytes_checked(z_h)?);
+        points.push(a);
     }
-}
 
-impl sealed::Sealed for EdDSAParameters {}
+    let b_coeff = Scalar::from_bytes_checked(b_coeff)?;
+    let mut sum = ExtPoint::from_multi_scalarmult_vartime(&b_coeff, &coeffs, &points);
+    for _ in 0..3 {
+        sum = sum.double();
+    }
+
+    let mut identity = [0; ELEM_LEN];
+    identity[0] = 1;
+    if sum.into_encoded_point() != identity {
+        return Err(error::Unspecified);
+    }
+    Ok(())
+}
 
 prefixed_extern! {
     fn x25519_ge_double_scalarmult_vartime(
@@ -15,7 +15,7 @@
 //! Elliptic curve operations on the birationally equivalent curves Curve25519
 //! and Edwards25519.
 
-pub use super::scalar::{MaskedScalar, Scalar, SCALAR_LEN};
+pub use super::scalar::{scalar_muladd, MaskedScalar, Scalar, SCALAR_LEN};
 use crate::{
     bssl, c, cpu, error,
     limb::{Limb, LIMB_BITS},
@@ -25,6 +25,7 @@ use core::marker::PhantomData;
 // Elem<T>` is `fe` in curve25519/internal.h.
 // Elem<L> is `fe_loose` in curve25519/internal.h.
 // Keep this in sync with curve25519/internal.h.
+#[derive(Clone)]
 #[repr(C)]
 pub struct Elem<E: Encoding> {
     limbs: [Limb; ELEM_LIMBS], // This is called `v` in the C code.
@@ -32,6 +33,7 @@ pub struct Elem<E: Encoding> {
 }
 
 pub trait Encoding {}
+#[derive(Clone)]
 pub struct T;
 impl Encoding for T {}
 
@@ -64,6 +66,7 @@ pub type EncodedPoint = [u8; ELEM_LEN];
 pub const ELEM_LEN: usize = 32;
 
 // Keep this in sync with `ge_p3` in curve25519/internal.h.
+#[derive(Clone)]
 #[repr(C)]
 pub struct ExtPoint {
     x: Elem<T>,
@@ -75,12 +78,7 @@ pub struct ExtPoint {
 impl ExtPoint {
     // Returns the result of multiplying the base point by the scalar in constant time.
     pub(super) fn from_scalarmult_base_consttime(scalar: &Scalar, cpu: cpu::Features) -> Self {
-        let mut r = Self {
-            x: Elem::zero(),
-            y: Elem::zero(),
-            z: Elem::zero(),
-            t: Elem::zero(),
-        };
+        let mut r = Self::zero();
         prefixed_extern! {
             fn x25519_ge_scalarmult_base(h: &mut ExtPoint, a: &Scalar, has_fe25519_adx: c::int);
         }
@@ -91,12 +89,7 @@ impl ExtPoint {
     }
 
     pub fn from_encoded_point_vartime(encoded: &EncodedPoint) -> Result<Self, error::Unspecified> {
-        let mut point = Self {
-            x: Elem::zero(),
-            y: Elem::zero(),
-            z: Elem::zero(),
-            t: Elem::zero(),
-        };
+        let mut point = Self::zero();
 
         Result::from(unsafe { x25519_ge_frombytes_vartime(&mut point, encoded) }).map(|()| point)
     }
@@ -109,6 +102,79 @@ impl ExtPoint {
         self.x.negate();
         self.t.negate();
     }
+
+    // Returns `[b]B + sum([a_i]A_i)` for the `a_i` in `a` and the `A_i` in
+    // `points`, where B is the base point, in variable time.
+    #[cfg(feature = "alloc")]
+    pub fn from_multi_scalarmult_vartime(b: &Scalar, a: &[Scalar], points: &[Self]) -> Self {
+        assert_eq!(a.len(), points.len());
+        let mut tables = alloc::vec![CachedPoint::zero(); 8 * points.len()];
+        let mut slides = alloc::vec![0i8; 256 * points.len()];
+        let mut r = Self::zero();
+        prefixed_extern! {
+            fn x25519_ge_multi_scalarmult_vartime(
+                r: &mut ExtPoint,
+                b: &Scalar,
+                a: *const Scalar,
+                points: *const ExtPoint,
+                num: c::size_t,
+                tables: *mut CachedPoint,
+                slides: *mut i8,
+            );
+        }
+        unsafe {
+            x25519_ge_multi_scalarmult_vartime(
+                &mut r,
+                b,
+                a.as_ptr(),
+                points.as_ptr(),
+                points.len(),
+                tables.as_mut_ptr(),
+                slides.as_mut_ptr(),
+            );
+        }
+        r
+    }
+
+    // Returns `2 * self`.
+    pub fn double(&self) -> Self {
+        let mut r = Self::zero();
+        unsafe { x25519_ge_p3_dbl(&mut r, self) };
+        r
+    }
+
+    fn zero() -> Self {
+        Self {
+            x: Elem::zero(),
+            y: Elem::zero(),
+            z: Elem::zero(),
+            t: Elem::zero(),
+        }
+    }
+}
+
+// Keep this in sync with `ge_cached` in curve25519/internal.h. It is only
+// used as scratch space by the C code.
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+#[repr(C)]
+struct CachedPoint {
+    yplusx: Elem<T>,
+    yminusx: Elem<T>,
+    z: Elem<T>,
+    t2d: Elem<T>,
+}
+
+#[cfg(feature = "alloc")]
+impl CachedPoint {
+    fn zero() -> Self {
+        Self {
+            yplusx: Elem::zero(),
+            yminusx: Elem::zero(),
+            z: Elem::zero(),
+            t2d: Elem::zero(),
+        }
+    }
 }
 
 // Keep this in sync with `ge_p2` in curve25519/internal.h.
@@ -180,4 +246,5 @@ prefixed_extern! {
     fn x25519_fe_neg(f: &mut Elem<T>);
     fn x25519_fe_tobytes(bytes: &mut EncodedPoint, elem: &Elem<T>);
     fn x25519_ge_frombytes_vartime(h: &mut ExtPoint, s: &EncodedPoint) -> bssl::Result;
+    fn x25519_ge_p3_dbl(r: &mut ExtPoint, p: &ExtPoint);
 }
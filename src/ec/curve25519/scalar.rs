@@ -53,6 +53,19 @@ impl Scalar {
     }
 }
 
+// Sets `s` to `a * b + c` (mod n).
+pub fn scalar_muladd(s: &mut [u8; SCALAR_LEN], a: &Scalar, b: &Scalar, c: &Scalar) {
+    prefixed_extern! {
+        fn x25519_sc_muladd(
+            s: &mut [u8; SCALAR_LEN],
+            a: &Scalar,
+            b: &Scalar,
+            c: &Scalar,
+        );
+    }
+    unsafe { x25519_sc_muladd(s, a, b, c) }
+}
+
 #[repr(transparent)]
 pub struct MaskedScalar([u8; SCALAR_LEN]);
 
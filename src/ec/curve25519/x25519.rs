@@ -15,7 +15,7 @@
 //! X25519 Key agreement.
 
 use super::{ops, scalar::SCALAR_LEN};
-use crate::{agreement, c, constant_time, cpu, ec, error, rand};
+use crate::{agreement, c, constant_time, cpu, ec, error, pkcs8, rand};
 
 static CURVE25519: ec::Curve = ec::Curve {
     public_key_len: PUBLIC_KEY_LEN,
@@ -170,6 +170,15 @@ fn x25519_neon(out: &mut ops::EncodedPoint, scalar: &ops::MaskedScalar, point: &
     unsafe { x25519_NEON(out, scalar, point) }
 }
 
+// The RFC 8410 PKCS#8 v2 document, containing the public key, used for static
+// X25519 keys.
+pub(crate) static PKCS8_TEMPLATE: pkcs8::Template = pkcs8::Template {
+    bytes: include_bytes!("x25519_pkcs8_v2_template.der"),
+    alg_id_range: core::ops::Range { start: 7, end: 12 },
+    curve_id_index: 0,
+    private_key_index: 0x10,
+};
+
 const ELEM_AND_SCALAR_LEN: usize = ops::ELEM_LEN;
 
 type PrivateKey = ops::MaskedScalar;
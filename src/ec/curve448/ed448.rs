@@ -0,0 +1,93 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! EdDSA Signatures using Edwards448.
+
+use super::ops::ENCODED_POINT_LEN;
+use crate::{digest, error};
+
+pub mod signing;
+pub mod verification;
+
+/// The length of an Ed448 public key.
+pub const ED448_PUBLIC_KEY_LEN: usize = ENCODED_POINT_LEN;
+
+/// The maximum length of the context of an Ed448 or Ed448ph signature.
+pub const ED448_MAX_CONTEXT_LEN: usize = 255;
+
+// The length of the SHAKE256 output used for key expansion and for hashing
+// in signing and verification.
+const HASH_LEN: usize = 2 * ENCODED_POINT_LEN;
+
+// The `dom4(phflag, context)` prefix of RFC 8032 Section 5.2.
+#[derive(Clone, Copy)]
+struct Dom4<'a> {
+    phflag: u8,
+    context: &'a [u8],
+}
+
+impl Dom4<'static> {
+    // Ed448 with an empty context.
+    const PURE: Self = Self {
+        phflag: 0,
+        context: &[],
+    };
+}
+
+impl<'a> Dom4<'a> {
+    // Ed448 with a context. Unlike Ed25519, pure Ed448 uses `dom4` too, and
+    // the context may be empty.
+    fn pure(context: &'a [u8]) -> Result<Self, error::Unspecified> {
+        Self::new(0, context)
+    }
+
+    // Ed448ph.
+    fn ph(context: &'a [u8]) -> Result<Self, error::Unspecified> {
+        Self::new(1, context)
+    }
+
+    fn new(phflag: u8, context: &'a [u8]) -> Result<Self, error::Unspecified> {
+        if context.len() > ED448_MAX_CONTEXT_LEN {
+            return Err(error::Unspecified);
+        }
+        Ok(Self { phflag, context })
+    }
+}
+
+// Returns SHAKE256(dom4 || parts[0] || parts[1] || ..., 114), as used for the
+// nonce and the challenge in RFC 8032 Section 5.2.6 and Section 5.2.7.
+fn eddsa_hash(dom4: Dom4, parts: &[&[u8]]) -> [u8; HASH_LEN] {
+    let mut ctx = digest::XofContext::new(&digest::SHAKE256);
+    #[allow(clippy::cast_possible_truncation)]
+    let context_len = dom4.context.len() as u8;
+    ctx.update(b"SigEd448");
+    ctx.update(&[dom4.phflag, context_len]);
+    ctx.update(dom4.context);
+    parts.iter().for_each(|part| ctx.update(part));
+    let mut out = [0u8; HASH_LEN];
+    ctx.finalize().squeeze(&mut out);
+    out
+}
+
+// The length of PH(M) for Ed448ph.
+const PREHASH_LEN: usize = 64;
+
+// Returns PH(M) = SHAKE256(M, 64) for Ed448ph, from RFC 8032 Section 5.2.
+fn prehash(msg: &[u8]) -> [u8; PREHASH_LEN] {
+    let mut ctx = digest::XofContext::new(&digest::SHAKE256);
+    ctx.update(msg);
+    let mut out = [0u8; PREHASH_LEN];
+    ctx.finalize().squeeze(&mut out);
+    out
+}
@@ -0,0 +1,286 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Ed448 signing.
+
+use super::{
+    super::{
+        ops::{Point, ENCODED_POINT_LEN},
+        scalar::{Scalar, ENCODED_SCALAR_LEN},
+    },
+    eddsa_hash, prehash, Dom4, ED448_PUBLIC_KEY_LEN, HASH_LEN,
+};
+use crate::{
+    digest, error,
+    io::der,
+    pkcs8, rand,
+    signature::{self, KeyPair as SigningKeyPair},
+};
+
+/// An Ed448 key pair, for signing.
+///
+/// Unlike *ring*'s Ed25519 implementation, Ed448 signing is implemented in
+/// portable Rust. It is written to be constant-time, and the code the
+/// compiler generates for x86_64 has been checked for secret-dependent
+/// branches, but it hasn't been audited. Prefer Ed25519 where the protocol
+/// allows it.
+pub struct Ed448KeyPair {
+    // RFC 8032 Section 5.2.5 calls this *s*.
+    private_scalar: Scalar,
+
+    // RFC 8032 Section 5.2.6 calls this *prefix*.
+    private_prefix: Prefix,
+
+    // RFC 8032 Section 5.2.5 calls this *A*.
+    public_key: PublicKey,
+}
+
+derive_debug_via_field!(Ed448KeyPair, stringify!(Ed448KeyPair), public_key);
+
+impl Ed448KeyPair {
+    /// Generates a new key pair and returns the key pair serialized as a
+    /// PKCS#8 document.
+    ///
+    /// The PKCS#8 document will be a v2 `OneAsymmetricKey` with the public key,
+    /// as described in [RFC 5958 Section 2] and [RFC 8410].
+    ///
+    /// [RFC 5958 Section 2]: https://tools.ietf.org/html/rfc5958#section-2
+    /// [RFC 8410]: https://tools.ietf.org/html/rfc8410
+    pub fn generate_pkcs8(
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<pkcs8::Document, error::Unspecified> {
+        let seed: [u8; SEED_LEN] = rand::generate(rng)?.expose();
+        let key_pair = Self::from_seed_(&seed);
+        Ok(pkcs8::wrap_key(
+            &PKCS8_TEMPLATE,
+            &seed[..],
+            key_pair.public_key().as_ref(),
+        ))
+    }
+
+    /// Constructs an Ed448 key pair by parsing an unencrypted PKCS#8 v2
+    /// Ed448 private key.
+    ///
+    /// `openssl genpkey -algorithm ED448` generates PKCS#8 v1 keys, which
+    /// require the use of `Ed448KeyPair::from_pkcs8_maybe_unchecked()`
+    /// instead of `Ed448KeyPair::from_pkcs8()`.
+    ///
+    /// The input must be in PKCS#8 v2 format, and in particular it must contain
+    /// the public key in addition to the private key. `from_pkcs8()` will
+    /// verify that the public key and the private key are consistent with each
+    /// other.
+    pub fn from_pkcs8(pkcs8: &[u8]) -> Result<Self, error::KeyRejected> {
+        let version = pkcs8::Version::V2Only(PUBLIC_KEY_OPTIONS);
+        let (seed, public_key) = unwrap_pkcs8(version, untrusted::Input::from(pkcs8))?;
+        Self::from_seed_and_public_key(
+            seed.as_slice_less_safe(),
+            public_key.unwrap().as_slice_less_safe(),
+        )
+    }
+
+    /// Constructs an Ed448 key pair by parsing an unencrypted PKCS#8 v1 or v2
+    /// Ed448 private key.
+    ///
+    /// It is recommended to use `Ed448KeyPair::from_pkcs8()`, which accepts
+    /// only PKCS#8 v2 files that contain the public key. PKCS#8 v1 files do
+    /// not contain the public key, so when a v1 file is parsed the public key
+    /// will be computed from the private key, and there will be no
+    /// consistency check between the public key and the private key.
+    ///
+    /// PKCS#8 v2 files are parsed exactly like `Ed448KeyPair::from_pkcs8()`.
+    pub fn from_pkcs8_maybe_unchecked(pkcs8: &[u8]) -> Result<Self, error::KeyRejected> {
+        let version = pkcs8::Version::V1OrV2(PUBLIC_KEY_OPTIONS);
+        let (seed, public_key) = unwrap_pkcs8(version, untrusted::Input::from(pkcs8))?;
+        if let Some(public_key) = public_key {
+            Self::from_seed_and_public_key(
+                seed.as_slice_less_safe(),
+                public_key.as_slice_less_safe(),
+            )
+        } else {
+            Self::from_seed_unchecked(seed.as_slice_less_safe())
+        }
+    }
+
+    /// Constructs an Ed448 key pair from the private key seed `seed` and its
+    /// public key `public_key`.
+    ///
+    /// It is recommended to use `Ed448KeyPair::from_pkcs8()` instead.
+    ///
+    /// The private and public keys will be verified to be consistent with each
+    /// other.
+    pub fn from_seed_and_public_key(
+        seed: &[u8],
+        public_key: &[u8],
+    ) -> Result<Self, error::KeyRejected> {
+        let pair = Self::from_seed_unchecked(seed)?;
+
+        // This implicitly verifies that `public_key` is the right length.
+        if public_key != pair.public_key.as_ref() {
+            let err = if public_key.len() != pair.public_key.as_ref().len() {
+                error::KeyRejected::invalid_encoding()
+            } else {
+                error::KeyRejected::inconsistent_components()
+            };
+            return Err(err);
+        }
+
+        Ok(pair)
+    }
+
+    /// Constructs an Ed448 key pair from the private key seed `seed`.
+    ///
+    /// It is recommended to use `Ed448KeyPair::from_pkcs8()` instead. When
+    /// that is not practical, it is recommended to use
+    /// `Ed448KeyPair::from_seed_and_public_key()` instead.
+    pub fn from_seed_unchecked(seed: &[u8]) -> Result<Self, error::KeyRejected> {
+        let seed = seed
+            .try_into()
+            .map_err(|_| error::KeyRejected::invalid_encoding())?;
+        Ok(Self::from_seed_(seed))
+    }
+
+    fn from_seed_(seed: &Seed) -> Self {
+        // RFC 8032 Section 5.2.5.
+        let mut h = [0u8; HASH_LEN];
+        let mut ctx = digest::XofContext::new(&digest::SHAKE256);
+        ctx.update(seed);
+        ctx.finalize().squeeze(&mut h);
+        let (private_scalar, private_prefix) = h.split_at_mut(ENCODED_SCALAR_LEN);
+
+        private_scalar[0] &= 0xfc;
+        private_scalar[ENCODED_SCALAR_LEN - 1] = 0;
+        private_scalar[ENCODED_SCALAR_LEN - 2] |= 0x80;
+        // The base point has order n, so reducing the scalar doesn't change
+        // the public key.
+        let private_scalar = Scalar::from_bytes_reduced(private_scalar);
+
+        let a = Point::from_scalarmult_base_consttime(&private_scalar);
+
+        Self {
+            private_scalar,
+            private_prefix: (&*private_prefix).try_into().unwrap(),
+            public_key: PublicKey(a.into_encoded_point()),
+        }
+    }
+
+    /// Returns the signature of the message `msg`.
+    pub fn sign(&self, msg: &[u8]) -> signature::Signature {
+        self.sign_(Dom4::PURE, msg)
+    }
+
+    /// Returns the Ed448 signature of the message `msg` with the context
+    /// `context`, as described in [RFC 8032 Section 5.2].
+    ///
+    /// `context` may be empty, in which case this is the same as
+    /// [`Self::sign`], and must be no more than `ED448_MAX_CONTEXT_LEN` bytes
+    /// long; otherwise an error is returned.
+    ///
+    /// [RFC 8032 Section 5.2]: https://tools.ietf.org/html/rfc8032#section-5.2
+    pub fn sign_ctx(
+        &self,
+        context: &[u8],
+        msg: &[u8],
+    ) -> Result<signature::Signature, error::Unspecified> {
+        let dom4 = Dom4::pure(context)?;
+        Ok(self.sign_(dom4, msg))
+    }
+
+    /// Returns the Ed448ph signature of the message `msg` with the context
+    /// `context`, as described in [RFC 8032 Section 5.2].
+    ///
+    /// `context` may be empty, and must be no more than
+    /// `ED448_MAX_CONTEXT_LEN` bytes long; otherwise an error is returned.
+    ///
+    /// [RFC 8032 Section 5.2]: https://tools.ietf.org/html/rfc8032#section-5.2
+    pub fn sign_ph(
+        &self,
+        context: &[u8],
+        msg: &[u8],
+    ) -> Result<signature::Signature, error::Unspecified> {
+        let dom4 = Dom4::ph(context)?;
+        Ok(self.sign_(dom4, &prehash(msg)))
+    }
+
+    // `msg` is PH(M) from RFC 8032 Section 5.2.
+    fn sign_(&self, dom4: Dom4, msg: &[u8]) -> signature::Signature {
+        // RFC 8032 Section 5.2.6.
+        signature::Signature::new(|signature_bytes| {
+            let (signature_bytes, _unused) = signature_bytes.split_at_mut(SIGNATURE_LEN);
+            let (signature_r, signature_s) = signature_bytes.split_at_mut(ENCODED_POINT_LEN);
+
+            let nonce = eddsa_hash(dom4, &[&self.private_prefix, msg]);
+            let nonce = Scalar::from_bytes_reduced(&nonce);
+
+            let r = Point::from_scalarmult_base_consttime(&nonce);
+            signature_r.copy_from_slice(&r.into_encoded_point());
+            let hram = eddsa_hash(dom4, &[signature_r, self.public_key.as_ref(), msg]);
+            let hram = Scalar::from_bytes_reduced(&hram);
+            let s = Scalar::muladd(&hram, &self.private_scalar, &nonce);
+            signature_s.copy_from_slice(&s.to_encoded());
+
+            SIGNATURE_LEN
+        })
+    }
+}
+
+impl signature::KeyPair for Ed448KeyPair {
+    type PublicKey = PublicKey;
+
+    fn public_key(&self) -> &Self::PublicKey {
+        &self.public_key
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct PublicKey([u8; ED448_PUBLIC_KEY_LEN]);
+
+impl AsRef<[u8]> for PublicKey {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+derive_debug_self_as_ref_hex_bytes!(PublicKey);
+
+fn unwrap_pkcs8(
+    version: pkcs8::Version,
+    input: untrusted::Input,
+) -> Result<(untrusted::Input, Option<untrusted::Input>), error::KeyRejected> {
+    let (private_key, public_key) = pkcs8::unwrap_key(&PKCS8_TEMPLATE, version, input)?;
+    let private_key = private_key
+        .read_all(error::Unspecified, |input| {
+            der::expect_tag_and_get_value(input, der::Tag::OctetString)
+        })
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+    Ok((private_key, public_key))
+}
+
+const PUBLIC_KEY_OPTIONS: pkcs8::PublicKeyOptions = pkcs8::PublicKeyOptions {
+    accept_legacy_ed25519_public_key_tag: false,
+};
+
+type Prefix = [u8; PREFIX_LEN];
+const PREFIX_LEN: usize = HASH_LEN - ENCODED_SCALAR_LEN;
+
+const SIGNATURE_LEN: usize = ENCODED_POINT_LEN + ENCODED_SCALAR_LEN;
+
+type Seed = [u8; SEED_LEN];
+const SEED_LEN: usize = 57;
+
+static PKCS8_TEMPLATE: pkcs8::Template = pkcs8::Template {
+    bytes: include_bytes!("ed448_pkcs8_v2_template.der"),
+    alg_id_range: core::ops::Range { start: 8, end: 13 },
+    curve_id_index: 0,
+    private_key_index: 0x11,
+};
@@ -0,0 +1,194 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Ed448 signature verification.
+
+use super::{
+    super::{
+        ops::{EncodedPoint, Point, ENCODED_POINT_LEN},
+        scalar::{Scalar, ENCODED_SCALAR_LEN},
+    },
+    eddsa_hash, prehash, Dom4,
+};
+use crate::{digest, error, sealed, signature};
+
+/// Parameters for Ed448 signature verification.
+pub struct Ed448Parameters;
+
+impl core::fmt::Debug for Ed448Parameters {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        write!(f, "ring::signature::ED448")
+    }
+}
+
+/// Verification of Ed448 signatures, as described in [RFC 8032 Section 5.2].
+///
+/// Ed448 uses SHAKE256 as the digest algorithm, with an empty context. Use
+/// [`Ed448Parameters::verify_with_context`] to verify signatures with a
+/// non-empty context.
+///
+/// Verification only involves public values, but like the rest of *ring*'s
+/// Curve448 code it is implemented in portable Rust that hasn't been audited,
+/// and it is much slower than Ed25519 verification.
+///
+/// [RFC 8032 Section 5.2]: https://tools.ietf.org/html/rfc8032#section-5.2
+pub static ED448: Ed448Parameters = Ed448Parameters;
+
+impl Ed448Parameters {
+    /// Verifies the Ed448 signature `signature` of the message `msg`, with the
+    /// context `context`, using the public key `public_key`.
+    ///
+    /// `context` may be empty and must be no more than
+    /// `ED448_MAX_CONTEXT_LEN` bytes long.
+    pub fn verify_with_context(
+        &self,
+        public_key: &[u8],
+        context: &[u8],
+        msg: &[u8],
+        signature: &[u8],
+    ) -> Result<(), error::Unspecified> {
+        verify(Dom4::pure(context)?, public_key, msg, signature)
+    }
+}
+
+impl signature::VerificationAlgorithm for Ed448Parameters {
+    fn verify(
+        &self,
+        public_key: untrusted::Input,
+        msg: untrusted::Input,
+        signature: untrusted::Input,
+    ) -> Result<(), error::Unspecified> {
+        verify(
+            Dom4::PURE,
+            public_key.as_slice_less_safe(),
+            msg.as_slice_less_safe(),
+            signature.as_slice_less_safe(),
+        )
+    }
+
+    fn verify_digest(
+        &self,
+        _public_key: untrusted::Input,
+        _digest: digest::Digest,
+        _signature: untrusted::Input,
+    ) -> Result<(), error::Unspecified> {
+        // Ed448 signs the message itself, not a digest of it.
+        Err(error::Unspecified)
+    }
+}
+
+impl sealed::Sealed for Ed448Parameters {}
+
+/// Parameters for Ed448ph verification.
+pub struct Ed448phParameters(());
+
+impl core::fmt::Debug for Ed448phParameters {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        write!(f, "ring::signature::ED448PH")
+    }
+}
+
+/// Verification of Ed448ph signatures, as described in
+/// [RFC 8032 Section 5.2], with an empty context.
+///
+/// Ed448ph signs the 64-byte SHAKE256 output for the message. *ring*'s
+/// `digest::Digest` can't hold SHAKE256 output, so `verify_digest` always
+/// fails. Use `verify_with_context` to verify signatures with a non-empty
+/// context.
+///
+/// [RFC 8032 Section 5.2]: https://tools.ietf.org/html/rfc8032#section-5.2
+pub static ED448PH: Ed448phParameters = Ed448phParameters(());
+
+impl Ed448phParameters {
+    /// Verifies the Ed448ph signature `signature` of the message `msg`, with
+    /// the context `context`, using the public key `public_key`.
+    ///
+    /// `context` may be empty and must be no more than
+    /// `ED448_MAX_CONTEXT_LEN` bytes long.
+    pub fn verify_with_context(
+        &self,
+        public_key: &[u8],
+        context: &[u8],
+        msg: &[u8],
+        signature: &[u8],
+    ) -> Result<(), error::Unspecified> {
+        verify(Dom4::ph(context)?, public_key, &prehash(msg), signature)
+    }
+}
+
+impl signature::VerificationAlgorithm for Ed448phParameters {
+    fn verify(
+        &self,
+        public_key: untrusted::Input,
+        msg: untrusted::Input,
+        signature: untrusted::Input,
+    ) -> Result<(), error::Unspecified> {
+        self.verify_with_context(
+            public_key.as_slice_less_safe(),
+            &[],
+            msg.as_slice_less_safe(),
+            signature.as_slice_less_safe(),
+        )
+    }
+
+    fn verify_digest(
+        &self,
+        _public_key: untrusted::Input,
+        _digest: digest::Digest,
+        _signature: untrusted::Input,
+    ) -> Result<(), error::Unspecified> {
+        Err(error::Unspecified)
+    }
+}
+
+impl sealed::Sealed for Ed448phParameters {}
+
+// RFC 8032 Section 5.2.7. `msg` is PH(M) from RFC 8032 Section 5.2.
+fn verify(
+    dom4: Dom4,
+    public_key: &[u8],
+    msg: &[u8],
+    signature: &[u8],
+) -> Result<(), error::Unspecified> {
+    let public_key: &EncodedPoint = public_key.try_into()?;
+    let (signature_r, signature_s) =
+        untrusted::Input::from(signature).read_all(error::Unspecified, |input| {
+            let signature_r: &EncodedPoint = input
+                .read_bytes(ENCODED_POINT_LEN)?
+                .as_slice_less_safe()
+                .try_into()?;
+            let signature_s: &[u8; ENCODED_SCALAR_LEN] = input
+                .read_bytes(ENCODED_SCALAR_LEN)?
+                .as_slice_less_safe()
+                .try_into()?;
+            Ok((signature_r, signature_s))
+        })?;
+
+    let signature_s = Scalar::from_bytes_checked(signature_s)?;
+
+    let a = Point::from_encoded_point_vartime(public_key)?;
+
+    let h = eddsa_hash(dom4, &[signature_r, public_key, msg]);
+    let h = Scalar::from_bytes_reduced(&h);
+
+    // Check [S]B == R + [k]A by computing [S]B - [k]A and comparing its
+    // encoding with R, like the Ed25519 verification does.
+    let sb = Point::from_scalarmult_base_consttime(&signature_s);
+    let ka = a.mul_consttime(&h);
+    let r_check = sb.add(&ka.neg()).into_encoded_point();
+    if *signature_r != r_check {
+        return Err(error::Unspecified);
+    }
+    Ok(())
+}
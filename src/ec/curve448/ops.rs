@@ -0,0 +1,578 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Elliptic curve operations on the curves Curve448 and Edwards448.
+//!
+//! All operations are constant-time unless their name ends in `_vartime`.
+//! Unlike the Curve25519 code this is portable Rust, so this relies on the
+//! following, which also applies to `super::scalar`:
+//!
+//! * Loop bounds and table and array indices depend only on public values
+//!   such as lengths and bit positions, never on secret values.
+//! * Secret values are selected with masks (`Elem::cmov`, `Elem::cswap`,
+//!   `eq_mask`) instead of with `if` or `match`, and scalar multiplication
+//!   reads every entry of its table.
+//! * The Edwards addition formulas are complete, so there are no special
+//!   cases for the identity or for doubling.
+//! * Multiplication uses `u128` products of 64-bit limbs. On 64-bit targets
+//!   these are single multiply instructions. On 32-bit targets the compiler
+//!   expands them into 32-bit multiplications, which are constant-time on
+//!   the same CPUs as *ring*'s other field arithmetic.
+//! * The final conditional subtraction of the scalar reduction uses the C
+//!   `LIMBS_reduce_once`, because the compiler turned the Rust equivalent
+//!   into a branch.
+//!
+//! The assembly that rustc 1.95 generates for x86_64 was checked for
+//! conditional branches in the field, scalar, X448 and signing functions;
+//! the only ones left are on loop counters and public lengths. Other targets
+//! and compilers haven't been checked.
+
+pub use super::scalar::{Scalar, SCALAR_LEN};
+use crate::error;
+
+// An element of GF(p), where p = 2**448 - 2**224 - 1, as eight 56-bit limbs
+// in little-endian order.
+//
+// Limbs are allowed to be slightly larger than 56 bits between operations;
+// every operation that produces an `Elem` leaves every limb less than
+// 2**56 + 2**8, and every operation accepts such inputs.
+#[derive(Clone, Copy)]
+pub struct Elem([u64; ELEM_LIMBS]);
+
+const ELEM_LIMBS: usize = 8;
+const LIMB_BITS: u32 = 56;
+const LIMB_MASK: u64 = (1 << LIMB_BITS) - 1;
+
+/// The length of an encoded field element.
+pub const ELEM_LEN: usize = 56;
+
+// p, in the same form as an `Elem`.
+const P: [u64; ELEM_LIMBS] = [
+    LIMB_MASK,
+    LIMB_MASK,
+    LIMB_MASK,
+    LIMB_MASK,
+    LIMB_MASK - 1,
+    LIMB_MASK,
+    LIMB_MASK,
+    LIMB_MASK,
+];
+
+impl Elem {
+    pub const ZERO: Self = Self([0; ELEM_LIMBS]);
+    pub const ONE: Self = Self([1, 0, 0, 0, 0, 0, 0, 0]);
+
+    pub fn from_u32(value: u32) -> Self {
+        Self([u64::from(value), 0, 0, 0, 0, 0, 0, 0])
+    }
+
+    // Decodes a little-endian encoded value, which may be non-canonical, i.e.
+    // not less than p.
+    pub fn from_le_bytes(bytes: &[u8; ELEM_LEN]) -> Self {
+        let mut r = Self::ZERO;
+        for (limb, bytes) in r.0.iter_mut().zip(bytes.chunks_exact(7)) {
+            let mut padded = [0u8; 8];
+            padded[..7].copy_from_slice(bytes);
+            *limb = u64::from_le_bytes(padded);
+        }
+        r
+    }
+
+    // Returns the canonical little-endian encoding of `self`.
+    pub fn to_le_bytes(self) -> [u8; ELEM_LEN] {
+        let mut reduced = self;
+        reduced.strong_reduce();
+        let mut bytes = [0u8; ELEM_LEN];
+        for (bytes, limb) in bytes.chunks_exact_mut(7).zip(reduced.0.iter()) {
+            bytes.copy_from_slice(&limb.to_le_bytes()[..7]);
+        }
+        bytes
+    }
+
+    pub fn add(&self, b: &Self) -> Self {
+        let mut r = Self::ZERO;
+        for ((r, a), b) in r.0.iter_mut().zip(self.0.iter()).zip(b.0.iter()) {
+            *r = a + b;
+        }
+        r.weak_reduce();
+        r
+    }
+
+    pub fn sub(&self, b: &Self) -> Self {
+        // Compute `self + 2p - b` so that no limb underflows.
+        let mut r = Self::ZERO;
+        for (((r, a), b), p) in
+            r.0.iter_mut()
+                .zip(self.0.iter())
+                .zip(b.0.iter())
+                .zip(P.iter())
+        {
+            *r = (a + 2 * p) - b;
+        }
+        r.weak_reduce();
+        r
+    }
+
+    pub fn neg(&self) -> Self {
+        Self::ZERO.sub(self)
+    }
+
+    pub fn mul(&self, b: &Self) -> Self {
+        let a = &self.0;
+        let b = &b.0;
+
+        let mut wide = [0u128; 2 * ELEM_LIMBS - 1];
+        for (i, a) in a.iter().enumerate() {
+            for (j, b) in b.iter().enumerate() {
+                wide[i + j] += u128::from(*a) * u128::from(*b);
+            }
+        }
+
+        // 2**448 == 2**224 + 1 (mod p). Fold the limbs from the top down so
+        // that the limbs folded into positions 8..12 are themselves folded.
+        for i in (ELEM_LIMBS..(2 * ELEM_LIMBS - 1)).rev() {
+            let top = wide[i];
+            wide[i - ELEM_LIMBS] += top;
+            wide[i - (ELEM_LIMBS / 2)] += top;
+        }
+
+        // Carry, folding the bits above 2**448 back in as above.
+        for i in 0..(ELEM_LIMBS - 1) {
+            wide[i + 1] += wide[i] >> LIMB_BITS;
+            wide[i] &= u128::from(LIMB_MASK);
+        }
+        let top = wide[ELEM_LIMBS - 1] >> LIMB_BITS;
+        wide[ELEM_LIMBS - 1] &= u128::from(LIMB_MASK);
+        wide[0] += top;
+        wide[ELEM_LIMBS / 2] += top;
+
+        let mut r = Self::ZERO;
+        for (r, wide) in r.0.iter_mut().zip(wide.iter()) {
+            #[allow(clippy::cast_possible_truncation)]
+            let limb = *wide as u64;
+            *r = limb;
+        }
+        r.weak_reduce();
+        r
+    }
+
+    pub fn square(&self) -> Self {
+        self.mul(self)
+    }
+
+    // Returns `self` squared `n` times.
+    fn square_times(&self, n: usize) -> Self {
+        let mut r = *self;
+        for _ in 0..n {
+            r = r.square();
+        }
+        r
+    }
+
+    // Returns (self**(2**222 - 1), self**(2**223 - 1)).
+    fn pow_2_222_minus_1_and_2_223_minus_1(&self) -> (Self, Self) {
+        let x1 = *self;
+        let x2 = x1.square().mul(&x1);
+        let x3 = x2.square().mul(&x1);
+        let x6 = x3.square_times(3).mul(&x3);
+        let x12 = x6.square_times(6).mul(&x6);
+        let x24 = x12.square_times(12).mul(&x12);
+        let x48 = x24.square_times(24).mul(&x24);
+        let x96 = x48.square_times(48).mul(&x48);
+        let x192 = x96.square_times(96).mul(&x96);
+        let x216 = x192.square_times(24).mul(&x24);
+        let x219 = x216.square_times(3).mul(&x3);
+        let x222 = x219.square_times(3).mul(&x3);
+        let x223 = x222.square().mul(&x1);
+        (x222, x223)
+    }
+
+    // Returns self**(p - 2), which is the inverse of `self`, or zero if
+    // `self` is zero.
+    pub fn invert(&self) -> Self {
+        // p - 2 is 223 one bits, a zero bit, 222 one bits, a zero bit, and a
+        // one bit.
+        let (x222, x223) = self.pow_2_222_minus_1_and_2_223_minus_1();
+        let r = x223.square().square_times(222).mul(&x222);
+        r.square_times(2).mul(self)
+    }
+
+    // Returns self**((p - 3) / 4).
+    fn pow_p_minus_3_over_4(&self) -> Self {
+        // (p - 3) / 4 is 223 one bits, a zero bit, and 222 one bits.
+        let (x222, x223) = self.pow_2_222_minus_1_and_2_223_minus_1();
+        x223.square().square_times(222).mul(&x222)
+    }
+
+    // Returns sqrt(u / v) if it exists. This is not constant-time with respect
+    // to whether the square root exists.
+    pub fn sqrt_ratio_vartime(u: &Self, v: &Self) -> Result<Self, error::Unspecified> {
+        // RFC 8032 Section 5.2.3, Step 3:
+        // x = u**3 * v * (u**5 * v**3)**((p-3)/4).
+        let u2 = u.square();
+        let u3 = u2.mul(u);
+        let u5 = u3.mul(&u2);
+        let v3 = v.square().mul(v);
+        let x = u3.mul(v).mul(&u5.mul(&v3).pow_p_minus_3_over_4());
+
+        // Step 4.
+        if !v.mul(&x.square()).equals_vartime(u) {
+            return Err(error::Unspecified);
+        }
+        Ok(x)
+    }
+
+    pub fn is_zero_vartime(&self) -> bool {
+        self.to_le_bytes().iter().all(|&b| b == 0)
+    }
+
+    pub fn equals_vartime(&self, other: &Self) -> bool {
+        self.to_le_bytes() == other.to_le_bytes()
+    }
+
+    // Returns whether the canonical value of `self` is odd.
+    pub fn is_negative(&self) -> u8 {
+        self.to_le_bytes()[0] & 1
+    }
+
+    // Sets `self` to `other` if `mask` is all ones; leaves `self` unchanged
+    // if `mask` is zero.
+    pub fn cmov(&mut self, other: &Self, mask: u64) {
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a ^= mask & (*a ^ *b);
+        }
+    }
+
+    // Swaps `a` and `b` if `mask` is all ones; leaves them unchanged if
+    // `mask` is zero.
+    pub fn cswap(a: &mut Self, b: &mut Self, mask: u64) {
+        for (a, b) in a.0.iter_mut().zip(b.0.iter_mut()) {
+            let t = mask & (*a ^ *b);
+            *a ^= t;
+            *b ^= t;
+        }
+    }
+
+    // Carries each limb into the next, folding the bits above 2**448 back in
+    // using 2**448 == 2**224 + 1 (mod p).
+    fn weak_reduce(&mut self) {
+        let top = self.0[ELEM_LIMBS - 1] >> LIMB_BITS;
+        self.0[ELEM_LIMBS - 1] &= LIMB_MASK;
+        self.0[0] += top;
+        self.0[ELEM_LIMBS / 2] += top;
+        for i in 0..(ELEM_LIMBS - 1) {
+            self.0[i + 1] += self.0[i] >> LIMB_BITS;
+            self.0[i] &= LIMB_MASK;
+        }
+    }
+
+    // Reduces `self` to its canonical value in [0, p).
+    fn strong_reduce(&mut self) {
+        self.weak_reduce();
+        self.weak_reduce();
+
+        // Now `self` < 2p. Subtract p, then add it back if that underflowed.
+        let mut borrow: i128 = 0;
+        for (limb, p) in self.0.iter_mut().zip(P.iter()) {
+            borrow += i128::from(*limb) - i128::from(*p);
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let low = (borrow as u64) & LIMB_MASK;
+            *limb = low;
+            borrow >>= LIMB_BITS;
+        }
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let underflowed = borrow as u64;
+        let mut carry: u64 = 0;
+        for (limb, p) in self.0.iter_mut().zip(P.iter()) {
+            carry += *limb + (underflowed & p);
+            *limb = carry & LIMB_MASK;
+            carry >>= LIMB_BITS;
+        }
+    }
+}
+
+// d = -39081.
+fn edwards_d() -> Elem {
+    Elem::from_u32(39081).neg()
+}
+
+/// The length of an encoded Edwards448 point.
+pub const ENCODED_POINT_LEN: usize = ELEM_LEN + 1;
+
+pub type EncodedPoint = [u8; ENCODED_POINT_LEN];
+
+// A point on Edwards448 in projective coordinates (X : Y : Z), where
+// x = X/Z and y = Y/Z.
+#[derive(Clone, Copy)]
+pub struct Point {
+    x: Elem,
+    y: Elem,
+    z: Elem,
+}
+
+impl Point {
+    const IDENTITY: Self = Self {
+        x: Elem::ZERO,
+        y: Elem::ONE,
+        z: Elem::ONE,
+    };
+
+    // The base point B of RFC 8032 Section 5.2.
+    const BASE: Self = Self {
+        x: Elem([
+            0x26a82bc70cc05e,
+            0x80e18b00938e26,
+            0xf72ab66511433b,
+            0xa3d3a46412ae1a,
+            0x0f1767ea6de324,
+            0x36da9e14657047,
+            0xed221d15a622bf,
+            0x4f1970c66bed0d,
+        ]),
+        y: Elem([
+            0x08795bf230fa14,
+            0x132c4ed7c8ad98,
+            0x1ce67c39c4fdbd,
+            0x05a0c2d73ad3ff,
+            0xa3984087789c1e,
+            0xc7624bea73736c,
+            0x248876203756c9,
+            0x693f46716eb6bc,
+        ]),
+        z: Elem::ONE,
+    };
+
+    // RFC 8032 Section 5.2.3.
+    pub fn from_encoded_point_vartime(encoded: &EncodedPoint) -> Result<Self, error::Unspecified> {
+        // Step 1.
+        let (y_bytes, last) = encoded.split_at(ELEM_LEN);
+        if last[0] & 0x7f != 0 {
+            return Err(error::Unspecified);
+        }
+        let x_0 = last[0] >> 7;
+        let y_bytes: &[u8; ELEM_LEN] = y_bytes.try_into()?;
+        let y = Elem::from_le_bytes(y_bytes);
+        if y.to_le_bytes() != *y_bytes {
+            return Err(error::Unspecified);
+        }
+
+        // Steps 2 and 3: u = y**2 - 1, v = d*y**2 - 1.
+        let y2 = y.square();
+        let u = y2.sub(&Elem::ONE);
+        let v = edwards_d().mul(&y2).sub(&Elem::ONE);
+        let mut x = Elem::sqrt_ratio_vartime(&u, &v)?;
+
+        // Step 4.
+        if x.is_zero_vartime() && x_0 == 1 {
+            return Err(error::Unspecified);
+        }
+        if x.is_negative() != x_0 {
+            x = x.neg();
+        }
+
+        Ok(Self { x, y, z: Elem::ONE })
+    }
+
+    // RFC 8032 Section 5.2.2.
+    pub fn into_encoded_point(self) -> EncodedPoint {
+        let z_inv = self.z.invert();
+        let x = self.x.mul(&z_inv);
+        let y = self.y.mul(&z_inv);
+        let mut encoded = [0u8; ENCODED_POINT_LEN];
+        encoded[..ELEM_LEN].copy_from_slice(&y.to_le_bytes());
+        encoded[ELEM_LEN] = x.is_negative() << 7;
+        encoded
+    }
+
+    // RFC 8032 Section 5.2.4.
+    pub fn add(&self, other: &Self) -> Self {
+        let a = self.z.mul(&other.z);
+        let b = a.square();
+        let c = self.x.mul(&other.x);
+        let d = self.y.mul(&other.y);
+        let e = edwards_d().mul(&c).mul(&d);
+        let f = b.sub(&e);
+        let g = b.add(&e);
+        let h = self.x.add(&self.y).mul(&other.x.add(&other.y));
+        Self {
+            x: a.mul(&f).mul(&h.sub(&c).sub(&d)),
+            y: a.mul(&g).mul(&d.sub(&c)),
+            z: f.mul(&g),
+        }
+    }
+
+    // RFC 8032 Section 5.2.4.
+    pub fn double(&self) -> Self {
+        let b = self.x.add(&self.y).square();
+        let c = self.x.square();
+        let d = self.y.square();
+        let e = c.add(&d);
+        let h = self.z.square();
+        let j = e.sub(&h.add(&h));
+        Self {
+            x: b.sub(&e).mul(&j),
+            y: e.mul(&c.sub(&d)),
+            z: e.mul(&j),
+        }
+    }
+
+    pub fn neg(&self) -> Self {
+        Self {
+            x: self.x.neg(),
+            y: self.y,
+            z: self.z,
+        }
+    }
+
+    // Returns [scalar]B.
+    pub fn from_scalarmult_base_consttime(scalar: &Scalar) -> Self {
+        Self::BASE.mul_consttime(scalar)
+    }
+
+    // Returns [scalar]self, using fixed 4-bit windows.
+    pub fn mul_consttime(&self, scalar: &Scalar) -> Self {
+        const WINDOW_BITS: usize = 4;
+        const TABLE_LEN: usize = 1 << WINDOW_BITS;
+
+        let mut table = [Self::IDENTITY; TABLE_LEN];
+        for i in 1..TABLE_LEN {
+            table[i] = table[i - 1].add(self);
+        }
+
+        let scalar = scalar.to_le_bytes();
+        let mut acc = Self::IDENTITY;
+        for window in (0..(SCALAR_LEN * 8 / WINDOW_BITS)).rev() {
+            for _ in 0..WINDOW_BITS {
+                acc = acc.double();
+            }
+            let digit = (scalar[window / 2] >> ((window % 2) * WINDOW_BITS)) & 0xf;
+            let mut selected = Self::IDENTITY;
+            for (i, entry) in table.iter().enumerate() {
+                #[allow(clippy::cast_possible_truncation)]
+                let mask = eq_mask(digit, i as u8);
+                selected.x.cmov(&entry.x, mask);
+                selected.y.cmov(&entry.y, mask);
+                selected.z.cmov(&entry.z, mask);
+            }
+            acc = acc.add(&selected);
+        }
+        acc
+    }
+}
+
+// Returns all ones if `a == b`, or zero otherwise.
+fn eq_mask(a: u8, b: u8) -> u64 {
+    let diff = u64::from(a ^ b);
+    // `diff - 1` underflows, setting the high bit, only if `diff` is zero.
+    0u64.wrapping_sub(diff.wrapping_sub(1) >> 63)
+}
+
+// RFC 7748 Section 5: The X448 function.
+pub fn x448(scalar: &[u8; ELEM_LEN], u: &[u8; ELEM_LEN]) -> [u8; ELEM_LEN] {
+    // Decode the scalar.
+    let mut k = *scalar;
+    k[0] &= 252;
+    k[ELEM_LEN - 1] |= 128;
+
+    // (156326 - 2) / 4.
+    let a24 = Elem::from_u32(39081);
+
+    let x_1 = Elem::from_le_bytes(u);
+    let mut x_2 = Elem::ONE;
+    let mut z_2 = Elem::ZERO;
+    let mut x_3 = x_1;
+    let mut z_3 = Elem::ONE;
+    let mut swap = 0;
+
+    for t in (0..(ELEM_LEN * 8)).rev() {
+        let k_t = u64::from((k[t / 8] >> (t % 8)) & 1);
+        swap ^= k_t;
+        let mask = 0u64.wrapping_sub(swap);
+        Elem::cswap(&mut x_2, &mut x_3, mask);
+        Elem::cswap(&mut z_2, &mut z_3, mask);
+        swap = k_t;
+
+        let a = x_2.add(&z_2);
+        let aa = a.square();
+        let b = x_2.sub(&z_2);
+        let bb = b.square();
+        let e = aa.sub(&bb);
+        let c = x_3.add(&z_3);
+        let d = x_3.sub(&z_3);
+        let da = d.mul(&a);
+        let cb = c.mul(&b);
+        x_3 = da.add(&cb).square();
+        z_3 = x_1.mul(&da.sub(&cb).square());
+        x_2 = aa.mul(&bb);
+        z_2 = e.mul(&aa.add(&a24.mul(&e)));
+    }
+    let mask = 0u64.wrapping_sub(swap);
+    Elem::cswap(&mut x_2, &mut x_3, mask);
+    Elem::cswap(&mut z_2, &mut z_3, mask);
+
+    x_2.mul(&z_2.invert()).to_le_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elem_reduction() {
+        // p - 1, p, and p + 1 in their non-canonical forms.
+        let mut p_minus_1 = [0xffu8; ELEM_LEN];
+        p_minus_1[0] = 0xfe;
+        p_minus_1[28] = 0xfe;
+        assert_eq!(Elem::from_le_bytes(&p_minus_1).to_le_bytes(), p_minus_1);
+
+        let mut p = p_minus_1;
+        p[0] = 0xff;
+        assert_eq!(Elem::from_le_bytes(&p).to_le_bytes(), [0u8; ELEM_LEN]);
+
+        let all_ones = [0xffu8; ELEM_LEN];
+        let mut expected = [0u8; ELEM_LEN];
+        expected[28] = 1;
+        assert_eq!(Elem::from_le_bytes(&all_ones).to_le_bytes(), expected);
+
+        // (p - 1)**2 == 1.
+        let p_minus_1 = Elem::from_le_bytes(&p_minus_1);
+        assert!(p_minus_1.square().equals_vartime(&Elem::ONE));
+        assert!(p_minus_1.add(&Elem::ONE).is_zero_vartime());
+        assert!(Elem::ZERO.sub(&Elem::ONE).equals_vartime(&p_minus_1));
+    }
+
+    #[test]
+    fn test_elem_invert() {
+        let a = Elem::from_u32(12345);
+        assert!(a.mul(&a.invert()).equals_vartime(&Elem::ONE));
+        let b = Elem::from_le_bytes(&[0xa5; ELEM_LEN]);
+        assert!(b.mul(&b.invert()).equals_vartime(&Elem::ONE));
+        assert!(Elem::ZERO.invert().is_zero_vartime());
+    }
+
+    #[test]
+    fn test_base_point_encoding() {
+        let encoded = Point::BASE.into_encoded_point();
+        let decoded = Point::from_encoded_point_vartime(&encoded).unwrap();
+        assert_eq!(decoded.into_encoded_point(), encoded);
+        assert_eq!(
+            Point::BASE.double().into_encoded_point(),
+            Point::BASE.add(&Point::BASE).into_encoded_point()
+        );
+        assert_eq!(
+            Point::BASE.add(&Point::BASE.neg()).into_encoded_point(),
+            Point::IDENTITY.into_encoded_point()
+        );
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use crate::{
+    error,
+    limb::{self, Limb},
+};
+
+// A scalar in the range [0, n), where n is the order of the Edwards448 base
+// point, as seven 64-bit limbs in little-endian order.
+#[derive(Clone, Copy)]
+pub struct Scalar([u64; SCALAR_LIMBS]);
+
+const SCALAR_LIMBS: usize = 7;
+
+/// The length of a scalar as used in scalar multiplication.
+pub const SCALAR_LEN: usize = SCALAR_LIMBS * 8;
+
+/// The length of an encoded scalar in an Ed448 signature.
+pub const ENCODED_SCALAR_LEN: usize = SCALAR_LEN + 1;
+
+// n = 2**446 - 13818066809895115352007386748515426880336692474882178609894547503885.
+const ORDER: [u64; SCALAR_LIMBS] = [
+    0x2378c292ab5844f3,
+    0x216cc2728dc58f55,
+    0xc44edb49aed63690,
+    0xffffffff7cca23e9,
+    0xffffffffffffffff,
+    0xffffffffffffffff,
+    0x3fffffffffffffff,
+];
+
+// 2**446 - n.
+const TWO_446_MINUS_ORDER: [u64; 4] = [
+    0xdc873d6d54a7bb0d,
+    0xde933d8d723a70aa,
+    0x3bb124b65129c96f,
+    0x000000008335dc16,
+];
+
+// The number of bits of n in its top limb: 446 - (64 * 6).
+const TOP_LIMB_BITS: u32 = 62;
+
+// Enough limbs for a 114-byte digest or the sum of a product of two scalars
+// and a scalar.
+const WIDE_LIMBS: usize = 16;
+
+impl Scalar {
+    // Constructs a `Scalar` from `bytes`, failing if `bytes` encodes a scalar
+    // that is not in the range [0, n).
+    pub fn from_bytes_checked(
+        bytes: &[u8; ENCODED_SCALAR_LEN],
+    ) -> Result<Self, error::Unspecified> {
+        if bytes[SCALAR_LEN] != 0 {
+            return Err(error::Unspecified);
+        }
+        let mut limbs = [0; SCALAR_LIMBS];
+        for (limb, bytes) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(bytes.try_into()?);
+        }
+        let (_, borrow) = sub_order(&limbs);
+        if borrow == 0 {
+            return Err(error::Unspecified);
+        }
+        Ok(Self(limbs))
+    }
+
+    // Constructs a `Scalar` from the little-endian encoded `bytes` reduced
+    // modulo n.
+    pub fn from_bytes_reduced(bytes: &[u8]) -> Self {
+        assert!(bytes.len() <= WIDE_LIMBS * 8);
+        let mut wide = [0u64; WIDE_LIMBS];
+        for (limb, bytes) in wide.iter_mut().zip(bytes.chunks(8)) {
+            let mut padded = [0u8; 8];
+            padded[..bytes.len()].copy_from_slice(bytes);
+            *limb = u64::from_le_bytes(padded);
+        }
+        Self::from_wide_reduced(wide)
+    }
+
+    // Returns `a * b + c` (mod n).
+    pub fn muladd(a: &Self, b: &Self, c: &Self) -> Self {
+        let mut wide = [0u64; WIDE_LIMBS];
+        wide[..SCALAR_LIMBS].copy_from_slice(&c.0);
+        for (i, a) in a.0.iter().enumerate() {
+            mul_add_limb(&mut wide[i..], *a, &b.0);
+        }
+        Self::from_wide_reduced(wide)
+    }
+
+    pub fn to_le_bytes(self) -> [u8; SCALAR_LEN] {
+        let mut bytes = [0u8; SCALAR_LEN];
+        for (bytes, limb) in bytes.chunks_exact_mut(8).zip(self.0.iter()) {
+            bytes.copy_from_slice(&limb.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn to_encoded(self) -> [u8; ENCODED_SCALAR_LEN] {
+        let mut encoded = [0u8; ENCODED_SCALAR_LEN];
+        encoded[..SCALAR_LEN].copy_from_slice(&self.to_le_bytes());
+        encoded
+    }
+
+    fn from_wide_reduced(mut wide: [u64; WIDE_LIMBS]) -> Self {
+        // 2**446 == 2**446 - n (mod n), which is less than 2**224, so each
+        // round replaces `lo + hi * 2**446` with `lo + hi * (2**446 - n)`,
+        // shrinking the value by about 222 bits until it is less than 2**446.
+        for _ in 0..5 {
+            let mut hi = [0u64; WIDE_LIMBS - SCALAR_LIMBS + 1];
+            for (i, hi) in hi.iter_mut().enumerate() {
+                let lo = wide[SCALAR_LIMBS - 1 + i] >> TOP_LIMB_BITS;
+                let hi_bits = wide
+                    .get(SCALAR_LIMBS + i)
+                    .map_or(0, |limb| limb << (64 - TOP_LIMB_BITS));
+                *hi = lo | hi_bits;
+            }
+            wide[SCALAR_LIMBS - 1] &= (1 << TOP_LIMB_BITS) - 1;
+            wide[SCALAR_LIMBS..].iter_mut().for_each(|limb| *limb = 0);
+            for (i, hi) in hi.iter().enumerate() {
+                mul_add_limb(&mut wide[i..], *hi, &TWO_446_MINUS_ORDER);
+            }
+        }
+
+        // Now the value is less than 2**446 < 2n, so subtracting n at most
+        // once reduces it. Selecting between the value and the difference in
+        // Rust was compiled into a branch on the (secret) top limb, so use
+        // the C implementation instead.
+        let mut limbs = [0; SCALAR_LIMBS];
+        limbs.copy_from_slice(&wide[..SCALAR_LIMBS]);
+        let mut r = to_limbs(&limbs);
+        limb::limbs_reduce_once_constant_time(&mut r, &to_limbs(&ORDER));
+        for (limb, bytes) in limbs.iter_mut().zip(limbs_to_le_bytes(&r).chunks_exact(8)) {
+            *limb = u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+        Self(limbs)
+    }
+}
+
+// `Limb`s are 32 bits on some targets, so the limbs of a `Scalar` are
+// converted through their little-endian encoding.
+type Limbs = [Limb; SCALAR_LEN / limb::LIMB_BYTES];
+
+fn to_limbs(a: &[u64; SCALAR_LIMBS]) -> Limbs {
+    let mut r = [0; SCALAR_LEN / limb::LIMB_BYTES];
+    for (r, bytes) in r
+        .iter_mut()
+        .zip(Scalar(*a).to_le_bytes().chunks_exact(limb::LIMB_BYTES))
+    {
+        *r = Limb::from_le_bytes(bytes.try_into().unwrap());
+    }
+    r
+}
+
+fn limbs_to_le_bytes(a: &Limbs) -> [u8; SCALAR_LEN] {
+    let mut bytes = [0u8; SCALAR_LEN];
+    for (bytes, a) in bytes.chunks_exact_mut(limb::LIMB_BYTES).zip(a.iter()) {
+        bytes.copy_from_slice(&a.to_le_bytes());
+    }
+    bytes
+}
+
+// Sets `acc += a * b`, propagating the carry through the rest of `acc`.
+fn mul_add_limb(acc: &mut [u64], a: u64, b: &[u64]) {
+    let mut carry: u64 = 0;
+    let (low, high) = acc.split_at_mut(b.len());
+    for (acc, b) in low.iter_mut().zip(b.iter()) {
+        let t = u128::from(*acc) + u128::from(a) * u128::from(*b) + u128::from(carry);
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            *acc = t as u64;
+            carry = (t >> 64) as u64;
+        }
+    }
+    for acc in high {
+        let (sum, overflowed) = acc.overflowing_add(carry);
+        *acc = sum;
+        carry = u64::from(overflowed);
+    }
+}
+
+// Returns `a - n` and the final borrow, which is 1 if `a < n`.
+fn sub_order(a: &[u64; SCALAR_LIMBS]) -> ([u64; SCALAR_LIMBS], u64) {
+    let mut r = [0; SCALAR_LIMBS];
+    let mut borrow = 0;
+    for ((r, a), n) in r.iter_mut().zip(a.iter()).zip(ORDER.iter()) {
+        let (diff, b1) = a.overflowing_sub(*n);
+        let (diff, b2) = diff.overflowing_sub(borrow);
+        *r = diff;
+        borrow = u64::from(b1 | b2);
+    }
+    (r, borrow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order_bytes() -> [u8; ENCODED_SCALAR_LEN] {
+        Scalar(ORDER).to_encoded()
+    }
+
+    #[test]
+    fn test_from_bytes_checked() {
+        let mut n = order_bytes();
+        assert!(Scalar::from_bytes_checked(&n).is_err());
+        n[0] -= 1;
+        assert!(Scalar::from_bytes_checked(&n).is_ok());
+        let mut top = [0u8; ENCODED_SCALAR_LEN];
+        top[SCALAR_LEN] = 1;
+        assert!(Scalar::from_bytes_checked(&top).is_err());
+    }
+
+    #[test]
+    fn test_reduction() {
+        let n = order_bytes();
+        assert_eq!(
+            Scalar::from_bytes_reduced(&n).to_le_bytes(),
+            [0; SCALAR_LEN]
+        );
+
+        let mut n_plus_1 = n;
+        n_plus_1[0] += 1;
+        let mut one = [0; SCALAR_LEN];
+        one[0] = 1;
+        assert_eq!(Scalar::from_bytes_reduced(&n_plus_1).to_le_bytes(), one);
+
+        // (n - 1) * (n - 1) + (n - 1) == 0 (mod n).
+        let mut n_minus_1 = n;
+        n_minus_1[0] -= 1;
+        let n_minus_1 = Scalar::from_bytes_checked(&n_minus_1).unwrap();
+        let r = Scalar::muladd(&n_minus_1, &n_minus_1, &n_minus_1);
+        assert_eq!(r.to_le_bytes(), [0; SCALAR_LEN]);
+
+        // 2**912 - 1, the largest value of `from_bytes_reduced` as used,
+        // reduces to a value less than n.
+        let r = Scalar::from_bytes_reduced(&[0xff; 114]);
+        let (_, borrow) = sub_order(&r.0);
+        assert_eq!(borrow, 1);
+    }
+}
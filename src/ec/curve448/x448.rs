@@ -0,0 +1,109 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! X448 Key agreement.
+
+use super::ops::{self, ELEM_LEN};
+use crate::{agreement, constant_time, ec, error, rand};
+
+static CURVE448: ec::Curve = ec::Curve {
+    public_key_len: PUBLIC_KEY_LEN,
+    elem_scalar_seed_len: ELEM_AND_SCALAR_LEN,
+    id: ec::CurveID::Curve448,
+    check_private_key_bytes: x448_check_private_key_bytes,
+    generate_private_key: x448_generate_private_key,
+    public_from_private: x448_public_from_private,
+};
+
+/// X448 (ECDH using Curve448) as described in [RFC 7748].
+///
+/// Everything is as described in RFC 7748. Key agreement will fail if the
+/// result of the X448 operation is zero; see the notes on the
+/// "all-zero value" in [RFC 7748 section 6.2].
+///
+/// X448 is implemented in portable Rust instead of the C and assembly code
+/// that *ring* uses for X25519. It is written to be constant-time, and the
+/// code the compiler generates for x86_64 has been checked for secret-dependent
+/// branches, but it hasn't been audited and it is much slower. Prefer X25519
+/// where the protocol allows it.
+///
+/// [RFC 7748]: https://tools.ietf.org/html/rfc7748
+/// [RFC 7748 section 6.2]: https://tools.ietf.org/html/rfc7748#section-6.2
+pub static X448: agreement::Algorithm = agreement::Algorithm {
+    curve: &CURVE448,
+    ecdh: x448_ecdh,
+};
+
+#[allow(clippy::unnecessary_wraps)]
+fn x448_check_private_key_bytes(bytes: &[u8]) -> Result<(), error::Unspecified> {
+    debug_assert_eq!(bytes.len(), PRIVATE_KEY_LEN);
+    Ok(())
+}
+
+fn x448_generate_private_key(
+    rng: &dyn rand::SecureRandom,
+    out: &mut [u8],
+) -> Result<(), error::Unspecified> {
+    rng.fill(out)
+}
+
+fn x448_public_from_private(
+    public_out: &mut [u8],
+    private_key: &ec::Seed,
+) -> Result<(), error::Unspecified> {
+    // The u-coordinate of the base point is 5.
+    const MONTGOMERY_BASE_POINT: PublicKey = {
+        let mut u = [0u8; PUBLIC_KEY_LEN];
+        u[0] = 5;
+        u
+    };
+
+    let public_out: &mut PublicKey = public_out.try_into()?;
+    let private_key: &PrivateKey = private_key.bytes_less_safe().try_into()?;
+    *public_out = ops::x448(private_key, &MONTGOMERY_BASE_POINT);
+    Ok(())
+}
+
+fn x448_ecdh(
+    out: &mut [u8],
+    my_private_key: &ec::Seed,
+    peer_public_key: untrusted::Input,
+) -> Result<(), error::Unspecified> {
+    let out: &mut SharedSecret = out.try_into()?;
+    let my_private_key: &PrivateKey = my_private_key.bytes_less_safe().try_into()?;
+    let peer_public_key: &PublicKey = peer_public_key.as_slice_less_safe().try_into()?;
+
+    *out = ops::x448(my_private_key, peer_public_key);
+
+    let zeros: SharedSecret = [0; SHARED_SECRET_LEN];
+    if constant_time::verify_slices_are_equal(out, &zeros).is_ok() {
+        // All-zero output results when the input is a point of small order.
+        return Err(error::Unspecified);
+    }
+
+    Ok(())
+}
+
+const ELEM_AND_SCALAR_LEN: usize = ELEM_LEN;
+
+type PrivateKey = [u8; PRIVATE_KEY_LEN];
+const PRIVATE_KEY_LEN: usize = ELEM_AND_SCALAR_LEN;
+
+// An X448 public key as an encoded Curve448 u-coordinate.
+type PublicKey = [u8; PUBLIC_KEY_LEN];
+const PUBLIC_KEY_LEN: usize = ELEM_AND_SCALAR_LEN;
+
+// An X448 shared secret as an encoded Curve448 u-coordinate.
+type SharedSecret = [u8; SHARED_SECRET_LEN];
+const SHARED_SECRET_LEN: usize = ELEM_AND_SCALAR_LEN;
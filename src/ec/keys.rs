@@ -1,5 +1,5 @@
 use super::{Curve, ELEM_MAX_BYTES, SEED_MAX_BYTES};
-use crate::{cpu, error, rand};
+use crate::{cpu, error, io::der, pkcs8, rand};
 
 pub struct KeyPair {
     seed: Seed,
@@ -20,6 +20,36 @@ impl KeyPair {
     }
 }
 
+// Parses an unencrypted PKCS#8 v1 or v2 private key in the form used for
+// X25519 keys in RFC 8410. When the public key is present it must be
+// consistent with the private key.
+pub(crate) fn key_pair_from_rfc8410_pkcs8(
+    curve: &'static Curve,
+    template: &pkcs8::Template,
+    input: untrusted::Input,
+    cpu_features: cpu::Features,
+) -> Result<KeyPair, error::KeyRejected> {
+    let version = pkcs8::Version::V1OrV2(pkcs8::PublicKeyOptions {
+        accept_legacy_ed25519_public_key_tag: false,
+    });
+    let (private_key, public_key) = pkcs8::unwrap_key(template, version, input)?;
+    let private_key = private_key
+        .read_all(error::Unspecified, |input| {
+            der::expect_tag_and_get_value(input, der::Tag::OctetString)
+        })
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+    let seed = Seed::from_bytes(curve, private_key, cpu_features)
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_component())?;
+    let key_pair = KeyPair::derive(seed)
+        .map_err(|error::Unspecified| error::KeyRejected::unexpected_error())?;
+    if let Some(public_key) = public_key {
+        if public_key.as_slice_less_safe() != key_pair.public_key().as_ref() {
+            return Err(error::KeyRejected::inconsistent_components());
+        }
+    }
+    Ok(key_pair)
+}
+
 pub struct Seed {
     bytes: [u8; SEED_MAX_BYTES],
     curve: &'static Curve,
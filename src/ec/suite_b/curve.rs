@@ -42,7 +42,7 @@ macro_rules! suite_b_curve {
         };
 
         fn $check_private_key_bytes(bytes: &[u8]) -> Result<(), error::Unspecified> {
-            debug_assert_eq!(bytes.len(), $bits / 8);
+            debug_assert_eq!(bytes.len(), ($bits + 7) / 8);
             ec::suite_b::private_key::check_scalar_big_endian_bytes($private_key_ops, bytes)
         }
 
@@ -81,3 +81,23 @@ suite_b_curve!(
     p384_generate_private_key,
     p384_public_from_private
 );
+
+suite_b_curve!(
+    P521,
+    521,
+    &ec::suite_b::ops::p521::PRIVATE_KEY_OPS,
+    ec::CurveID::P521,
+    p521_check_private_key_bytes,
+    p521_generate_private_key,
+    p521_public_from_private
+);
+
+suite_b_curve!(
+    SECP256K1,
+    256,
+    &ec::suite_b::ops::secp256k1::PRIVATE_KEY_OPS,
+    ec::CurveID::Secp256k1,
+    secp256k1_check_private_key_bytes,
+    secp256k1_generate_private_key,
+    secp256k1_public_from_private
+);
@@ -12,7 +12,7 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-//! ECDH key agreement using the P-256 and P-384 curves.
+//! ECDH key agreement using the P-256, P-384, and P-521 curves.
 
 use super::{ops::*, private_key::*, public_key::*};
 use crate::{agreement, ec, error};
@@ -77,6 +77,15 @@ ecdh!(
     p384_ecdh
 );
 
+ecdh!(
+    ECDH_P521,
+    &ec::suite_b::curve::P521,
+    "P-521 (secp521r1)",
+    &p521::PRIVATE_KEY_OPS,
+    &p521::PUBLIC_KEY_OPS,
+    p521_ecdh
+);
+
 fn ecdh(
     private_key_ops: &PrivateKeyOps,
     public_key_ops: &PublicKeyOps,
@@ -141,9 +150,9 @@ fn ecdh(
 #[cfg(test)]
 mod tests {
     use super::super::ops;
-    use crate::{agreement, ec, limb, test};
+    use crate::{agreement, ec, test};
 
-    static SUPPORTED_SUITE_B_ALGS: [(&str, &agreement::Algorithm, &ec::Curve, &ops::CommonOps); 2] = [
+    static SUPPORTED_SUITE_B_ALGS: [(&str, &agreement::Algorithm, &ec::Curve, &ops::CommonOps); 3] = [
         (
             "P-256",
             &agreement::ECDH_P256,
@@ -156,6 +165,12 @@ mod tests {
             &super::super::curve::P384,
             &super::super::ops::p384::COMMON_OPS,
         ),
+        (
+            "P-521",
+            &agreement::ECDH_P521,
+            &super::super::curve::P521,
+            &super::super::ops::p521::COMMON_OPS,
+        ),
     ];
 
     #[test]
@@ -183,7 +198,7 @@ mod tests {
             // getting that value from the PRNG.
             let mut n_bytes = [0u8; ec::SCALAR_MAX_BYTES];
             let num_bytes = curve.elem_scalar_seed_len;
-            limb::big_endian_from_limbs(ops.n_limbs(), &mut n_bytes[..num_bytes]);
+            ops::big_endian_fixed_from_limbs(ops, ops.n_limbs(), &mut n_bytes[..num_bytes]);
             {
                 let n_bytes = &mut n_bytes[..num_bytes];
                 let rng = test::rand::FixedSliceRandom { bytes: n_bytes };
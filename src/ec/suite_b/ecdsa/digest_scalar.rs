@@ -40,8 +40,11 @@ use crate::{digest, ec::suite_b::ops::*};
 /// digest to 256 bits and converted it to an integer, it will have a value
 /// less than 2**256. If the value is larger than `n` then shifting it one bit
 /// right will give a value less than 2**255, which is less than `n`. The
-/// analogous argument applies for P-384. However, it does *not* apply in
-/// general; for example, it doesn't apply to P-521.
+/// analogous argument applies for P-384 and secp256k1. However, it does *not*
+/// apply in general; for example, it doesn't apply to P-521. For P-521 no
+/// reduction is needed at all because the longest supported digest, SHA-512,
+/// is shorter than `n`, so the digest is never truncated and its value is
+/// always less than `n`.
 pub fn digest_scalar(ops: &ScalarOps, msg: digest::Digest) -> Scalar {
     digest_scalar_(ops, msg.as_ref())
 }
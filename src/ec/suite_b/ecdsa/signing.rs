@@ -12,7 +12,7 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-//! ECDSA Signatures using the P-256 and P-384 curves.
+//! ECDSA Signatures using the P-256, P-384, P-521, and secp256k1 curves.
 
 use super::digest_scalar::digest_scalar;
 use crate::{
@@ -22,11 +22,20 @@ use crate::{
         self,
         suite_b::{ops::*, private_key},
     },
-    error,
+    error, hmac,
     io::der,
-    limb, pkcs8, rand, sealed, signature,
+    pkcs8, rand, sealed, signature,
 };
+use core::cell::RefCell;
+
 /// An ECDSA signing algorithm.
+///
+/// The `*_DETERMINISTIC` algorithms derive each nonce from the private key
+/// and the digest of the message as specified in [RFC 6979] instead of
+/// generating it with the RNG passed to `EcdsaKeyPair::sign()`, so signing the
+/// same message with the same key always produces the same signature.
+///
+/// [RFC 6979]: https://tools.ietf.org/html/rfc6979
 pub struct EcdsaSigningAlgorithm {
     curve: &'static ec::Curve,
     private_scalar_ops: &'static PrivateScalarOps,
@@ -34,6 +43,14 @@ pub struct EcdsaSigningAlgorithm {
     digest_alg: &'static digest::Algorithm,
     pkcs8_template: &'static pkcs8::Template,
     format_rs: fn(ops: &'static ScalarOps, r: &Scalar, s: &Scalar, out: &mut [u8]) -> usize,
+
+    // Whether to replace `s` with `n - s` when `s` > (n - 1) / 2.
+    low_s: bool,
+
+    // When set, nonces are generated deterministically as specified in
+    // RFC 6979 using HMAC with this algorithm, instead of by the RNG.
+    rfc6979_hmac: Option<&'static hmac::Algorithm>,
+
     id: AlgorithmID,
 }
 
@@ -43,6 +60,20 @@ enum AlgorithmID {
     ECDSA_P384_SHA384_FIXED_SIGNING,
     ECDSA_P256_SHA256_ASN1_SIGNING,
     ECDSA_P384_SHA384_ASN1_SIGNING,
+    ECDSA_P521_SHA512_FIXED_SIGNING,
+    ECDSA_P521_SHA512_ASN1_SIGNING,
+    ECDSA_SECP256K1_SHA256_FIXED_SIGNING,
+    ECDSA_SECP256K1_SHA256_ASN1_SIGNING,
+    ECDSA_SECP256K1_SHA256_LOW_S_FIXED_SIGNING,
+    ECDSA_SECP256K1_SHA256_LOW_S_ASN1_SIGNING,
+    ECDSA_P256_SHA256_FIXED_SIGNING_DETERMINISTIC,
+    ECDSA_P384_SHA384_FIXED_SIGNING_DETERMINISTIC,
+    ECDSA_P256_SHA256_ASN1_SIGNING_DETERMINISTIC,
+    ECDSA_P384_SHA384_ASN1_SIGNING_DETERMINISTIC,
+    ECDSA_P521_SHA512_FIXED_SIGNING_DETERMINISTIC,
+    ECDSA_P521_SHA512_ASN1_SIGNING_DETERMINISTIC,
+    ECDSA_SECP256K1_SHA256_LOW_S_FIXED_SIGNING_DETERMINISTIC,
+    ECDSA_SECP256K1_SHA256_LOW_S_ASN1_SIGNING_DETERMINISTIC,
 }
 
 derive_debug_via_id!(EcdsaSigningAlgorithm);
@@ -166,6 +197,9 @@ impl EcdsaKeyPair {
     }
 
     /// Returns the signature of the `message` using a random nonce generated by `rng`.
+    ///
+    /// For the `*_DETERMINISTIC` algorithms, `rng` is not used; see
+    /// [`EcdsaSigningAlgorithm`].
     pub fn sign(
         &self,
         rng: &dyn rand::SecureRandom,
@@ -174,16 +208,43 @@ impl EcdsaKeyPair {
         // Step 4 (out of order).
         let h = digest::digest(self.alg.digest_alg, message);
 
-        // Incorporate `h` into the nonce to hedge against faulty RNGs. (This
-        // is not an approved random number generator that is mandated in
-        // the spec.)
-        let nonce_rng = NonceRandom {
-            key: &self.nonce_key,
-            message_digest: &h,
-            rng,
-        };
+        self.sign_digest(rng, h)
+    }
+
+    /// Returns the signature of the message that was digested to `h`.
+    ///
+    /// This is like `sign` except the message was already digested, e.g. by
+    /// a streaming pipeline or a remote client. `h` must have been computed
+    /// using the digest algorithm of the signing algorithm (e.g. SHA-256 for
+    /// `ECDSA_P256_SHA256_ASN1_SIGNING`); otherwise an error is returned.
+    pub fn sign_digest(
+        &self,
+        rng: &dyn rand::SecureRandom,
+        h: digest::Digest,
+    ) -> Result<signature::Signature, error::Unspecified> {
+        // Step 4 was done by the caller, so just check that it used the right
+        // hash function.
+        if h.algorithm() != self.alg.digest_alg {
+            return Err(error::Unspecified);
+        }
 
-        self.sign_digest(h, &nonce_rng)
+        match self.alg.rfc6979_hmac {
+            Some(hmac_alg) => {
+                let nonce_rng = NonceRfc6979::new(*hmac_alg, self, &h);
+                self.sign_digest_(h, &nonce_rng)
+            }
+            None => {
+                // Incorporate `h` into the nonce to hedge against faulty RNGs.
+                // (This is not an approved random number generator that is
+                // mandated in the spec.)
+                let nonce_rng = NonceRandom {
+                    key: &self.nonce_key,
+                    message_digest: &h,
+                    rng,
+                };
+                self.sign_digest_(h, &nonce_rng)
+            }
+        }
     }
 
     #[cfg(test)]
@@ -195,12 +256,12 @@ impl EcdsaKeyPair {
         // Step 4 (out of order).
         let h = digest::digest(self.alg.digest_alg, message);
 
-        self.sign_digest(h, rng)
+        self.sign_digest_(h, rng)
     }
 
     /// Returns the signature of message digest `h` using a "random" nonce
     /// generated by `rng`.
-    fn sign_digest(
+    fn sign_digest_(
         &self,
         h: digest::Digest,
         rng: &dyn rand::SecureRandom,
@@ -268,6 +329,16 @@ impl EcdsaKeyPair {
                 continue;
             }
 
+            // (r, n - s) is also a valid signature. Some protocols, such as
+            // Bitcoin, require the one with the smaller `s` to prevent
+            // signature malleability. `s` isn't secret since it is part of
+            // the signature, so this doesn't need to be constant-time.
+            let s = if self.alg.low_s && scalar_ops.scalar_is_high_vartime(&s) {
+                scalar_ops.scalar_negated(&s)
+            } else {
+                s
+            };
+
             // Step 7 with encoding.
             return Ok(signature::Signature::new(|sig_bytes| {
                 (self.alg.format_rs)(scalar_ops, &r, &s, sig_bytes)
@@ -295,39 +366,39 @@ impl core::fmt::Debug for NonceRandom<'_> {
 impl rand::sealed::SecureRandom for NonceRandom<'_> {
     fn fill_impl(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
         // Use the same digest algorithm that will be used to digest the
-        // message. The digest algorithm's output is exactly the right size;
-        // this is checked below.
+        // message. The digest algorithm's output is exactly the right size,
+        // except for P-521, where the output of SHA-512 is two bytes too short.
+        // In that case the nonce is the concatenation of multiple digests,
+        // where each digest after the first is domain-separated by a counter.
         //
-        // XXX(perf): The single iteration will require two digest block
-        // operations because the amount of data digested is larger than one
-        // block.
+        // XXX(perf): Each iteration will require two digest block operations
+        // because the amount of data digested is larger than one block.
         let digest_alg = self.key.0.algorithm();
-        let mut ctx = digest::Context::new(digest_alg);
 
         // Digest the randomized digest of the private key.
         let key = self.key.0.as_ref();
-        ctx.update(key);
 
         // The random value is digested between the key and the message so that
         // the key and the message are not directly digested in the same digest
         // block.
         assert!(key.len() <= digest_alg.block_len() / 2);
-        {
-            let mut rand = [0u8; digest::MAX_BLOCK_LEN];
-            let rand = &mut rand[..digest_alg.block_len() - key.len()];
-            assert!(rand.len() >= dest.len());
-            self.rng.fill(rand)?;
+        let mut rand = [0u8; digest::MAX_BLOCK_LEN];
+        let rand = &mut rand[..digest_alg.block_len() - key.len()];
+        assert!(rand.len() >= digest_alg.output_len());
+        self.rng.fill(rand)?;
+
+        for (counter, dest) in (0u8..).zip(dest.chunks_mut(digest_alg.output_len())) {
+            let mut ctx = digest::Context::new(digest_alg);
+            ctx.update(key);
             ctx.update(rand);
+            ctx.update(self.message_digest.as_ref());
+            if counter > 0 {
+                ctx.update(&[counter]);
+            }
+            let nonce = ctx.finish();
+            dest.copy_from_slice(&nonce.as_ref()[..dest.len()]);
         }
 
-        ctx.update(self.message_digest.as_ref());
-
-        let nonce = ctx.finish();
-
-        // `copy_from_slice()` panics if the lengths differ, so we don't have
-        // to separately assert that the lengths are the same.
-        dest.copy_from_slice(nonce.as_ref());
-
         Ok(())
     }
 }
@@ -336,13 +407,138 @@ impl<'a> sealed::Sealed for NonceRandom<'a> {}
 
 struct NonceRandomKey(digest::Digest);
 
+/// Generates ECDSA nonces deterministically using HMAC_DRBG as specified in
+/// [RFC 6979 Section 3.2].
+///
+/// Each call to `fill()` produces the next candidate `k`. The first call
+/// produces the candidate from step h.2; later calls first do the update of
+/// `K` and `V` from step h.3, as required when the previous candidate was
+/// rejected, either because it wasn't in the range [1, n) or because it
+/// resulted in `r` or `s` being zero.
+///
+/// [RFC 6979 Section 3.2]: https://tools.ietf.org/html/rfc6979#section-3.2
+struct NonceRfc6979 {
+    algorithm: hmac::Algorithm,
+    order_bits: usize,
+    state: RefCell<Rfc6979State>,
+}
+
+struct Rfc6979State {
+    k: [u8; digest::MAX_OUTPUT_LEN],
+    v: [u8; digest::MAX_OUTPUT_LEN],
+    generated: bool,
+}
+
+impl NonceRfc6979 {
+    fn new(algorithm: hmac::Algorithm, key_pair: &EcdsaKeyPair, h: &digest::Digest) -> Self {
+        let scalar_ops = key_pair.alg.private_scalar_ops.scalar_ops;
+        let cops = scalar_ops.common;
+        let len = scalar_ops.scalar_bytes_len();
+
+        // int2octets(x).
+        let mut x = [0u8; ec::SCALAR_MAX_BYTES];
+        let x = &mut x[..len];
+        let d = scalar_ops.scalar_unencoded(&key_pair.d);
+        big_endian_fixed_from_limbs(cops, scalar_ops.leak_limbs(&d), x);
+
+        // bits2octets(h1). `digest_scalar` takes the leftmost bits of the
+        // digest and reduces the result mod n exactly as bits2octets does.
+        let mut h1 = [0u8; ec::SCALAR_MAX_BYTES];
+        let h1 = &mut h1[..len];
+        let e = digest_scalar(scalar_ops, *h);
+        big_endian_fixed_from_limbs(cops, scalar_ops.leak_limbs(&e), h1);
+
+        // Steps b and c.
+        let mut state = Rfc6979State {
+            k: [0u8; digest::MAX_OUTPUT_LEN],
+            v: [1u8; digest::MAX_OUTPUT_LEN],
+            generated: false,
+        };
+
+        // Steps d through g.
+        state.update(algorithm, 0x00, &[x, h1]);
+        state.update(algorithm, 0x01, &[x, h1]);
+
+        Self {
+            algorithm,
+            order_bits: cops.order_bits().as_bits(),
+            state: RefCell::new(state),
+        }
+    }
+}
+
+impl Rfc6979State {
+    // K = HMAC_K(V || `separator` || `extra`), then V = HMAC_K(V).
+    fn update(&mut self, algorithm: hmac::Algorithm, separator: u8, extra: &[&[u8]]) {
+        let len = algorithm.digest_algorithm().output_len();
+        let key = hmac::Key::new(algorithm, &self.k[..len]);
+        let mut ctx = hmac::Context::with_key(&key);
+        ctx.update(&self.v[..len]);
+        ctx.update(&[separator]);
+        for extra in extra {
+            ctx.update(extra);
+        }
+        self.k[..len].copy_from_slice(ctx.sign().as_ref());
+        self.next_v(algorithm);
+    }
+
+    // V = HMAC_K(V).
+    fn next_v(&mut self, algorithm: hmac::Algorithm) {
+        let len = algorithm.digest_algorithm().output_len();
+        let key = hmac::Key::new(algorithm, &self.k[..len]);
+        let v = hmac::sign(&key, &self.v[..len]);
+        self.v[..len].copy_from_slice(v.as_ref());
+    }
+}
+
+impl core::fmt::Debug for NonceRfc6979 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("NonceRfc6979").finish()
+    }
+}
+
+impl rand::sealed::SecureRandom for NonceRfc6979 {
+    fn fill_impl(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+        let mut state = self.state.borrow_mut();
+
+        // Step h.3, for every candidate after the first.
+        if state.generated {
+            state.update(self.algorithm, 0x00, &[]);
+        }
+        state.generated = true;
+
+        // Steps h.1 and h.2: T is the concatenation of successive values of V.
+        // Only the leftmost `dest.len()` bytes of T are needed since they
+        // contain the leftmost `order_bits` bits.
+        let len = self.algorithm.digest_algorithm().output_len();
+        for chunk in dest.chunks_mut(len) {
+            state.next_v(self.algorithm);
+            chunk.copy_from_slice(&state.v[..chunk.len()]);
+        }
+
+        // bits2int(T): When the order's bit length isn't a multiple of 8
+        // (P-521), shift the excess low bits out.
+        let excess_bits = (dest.len() * 8) - self.order_bits;
+        if excess_bits > 0 {
+            for i in (0..dest.len()).rev() {
+                let prev = if i > 0 { dest[i - 1] } else { 0 };
+                dest[i] = (dest[i] >> excess_bits) | (prev << (8 - excess_bits));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl sealed::Sealed for NonceRfc6979 {}
+
 impl NonceRandomKey {
     fn new(
         alg: &EcdsaSigningAlgorithm,
         seed: &ec::Seed,
         rng: &dyn rand::SecureRandom,
     ) -> Result<Self, error::KeyRejected> {
-        let mut rand = [0; digest::MAX_OUTPUT_LEN];
+        let mut rand = [0; ec::SCALAR_MAX_BYTES];
         let rand = &mut rand[0..alg.curve.elem_scalar_seed_len];
 
         // XXX: `KeyRejected` isn't the right way to model  failure of the RNG,
@@ -381,10 +577,10 @@ fn format_rs_fixed(ops: &'static ScalarOps, r: &Scalar, s: &Scalar, out: &mut [u
     let scalar_len = ops.scalar_bytes_len();
 
     let (r_out, rest) = out.split_at_mut(scalar_len);
-    limb::big_endian_from_limbs(ops.leak_limbs(r), r_out);
+    big_endian_fixed_from_limbs(ops.common, ops.leak_limbs(r), r_out);
 
     let (s_out, _) = rest.split_at_mut(scalar_len);
-    limb::big_endian_from_limbs(ops.leak_limbs(s), s_out);
+    big_endian_fixed_from_limbs(ops.common, ops.leak_limbs(s), s_out);
 
     2 * scalar_len
 }
@@ -395,7 +591,7 @@ fn format_rs_asn1(ops: &'static ScalarOps, r: &Scalar, s: &Scalar, out: &mut [u8
     fn format_integer_tlv(ops: &ScalarOps, a: &Scalar, out: &mut [u8]) -> usize {
         let mut fixed = [0u8; ec::SCALAR_MAX_BYTES + 1];
         let fixed = &mut fixed[..(ops.scalar_bytes_len() + 1)];
-        limb::big_endian_from_limbs(ops.leak_limbs(a), &mut fixed[1..]);
+        big_endian_fixed_from_limbs(ops.common, ops.leak_limbs(a), &mut fixed[1..]);
 
         // Since `a_fixed_out` is an extra byte long, it is guaranteed to start
         // with a zero.
@@ -426,19 +622,29 @@ fn format_rs_asn1(ops: &'static ScalarOps, r: &Scalar, s: &Scalar, out: &mut [u8
         2 + value.len()
     }
 
+    // Leave room for a two-byte length, which is needed for P-521 when the
+    // value is 128 bytes or longer.
     out[0] = der::Tag::Sequence.into();
-    let r_tlv_len = format_integer_tlv(ops, r, &mut out[2..]);
-    let s_tlv_len = format_integer_tlv(ops, s, &mut out[2..][r_tlv_len..]);
+    let r_tlv_len = format_integer_tlv(ops, r, &mut out[3..]);
+    let s_tlv_len = format_integer_tlv(ops, s, &mut out[3..][r_tlv_len..]);
 
-    // Lengths less than 128 are encoded in one byte.
     let value_len = r_tlv_len + s_tlv_len;
-    assert!(value_len < 128);
+    let header_len = if value_len < 128 {
+        // Lengths less than 128 are encoded in one byte.
+        out.copy_within(3..(3 + value_len), 2);
+        2
+    } else {
+        // Lengths less than 256 are encoded in two bytes.
+        assert!(value_len < 256);
+        out[1] = 0x81;
+        3
+    };
     #[allow(clippy::cast_possible_truncation)]
     {
-        out[1] = value_len as u8;
+        out[header_len - 1] = value_len as u8;
     }
 
-    2 + value_len
+    header_len + value_len
 }
 
 /// Signing of fixed-length (PKCS#11 style) ECDSA signatures using the
@@ -453,6 +659,8 @@ pub static ECDSA_P256_SHA256_FIXED_SIGNING: EcdsaSigningAlgorithm = EcdsaSigning
     digest_alg: &digest::SHA256,
     pkcs8_template: &EC_PUBLIC_KEY_P256_PKCS8_V1_TEMPLATE,
     format_rs: format_rs_fixed,
+    low_s: false,
+    rfc6979_hmac: None,
     id: AlgorithmID::ECDSA_P256_SHA256_FIXED_SIGNING,
 };
 
@@ -468,6 +676,8 @@ pub static ECDSA_P384_SHA384_FIXED_SIGNING: EcdsaSigningAlgorithm = EcdsaSigning
     digest_alg: &digest::SHA384,
     pkcs8_template: &EC_PUBLIC_KEY_P384_PKCS8_V1_TEMPLATE,
     format_rs: format_rs_fixed,
+    low_s: false,
+    rfc6979_hmac: None,
     id: AlgorithmID::ECDSA_P384_SHA384_FIXED_SIGNING,
 };
 
@@ -483,6 +693,8 @@ pub static ECDSA_P256_SHA256_ASN1_SIGNING: EcdsaSigningAlgorithm = EcdsaSigningA
     digest_alg: &digest::SHA256,
     pkcs8_template: &EC_PUBLIC_KEY_P256_PKCS8_V1_TEMPLATE,
     format_rs: format_rs_asn1,
+    low_s: false,
+    rfc6979_hmac: None,
     id: AlgorithmID::ECDSA_P256_SHA256_ASN1_SIGNING,
 };
 
@@ -498,26 +710,278 @@ pub static ECDSA_P384_SHA384_ASN1_SIGNING: EcdsaSigningAlgorithm = EcdsaSigningA
     digest_alg: &digest::SHA384,
     pkcs8_template: &EC_PUBLIC_KEY_P384_PKCS8_V1_TEMPLATE,
     format_rs: format_rs_asn1,
+    low_s: false,
+    rfc6979_hmac: None,
     id: AlgorithmID::ECDSA_P384_SHA384_ASN1_SIGNING,
 };
 
-static EC_PUBLIC_KEY_P256_PKCS8_V1_TEMPLATE: pkcs8::Template = pkcs8::Template {
+/// Signing of fixed-length (PKCS#11 style) ECDSA signatures using the
+/// P-521 curve and SHA-512.
+///
+/// See "`ECDSA_*_FIXED` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+pub static ECDSA_P521_SHA512_FIXED_SIGNING: EcdsaSigningAlgorithm = EcdsaSigningAlgorithm {
+    curve: &ec::suite_b::curve::P521,
+    private_scalar_ops: &p521::PRIVATE_SCALAR_OPS,
+    private_key_ops: &p521::PRIVATE_KEY_OPS,
+    digest_alg: &digest::SHA512,
+    pkcs8_template: &EC_PUBLIC_KEY_P521_PKCS8_V1_TEMPLATE,
+    format_rs: format_rs_fixed,
+    low_s: false,
+    rfc6979_hmac: None,
+    id: AlgorithmID::ECDSA_P521_SHA512_FIXED_SIGNING,
+};
+
+/// Signing of ASN.1 DER-encoded ECDSA signatures using the P-521 curve and
+/// SHA-512.
+///
+/// See "`ECDSA_*_ASN1` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+pub static ECDSA_P521_SHA512_ASN1_SIGNING: EcdsaSigningAlgorithm = EcdsaSigningAlgorithm {
+    curve: &ec::suite_b::curve::P521,
+    private_scalar_ops: &p521::PRIVATE_SCALAR_OPS,
+    private_key_ops: &p521::PRIVATE_KEY_OPS,
+    digest_alg: &digest::SHA512,
+    pkcs8_template: &EC_PUBLIC_KEY_P521_PKCS8_V1_TEMPLATE,
+    format_rs: format_rs_asn1,
+    low_s: false,
+    rfc6979_hmac: None,
+    id: AlgorithmID::ECDSA_P521_SHA512_ASN1_SIGNING,
+};
+
+/// Signing of fixed-length (PKCS#11 style) ECDSA signatures using the
+/// secp256k1 curve and SHA-256.
+///
+/// The signatures are not normalized; about half of them will have a "high"
+/// `s`. Use `ECDSA_SECP256K1_SHA256_LOW_S_FIXED_SIGNING` when low-S signatures
+/// are required.
+///
+/// See "`ECDSA_*_FIXED` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+pub static ECDSA_SECP256K1_SHA256_FIXED_SIGNING: EcdsaSigningAlgorithm = EcdsaSigningAlgorithm {
+    curve: &ec::suite_b::curve::SECP256K1,
+    private_scalar_ops: &secp256k1::PRIVATE_SCALAR_OPS,
+    private_key_ops: &secp256k1::PRIVATE_KEY_OPS,
+    digest_alg: &digest::SHA256,
+    pkcs8_template: &EC_PUBLIC_KEY_SECP256K1_PKCS8_V1_TEMPLATE,
+    format_rs: format_rs_fixed,
+    low_s: false,
+    rfc6979_hmac: None,
+    id: AlgorithmID::ECDSA_SECP256K1_SHA256_FIXED_SIGNING,
+};
+
+/// Signing of ASN.1 DER-encoded ECDSA signatures using the secp256k1 curve
+/// and SHA-256.
+///
+/// The signatures are not normalized; about half of them will have a "high"
+/// `s`. Use `ECDSA_SECP256K1_SHA256_LOW_S_ASN1_SIGNING` when low-S signatures
+/// are required.
+///
+/// See "`ECDSA_*_ASN1` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+pub static ECDSA_SECP256K1_SHA256_ASN1_SIGNING: EcdsaSigningAlgorithm = EcdsaSigningAlgorithm {
+    curve: &ec::suite_b::curve::SECP256K1,
+    private_scalar_ops: &secp256k1::PRIVATE_SCALAR_OPS,
+    private_key_ops: &secp256k1::PRIVATE_KEY_OPS,
+    digest_alg: &digest::SHA256,
+    pkcs8_template: &EC_PUBLIC_KEY_SECP256K1_PKCS8_V1_TEMPLATE,
+    format_rs: format_rs_asn1,
+    low_s: false,
+    rfc6979_hmac: None,
+    id: AlgorithmID::ECDSA_SECP256K1_SHA256_ASN1_SIGNING,
+};
+
+/// Signing of fixed-length (PKCS#11 style) ECDSA signatures using the
+/// secp256k1 curve and SHA-256, with low-S normalization.
+///
+/// Whenever `s` > (n - 1) / 2, `s` is replaced with `n - s`, as required by
+/// Bitcoin's [BIP 62] and [BIP 146].
+///
+/// See "`ECDSA_*_FIXED` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+///
+/// [BIP 62]: https://github.com/bitcoin/bips/blob/master/bip-0062.mediawiki
+/// [BIP 146]: https://github.com/bitcoin/bips/blob/master/bip-0146.mediawiki
+pub static ECDSA_SECP256K1_SHA256_LOW_S_FIXED_SIGNING: EcdsaSigningAlgorithm =
+    EcdsaSigningAlgorithm {
+        curve: &ec::suite_b::curve::SECP256K1,
+        private_scalar_ops: &secp256k1::PRIVATE_SCALAR_OPS,
+        private_key_ops: &secp256k1::PRIVATE_KEY_OPS,
+        digest_alg: &digest::SHA256,
+        pkcs8_template: &EC_PUBLIC_KEY_SECP256K1_PKCS8_V1_TEMPLATE,
+        format_rs: format_rs_fixed,
+        low_s: true,
+        rfc6979_hmac: None,
+        id: AlgorithmID::ECDSA_SECP256K1_SHA256_LOW_S_FIXED_SIGNING,
+    };
+
+/// Signing of ASN.1 DER-encoded ECDSA signatures using the secp256k1 curve
+/// and SHA-256, with low-S normalization.
+///
+/// Whenever `s` > (n - 1) / 2, `s` is replaced with `n - s`, as required by
+/// Bitcoin's [BIP 62] and [BIP 146].
+///
+/// See "`ECDSA_*_ASN1` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+///
+/// [BIP 62]: https://github.com/bitcoin/bips/blob/master/bip-0062.mediawiki
+/// [BIP 146]: https://github.com/bitcoin/bips/blob/master/bip-0146.mediawiki
+pub static ECDSA_SECP256K1_SHA256_LOW_S_ASN1_SIGNING: EcdsaSigningAlgorithm =
+    EcdsaSigningAlgorithm {
+        curve: &ec::suite_b::curve::SECP256K1,
+        private_scalar_ops: &secp256k1::PRIVATE_SCALAR_OPS,
+        private_key_ops: &secp256k1::PRIVATE_KEY_OPS,
+        digest_alg: &digest::SHA256,
+        pkcs8_template: &EC_PUBLIC_KEY_SECP256K1_PKCS8_V1_TEMPLATE,
+        format_rs: format_rs_asn1,
+        low_s: true,
+        rfc6979_hmac: None,
+        id: AlgorithmID::ECDSA_SECP256K1_SHA256_LOW_S_ASN1_SIGNING,
+    };
+
+/// Like [`ECDSA_P256_SHA256_FIXED_SIGNING`], but with deterministic nonces.
+pub static ECDSA_P256_SHA256_FIXED_SIGNING_DETERMINISTIC: EcdsaSigningAlgorithm =
+    EcdsaSigningAlgorithm {
+        curve: &ec::suite_b::curve::P256,
+        private_scalar_ops: &p256::PRIVATE_SCALAR_OPS,
+        private_key_ops: &p256::PRIVATE_KEY_OPS,
+        digest_alg: &digest::SHA256,
+        pkcs8_template: &EC_PUBLIC_KEY_P256_PKCS8_V1_TEMPLATE,
+        format_rs: format_rs_fixed,
+        low_s: false,
+        rfc6979_hmac: Some(&hmac::HMAC_SHA256),
+        id: AlgorithmID::ECDSA_P256_SHA256_FIXED_SIGNING_DETERMINISTIC,
+    };
+
+/// Like [`ECDSA_P384_SHA384_FIXED_SIGNING`], but with deterministic nonces.
+pub static ECDSA_P384_SHA384_FIXED_SIGNING_DETERMINISTIC: EcdsaSigningAlgorithm =
+    EcdsaSigningAlgorithm {
+        curve: &ec::suite_b::curve::P384,
+        private_scalar_ops: &p384::PRIVATE_SCALAR_OPS,
+        private_key_ops: &p384::PRIVATE_KEY_OPS,
+        digest_alg: &digest::SHA384,
+        pkcs8_template: &EC_PUBLIC_KEY_P384_PKCS8_V1_TEMPLATE,
+        format_rs: format_rs_fixed,
+        low_s: false,
+        rfc6979_hmac: Some(&hmac::HMAC_SHA384),
+        id: AlgorithmID::ECDSA_P384_SHA384_FIXED_SIGNING_DETERMINISTIC,
+    };
+
+/// Like [`ECDSA_P256_SHA256_ASN1_SIGNING`], but with deterministic nonces.
+pub static ECDSA_P256_SHA256_ASN1_SIGNING_DETERMINISTIC: EcdsaSigningAlgorithm =
+    EcdsaSigningAlgorithm {
+        curve: &ec::suite_b::curve::P256,
+        private_scalar_ops: &p256::PRIVATE_SCALAR_OPS,
+        private_key_ops: &p256::PRIVATE_KEY_OPS,
+        digest_alg: &digest::SHA256,
+        pkcs8_template: &EC_PUBLIC_KEY_P256_PKCS8_V1_TEMPLATE,
+        format_rs: format_rs_asn1,
+        low_s: false,
+        rfc6979_hmac: Some(&hmac::HMAC_SHA256),
+        id: AlgorithmID::ECDSA_P256_SHA256_ASN1_SIGNING_DETERMINISTIC,
+    };
+
+/// Like [`ECDSA_P384_SHA384_ASN1_SIGNING`], but with deterministic nonces.
+pub static ECDSA_P384_SHA384_ASN1_SIGNING_DETERMINISTIC: EcdsaSigningAlgorithm =
+    EcdsaSigningAlgorithm {
+        curve: &ec::suite_b::curve::P384,
+        private_scalar_ops: &p384::PRIVATE_SCALAR_OPS,
+        private_key_ops: &p384::PRIVATE_KEY_OPS,
+        digest_alg: &digest::SHA384,
+        pkcs8_template: &EC_PUBLIC_KEY_P384_PKCS8_V1_TEMPLATE,
+        format_rs: format_rs_asn1,
+        low_s: false,
+        rfc6979_hmac: Some(&hmac::HMAC_SHA384),
+        id: AlgorithmID::ECDSA_P384_SHA384_ASN1_SIGNING_DETERMINISTIC,
+    };
+
+/// Like [`ECDSA_P521_SHA512_FIXED_SIGNING`], but with deterministic nonces.
+pub static ECDSA_P521_SHA512_FIXED_SIGNING_DETERMINISTIC: EcdsaSigningAlgorithm =
+    EcdsaSigningAlgorithm {
+        curve: &ec::suite_b::curve::P521,
+        private_scalar_ops: &p521::PRIVATE_SCALAR_OPS,
+        private_key_ops: &p521::PRIVATE_KEY_OPS,
+        digest_alg: &digest::SHA512,
+        pkcs8_template: &EC_PUBLIC_KEY_P521_PKCS8_V1_TEMPLATE,
+        format_rs: format_rs_fixed,
+        low_s: false,
+        rfc6979_hmac: Some(&hmac::HMAC_SHA512),
+        id: AlgorithmID::ECDSA_P521_SHA512_FIXED_SIGNING_DETERMINISTIC,
+    };
+
+/// Like [`ECDSA_P521_SHA512_ASN1_SIGNING`], but with deterministic nonces.
+pub static ECDSA_P521_SHA512_ASN1_SIGNING_DETERMINISTIC: EcdsaSigningAlgorithm =
+    EcdsaSigningAlgorithm {
+        curve: &ec::suite_b::curve::P521,
+        private_scalar_ops: &p521::PRIVATE_SCALAR_OPS,
+        private_key_ops: &p521::PRIVATE_KEY_OPS,
+        digest_alg: &digest::SHA512,
+        pkcs8_template: &EC_PUBLIC_KEY_P521_PKCS8_V1_TEMPLATE,
+        format_rs: format_rs_asn1,
+        low_s: false,
+        rfc6979_hmac: Some(&hmac::HMAC_SHA512),
+        id: AlgorithmID::ECDSA_P521_SHA512_ASN1_SIGNING_DETERMINISTIC,
+    };
+
+/// Like [`ECDSA_SECP256K1_SHA256_LOW_S_FIXED_SIGNING`], but with deterministic nonces.
+pub static ECDSA_SECP256K1_SHA256_LOW_S_FIXED_SIGNING_DETERMINISTIC: EcdsaSigningAlgorithm =
+    EcdsaSigningAlgorithm {
+        curve: &ec::suite_b::curve::SECP256K1,
+        private_scalar_ops: &secp256k1::PRIVATE_SCALAR_OPS,
+        private_key_ops: &secp256k1::PRIVATE_KEY_OPS,
+        digest_alg: &digest::SHA256,
+        pkcs8_template: &EC_PUBLIC_KEY_SECP256K1_PKCS8_V1_TEMPLATE,
+        format_rs: format_rs_fixed,
+        low_s: true,
+        rfc6979_hmac: Some(&hmac::HMAC_SHA256),
+        id: AlgorithmID::ECDSA_SECP256K1_SHA256_LOW_S_FIXED_SIGNING_DETERMINISTIC,
+    };
+
+/// Like [`ECDSA_SECP256K1_SHA256_LOW_S_ASN1_SIGNING`], but with deterministic nonces.
+pub static ECDSA_SECP256K1_SHA256_LOW_S_ASN1_SIGNING_DETERMINISTIC: EcdsaSigningAlgorithm =
+    EcdsaSigningAlgorithm {
+        curve: &ec::suite_b::curve::SECP256K1,
+        private_scalar_ops: &secp256k1::PRIVATE_SCALAR_OPS,
+        private_key_ops: &secp256k1::PRIVATE_KEY_OPS,
+        digest_alg: &digest::SHA256,
+        pkcs8_template: &EC_PUBLIC_KEY_SECP256K1_PKCS8_V1_TEMPLATE,
+        format_rs: format_rs_asn1,
+        low_s: true,
+        rfc6979_hmac: Some(&hmac::HMAC_SHA256),
+        id: AlgorithmID::ECDSA_SECP256K1_SHA256_LOW_S_ASN1_SIGNING_DETERMINISTIC,
+    };
+
+pub(crate) static EC_PUBLIC_KEY_P256_PKCS8_V1_TEMPLATE: pkcs8::Template = pkcs8::Template {
     bytes: include_bytes!("ecPublicKey_p256_pkcs8_v1_template.der"),
     alg_id_range: core::ops::Range { start: 8, end: 27 },
     curve_id_index: 9,
     private_key_index: 0x24,
 };
 
-static EC_PUBLIC_KEY_P384_PKCS8_V1_TEMPLATE: pkcs8::Template = pkcs8::Template {
+pub(crate) static EC_PUBLIC_KEY_P384_PKCS8_V1_TEMPLATE: pkcs8::Template = pkcs8::Template {
     bytes: include_bytes!("ecPublicKey_p384_pkcs8_v1_template.der"),
     alg_id_range: core::ops::Range { start: 8, end: 24 },
     curve_id_index: 9,
     private_key_index: 0x23,
 };
 
+static EC_PUBLIC_KEY_P521_PKCS8_V1_TEMPLATE: pkcs8::Template = pkcs8::Template {
+    bytes: include_bytes!("ecPublicKey_p521_pkcs8_v1_template.der"),
+    alg_id_range: core::ops::Range { start: 8, end: 24 },
+    curve_id_index: 9,
+    private_key_index: 0x23,
+};
+
+static EC_PUBLIC_KEY_SECP256K1_PKCS8_V1_TEMPLATE: pkcs8::Template = pkcs8::Template {
+    bytes: include_bytes!("ecPublicKey_secp256k1_pkcs8_v1_template.der"),
+    alg_id_range: core::ops::Range { start: 8, end: 24 },
+    curve_id_index: 9,
+    private_key_index: 0x21,
+};
+
 #[cfg(test)]
 mod tests {
-    use crate::{rand, signature, test};
+    use crate::{ec, rand, signature, test};
 
     #[test]
     fn signature_ecdsa_sign_fixed_test() {
@@ -540,6 +1004,8 @@ mod tests {
                 let alg = match (curve_name.as_str(), digest_name.as_str()) {
                     ("P-256", "SHA256") => &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
                     ("P-384", "SHA384") => &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+                    ("P-521", "SHA512") => &signature::ECDSA_P521_SHA512_FIXED_SIGNING,
+                    ("secp256k1", "SHA256") => &signature::ECDSA_SECP256K1_SHA256_FIXED_SIGNING,
                     _ => {
                         panic!("Unsupported curve+digest: {}+{}", curve_name, digest_name);
                     }
@@ -582,6 +1048,8 @@ mod tests {
                 let alg = match (curve_name.as_str(), digest_name.as_str()) {
                     ("P-256", "SHA256") => &signature::ECDSA_P256_SHA256_ASN1_SIGNING,
                     ("P-384", "SHA384") => &signature::ECDSA_P384_SHA384_ASN1_SIGNING,
+                    ("P-521", "SHA512") => &signature::ECDSA_P521_SHA512_ASN1_SIGNING,
+                    ("secp256k1", "SHA256") => &signature::ECDSA_SECP256K1_SHA256_ASN1_SIGNING,
                     _ => {
                         panic!("Unsupported curve+digest: {}+{}", curve_name, digest_name);
                     }
@@ -602,4 +1070,153 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn signature_ecdsa_sign_low_s_test() {
+        use crate::{
+            ec::suite_b::ops::{self, secp256k1},
+            limb::AllowZero,
+        };
+
+        let rng = rand::SystemRandom::new();
+
+        test::run(
+            test_file!("ecdsa_sign_fixed_tests.txt"),
+            |section, test_case| {
+                assert_eq!(section, "");
+
+                let curve_name = test_case.consume_string("Curve");
+                let _digest_name = test_case.consume_string("Digest");
+                let msg = test_case.consume_bytes("Msg");
+                let d = test_case.consume_bytes("d");
+                let q = test_case.consume_bytes("Q");
+                let k = test_case.consume_bytes("k");
+                let expected_result = test_case.consume_bytes("Sig");
+
+                if curve_name != "secp256k1" {
+                    return Ok(());
+                }
+
+                // The expected low-S signature is the known answer with `s`
+                // replaced by `n - s` if `s` is high.
+                let ops = &secp256k1::SCALAR_OPS;
+                let (expected_r, expected_s) = expected_result.split_at(32);
+                let expected_s = ops::scalar_parse_big_endian_variable(
+                    ops.common,
+                    AllowZero::No,
+                    untrusted::Input::from(expected_s),
+                )
+                .unwrap();
+                let expected_s = if ops.scalar_is_high_vartime(&expected_s) {
+                    ops.scalar_negated(&expected_s)
+                } else {
+                    expected_s
+                };
+                let mut expected_low_s = [0u8; 32];
+                ops::big_endian_fixed_from_limbs(
+                    ops.common,
+                    ops.leak_limbs(&expected_s),
+                    &mut expected_low_s,
+                );
+
+                let private_key = signature::EcdsaKeyPair::from_private_key_and_public_key(
+                    &signature::ECDSA_SECP256K1_SHA256_LOW_S_FIXED_SIGNING,
+                    &d,
+                    &q,
+                    &rng,
+                )
+                .unwrap();
+                let rng = test::rand::FixedSliceRandom { bytes: &k };
+
+                let actual_result = private_key
+                    .sign_with_fixed_nonce_during_test(&rng, &msg)
+                    .unwrap();
+                let (actual_r, actual_s) = actual_result.as_ref().split_at(32);
+
+                assert_eq!(actual_r, expected_r);
+                assert_eq!(actual_s, &expected_low_s[..]);
+                assert!(!ops.scalar_is_high_vartime(&expected_s));
+
+                Ok(())
+            },
+        );
+    }
+
+    #[test]
+    fn signature_ecdsa_sign_deterministic_test() {
+        use super::NonceRfc6979;
+        use crate::{
+            digest,
+            ec::suite_b::{ops, private_key},
+        };
+
+        let rng = rand::SystemRandom::new();
+
+        test::run(
+            test_file!("ecdsa_sign_deterministic_tests.txt"),
+            |section, test_case| {
+                assert_eq!(section, "");
+
+                let curve_name = test_case.consume_string("Curve");
+                let digest_name = test_case.consume_string("Digest");
+                let msg = test_case.consume_bytes("Msg");
+                let d = test_case.consume_bytes("d");
+                let q = test_case.consume_bytes("Q");
+                let k = test_case.consume_bytes("k");
+                let expected_fixed = test_case.consume_bytes("Sig");
+                let expected_asn1 = test_case.consume_bytes("SigAsn1");
+
+                let (fixed_alg, asn1_alg) = match (curve_name.as_str(), digest_name.as_str()) {
+                    ("P-256", "SHA256") => (
+                        &signature::ECDSA_P256_SHA256_FIXED_SIGNING_DETERMINISTIC,
+                        &signature::ECDSA_P256_SHA256_ASN1_SIGNING_DETERMINISTIC,
+                    ),
+                    ("P-384", "SHA384") => (
+                        &signature::ECDSA_P384_SHA384_FIXED_SIGNING_DETERMINISTIC,
+                        &signature::ECDSA_P384_SHA384_ASN1_SIGNING_DETERMINISTIC,
+                    ),
+                    ("P-521", "SHA512") => (
+                        &signature::ECDSA_P521_SHA512_FIXED_SIGNING_DETERMINISTIC,
+                        &signature::ECDSA_P521_SHA512_ASN1_SIGNING_DETERMINISTIC,
+                    ),
+                    ("secp256k1", "SHA256") => (
+                        &signature::ECDSA_SECP256K1_SHA256_LOW_S_FIXED_SIGNING_DETERMINISTIC,
+                        &signature::ECDSA_SECP256K1_SHA256_LOW_S_ASN1_SIGNING_DETERMINISTIC,
+                    ),
+                    _ => {
+                        panic!("Unsupported curve+digest: {}+{}", curve_name, digest_name);
+                    }
+                };
+
+                for (alg, expected) in [(fixed_alg, &expected_fixed), (asn1_alg, &expected_asn1)] {
+                    let key_pair =
+                        signature::EcdsaKeyPair::from_private_key_and_public_key(alg, &d, &q, &rng)
+                            .unwrap();
+
+                    // The first candidate nonce is the expected one.
+                    let h = digest::digest(alg.digest_alg, &msg);
+                    let nonce_rng = NonceRfc6979::new(*alg.rfc6979_hmac.unwrap(), &key_pair, &h);
+                    let actual_k =
+                        private_key::random_scalar(alg.private_key_ops, &nonce_rng).unwrap();
+                    let scalar_ops = alg.private_scalar_ops.scalar_ops;
+                    let mut actual_k_bytes = [0u8; ec::SCALAR_MAX_BYTES];
+                    let actual_k_bytes = &mut actual_k_bytes[..scalar_ops.scalar_bytes_len()];
+                    ops::big_endian_fixed_from_limbs(
+                        scalar_ops.common,
+                        scalar_ops.leak_limbs(&actual_k),
+                        actual_k_bytes,
+                    );
+                    assert_eq!(&actual_k_bytes[..], &k[..]);
+
+                    // Signing is reproducible and doesn't depend on the RNG.
+                    for _ in 0..2 {
+                        let actual = key_pair.sign(&rng, &msg).unwrap();
+                        assert_eq!(actual.as_ref(), &expected[..]);
+                    }
+                }
+
+                Ok(())
+            },
+        );
+    }
 }
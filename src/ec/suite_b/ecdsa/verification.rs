@@ -12,7 +12,7 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-//! ECDSA Signatures using the P-256 and P-384 curves.
+//! ECDSA Signatures using the P-256, P-384, P-521, and secp256k1 curves.
 
 use super::digest_scalar::digest_scalar;
 use crate::{
@@ -34,17 +34,29 @@ pub struct EcdsaVerificationAlgorithm {
             input: &mut untrusted::Reader<'a>,
         )
             -> Result<(untrusted::Input<'a>, untrusted::Input<'a>), error::Unspecified>,
+
+    // Whether to reject signatures where `s` > (n - 1) / 2.
+    low_s: bool,
+
     id: AlgorithmID,
 }
 
 #[derive(Debug)]
 enum AlgorithmID {
+    ECDSA_P256_SHA224_ASN1,
     ECDSA_P256_SHA256_ASN1,
     ECDSA_P256_SHA256_FIXED,
     ECDSA_P256_SHA384_ASN1,
+    ECDSA_P384_SHA224_ASN1,
     ECDSA_P384_SHA256_ASN1,
     ECDSA_P384_SHA384_ASN1,
     ECDSA_P384_SHA384_FIXED,
+    ECDSA_P521_SHA512_ASN1,
+    ECDSA_P521_SHA512_FIXED,
+    ECDSA_SECP256K1_SHA256_ASN1,
+    ECDSA_SECP256K1_SHA256_FIXED,
+    ECDSA_SECP256K1_SHA256_LOW_S_ASN1,
+    ECDSA_SECP256K1_SHA256_LOW_S_FIXED,
 }
 
 derive_debug_via_id!(EcdsaVerificationAlgorithm);
@@ -66,13 +78,31 @@ impl signature::VerificationAlgorithm for EcdsaVerificationAlgorithm {
             digest_scalar(self.ops.scalar_ops, h)
         };
 
-        self.verify_digest(public_key, e, signature)
+        self.verify_e(public_key, e, signature)
+    }
+
+    fn verify_digest(
+        &self,
+        public_key: untrusted::Input,
+        digest: digest::Digest,
+        signature: untrusted::Input,
+    ) -> Result<(), error::Unspecified> {
+        // NSA Guide Step 2 was done by the caller, so just check that it used
+        // the right hash function.
+        if digest.algorithm() != self.digest_alg {
+            return Err(error::Unspecified);
+        }
+
+        // NSA Guide Step 3.
+        let e = digest_scalar(self.ops.scalar_ops, digest);
+
+        self.verify_e(public_key, e, signature)
     }
 }
 
 impl EcdsaVerificationAlgorithm {
     /// This is intentionally not public.
-    fn verify_digest(
+    fn verify_e(
         &self,
         public_key: untrusted::Input,
         e: Scalar,
@@ -111,6 +141,12 @@ impl EcdsaVerificationAlgorithm {
         let r = scalar_parse_big_endian_variable(public_key_ops.common, limb::AllowZero::No, r)?;
         let s = scalar_parse_big_endian_variable(public_key_ops.common, limb::AllowZero::No, s)?;
 
+        // Optionally reject (r, s) when (r, n - s) is the canonical low-S
+        // form of the signature.
+        if self.low_s && scalar_ops.scalar_is_high_vartime(&s) {
+            return Err(error::Unspecified);
+        }
+
         // NSA Guide Step 4: "Compute w = s**−1 mod n, using the routine in
         // Appendix B.1."
         let w = self.ops.scalar_inv_to_mont_vartime(&s);
@@ -199,6 +235,7 @@ pub static ECDSA_P256_SHA256_FIXED: EcdsaVerificationAlgorithm = EcdsaVerificati
     ops: &p256::PUBLIC_SCALAR_OPS,
     digest_alg: &digest::SHA256,
     split_rs: split_rs_fixed,
+    low_s: false,
     id: AlgorithmID::ECDSA_P256_SHA256_FIXED,
 };
 
@@ -211,9 +248,23 @@ pub static ECDSA_P384_SHA384_FIXED: EcdsaVerificationAlgorithm = EcdsaVerificati
     ops: &p384::PUBLIC_SCALAR_OPS,
     digest_alg: &digest::SHA384,
     split_rs: split_rs_fixed,
+    low_s: false,
     id: AlgorithmID::ECDSA_P384_SHA384_FIXED,
 };
 
+/// Verification of fixed-length (PKCS#11 style) ECDSA signatures using the
+/// P-521 curve and SHA-512.
+///
+/// See "`ECDSA_*_FIXED` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+pub static ECDSA_P521_SHA512_FIXED: EcdsaVerificationAlgorithm = EcdsaVerificationAlgorithm {
+    ops: &p521::PUBLIC_SCALAR_OPS,
+    digest_alg: &digest::SHA512,
+    split_rs: split_rs_fixed,
+    low_s: false,
+    id: AlgorithmID::ECDSA_P521_SHA512_FIXED,
+};
+
 /// Verification of ASN.1 DER-encoded ECDSA signatures using the P-256 curve
 /// and SHA-256.
 ///
@@ -223,9 +274,27 @@ pub static ECDSA_P256_SHA256_ASN1: EcdsaVerificationAlgorithm = EcdsaVerificatio
     ops: &p256::PUBLIC_SCALAR_OPS,
     digest_alg: &digest::SHA256,
     split_rs: split_rs_asn1,
+    low_s: false,
     id: AlgorithmID::ECDSA_P256_SHA256_ASN1,
 };
 
+/// *Not recommended*. Verification of ASN.1 DER-encoded ECDSA signatures using
+/// the P-256 curve and SHA-224.
+///
+/// SHA-224 provides less collision resistance than the P-256 curve provides
+/// security. This is intended only for interoperability with legacy systems,
+/// such as some X.509 PKIs, that sign with SHA-224.
+///
+/// See "`ECDSA_*_ASN1` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+pub static ECDSA_P256_SHA224_ASN1: EcdsaVerificationAlgorithm = EcdsaVerificationAlgorithm {
+    ops: &p256::PUBLIC_SCALAR_OPS,
+    digest_alg: &digest::SHA224,
+    split_rs: split_rs_asn1,
+    low_s: false,
+    id: AlgorithmID::ECDSA_P256_SHA224_ASN1,
+};
+
 /// *Not recommended*. Verification of ASN.1 DER-encoded ECDSA signatures using
 /// the P-256 curve and SHA-384.
 ///
@@ -240,9 +309,27 @@ pub static ECDSA_P256_SHA384_ASN1: EcdsaVerificationAlgorithm = EcdsaVerificatio
     ops: &p256::PUBLIC_SCALAR_OPS,
     digest_alg: &digest::SHA384,
     split_rs: split_rs_asn1,
+    low_s: false,
     id: AlgorithmID::ECDSA_P256_SHA384_ASN1,
 };
 
+/// *Not recommended*. Verification of ASN.1 DER-encoded ECDSA signatures using
+/// the P-384 curve and SHA-224.
+///
+/// SHA-224 provides much less collision resistance than the P-384 curve
+/// provides security. This is intended only for interoperability with legacy
+/// systems, such as some X.509 PKIs, that sign with SHA-224.
+///
+/// See "`ECDSA_*_ASN1` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+pub static ECDSA_P384_SHA224_ASN1: EcdsaVerificationAlgorithm = EcdsaVerificationAlgorithm {
+    ops: &p384::PUBLIC_SCALAR_OPS,
+    digest_alg: &digest::SHA224,
+    split_rs: split_rs_asn1,
+    low_s: false,
+    id: AlgorithmID::ECDSA_P384_SHA224_ASN1,
+};
+
 /// *Not recommended*. Verification of ASN.1 DER-encoded ECDSA signatures using
 /// the P-384 curve and SHA-256.
 ///
@@ -257,6 +344,7 @@ pub static ECDSA_P384_SHA256_ASN1: EcdsaVerificationAlgorithm = EcdsaVerificatio
     ops: &p384::PUBLIC_SCALAR_OPS,
     digest_alg: &digest::SHA256,
     split_rs: split_rs_asn1,
+    low_s: false,
     id: AlgorithmID::ECDSA_P384_SHA256_ASN1,
 };
 
@@ -269,9 +357,95 @@ pub static ECDSA_P384_SHA384_ASN1: EcdsaVerificationAlgorithm = EcdsaVerificatio
     ops: &p384::PUBLIC_SCALAR_OPS,
     digest_alg: &digest::SHA384,
     split_rs: split_rs_asn1,
+    low_s: false,
     id: AlgorithmID::ECDSA_P384_SHA384_ASN1,
 };
 
+/// Verification of ASN.1 DER-encoded ECDSA signatures using the P-521 curve
+/// and SHA-512.
+///
+/// See "`ECDSA_*_ASN1` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+pub static ECDSA_P521_SHA512_ASN1: EcdsaVerificationAlgorithm = EcdsaVerificationAlgorithm {
+    ops: &p521::PUBLIC_SCALAR_OPS,
+    digest_alg: &digest::SHA512,
+    split_rs: split_rs_asn1,
+    low_s: false,
+    id: AlgorithmID::ECDSA_P521_SHA512_ASN1,
+};
+
+/// Verification of fixed-length (PKCS#11 style) ECDSA signatures using the
+/// secp256k1 curve and SHA-256.
+///
+/// Signatures with a "high" `s` are accepted. Use
+/// `ECDSA_SECP256K1_SHA256_LOW_S_FIXED` to reject them.
+///
+/// See "`ECDSA_*_FIXED` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+pub static ECDSA_SECP256K1_SHA256_FIXED: EcdsaVerificationAlgorithm = EcdsaVerificationAlgorithm {
+    ops: &secp256k1::PUBLIC_SCALAR_OPS,
+    digest_alg: &digest::SHA256,
+    split_rs: split_rs_fixed,
+    low_s: false,
+    id: AlgorithmID::ECDSA_SECP256K1_SHA256_FIXED,
+};
+
+/// Verification of ASN.1 DER-encoded ECDSA signatures using the secp256k1
+/// curve and SHA-256.
+///
+/// Signatures with a "high" `s` are accepted. Use
+/// `ECDSA_SECP256K1_SHA256_LOW_S_ASN1` to reject them.
+///
+/// See "`ECDSA_*_ASN1` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+pub static ECDSA_SECP256K1_SHA256_ASN1: EcdsaVerificationAlgorithm = EcdsaVerificationAlgorithm {
+    ops: &secp256k1::PUBLIC_SCALAR_OPS,
+    digest_alg: &digest::SHA256,
+    split_rs: split_rs_asn1,
+    low_s: false,
+    id: AlgorithmID::ECDSA_SECP256K1_SHA256_ASN1,
+};
+
+/// Verification of fixed-length (PKCS#11 style) ECDSA signatures using the
+/// secp256k1 curve and SHA-256, accepting only low-S signatures.
+///
+/// Signatures where `s` > (n - 1) / 2 are rejected, as required by Bitcoin's
+/// [BIP 62] and [BIP 146].
+///
+/// See "`ECDSA_*_FIXED` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+///
+/// [BIP 62]: https://github.com/bitcoin/bips/blob/master/bip-0062.mediawiki
+/// [BIP 146]: https://github.com/bitcoin/bips/blob/master/bip-0146.mediawiki
+pub static ECDSA_SECP256K1_SHA256_LOW_S_FIXED: EcdsaVerificationAlgorithm =
+    EcdsaVerificationAlgorithm {
+        ops: &secp256k1::PUBLIC_SCALAR_OPS,
+        digest_alg: &digest::SHA256,
+        split_rs: split_rs_fixed,
+        low_s: true,
+        id: AlgorithmID::ECDSA_SECP256K1_SHA256_LOW_S_FIXED,
+    };
+
+/// Verification of ASN.1 DER-encoded ECDSA signatures using the secp256k1
+/// curve and SHA-256, accepting only low-S signatures.
+///
+/// Signatures where `s` > (n - 1) / 2 are rejected, as required by Bitcoin's
+/// [BIP 62] and [BIP 146].
+///
+/// See "`ECDSA_*_ASN1` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+///
+/// [BIP 62]: https://github.com/bitcoin/bips/blob/master/bip-0062.mediawiki
+/// [BIP 146]: https://github.com/bitcoin/bips/blob/master/bip-0146.mediawiki
+pub static ECDSA_SECP256K1_SHA256_LOW_S_ASN1: EcdsaVerificationAlgorithm =
+    EcdsaVerificationAlgorithm {
+        ops: &secp256k1::PUBLIC_SCALAR_OPS,
+        digest_alg: &digest::SHA256,
+        split_rs: split_rs_asn1,
+        low_s: true,
+        id: AlgorithmID::ECDSA_SECP256K1_SHA256_LOW_S_ASN1,
+    };
+
 #[cfg(test)]
 mod tests {
     extern crate alloc;
@@ -318,7 +492,7 @@ mod tests {
                     alg.ops.scalar_ops,
                     &digest[..],
                 );
-                let actual_result = alg.verify_digest(
+                let actual_result = alg.verify_e(
                     untrusted::Input::from(&public_key[..]),
                     digest,
                     untrusted::Input::from(&sig[..]),
@@ -12,7 +12,9 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-use crate::{arithmetic::limbs_from_hex, arithmetic::montgomery::*, error, limb::*};
+use crate::{
+    arithmetic::limbs_from_hex, arithmetic::montgomery::*, bits::BitLength, error, limb::*,
+};
 use core::marker::PhantomData;
 
 pub use self::elem::*;
@@ -53,10 +55,11 @@ impl Point {
 /// Operations and values needed by all curve operations.
 pub struct CommonOps {
     num_limbs: usize,
+    order_bits: BitLength,
     q: Modulus,
     n: Elem<Unencoded>,
 
-    pub a: Elem<R>, // Must be -3 mod q
+    pub a: Elem<R>, // Must be -3 mod q, or 0 for secp256k1
     pub b: Elem<R>,
 
     // In all cases, `r`, `a`, and `b` may all alias each other.
@@ -70,7 +73,12 @@ impl CommonOps {
     // The length of a field element, which is the same as the length of a
     // scalar, in bytes.
     pub fn len(&self) -> usize {
-        self.num_limbs * LIMB_BYTES
+        self.order_bits.as_usize_bytes_rounded_up()
+    }
+
+    // The bit length of the group order *n*.
+    pub fn order_bits(&self) -> BitLength {
+        self.order_bits
     }
 
     #[cfg(test)]
@@ -262,6 +270,34 @@ impl ScalarOps {
         &s.limbs[..self.common.num_limbs]
     }
 
+    /// Returns `n - a`, i.e. `-a (mod n)`.
+    pub fn scalar_negated(&self, a: &Scalar) -> Scalar {
+        let num_limbs = self.common.num_limbs;
+        let mut r = Scalar::zero();
+        limbs_sub_assign_mod(
+            &mut r.limbs[..num_limbs],
+            &a.limbs[..num_limbs],
+            &self.common.n.limbs[..num_limbs],
+        );
+        r
+    }
+
+    /// Returns true if `a` > (n - 1) / 2, i.e. if `a` is not "low-S."
+    ///
+    /// This is not constant-time; `a` must be public, e.g. the `s` component
+    /// of a signature.
+    pub fn scalar_is_high_vartime(&self, a: &Scalar) -> bool {
+        let num_limbs = self.common.num_limbs;
+        let negated = self.scalar_negated(a);
+        limbs_less_than_limbs_vartime(&negated.limbs[..num_limbs], &a.limbs[..num_limbs])
+    }
+
+    #[inline]
+    pub fn scalar_unencoded(&self, a: &Scalar<R>) -> Scalar {
+        const ONE: Scalar<Unencoded> = Scalar::from_hex("1");
+        self.scalar_product(a, &ONE)
+    }
+
     #[inline]
     pub fn scalar_product<EA: Encoding, EB: Encoding>(
         &self,
@@ -435,6 +471,21 @@ pub fn scalar_parse_big_endian_partially_reduced_variable_consttime(
     Ok(r)
 }
 
+// Serializes `limbs` as a big-endian, zero-padded value of exactly
+// `ops.len()` bytes. `limbs` must have `ops.num_limbs` limbs, and the value
+// must fit in `ops.len()` bytes.
+pub fn big_endian_fixed_from_limbs(ops: &CommonOps, limbs: &[Limb], out: &mut [u8]) {
+    assert_eq!(out.len(), ops.len());
+    let mut be_bytes = unstripped_be_bytes(limbs);
+    let padding = be_bytes.len() - out.len();
+    be_bytes.by_ref().take(padding).for_each(|b| {
+        debug_assert_eq!(b, 0);
+    });
+    out.iter_mut().zip(be_bytes).for_each(|(o, i)| {
+        *o = i;
+    });
+}
+
 fn parse_big_endian_fixed_consttime<M>(
     ops: &CommonOps,
     bytes: untrusted::Input,
@@ -500,6 +551,16 @@ mod tests {
         q_minus_n_plus_n_equals_0_test(&p384::PUBLIC_SCALAR_OPS);
     }
 
+    #[test]
+    fn p521_q_minus_n_plus_n_equals_0_test() {
+        q_minus_n_plus_n_equals_0_test(&p521::PUBLIC_SCALAR_OPS);
+    }
+
+    #[test]
+    fn secp256k1_q_minus_n_plus_n_equals_0_test() {
+        q_minus_n_plus_n_equals_0_test(&secp256k1::PUBLIC_SCALAR_OPS);
+    }
+
     #[test]
     fn p256_elem_add_test() {
         elem_add_test(
@@ -516,6 +577,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn p521_elem_add_test() {
+        elem_add_test(
+            &p521::PUBLIC_SCALAR_OPS,
+            test_file!("ops/p521_elem_sum_tests.txt"),
+        );
+    }
+
+    #[test]
+    fn secp256k1_elem_add_test() {
+        elem_add_test(
+            &secp256k1::PUBLIC_SCALAR_OPS,
+            test_file!("ops/secp256k1_elem_sum_tests.txt"),
+        );
+    }
+
     fn elem_add_test(ops: &PublicScalarOps, test_file: test::File) {
         test::run(test_file, |section, test_case| {
             assert_eq!(section, "");
@@ -552,6 +629,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn p521_elem_sub_test() {
+        prefixed_extern! {
+            fn p521_elem_sub(r: *mut Limb, a: *const Limb, b: *const Limb);
+        }
+        elem_sub_test(
+            &p521::COMMON_OPS,
+            p521_elem_sub,
+            test_file!("ops/p521_elem_sum_tests.txt"),
+        );
+    }
+
+    #[test]
+    fn secp256k1_elem_sub_test() {
+        prefixed_extern! {
+            fn secp256k1_elem_sub(r: *mut Limb, a: *const Limb, b: *const Limb);
+        }
+        elem_sub_test(
+            &secp256k1::COMMON_OPS,
+            secp256k1_elem_sub,
+            test_file!("ops/secp256k1_elem_sum_tests.txt"),
+        );
+    }
+
     fn elem_sub_test(
         ops: &CommonOps,
         elem_sub: unsafe extern "C" fn(r: *mut Limb, a: *const Limb, b: *const Limb),
@@ -603,6 +704,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn p521_elem_div_by_2_test() {
+        prefixed_extern! {
+            fn p521_elem_div_by_2(r: *mut Limb, a: *const Limb);
+        }
+        elem_div_by_2_test(
+            &p521::COMMON_OPS,
+            p521_elem_div_by_2,
+            test_file!("ops/p521_elem_div_by_2_tests.txt"),
+        );
+    }
+
+    #[test]
+    fn secp256k1_elem_div_by_2_test() {
+        prefixed_extern! {
+            fn secp256k1_elem_div_by_2(r: *mut Limb, a: *const Limb);
+        }
+        elem_div_by_2_test(
+            &secp256k1::COMMON_OPS,
+            secp256k1_elem_div_by_2,
+            test_file!("ops/secp256k1_elem_div_by_2_tests.txt"),
+        );
+    }
+
     fn elem_div_by_2_test(
         ops: &CommonOps,
         elem_div_by_2: unsafe extern "C" fn(r: *mut Limb, a: *const Limb),
@@ -650,6 +775,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn p521_elem_neg_test() {
+        prefixed_extern! {
+            fn p521_elem_neg(r: *mut Limb, a: *const Limb);
+        }
+        elem_neg_test(
+            &p521::COMMON_OPS,
+            p521_elem_neg,
+            test_file!("ops/p521_elem_neg_tests.txt"),
+        );
+    }
+
+    #[test]
+    fn secp256k1_elem_neg_test() {
+        prefixed_extern! {
+            fn secp256k1_elem_neg(r: *mut Limb, a: *const Limb);
+        }
+        elem_neg_test(
+            &secp256k1::COMMON_OPS,
+            secp256k1_elem_neg,
+            test_file!("ops/secp256k1_elem_neg_tests.txt"),
+        );
+    }
+
     fn elem_neg_test(
         ops: &CommonOps,
         elem_neg: unsafe extern "C" fn(r: *mut Limb, a: *const Limb),
@@ -693,6 +842,19 @@ mod tests {
         elem_mul_test(&p384::COMMON_OPS, test_file!("ops/p384_elem_mul_tests.txt"));
     }
 
+    #[test]
+    fn p521_elem_mul_test() {
+        elem_mul_test(&p521::COMMON_OPS, test_file!("ops/p521_elem_mul_tests.txt"));
+    }
+
+    #[test]
+    fn secp256k1_elem_mul_test() {
+        elem_mul_test(
+            &secp256k1::COMMON_OPS,
+            test_file!("ops/secp256k1_elem_mul_tests.txt"),
+        );
+    }
+
     fn elem_mul_test(ops: &CommonOps, test_file: test::File) {
         test::run(test_file, |section, test_case| {
             assert_eq!(section, "");
@@ -723,6 +885,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn p521_scalar_mul_test() {
+        scalar_mul_test(
+            &p521::SCALAR_OPS,
+            test_file!("ops/p521_scalar_mul_tests.txt"),
+        );
+    }
+
+    #[test]
+    fn secp256k1_scalar_mul_test() {
+        scalar_mul_test(
+            &secp256k1::SCALAR_OPS,
+            test_file!("ops/secp256k1_scalar_mul_tests.txt"),
+        );
+    }
+
     fn scalar_mul_test(ops: &ScalarOps, test_file: test::File) {
         test::run(test_file, |section, test_case| {
             assert_eq!(section, "");
@@ -796,6 +974,18 @@ mod tests {
         let _ = p384::PRIVATE_SCALAR_OPS.scalar_inv_to_mont(&ZERO_SCALAR);
     }
 
+    #[test]
+    #[should_panic(expected = "!self.scalar_ops.common.is_zero(a)")]
+    fn p521_scalar_inv_to_mont_zero_panic_test() {
+        let _ = p521::PRIVATE_SCALAR_OPS.scalar_inv_to_mont(&ZERO_SCALAR);
+    }
+
+    #[test]
+    #[should_panic(expected = "!self.scalar_ops.common.is_zero(a)")]
+    fn secp256k1_scalar_inv_to_mont_zero_panic_test() {
+        let _ = secp256k1::PRIVATE_SCALAR_OPS.scalar_inv_to_mont(&ZERO_SCALAR);
+    }
+
     #[test]
     fn p256_point_sum_test() {
         point_sum_test(
@@ -812,6 +1002,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn p521_point_sum_test() {
+        point_sum_test(
+            &p521::PRIVATE_KEY_OPS,
+            test_file!("ops/p521_point_sum_tests.txt"),
+        );
+    }
+
+    #[test]
+    fn secp256k1_point_sum_test() {
+        point_sum_test(
+            &secp256k1::PRIVATE_KEY_OPS,
+            test_file!("ops/secp256k1_point_sum_tests.txt"),
+        );
+    }
+
     fn point_sum_test(ops: &PrivateKeyOps, test_file: test::File) {
         test::run(test_file, |section, test_case| {
             assert_eq!(section, "");
@@ -902,6 +1108,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn p521_point_double_test() {
+        prefixed_extern! {
+            fn p521_point_double(
+                r: *mut Limb,   // [p521::COMMON_OPS.num_limbs*3]
+                a: *const Limb, // [p521::COMMON_OPS.num_limbs*3]
+            );
+        }
+        point_double_test(
+            &p521::PRIVATE_KEY_OPS,
+            p521_point_double,
+            test_file!("ops/p521_point_double_tests.txt"),
+        );
+    }
+
+    #[test]
+    fn secp256k1_point_double_test() {
+        prefixed_extern! {
+            fn secp256k1_point_double(
+                r: *mut Limb,   // [secp256k1::COMMON_OPS.num_limbs*3]
+                a: *const Limb, // [secp256k1::COMMON_OPS.num_limbs*3]
+            );
+        }
+        point_double_test(
+            &secp256k1::PRIVATE_KEY_OPS,
+            secp256k1_point_double,
+            test_file!("ops/secp256k1_point_double_tests.txt"),
+        );
+    }
+
     fn point_double_test(
         ops: &PrivateKeyOps,
         point_double: unsafe extern "C" fn(
@@ -947,6 +1183,25 @@ mod tests {
         );
     }
 
+    /// TODO: We should be testing `point_mul` with points other than the generator.
+    #[test]
+    fn p521_point_mul_test() {
+        point_mul_base_tests(
+            &p521::PRIVATE_KEY_OPS,
+            |s| p521::PRIVATE_KEY_OPS.point_mul(s, &p521::GENERATOR),
+            test_file!("ops/p521_point_mul_base_tests.txt"),
+        );
+    }
+
+    #[test]
+    fn secp256k1_point_mul_test() {
+        point_mul_base_tests(
+            &secp256k1::PRIVATE_KEY_OPS,
+            |s| secp256k1::PRIVATE_KEY_OPS.point_mul(s, &secp256k1::GENERATOR),
+            test_file!("ops/secp256k1_point_mul_base_tests.txt"),
+        );
+    }
+
     #[test]
     fn p256_point_mul_serialized_test() {
         point_mul_serialized_test(
@@ -1014,6 +1269,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn p521_point_mul_base_test() {
+        point_mul_base_tests(
+            &p521::PRIVATE_KEY_OPS,
+            |s| p521::PRIVATE_KEY_OPS.point_mul_base(s),
+            test_file!("ops/p521_point_mul_base_tests.txt"),
+        );
+    }
+
+    #[test]
+    fn secp256k1_point_mul_base_test() {
+        point_mul_base_tests(
+            &secp256k1::PRIVATE_KEY_OPS,
+            |s| secp256k1::PRIVATE_KEY_OPS.point_mul_base(s),
+            test_file!("ops/secp256k1_point_mul_base_tests.txt"),
+        );
+    }
+
     pub(super) fn point_mul_base_tests(
         ops: &PrivateKeyOps,
         f: impl Fn(&Scalar) -> Point,
@@ -1216,3 +1489,5 @@ mod tests {
 mod elem;
 pub mod p256;
 pub mod p384;
+pub mod p521;
+pub mod secp256k1;
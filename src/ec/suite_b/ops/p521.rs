@@ -0,0 +1,298 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::{
+    elem::{binary_op, binary_op_assign},
+    elem_sqr_mul, Modulus, *,
+};
+
+// Elements and scalars are 521 bits, but they are stored in 576 bits, which
+// is a whole number of limbs for both 32-bit and 64-bit targets. Accordingly,
+// the Montgomery factor R is 2**576 on all targets.
+pub static COMMON_OPS: CommonOps = CommonOps {
+    num_limbs: 576 / LIMB_BITS,
+    order_bits: BitLength::from_usize_bits(521),
+
+    q: Modulus {
+        p: limbs_from_hex("1ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"),
+        rr: limbs_from_hex("4000000000000000000000000000"),
+    },
+    n: Elem::from_hex("1fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffa51868783bf2f966b7fcc0148f709a5d03bb5c9b8899c47aebb6fb71e91386409"),
+
+    a: Elem::from_hex("1fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe7fffffffffffff"),
+    b: Elem::from_hex("4d0fc94d10d05b42a077516d392dccd98af9dc5a44c8c77884f0ab0c9ca8f63f49bd8b29605e9dd8df839ab9efc41e961a78f7a28fea35a81f8014654fae586387"),
+
+    elem_mul_mont: p521_elem_mul_mont,
+    elem_sqr_mont: p521_elem_sqr_mont,
+
+    point_add_jacobian_impl: p521_point_add,
+};
+
+pub(super) static GENERATOR: (Elem<R>, Elem<R>) = (
+    Elem::from_hex("74e6cf1f65b311cada214e32409c829fda90fc1457b035a69edd50a5af3bf7f3ac947f0ee093d17fd46f19a459e0c2b5214dfcbf3f18e172deb331a16381adc101"),
+    Elem::from_hex("1e0022e452fda163e8deccc7aa224abcda2340bd7de8b939f33164bf7394caf7a132062a85c809fd683b09a9e384351396120445f4a3b4fe8b328460e4a5a9e268e"),
+);
+
+pub static PRIVATE_KEY_OPS: PrivateKeyOps = PrivateKeyOps {
+    common: &COMMON_OPS,
+    elem_inv_squared: p521_elem_inv_squared,
+    point_mul_base_impl: p521_point_mul_base_impl,
+    point_mul_impl: p521_point_mul,
+};
+
+fn p521_elem_inv_squared(a: &Elem<R>) -> Elem<R> {
+    // Calculate a**-2 (mod q) == a**(q - 3) (mod q)
+    //
+    // The exponent (q - 3) is 2**521 - 4, i.e. 519 one bits followed by two
+    // zero bits.
+
+    #[inline]
+    fn sqr_mul(a: &Elem<R>, squarings: usize, b: &Elem<R>) -> Elem<R> {
+        elem_sqr_mul(&COMMON_OPS, a, squarings, b)
+    }
+
+    let b_1 = &a;
+    let b_11 = sqr_mul(b_1, 1, b_1);
+    let b_111 = sqr_mul(&b_11, 1, b_1);
+    let f_1 = sqr_mul(&b_11, 2, &b_11);
+    let f_2 = sqr_mul(&f_1, 4, &f_1);
+    let f_4 = sqr_mul(&f_2, 8, &f_2);
+    let f_8 = sqr_mul(&f_4, 16, &f_4);
+    let f_16 = sqr_mul(&f_8, 32, &f_8);
+    let f_32 = sqr_mul(&f_16, 64, &f_16);
+    let f_64 = sqr_mul(&f_32, 128, &f_32);
+    let f_128 = sqr_mul(&f_64, 256, &f_64);
+    let f_1_111 = sqr_mul(&f_1, 3, &b_111);
+
+    // 519 one bits.
+    let mut acc = sqr_mul(&f_128, 7, &f_1_111);
+
+    // 519 one bits followed by two zero bits.
+    COMMON_OPS.elem_square(&mut acc);
+    COMMON_OPS.elem_square(&mut acc);
+
+    acc
+}
+
+fn p521_point_mul_base_impl(a: &Scalar) -> Point {
+    // XXX: Not efficient. TODO: Precompute multiples of the generator.
+    PRIVATE_KEY_OPS.point_mul(a, &GENERATOR)
+}
+
+pub static PUBLIC_KEY_OPS: PublicKeyOps = PublicKeyOps {
+    common: &COMMON_OPS,
+};
+
+pub static SCALAR_OPS: ScalarOps = ScalarOps {
+    common: &COMMON_OPS,
+    scalar_mul_mont: p521_scalar_mul_mont,
+};
+
+pub static PUBLIC_SCALAR_OPS: PublicScalarOps = PublicScalarOps {
+    scalar_ops: &SCALAR_OPS,
+    public_key_ops: &PUBLIC_KEY_OPS,
+    twin_mul: |g_scalar, p_scalar, p_xy| {
+        twin_mul_inefficient(&PRIVATE_KEY_OPS, g_scalar, p_scalar, p_xy)
+    },
+
+    q_minus_n: Elem::from_hex("5ae79787c40d069948033feb708f65a2fc44a36477663b851449048e16ec79bf6"),
+
+    // TODO: Use an optimized variable-time implementation.
+    scalar_inv_to_mont_vartime: |s| PRIVATE_SCALAR_OPS.scalar_inv_to_mont(s),
+};
+
+pub static PRIVATE_SCALAR_OPS: PrivateScalarOps = PrivateScalarOps {
+    scalar_ops: &SCALAR_OPS,
+
+    oneRR_mod_n: Scalar::from_hex("3d2d8e03d1492d0d455bcc6d61a8e567bccff3d142b7756e3edd6e23d82e49c7dbd3721ef557f75e0612a78d38794573fff707badce5547ea3137cd04dcf15dd04"),
+    scalar_inv_to_mont: p521_scalar_inv_to_mont,
+};
+
+fn p521_scalar_inv_to_mont(a: Scalar<R>) -> Scalar<R> {
+    // Calculate the modular inverse of scalar |a| using Fermat's Little
+    // Theorem:
+    //
+    //    a**-1 (mod n) == a**(n - 2) (mod n)
+    //
+    // The exponent (n - 2) is:
+    //
+    //    0x1fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+    //      ffa51868783bf2f966b7fcc0148f709a5d03bb5c9b8899c47aebb6fb71e91386407
+
+    fn mul(a: &Scalar<R>, b: &Scalar<R>) -> Scalar<R> {
+        binary_op(p521_scalar_mul_mont, a, b)
+    }
+
+    fn sqr(a: &Scalar<R>) -> Scalar<R> {
+        binary_op(p521_scalar_mul_mont, a, a)
+    }
+
+    fn sqr_mut(a: &mut Scalar<R>) {
+        unary_op_from_binary_op_assign(p521_scalar_mul_mont, a);
+    }
+
+    // Returns (`a` squared `squarings` times) * `b`.
+    fn sqr_mul(a: &Scalar<R>, squarings: usize, b: &Scalar<R>) -> Scalar<R> {
+        debug_assert!(squarings >= 1);
+        let mut tmp = sqr(a);
+        for _ in 1..squarings {
+            sqr_mut(&mut tmp);
+        }
+        mul(&tmp, b)
+    }
+
+    // Sets `acc` = (`acc` squared `squarings` times) * `b`.
+    fn sqr_mul_acc(acc: &mut Scalar<R>, squarings: usize, b: &Scalar<R>) {
+        debug_assert!(squarings >= 1);
+        for _ in 0..squarings {
+            sqr_mut(acc);
+        }
+        binary_op_assign(p521_scalar_mul_mont, acc, b)
+    }
+
+    // Indexes into `d`.
+    const B_1: usize = 0;
+    const B_11: usize = 1;
+    const B_101: usize = 2;
+    const B_111: usize = 3;
+    const B_1001: usize = 4;
+    const B_1011: usize = 5;
+    const B_1101: usize = 6;
+    const B_1111: usize = 7;
+    const DIGIT_COUNT: usize = 8;
+
+    let mut d = [Scalar::zero(); DIGIT_COUNT];
+    d[B_1] = a;
+    let b_10 = sqr(&d[B_1]);
+    for i in B_11..DIGIT_COUNT {
+        d[i] = mul(&d[i - 1], &b_10);
+    }
+
+    let ff = sqr_mul(&d[B_1111], 0 + 4, &d[B_1111]);
+    let ffff = sqr_mul(&ff, 0 + 8, &ff);
+    let ffffffff = sqr_mul(&ffff, 0 + 16, &ffff);
+    let f_16 = sqr_mul(&ffffffff, 0 + 32, &ffffffff);
+    let f_32 = sqr_mul(&f_16, 0 + 64, &f_16);
+    let f_64 = sqr_mul(&f_32, 0 + 128, &f_32);
+
+    // 260 one bits.
+    let mut acc = sqr_mul(&f_64, 0 + 4, &d[B_1111]);
+
+    // 262 one bits.
+    sqr_mul_acc(&mut acc, 0 + 2, &d[B_11]);
+
+    // The rest of the exponent, in binary, is:
+    //
+    //    0100101000110000110100001111000001110111111001011111001011001101
+    //    0110111111111001100000000010100100011110111000010011010010111010
+    //    0000011101110110101110010011011100010001001100111000100011110101
+    //    1101011101101101111101101110001111010010001001110000110010000000
+    //    111
+
+    #[allow(clippy::cast_possible_truncation)]
+    static REMAINING_WINDOWS: [(u8, u8); 52] = [
+        (1 + 4, B_1001 as u8),
+        (1 + 1, B_1 as u8),
+        (3 + 2, B_11 as u8),
+        (4 + 4, B_1101 as u8),
+        (4 + 4, B_1111 as u8),
+        (5 + 3, B_111 as u8),
+        (1 + 4, B_1111 as u8),
+        (2, B_11 as u8),
+        (2 + 4, B_1011 as u8),
+        (3, B_111 as u8),
+        (2 + 4, B_1011 as u8),
+        (2 + 4, B_1101 as u8),
+        (1 + 4, B_1101 as u8),
+        (4, B_1111 as u8),
+        (4, B_1111 as u8),
+        (2 + 2, B_11 as u8),
+        (9 + 3, B_101 as u8),
+        (2 + 1, B_1 as u8),
+        (3 + 4, B_1111 as u8),
+        (1 + 3, B_111 as u8),
+        (4 + 4, B_1001 as u8),
+        (3, B_101 as u8),
+        (2 + 4, B_1011 as u8),
+        (3, B_101 as u8),
+        (6 + 3, B_111 as u8),
+        (1 + 3, B_111 as u8),
+        (1 + 4, B_1101 as u8),
+        (1 + 3, B_111 as u8),
+        (2 + 4, B_1001 as u8),
+        (4, B_1011 as u8),
+        (1, B_1 as u8),
+        (3 + 1, B_1 as u8),
+        (3 + 4, B_1001 as u8),
+        (4, B_1001 as u8),
+        (2, B_11 as u8),
+        (3 + 1, B_1 as u8),
+        (3 + 4, B_1111 as u8),
+        (1 + 4, B_1011 as u8),
+        (3, B_101 as u8),
+        (1 + 3, B_111 as u8),
+        (1 + 4, B_1101 as u8),
+        (4, B_1011 as u8),
+        (3, B_111 as u8),
+        (1 + 4, B_1101 as u8),
+        (2, B_11 as u8),
+        (3 + 4, B_1111 as u8),
+        (1 + 4, B_1001 as u8),
+        (3 + 4, B_1001 as u8),
+        (2, B_11 as u8),
+        (4 + 2, B_11 as u8),
+        (2 + 1, B_1 as u8),
+        (7 + 3, B_111 as u8),
+    ];
+
+    for &(squarings, digit) in &REMAINING_WINDOWS[..] {
+        sqr_mul_acc(&mut acc, usize::from(squarings), &d[usize::from(digit)]);
+    }
+
+    acc
+}
+
+unsafe extern "C" fn p521_elem_sqr_mont(
+    r: *mut Limb,   // [COMMON_OPS.num_limbs]
+    a: *const Limb, // [COMMON_OPS.num_limbs]
+) {
+    // XXX: Inefficient. TODO: Make a dedicated squaring routine.
+    p521_elem_mul_mont(r, a, a);
+}
+
+prefixed_extern! {
+    fn p521_elem_mul_mont(
+        r: *mut Limb,   // [COMMON_OPS.num_limbs]
+        a: *const Limb, // [COMMON_OPS.num_limbs]
+        b: *const Limb, // [COMMON_OPS.num_limbs]
+    );
+
+    fn p521_point_add(
+        r: *mut Limb,   // [3][COMMON_OPS.num_limbs]
+        a: *const Limb, // [3][COMMON_OPS.num_limbs]
+        b: *const Limb, // [3][COMMON_OPS.num_limbs]
+    );
+    fn p521_point_mul(
+        r: *mut Limb,          // [3][COMMON_OPS.num_limbs]
+        p_scalar: *const Limb, // [COMMON_OPS.num_limbs]
+        p_x: *const Limb,      // [COMMON_OPS.num_limbs]
+        p_y: *const Limb,      // [COMMON_OPS.num_limbs]
+    );
+
+    fn p521_scalar_mul_mont(
+        r: *mut Limb,   // [COMMON_OPS.num_limbs]
+        a: *const Limb, // [COMMON_OPS.num_limbs]
+        b: *const Limb, // [COMMON_OPS.num_limbs]
+    );
+}
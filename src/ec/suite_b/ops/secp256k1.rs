@@ -0,0 +1,283 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::{
+    elem::{binary_op, binary_op_assign},
+    elem_sqr_mul, elem_sqr_mul_acc, Modulus, *,
+};
+
+pub static COMMON_OPS: CommonOps = CommonOps {
+    num_limbs: 256 / LIMB_BITS,
+    order_bits: BitLength::from_usize_bits(256),
+
+    q: Modulus {
+        p: limbs_from_hex("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"),
+        rr: limbs_from_hex("1000007a2000e90a1"),
+    },
+    n: Elem::from_hex("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"),
+
+    // Unlike the NIST curves, where a == -3, secp256k1 has a == 0.
+    a: Elem::from_hex("0"),
+    b: Elem::from_hex("700001ab7"),
+
+    elem_mul_mont: secp256k1_elem_mul_mont,
+    elem_sqr_mont: secp256k1_elem_sqr_mont,
+
+    point_add_jacobian_impl: secp256k1_point_add,
+};
+
+pub(super) static GENERATOR: (Elem<R>, Elem<R>) = (
+    Elem::from_hex("9981e643e9089f48979f48c033fd129c231e295329bc66dbd7362e5a487e2097"),
+    Elem::from_hex("cf3f851fd4a582d670b6b59aac19c1368dfc5d5d1f1dc64db15ea6d2d3dbabe2"),
+);
+
+pub static PRIVATE_KEY_OPS: PrivateKeyOps = PrivateKeyOps {
+    common: &COMMON_OPS,
+    elem_inv_squared: secp256k1_elem_inv_squared,
+    point_mul_base_impl: secp256k1_point_mul_base_impl,
+    point_mul_impl: secp256k1_point_mul,
+};
+
+fn secp256k1_elem_inv_squared(a: &Elem<R>) -> Elem<R> {
+    // Calculate a**-2 (mod q) == a**(q - 3) (mod q)
+    //
+    // The exponent (q - 3) is:
+    //
+    //    0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2c
+    //
+    // i.e. 223 one bits, a zero bit, 22 one bits, and then 0000101100.
+
+    #[inline]
+    fn sqr_mul(a: &Elem<R>, squarings: usize, b: &Elem<R>) -> Elem<R> {
+        elem_sqr_mul(&COMMON_OPS, a, squarings, b)
+    }
+
+    #[inline]
+    fn sqr_mul_acc(a: &mut Elem<R>, squarings: usize, b: &Elem<R>) {
+        elem_sqr_mul_acc(&COMMON_OPS, a, squarings, b)
+    }
+
+    // `x_n` is `n` one bits.
+    let b_1 = &a;
+    let x_2 = sqr_mul(b_1, 1, b_1);
+    let x_3 = sqr_mul(&x_2, 1, b_1);
+    let x_6 = sqr_mul(&x_3, 3, &x_3);
+    let x_9 = sqr_mul(&x_6, 3, &x_3);
+    let x_11 = sqr_mul(&x_9, 2, &x_2);
+    let x_22 = sqr_mul(&x_11, 11, &x_11);
+    let x_44 = sqr_mul(&x_22, 22, &x_22);
+    let x_88 = sqr_mul(&x_44, 44, &x_44);
+    let x_176 = sqr_mul(&x_88, 88, &x_88);
+    let x_220 = sqr_mul(&x_176, 44, &x_44);
+
+    // 223 one bits.
+    let mut acc = sqr_mul(&x_220, 3, &x_3);
+
+    // 223 one bits, a zero bit, and 22 one bits.
+    sqr_mul_acc(&mut acc, 1 + 22, &x_22);
+
+    // ...0000101100.
+    sqr_mul_acc(&mut acc, 4 + 1, b_1);
+    sqr_mul_acc(&mut acc, 1 + 2, &x_2);
+    COMMON_OPS.elem_square(&mut acc);
+    COMMON_OPS.elem_square(&mut acc);
+
+    acc
+}
+
+fn secp256k1_point_mul_base_impl(a: &Scalar) -> Point {
+    // XXX: Not efficient. TODO: Precompute multiples of the generator.
+    PRIVATE_KEY_OPS.point_mul(a, &GENERATOR)
+}
+
+pub static PUBLIC_KEY_OPS: PublicKeyOps = PublicKeyOps {
+    common: &COMMON_OPS,
+};
+
+pub static SCALAR_OPS: ScalarOps = ScalarOps {
+    common: &COMMON_OPS,
+    scalar_mul_mont: secp256k1_scalar_mul_mont,
+};
+
+pub static PUBLIC_SCALAR_OPS: PublicScalarOps = PublicScalarOps {
+    scalar_ops: &SCALAR_OPS,
+    public_key_ops: &PUBLIC_KEY_OPS,
+    twin_mul: |g_scalar, p_scalar, p_xy| {
+        twin_mul_inefficient(&PRIVATE_KEY_OPS, g_scalar, p_scalar, p_xy)
+    },
+
+    q_minus_n: Elem::from_hex("14551231950b75fc4402da1722fc9baee"),
+
+    // TODO: Use an optimized variable-time implementation.
+    scalar_inv_to_mont_vartime: |s| PRIVATE_SCALAR_OPS.scalar_inv_to_mont(s),
+};
+
+pub static PRIVATE_SCALAR_OPS: PrivateScalarOps = PrivateScalarOps {
+    scalar_ops: &SCALAR_OPS,
+
+    oneRR_mod_n: Scalar::from_hex(
+        "9d671cd581c69bc5e697f5e45bcd07c6741496c20e7cf878896cf21467d7d140",
+    ),
+    scalar_inv_to_mont: secp256k1_scalar_inv_to_mont,
+};
+
+fn secp256k1_scalar_inv_to_mont(a: Scalar<R>) -> Scalar<R> {
+    // Calculate the modular inverse of scalar |a| using Fermat's Little
+    // Theorem:
+    //
+    //    a**-1 (mod n) == a**(n - 2) (mod n)
+    //
+    // The exponent (n - 2) is:
+    //
+    //    0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd036413f
+
+    fn mul(a: &Scalar<R>, b: &Scalar<R>) -> Scalar<R> {
+        binary_op(secp256k1_scalar_mul_mont, a, b)
+    }
+
+    fn sqr(a: &Scalar<R>) -> Scalar<R> {
+        binary_op(secp256k1_scalar_mul_mont, a, a)
+    }
+
+    fn sqr_mut(a: &mut Scalar<R>) {
+        unary_op_from_binary_op_assign(secp256k1_scalar_mul_mont, a);
+    }
+
+    // Returns (`a` squared `squarings` times) * `b`.
+    fn sqr_mul(a: &Scalar<R>, squarings: usize, b: &Scalar<R>) -> Scalar<R> {
+        debug_assert!(squarings >= 1);
+        let mut tmp = sqr(a);
+        for _ in 1..squarings {
+            sqr_mut(&mut tmp);
+        }
+        mul(&tmp, b)
+    }
+
+    // Sets `acc` = (`acc` squared `squarings` times) * `b`.
+    fn sqr_mul_acc(acc: &mut Scalar<R>, squarings: usize, b: &Scalar<R>) {
+        debug_assert!(squarings >= 1);
+        for _ in 0..squarings {
+            sqr_mut(acc);
+        }
+        binary_op_assign(secp256k1_scalar_mul_mont, acc, b)
+    }
+
+    // Indexes into `d`.
+    const B_1: usize = 0;
+    const B_11: usize = 1;
+    const B_101: usize = 2;
+    const B_111: usize = 3;
+    const B_1001: usize = 4;
+    const B_1011: usize = 5;
+    const B_1101: usize = 6;
+    const B_1111: usize = 7;
+    const DIGIT_COUNT: usize = 8;
+
+    let mut d = [Scalar::zero(); DIGIT_COUNT];
+    d[B_1] = a;
+    let b_10 = sqr(&d[B_1]);
+    for i in B_11..DIGIT_COUNT {
+        d[i] = mul(&d[i - 1], &b_10);
+    }
+
+    let ff = sqr_mul(&d[B_1111], 0 + 4, &d[B_1111]);
+    let ffff = sqr_mul(&ff, 0 + 8, &ff);
+    let ffffffff = sqr_mul(&ffff, 0 + 16, &ffff);
+    let ffffffffffffffff = sqr_mul(&ffffffff, 0 + 32, &ffffffff);
+
+    // 96 one bits.
+    let mut acc = sqr_mul(&ffffffffffffffff, 0 + 32, &ffffffff);
+
+    // 127 one bits.
+    sqr_mul_acc(&mut acc, 0 + 16, &ffff);
+    sqr_mul_acc(&mut acc, 0 + 8, &ff);
+    sqr_mul_acc(&mut acc, 0 + 4, &d[B_1111]);
+    sqr_mul_acc(&mut acc, 0 + 3, &d[B_111]);
+
+    // The rest of the exponent, in binary, is:
+    //
+    //    0101110101010111011011100111001101010111101001000101000000011101
+    //    1101111111101001001011110100011001101000000110110010000010011111
+    //    1
+
+    #[allow(clippy::cast_possible_truncation)]
+    static REMAINING_WINDOWS: [(u8, u8); 26] = [
+        (1 + 4, B_1011 as u8),
+        (3, B_101 as u8),
+        (1 + 3, B_101 as u8),
+        (1 + 3, B_111 as u8),
+        (1 + 4, B_1101 as u8),
+        (2, B_11 as u8),
+        (2 + 3, B_111 as u8),
+        (2 + 4, B_1101 as u8),
+        (1 + 4, B_1011 as u8),
+        (4, B_1101 as u8),
+        (2 + 1, B_1 as u8),
+        (3 + 3, B_101 as u8),
+        (7 + 3, B_111 as u8),
+        (1 + 3, B_111 as u8),
+        (1 + 4, B_1111 as u8),
+        (4, B_1111 as u8),
+        (1 + 4, B_1001 as u8),
+        (2 + 4, B_1011 as u8),
+        (4, B_1101 as u8),
+        (3 + 2, B_11 as u8),
+        (2 + 4, B_1101 as u8),
+        (6 + 4, B_1101 as u8),
+        (4, B_1001 as u8),
+        (5 + 4, B_1001 as u8),
+        (4, B_1111 as u8),
+        (1, B_1 as u8),
+    ];
+
+    for &(squarings, digit) in &REMAINING_WINDOWS[..] {
+        sqr_mul_acc(&mut acc, usize::from(squarings), &d[usize::from(digit)]);
+    }
+
+    acc
+}
+
+unsafe extern "C" fn secp256k1_elem_sqr_mont(
+    r: *mut Limb,   // [COMMON_OPS.num_limbs]
+    a: *const Limb, // [COMMON_OPS.num_limbs]
+) {
+    // XXX: Inefficient. TODO: Make a dedicated squaring routine.
+    secp256k1_elem_mul_mont(r, a, a);
+}
+
+prefixed_extern! {
+    fn secp256k1_elem_mul_mont(
+        r: *mut Limb,   // [COMMON_OPS.num_limbs]
+        a: *const Limb, // [COMMON_OPS.num_limbs]
+        b: *const Limb, // [COMMON_OPS.num_limbs]
+    );
+
+    fn secp256k1_point_add(
+        r: *mut Limb,   // [3][COMMON_OPS.num_limbs]
+        a: *const Limb, // [3][COMMON_OPS.num_limbs]
+        b: *const Limb, // [3][COMMON_OPS.num_limbs]
+    );
+    fn secp256k1_point_mul(
+        r: *mut Limb,          // [3][COMMON_OPS.num_limbs]
+        p_scalar: *const Limb, // [COMMON_OPS.num_limbs]
+        p_x: *const Limb,      // [COMMON_OPS.num_limbs]
+        p_y: *const Limb,      // [COMMON_OPS.num_limbs]
+    );
+
+    fn secp256k1_scalar_mul_mont(
+        r: *mut Limb,   // [COMMON_OPS.num_limbs]
+        a: *const Limb, // [COMMON_OPS.num_limbs]
+        b: *const Limb, // [COMMON_OPS.num_limbs]
+    );
+}
@@ -16,7 +16,7 @@
 //! ECDSA signing).
 
 use super::{ops::*, verify_affine_point_is_on_the_curve};
-use crate::{arithmetic::montgomery::R, ec, error, limb, rand};
+use crate::{arithmetic::montgomery::R, ec, error, rand};
 
 /// Generates a random scalar in the range [1, n).
 pub fn random_scalar(
@@ -65,6 +65,11 @@ pub fn generate_private_scalar_bytes(
         // requested security strength is delegated to `rng`.
         rng.fill(candidate)?;
 
+        // When the order's bit length isn't a multiple of 8 (P-521), clear the
+        // excess high bits so that candidates aren't almost always rejected.
+        let excess_bits = (candidate.len() * 8) - ops.common.order_bits().as_bits();
+        candidate[0] &= 0xff >> excess_bits;
+
         // NSA Guide Steps 5, 6, and 7.
         if check_scalar_big_endian_bytes(ops, candidate).is_err() {
             continue;
@@ -185,11 +190,11 @@ pub fn big_endian_affine_from_jacobian(
     let (x_aff, y_aff) = affine_from_jacobian(ops, p)?;
     if let Some(x_out) = x_out {
         let x = ops.common.elem_unencoded(&x_aff);
-        limb::big_endian_from_limbs(ops.leak_limbs(&x), x_out);
+        big_endian_fixed_from_limbs(ops.common, ops.leak_limbs(&x), x_out);
     }
     if let Some(y_out) = y_out {
         let y = ops.common.elem_unencoded(&y_aff);
-        limb::big_endian_from_limbs(ops.leak_limbs(&y), y_out);
+        big_endian_fixed_from_limbs(ops.common, ops.leak_limbs(&y), y_out);
     }
 
     Ok(())
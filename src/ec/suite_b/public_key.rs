@@ -100,6 +100,8 @@ mod tests {
             &ops::p256::PUBLIC_KEY_OPS
         } else if curve_name == "P-384" {
             &ops::p384::PUBLIC_KEY_OPS
+        } else if curve_name == "P-521" {
+            &ops::p521::PUBLIC_KEY_OPS
         } else {
             panic!("Unsupported curve: {}", curve_name);
         }
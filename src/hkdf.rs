@@ -36,6 +36,9 @@ impl Algorithm {
 pub static HKDF_SHA1_FOR_LEGACY_USE_ONLY: Algorithm =
     Algorithm(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY);
 
+/// HKDF using HMAC-SHA-224.
+pub static HKDF_SHA224: Algorithm = Algorithm(hmac::HMAC_SHA224);
+
 /// HKDF using HMAC-SHA-256.
 pub static HKDF_SHA256: Algorithm = Algorithm(hmac::HMAC_SHA256);
 
@@ -45,6 +48,18 @@ pub static HKDF_SHA384: Algorithm = Algorithm(hmac::HMAC_SHA384);
 /// HKDF using HMAC-SHA-512.
 pub static HKDF_SHA512: Algorithm = Algorithm(hmac::HMAC_SHA512);
 
+/// HKDF using HMAC-SHA-512/224.
+pub static HKDF_SHA512_224: Algorithm = Algorithm(hmac::HMAC_SHA512_224);
+
+/// HKDF using HMAC-SHA3-256.
+pub static HKDF_SHA3_256: Algorithm = Algorithm(hmac::HMAC_SHA3_256);
+
+/// HKDF using HMAC-SHA3-384.
+pub static HKDF_SHA3_384: Algorithm = Algorithm(hmac::HMAC_SHA3_384);
+
+/// HKDF using HMAC-SHA3-512.
+pub static HKDF_SHA3_512: Algorithm = Algorithm(hmac::HMAC_SHA3_512);
+
 impl KeyType for Algorithm {
     fn len(&self) -> usize {
         self.0.digest_algorithm().output_len()
@@ -126,6 +126,9 @@ impl Algorithm {
 /// HMAC using SHA-1. Obsolete.
 pub static HMAC_SHA1_FOR_LEGACY_USE_ONLY: Algorithm = Algorithm(&digest::SHA1_FOR_LEGACY_USE_ONLY);
 
+/// HMAC using SHA-224.
+pub static HMAC_SHA224: Algorithm = Algorithm(&digest::SHA224);
+
 /// HMAC using SHA-256.
 pub static HMAC_SHA256: Algorithm = Algorithm(&digest::SHA256);
 
@@ -135,6 +138,18 @@ pub static HMAC_SHA384: Algorithm = Algorithm(&digest::SHA384);
 /// HMAC using SHA-512.
 pub static HMAC_SHA512: Algorithm = Algorithm(&digest::SHA512);
 
+/// HMAC using SHA-512/224.
+pub static HMAC_SHA512_224: Algorithm = Algorithm(&digest::SHA512_224);
+
+/// HMAC using SHA3-256.
+pub static HMAC_SHA3_256: Algorithm = Algorithm(&digest::SHA3_256);
+
+/// HMAC using SHA3-384.
+pub static HMAC_SHA3_384: Algorithm = Algorithm(&digest::SHA3_384);
+
+/// HMAC using SHA3-512.
+pub static HMAC_SHA3_512: Algorithm = Algorithm(&digest::SHA3_512);
+
 /// An HMAC tag.
 ///
 /// For a given tag `t`, use `t.as_ref()` to get the tag value as a byte slice.
@@ -226,7 +241,7 @@ impl Key {
 
         const IPAD: u8 = 0x36;
 
-        let mut padded_key = [IPAD; digest::MAX_BLOCK_LEN];
+        let mut padded_key = [IPAD; digest::MAX_ANY_BLOCK_LEN];
         let padded_key = &mut padded_key[..block_len];
 
         // If the key is shorter than one block then we're supposed to act like
@@ -301,6 +316,30 @@ impl Context {
         self.inner.update(data);
     }
 
+    /// Exports the state of the HMAC calculation, so that it can be resumed
+    /// later, possibly in another process, with [`Self::restore`].
+    ///
+    /// Only the state of the inner hash is exported; the key must be supplied
+    /// again to `restore`. Nevertheless, the exported state is derived from
+    /// the key, so it should be protected like the key. See
+    /// [`digest::Context::export_state`] for details of the exported state.
+    pub fn export_state(&self) -> digest::ExportedState {
+        self.inner.export_state()
+    }
+
+    /// Restores a context from a state exported by [`Self::export_state`]
+    /// for a context that was constructed with `key`.
+    ///
+    /// Fails if `state` is malformed. Restoring a state with a different key
+    /// is not detected, but results in incorrect tags.
+    pub fn restore(key: &Key, state: &[u8]) -> Result<Self, error::Unspecified> {
+        let inner = digest::Context::restore(key.inner.algorithm, state)?;
+        Ok(Self {
+            inner,
+            outer: key.outer.clone(),
+        })
+    }
+
     /// Finalizes the HMAC calculation and returns the HMAC value. `sign`
     /// consumes the context so it cannot be (mis-)used after `sign` has been
     /// called.
@@ -310,7 +349,7 @@ impl Context {
     /// instead.
     pub fn sign(self) -> Tag {
         let algorithm = self.inner.algorithm();
-        let mut pending = [0u8; digest::MAX_BLOCK_LEN];
+        let mut pending = [0u8; digest::MAX_ANY_BLOCK_LEN];
         let pending = &mut pending[..algorithm.block_len()];
         let num_pending = algorithm.output_len();
         pending[..num_pending].copy_from_slice(self.inner.finish().as_ref());
@@ -0,0 +1,842 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Hybrid Public Key Encryption (HPKE), as specified in [RFC 9180].
+//!
+//! A sender uses the recipient's public key to set up a `SenderContext`,
+//! which produces an encapsulated key that is sent to the recipient along
+//! with the ciphertexts. The recipient uses the encapsulated key and its
+//! `agreement::StaticPrivateKey` to set up the matching `ReceiverContext`.
+//! Both contexts can seal or open, respectively, any number of messages, in
+//! order, and can export secrets derived from the shared context.
+//!
+//! The Base and PSK modes are supported, with the DHKEMs for X25519, X448,
+//! P-256, P-384, and P-521, the HKDF-SHA-2 KDFs, and AES-GCM and
+//! ChaCha20-Poly1305 as well as the export-only AEAD.
+//!
+//! # Example
+//!
+//! ```
+//! use ring::{aead::Aad, agreement, hpke, rand};
+//!
+//! let rng = rand::SystemRandom::new();
+//! let suite = hpke::Suite::new(
+//!     &hpke::DHKEM_X25519_HKDF_SHA256,
+//!     &hpke::HKDF_SHA256,
+//!     &hpke::AES_128_GCM,
+//! );
+//!
+//! let recipient_key = agreement::StaticPrivateKey::generate(&agreement::X25519, &rng)?;
+//!
+//! let (enc, mut sender) =
+//!     hpke::setup_base_sender(&suite, recipient_key.public_key().as_ref(), b"info", &rng)?;
+//! let mut in_out = b"hello".to_vec();
+//! sender.seal_in_place_append_tag(Aad::empty(), &mut in_out)?;
+//!
+//! let mut receiver = hpke::setup_base_receiver(&suite, enc.as_ref(), &recipient_key, b"info")?;
+//! let plaintext = receiver.open_in_place(Aad::empty(), &mut in_out)?;
+//! assert_eq!(plaintext, b"hello");
+//! # Ok::<(), ring::error::Unspecified>(())
+//! ```
+//!
+//! [RFC 9180]: https://www.rfc-editor.org/rfc/rfc9180
+
+use crate::{aead, agreement, digest, error, hkdf, hmac, rand};
+
+/// A Key Encapsulation Mechanism (KEM) for HPKE.
+pub struct Kem {
+    id: KemID,
+    agreement_algorithm: &'static agreement::Algorithm,
+    kdf: &'static hkdf::Algorithm,
+
+    // Nsecret.
+    shared_secret_len: usize,
+
+    // Nsk.
+    private_key_len: usize,
+
+    // The mask applied to the first byte of each candidate private key in
+    // `DeriveKeyPair`, or `None` for the curves whose private keys are
+    // arbitrary byte strings.
+    candidate_bitmask: Option<u8>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u16)]
+#[allow(non_camel_case_types)]
+enum KemID {
+    DHKEM_P256_HKDF_SHA256 = 0x0010,
+    DHKEM_P384_HKDF_SHA384 = 0x0011,
+    DHKEM_P521_HKDF_SHA512 = 0x0012,
+    DHKEM_X25519_HKDF_SHA256 = 0x0020,
+    DHKEM_X448_HKDF_SHA512 = 0x0021,
+}
+
+derive_debug_via_id!(Kem);
+
+impl Eq for Kem {}
+
+impl PartialEq for Kem {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+/// DHKEM(P-256, HKDF-SHA256).
+pub static DHKEM_P256_HKDF_SHA256: Kem = Kem {
+    id: KemID::DHKEM_P256_HKDF_SHA256,
+    agreement_algorithm: &agreement::ECDH_P256,
+    kdf: &hkdf::HKDF_SHA256,
+    shared_secret_len: 32,
+    private_key_len: 32,
+    candidate_bitmask: Some(0xff),
+};
+
+/// DHKEM(P-384, HKDF-SHA384).
+pub static DHKEM_P384_HKDF_SHA384: Kem = Kem {
+    id: KemID::DHKEM_P384_HKDF_SHA384,
+    agreement_algorithm: &agreement::ECDH_P384,
+    kdf: &hkdf::HKDF_SHA384,
+    shared_secret_len: 48,
+    private_key_len: 48,
+    candidate_bitmask: Some(0xff),
+};
+
+/// DHKEM(P-521, HKDF-SHA512).
+pub static DHKEM_P521_HKDF_SHA512: Kem = Kem {
+    id: KemID::DHKEM_P521_HKDF_SHA512,
+    agreement_algorithm: &agreement::ECDH_P521,
+    kdf: &hkdf::HKDF_SHA512,
+    shared_secret_len: 64,
+    private_key_len: 66,
+    candidate_bitmask: Some(0x01),
+};
+
+/// DHKEM(X25519, HKDF-SHA256).
+pub static DHKEM_X25519_HKDF_SHA256: Kem = Kem {
+    id: KemID::DHKEM_X25519_HKDF_SHA256,
+    agreement_algorithm: &agreement::X25519,
+    kdf: &hkdf::HKDF_SHA256,
+    shared_secret_len: 32,
+    private_key_len: 32,
+    candidate_bitmask: None,
+};
+
+/// DHKEM(X448, HKDF-SHA512).
+pub static DHKEM_X448_HKDF_SHA512: Kem = Kem {
+    id: KemID::DHKEM_X448_HKDF_SHA512,
+    agreement_algorithm: &agreement::X448,
+    kdf: &hkdf::HKDF_SHA512,
+    shared_secret_len: 64,
+    private_key_len: 56,
+    candidate_bitmask: None,
+};
+
+impl Kem {
+    /// The KEM's identifier in the HPKE KEM registry.
+    #[inline]
+    pub fn id(&self) -> u16 {
+        self.id as u16
+    }
+
+    /// The key agreement algorithm of the recipient's keys.
+    #[inline]
+    pub fn agreement_algorithm(&self) -> &'static agreement::Algorithm {
+        self.agreement_algorithm
+    }
+
+    /// The length of the KEM's private keys, which is also the minimum
+    /// length of the input to `derive_key_pair`.
+    #[inline]
+    pub fn private_key_len(&self) -> usize {
+        self.private_key_len
+    }
+
+    /// Deterministically derives a key pair from the input keying material
+    /// `ikm`, using `DeriveKeyPair` from [RFC 9180 Section 7.1.3].
+    ///
+    /// `ikm` must have at least `private_key_len()` bytes of entropy; it is
+    /// rejected if it is shorter than that.
+    ///
+    /// [RFC 9180 Section 7.1.3]: https://www.rfc-editor.org/rfc/rfc9180#section-7.1.3
+    pub fn derive_key_pair(
+        &self,
+        ikm: &[u8],
+    ) -> Result<agreement::StaticPrivateKey, error::Unspecified> {
+        if ikm.len() < self.private_key_len {
+            return Err(error::Unspecified);
+        }
+        let suite_id = self.suite_id();
+        let dkp_prk = self.prk(labeled_extract(self.kdf, &suite_id, b"", b"dkp_prk", ikm));
+
+        let mut sk = [0u8; MAX_PRIVATE_KEY_LEN];
+        let sk = &mut sk[..self.private_key_len];
+        let bitmask = match self.candidate_bitmask {
+            Some(bitmask) => bitmask,
+            None => {
+                labeled_expand(&dkp_prk, &suite_id, b"sk", &[], sk)?;
+                return agreement::StaticPrivateKey::from_private_key_bytes(
+                    self.agreement_algorithm,
+                    sk,
+                )
+                .map_err(error::Unspecified::from);
+            }
+        };
+        for counter in 0..=u8::MAX {
+            labeled_expand(&dkp_prk, &suite_id, b"candidate", &[counter], sk)?;
+            sk[0] &= bitmask;
+            if let Ok(key) =
+                agreement::StaticPrivateKey::from_private_key_bytes(self.agreement_algorithm, sk)
+            {
+                return Ok(key);
+            }
+        }
+        Err(error::Unspecified)
+    }
+
+    fn suite_id(&self) -> [u8; KEM_SUITE_ID_LEN] {
+        let mut suite_id = [0u8; KEM_SUITE_ID_LEN];
+        suite_id[..3].copy_from_slice(b"KEM");
+        suite_id[3..].copy_from_slice(&self.id().to_be_bytes());
+        suite_id
+    }
+
+    fn prk(&self, tag: hmac::Tag) -> hkdf::Prk {
+        hkdf::Prk::new_less_safe(*self.kdf, tag.as_ref())
+    }
+
+    // `Encap()`, with the ephemeral key pair derived from `rng`'s output.
+    fn encap(
+        &self,
+        pk_r: &[u8],
+        rng: &dyn rand::SecureRandom,
+        shared_secret: &mut [u8],
+    ) -> Result<agreement::PublicKey, error::Unspecified> {
+        let mut ikm_e = [0u8; MAX_PRIVATE_KEY_LEN];
+        let ikm_e = &mut ikm_e[..self.private_key_len];
+        rng.fill(ikm_e)?;
+        let sk_e = self.derive_key_pair(ikm_e)?;
+        let enc = sk_e.public_key().clone();
+        self.shared_secret(&sk_e, pk_r, enc.as_ref(), pk_r, shared_secret)?;
+        Ok(enc)
+    }
+
+    // `Decap()`.
+    fn decap(
+        &self,
+        enc: &[u8],
+        sk_r: &agreement::StaticPrivateKey,
+        shared_secret: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        if sk_r.algorithm() != self.agreement_algorithm {
+            return Err(error::Unspecified);
+        }
+        self.shared_secret(sk_r, enc, enc, sk_r.public_key().as_ref(), shared_secret)
+    }
+
+    // Computes the shared secret from the DH of `my_private_key` and
+    // `peer_public_key`, bound to the KEM context `enc || pk_r`.
+    fn shared_secret(
+        &self,
+        my_private_key: &agreement::StaticPrivateKey,
+        peer_public_key: &[u8],
+        enc: &[u8],
+        pk_r: &[u8],
+        shared_secret: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        let peer_public_key =
+            agreement::UnparsedPublicKey::new(self.agreement_algorithm, peer_public_key);
+        let eae_prk = agreement::agree_static(my_private_key, &peer_public_key, |dh| {
+            labeled_extract(self.kdf, &self.suite_id(), b"", b"eae_prk", dh)
+        })?;
+        let eae_prk = self.prk(eae_prk);
+
+        let mut kem_context = [0u8; 2 * MAX_PUBLIC_KEY_LEN];
+        let kem_context_len = enc.len() + pk_r.len();
+        let kem_context = kem_context
+            .get_mut(..kem_context_len)
+            .ok_or(error::Unspecified)?;
+        let (enc_out, pk_r_out) = kem_context.split_at_mut(enc.len());
+        enc_out.copy_from_slice(enc);
+        pk_r_out.copy_from_slice(pk_r);
+
+        labeled_expand(
+            &eae_prk,
+            &self.suite_id(),
+            b"shared_secret",
+            kem_context,
+            &mut shared_secret[..self.shared_secret_len],
+        )
+    }
+}
+
+/// A Key Derivation Function (KDF) for HPKE.
+pub struct Kdf {
+    id: KdfID,
+    algorithm: &'static hkdf::Algorithm,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u16)]
+#[allow(non_camel_case_types)]
+enum KdfID {
+    HKDF_SHA256 = 0x0001,
+    HKDF_SHA384 = 0x0002,
+    HKDF_SHA512 = 0x0003,
+}
+
+derive_debug_via_id!(Kdf);
+
+impl Eq for Kdf {}
+
+impl PartialEq for Kdf {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+/// HKDF-SHA256.
+pub static HKDF_SHA256: Kdf = Kdf {
+    id: KdfID::HKDF_SHA256,
+    algorithm: &hkdf::HKDF_SHA256,
+};
+
+/// HKDF-SHA384.
+pub static HKDF_SHA384: Kdf = Kdf {
+    id: KdfID::HKDF_SHA384,
+    algorithm: &hkdf::HKDF_SHA384,
+};
+
+/// HKDF-SHA512.
+pub static HKDF_SHA512: Kdf = Kdf {
+    id: KdfID::HKDF_SHA512,
+    algorithm: &hkdf::HKDF_SHA512,
+};
+
+impl Kdf {
+    /// The KDF's identifier in the HPKE KDF registry.
+    #[inline]
+    pub fn id(&self) -> u16 {
+        self.id as u16
+    }
+
+    /// The underlying HKDF algorithm.
+    #[inline]
+    pub fn hkdf_algorithm(&self) -> hkdf::Algorithm {
+        *self.algorithm
+    }
+}
+
+/// An AEAD algorithm for HPKE.
+pub struct Aead {
+    id: AeadID,
+
+    // `None` for the export-only AEAD.
+    algorithm: Option<&'static aead::Algorithm>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u16)]
+#[allow(non_camel_case_types)]
+enum AeadID {
+    AES_128_GCM = 0x0001,
+    AES_256_GCM = 0x0002,
+    CHACHA20_POLY1305 = 0x0003,
+    EXPORT_ONLY = 0xffff,
+}
+
+derive_debug_via_id!(Aead);
+
+impl Eq for Aead {}
+
+impl PartialEq for Aead {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+/// AES-128-GCM.
+pub static AES_128_GCM: Aead = Aead {
+    id: AeadID::AES_128_GCM,
+    algorithm: Some(&aead::AES_128_GCM),
+};
+
+/// AES-256-GCM.
+pub static AES_256_GCM: Aead = Aead {
+    id: AeadID::AES_256_GCM,
+    algorithm: Some(&aead::AES_256_GCM),
+};
+
+/// ChaCha20-Poly1305.
+pub static CHACHA20_POLY1305: Aead = Aead {
+    id: AeadID::CHACHA20_POLY1305,
+    algorithm: Some(&aead::CHACHA20_POLY1305),
+};
+
+/// The export-only AEAD.
+///
+/// Contexts using it can only be used with `export`; sealing and opening
+/// always fail.
+pub static EXPORT_ONLY: Aead = Aead {
+    id: AeadID::EXPORT_ONLY,
+    algorithm: None,
+};
+
+impl Aead {
+    /// The AEAD's identifier in the HPKE AEAD registry.
+    #[inline]
+    pub fn id(&self) -> u16 {
+        self.id as u16
+    }
+
+    /// The underlying AEAD algorithm, or `None` for `EXPORT_ONLY`.
+    #[inline]
+    pub fn aead_algorithm(&self) -> Option<&'static aead::Algorithm> {
+        self.algorithm
+    }
+}
+
+/// An HPKE cipher suite: a KEM, a KDF, and an AEAD.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Suite {
+    kem: &'static Kem,
+    kdf: &'static Kdf,
+    aead: &'static Aead,
+}
+
+impl Suite {
+    /// Constructs a cipher suite from its components.
+    pub const fn new(kem: &'static Kem, kdf: &'static Kdf, aead: &'static Aead) -> Self {
+        Self { kem, kdf, aead }
+    }
+
+    /// The suite's KEM.
+    #[inline]
+    pub fn kem(&self) -> &'static Kem {
+        self.kem
+    }
+
+    /// The suite's KDF.
+    #[inline]
+    pub fn kdf(&self) -> &'static Kdf {
+        self.kdf
+    }
+
+    /// The suite's AEAD.
+    #[inline]
+    pub fn aead(&self) -> &'static Aead {
+        self.aead
+    }
+
+    fn suite_id(&self) -> [u8; HPKE_SUITE_ID_LEN] {
+        let mut suite_id = [0u8; HPKE_SUITE_ID_LEN];
+        let (label, ids) = suite_id.split_at_mut(4);
+        label.copy_from_slice(b"HPKE");
+        for (out, id) in ids
+            .chunks_exact_mut(2)
+            .zip([self.kem.id(), self.kdf.id(), self.aead.id()])
+        {
+            out.copy_from_slice(&id.to_be_bytes());
+        }
+        suite_id
+    }
+}
+
+/// Sets up a context for sealing messages to the holder of the private key
+/// for `pk_r` in Base mode.
+///
+/// `pk_r` is the recipient's public key, encoded as for
+/// `agreement::UnparsedPublicKey` with the KEM's agreement algorithm. Returns
+/// the encapsulated key, which must be sent to the recipient, and the
+/// context.
+pub fn setup_base_sender(
+    suite: &Suite,
+    pk_r: &[u8],
+    info: &[u8],
+    rng: &dyn rand::SecureRandom,
+) -> Result<(agreement::PublicKey, SenderContext), error::Unspecified> {
+    setup_sender(suite, Mode::Base, pk_r, info, rng)
+}
+
+/// Sets up a context for sealing messages to the holder of the private key
+/// for `pk_r` in PSK mode, authenticating the sender by the pre-shared key
+/// `psk` with the identifier `psk_id`.
+///
+/// Fails if `psk` or `psk_id` is empty. Otherwise it works like
+/// `setup_base_sender`.
+pub fn setup_psk_sender(
+    suite: &Suite,
+    pk_r: &[u8],
+    info: &[u8],
+    psk: &[u8],
+    psk_id: &[u8],
+    rng: &dyn rand::SecureRandom,
+) -> Result<(agreement::PublicKey, SenderContext), error::Unspecified> {
+    setup_sender(suite, Mode::Psk { psk, psk_id }, pk_r, info, rng)
+}
+
+/// Sets up a context for opening messages sealed by the context that
+/// produced the encapsulated key `enc`, in Base mode.
+///
+/// Fails if `sk_r` isn't a key for the suite's KEM or if `enc` is invalid.
+pub fn setup_base_receiver(
+    suite: &Suite,
+    enc: &[u8],
+    sk_r: &agreement::StaticPrivateKey,
+    info: &[u8],
+) -> Result<ReceiverContext, error::Unspecified> {
+    setup_receiver(suite, Mode::Base, enc, sk_r, info)
+}
+
+/// Sets up a context for opening messages sealed by the context that
+/// produced the encapsulated key `enc`, in PSK mode.
+///
+/// Fails if `psk` or `psk_id` is empty. Otherwise it works like
+/// `setup_base_receiver`.
+pub fn setup_psk_receiver(
+    suite: &Suite,
+    enc: &[u8],
+    sk_r: &agreement::StaticPrivateKey,
+    info: &[u8],
+    psk: &[u8],
+    psk_id: &[u8],
+) -> Result<ReceiverContext, error::Unspecified> {
+    setup_receiver(suite, Mode::Psk { psk, psk_id }, enc, sk_r, info)
+}
+
+fn setup_sender(
+    suite: &Suite,
+    mode: Mode,
+    pk_r: &[u8],
+    info: &[u8],
+    rng: &dyn rand::SecureRandom,
+) -> Result<(agreement::PublicKey, SenderContext), error::Unspecified> {
+    mode.verify()?;
+    let mut shared_secret = [0u8; MAX_SHARED_SECRET_LEN];
+    let enc = suite.kem.encap(pk_r, rng, &mut shared_secret)?;
+    let shared_secret = &shared_secret[..suite.kem.shared_secret_len];
+    let context = Context::new(suite, mode, shared_secret, info)?;
+    Ok((enc, SenderContext { context }))
+}
+
+fn setup_receiver(
+    suite: &Suite,
+    mode: Mode,
+    enc: &[u8],
+    sk_r: &agreement::StaticPrivateKey,
+    info: &[u8],
+) -> Result<ReceiverContext, error::Unspecified> {
+    mode.verify()?;
+    let mut shared_secret = [0u8; MAX_SHARED_SECRET_LEN];
+    suite.kem.decap(enc, sk_r, &mut shared_secret)?;
+    let shared_secret = &shared_secret[..suite.kem.shared_secret_len];
+    let context = Context::new(suite, mode, shared_secret, info)?;
+    Ok(ReceiverContext { context })
+}
+
+/// A context for sealing messages, set up by `setup_base_sender` or
+/// `setup_psk_sender`.
+pub struct SenderContext {
+    context: Context,
+}
+
+impl SenderContext {
+    /// Seals the next message, appending the tag to `in_out`.
+    ///
+    /// Messages must be opened in the order in which they were sealed. Fails
+    /// if the suite's AEAD is `EXPORT_ONLY` or if the sequence number is
+    /// exhausted.
+    pub fn seal_in_place_append_tag<A, InOut>(
+        &mut self,
+        aad: aead::Aad<A>,
+        in_out: &mut InOut,
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+        InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    {
+        let (key, nonce) = self.context.key_and_nonce()?;
+        key.seal_in_place_append_tag(nonce, aad, in_out)?;
+        self.context.increment_seq();
+        Ok(())
+    }
+
+    /// Fills `out` with a secret derived from the context and
+    /// `exporter_context`.
+    ///
+    /// Fails if `out` is longer than 255 times the output length of the
+    /// suite's KDF.
+    pub fn export(
+        &self,
+        exporter_context: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        self.context.export(exporter_context, out)
+    }
+
+    /// The context's cipher suite.
+    #[inline]
+    pub fn suite(&self) -> &Suite {
+        &self.context.suite
+    }
+}
+
+derive_debug_via_field!(SenderContext, context);
+
+/// A context for opening messages, set up by `setup_base_receiver` or
+/// `setup_psk_receiver`.
+pub struct ReceiverContext {
+    context: Context,
+}
+
+impl ReceiverContext {
+    /// Opens the next message.
+    ///
+    /// `in_out` is the ciphertext followed by its tag. On success, returns the
+    /// plaintext. A failure doesn't advance the sequence number, so the
+    /// context may continue to be used for the following messages. Fails if
+    /// the suite's AEAD is `EXPORT_ONLY`.
+    pub fn open_in_place<'in_out, A>(
+        &mut self,
+        aad: aead::Aad<A>,
+        in_out: &'in_out mut [u8],
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        let (key, nonce) = self.context.key_and_nonce()?;
+        let plaintext = key.open_in_place(nonce, aad, in_out)?;
+        self.context.increment_seq();
+        Ok(plaintext)
+    }
+
+    /// Fills `out` with a secret derived from the context and
+    /// `exporter_context`.
+    ///
+    /// Fails if `out` is longer than 255 times the output length of the
+    /// suite's KDF.
+    pub fn export(
+        &self,
+        exporter_context: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        self.context.export(exporter_context, out)
+    }
+
+    /// The context's cipher suite.
+    #[inline]
+    pub fn suite(&self) -> &Suite {
+        &self.context.suite
+    }
+}
+
+derive_debug_via_field!(ReceiverContext, context);
+
+#[derive(Clone, Copy)]
+enum Mode<'a> {
+    Base,
+    Psk { psk: &'a [u8], psk_id: &'a [u8] },
+}
+
+impl Mode<'_> {
+    fn id(&self) -> u8 {
+        match self {
+            Self::Base => 0x00,
+            Self::Psk { .. } => 0x01,
+        }
+    }
+
+    // `VerifyPSKInputs()`; in Base mode the PSK inputs are implicitly empty.
+    fn verify(&self) -> Result<(), error::Unspecified> {
+        match self {
+            Self::Base => Ok(()),
+            Self::Psk { psk, psk_id } if !psk.is_empty() && !psk_id.is_empty() => Ok(()),
+            Self::Psk { .. } => Err(error::Unspecified),
+        }
+    }
+
+    fn psk_and_psk_id(&self) -> (&[u8], &[u8]) {
+        match self {
+            Self::Base => (&[], &[]),
+            Self::Psk { psk, psk_id } => (psk, psk_id),
+        }
+    }
+}
+
+struct Context {
+    suite: Suite,
+    key: Option<aead::LessSafeKey>,
+    base_nonce: [u8; aead::NONCE_LEN],
+    seq: u64,
+    exporter_secret: hkdf::Prk,
+}
+
+impl core::fmt::Debug for Context {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Context")
+            .field("suite", &self.suite)
+            .field("seq", &self.seq)
+            .finish()
+    }
+}
+
+impl Context {
+    // `KeySchedule()`.
+    fn new(
+        suite: &Suite,
+        mode: Mode,
+        shared_secret: &[u8],
+        info: &[u8],
+    ) -> Result<Self, error::Unspecified> {
+        let kdf = suite.kdf.algorithm;
+        let suite_id = suite.suite_id();
+        let (psk, psk_id) = mode.psk_and_psk_id();
+
+        let psk_id_hash = labeled_extract(kdf, &suite_id, b"", b"psk_id_hash", psk_id);
+        let info_hash = labeled_extract(kdf, &suite_id, b"", b"info_hash", info);
+        let mut key_schedule_context = [0u8; 1 + 2 * digest::MAX_OUTPUT_LEN];
+        let key_schedule_context = {
+            let hash_len = psk_id_hash.as_ref().len();
+            let (mode_id, hashes) = key_schedule_context[..1 + 2 * hash_len].split_at_mut(1);
+            mode_id[0] = mode.id();
+            let (psk_id_hash_out, info_hash_out) = hashes.split_at_mut(hash_len);
+            psk_id_hash_out.copy_from_slice(psk_id_hash.as_ref());
+            info_hash_out.copy_from_slice(info_hash.as_ref());
+            &key_schedule_context[..1 + 2 * hash_len]
+        };
+
+        let secret = labeled_extract(kdf, &suite_id, shared_secret, b"secret", psk);
+        let secret = hkdf::Prk::new_less_safe(*kdf, secret.as_ref());
+
+        let mut base_nonce = [0u8; aead::NONCE_LEN];
+        let key = match suite.aead.algorithm {
+            Some(algorithm) => {
+                let mut key = [0u8; aead::MAX_KEY_LEN];
+                let key = &mut key[..algorithm.key_len()];
+                labeled_expand(&secret, &suite_id, b"key", key_schedule_context, key)?;
+                labeled_expand(
+                    &secret,
+                    &suite_id,
+                    b"base_nonce",
+                    key_schedule_context,
+                    &mut base_nonce,
+                )?;
+                Some(aead::LessSafeKey::new(aead::UnboundKey::new(
+                    algorithm, key,
+                )?))
+            }
+            None => None,
+        };
+
+        let mut exporter_secret = [0u8; digest::MAX_OUTPUT_LEN];
+        let exporter_secret = &mut exporter_secret[..hkdf::KeyType::len(kdf)];
+        labeled_expand(
+            &secret,
+            &suite_id,
+            b"exp",
+            key_schedule_context,
+            exporter_secret,
+        )?;
+
+        Ok(Self {
+            suite: *suite,
+            key,
+            base_nonce,
+            seq: 0,
+            exporter_secret: hkdf::Prk::new_less_safe(*kdf, exporter_secret),
+        })
+    }
+
+    // Returns the key and the nonce for the current sequence number,
+    // `base_nonce ^ I2OSP(seq, Nn)`.
+    fn key_and_nonce(&self) -> Result<(&aead::LessSafeKey, aead::Nonce), error::Unspecified> {
+        let key = self.key.as_ref().ok_or(error::Unspecified)?;
+        if self.seq == u64::MAX {
+            return Err(error::Unspecified);
+        }
+        let mut nonce = self.base_nonce;
+        let (_, seq_bytes) = nonce.split_at_mut(aead::NONCE_LEN - 8);
+        seq_bytes
+            .iter_mut()
+            .zip(self.seq.to_be_bytes())
+            .for_each(|(nonce, seq)| *nonce ^= seq);
+        Ok((key, aead::Nonce::assume_unique_for_key(nonce)))
+    }
+
+    fn increment_seq(&mut self) {
+        self.seq += 1;
+    }
+
+    fn export(&self, exporter_context: &[u8], out: &mut [u8]) -> Result<(), error::Unspecified> {
+        labeled_expand(
+            &self.exporter_secret,
+            &self.suite.suite_id(),
+            b"sec",
+            exporter_context,
+            out,
+        )
+    }
+}
+
+// `LabeledExtract()`. The result is returned as an `hmac::Tag` since some
+// callers need its value rather than a `Prk`.
+fn labeled_extract(
+    kdf: &hkdf::Algorithm,
+    suite_id: &[u8],
+    salt: &[u8],
+    label: &[u8],
+    ikm: &[u8],
+) -> hmac::Tag {
+    // As in `hkdf::Salt::extract`, an empty salt is equivalent to a salt of
+    // `Nh` zeros since HMAC keys are zero-padded.
+    let salt = hmac::Key::new(kdf.hmac_algorithm(), salt);
+    let mut ctx = hmac::Context::with_key(&salt);
+    for part in [HPKE_VERSION_LABEL, suite_id, label, ikm] {
+        ctx.update(part);
+    }
+    ctx.sign()
+}
+
+// `LabeledExpand()`, with `L` being `out.len()`.
+fn labeled_expand(
+    prk: &hkdf::Prk,
+    suite_id: &[u8],
+    label: &[u8],
+    info: &[u8],
+    out: &mut [u8],
+) -> Result<(), error::Unspecified> {
+    let len = u16::try_from(out.len()).map_err(|_| error::Unspecified)?;
+    let len_bytes = len.to_be_bytes();
+    let info = [&len_bytes[..], HPKE_VERSION_LABEL, suite_id, label, info];
+    prk.expand(&info, Len(out.len()))?.fill(out)
+}
+
+struct Len(usize);
+
+impl hkdf::KeyType for Len {
+    fn len(&self) -> usize {
+        self.0
+    }
+}
+
+const HPKE_VERSION_LABEL: &[u8] = b"HPKE-v1";
+
+const KEM_SUITE_ID_LEN: usize = 3 + 2;
+const HPKE_SUITE_ID_LEN: usize = 4 + 3 * 2;
+
+// P-521's.
+const MAX_PRIVATE_KEY_LEN: usize = 66;
+const MAX_PUBLIC_KEY_LEN: usize = 1 + 2 * 66;
+
+const MAX_SHARED_SECRET_LEN: usize = digest::MAX_OUTPUT_LEN;
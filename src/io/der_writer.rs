@@ -45,7 +45,11 @@ pub(crate) fn write_all(
     Ok(output.into())
 }
 
-fn write_tlv<F>(output: &mut dyn Accumulator, tag: Tag, write_value: F) -> Result<(), TooLongError>
+pub(crate) fn write_tlv<F>(
+    output: &mut dyn Accumulator,
+    tag: Tag,
+    write_value: F,
+) -> Result<(), TooLongError>
 where
     F: Fn(&mut dyn Accumulator) -> Result<(), TooLongError>,
 {
@@ -0,0 +1,395 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Key Encapsulation Mechanisms (KEMs): ML-KEM-768 and ML-KEM-1024.
+//!
+//! A KEM is used like a key agreement in which only one party has a key
+//! pair. The holder of a `DecapsulationKey` publishes its encapsulation key.
+//! A peer uses it to `encapsulate` a fresh shared secret, sending the
+//! resulting ciphertext back, and the holder of the `DecapsulationKey`
+//! recovers the same shared secret with `decapsulate`.
+//!
+//! ML-KEM, specified in [FIPS 203], is believed to be secure against attacks
+//! using quantum computers.
+//!
+//! ML-KEM-512 isn't supported. It only targets NIST security category 1,
+//! and the protocols that use ML-KEM, such as the hybrid key exchanges in
+//! TLS 1.3, use ML-KEM-768 or ML-KEM-1024 instead. ML-KEM-768 is the
+//! recommended default; ML-KEM-1024 is for applications that require
+//! category 5, such as those following CNSA 2.0.
+//!
+//! # Example
+//!
+//! ```
+//! use ring::{kem, rand};
+//!
+//! let rng = rand::SystemRandom::new();
+//!
+//! let decapsulation_key = kem::DecapsulationKey::generate(&kem::ML_KEM_768, &rng)?;
+//!
+//! // In a real application, the encapsulation key would be sent to the peer
+//! // in a protocol message, and the peer would parse it out of that message.
+//! let encapsulation_key = kem::UnparsedEncapsulationKey::new(
+//!     &kem::ML_KEM_768,
+//!     decapsulation_key.encapsulation_key().as_ref(),
+//! );
+//! let (ciphertext, peer_shared_secret) =
+//!     kem::encapsulate(&encapsulation_key, &rng, |shared_secret| {
+//!         // In a real application, we'd apply a KDF to the shared secret and
+//!         // derive session keys from the result.
+//!         shared_secret.to_vec()
+//!     })?;
+//!
+//! let ciphertext = kem::UnparsedCiphertext::new(&kem::ML_KEM_768, ciphertext.as_ref());
+//! let shared_secret =
+//!     kem::decapsulate(&decapsulation_key, &ciphertext, |shared_secret| shared_secret.to_vec())?;
+//! assert_eq!(shared_secret, peer_shared_secret);
+//!
+//! # Ok::<(), ring::error::Unspecified>(())
+//! ```
+//!
+//! [FIPS 203]: https://doi.org/10.6028/NIST.FIPS.203
+
+use crate::{debug, error, rand};
+
+pub use self::ml_kem::{ML_KEM_1024, ML_KEM_768};
+
+mod ml_kem;
+
+/// A key encapsulation mechanism.
+pub struct Algorithm {
+    id: AlgorithmID,
+    params: ml_kem::Params,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[allow(non_camel_case_types)]
+enum AlgorithmID {
+    ML_KEM_768,
+    ML_KEM_1024,
+}
+
+derive_debug_via_id!(Algorithm);
+
+impl Eq for Algorithm {}
+
+impl PartialEq for Algorithm {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Algorithm {
+    /// The length of an encapsulation key.
+    #[inline]
+    pub fn encapsulation_key_len(&self) -> usize {
+        self.params.encapsulation_key_len()
+    }
+
+    /// The length of a ciphertext.
+    #[inline]
+    pub fn ciphertext_len(&self) -> usize {
+        self.params.ciphertext_len()
+    }
+
+    /// The length of a shared secret.
+    #[inline]
+    pub fn shared_secret_len(&self) -> usize {
+        ml_kem::SHARED_SECRET_LEN
+    }
+}
+
+/// The length of the seed from which a `DecapsulationKey` is generated.
+pub const SEED_LEN: usize = ml_kem::SEED_LEN;
+
+/// A private key for decapsulating shared secrets.
+///
+/// A `DecapsulationKey` can be used for any number of decapsulations. It can
+/// be serialized as the `SEED_LEN`-byte seed `d || z` of FIPS 203, which is
+/// the most compact form of an ML-KEM decapsulation key.
+pub struct DecapsulationKey {
+    seed: [u8; SEED_LEN],
+    inner: ml_kem::DecapsulationKey,
+    encapsulation_key: EncapsulationKey,
+}
+
+derive_debug_via_field!(DecapsulationKey, encapsulation_key);
+
+impl DecapsulationKey {
+    /// Generates a new decapsulation key for the given algorithm.
+    pub fn generate(
+        alg: &'static Algorithm,
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<Self, error::Unspecified> {
+        let mut seed = [0u8; SEED_LEN];
+        rng.fill(&mut seed)?;
+        Self::from_seed(alg, &seed).map_err(error::Unspecified::from)
+    }
+
+    /// Constructs a decapsulation key deterministically from its seed, as
+    /// returned by `seed_bytes_less_safe`.
+    ///
+    /// This is `ML-KEM.KeyGen_internal` from FIPS 203, where `seed` is the
+    /// concatenation `d || z`. Fails if `seed` isn't `SEED_LEN` bytes long.
+    pub fn from_seed(alg: &'static Algorithm, seed: &[u8]) -> Result<Self, error::KeyRejected> {
+        let seed: &[u8; SEED_LEN] = seed
+            .try_into()
+            .map_err(|_| error::KeyRejected::invalid_encoding())?;
+        let mut encapsulation_key = EncapsulationKey {
+            algorithm: alg,
+            bytes: [0; ml_kem::MAX_ENCAPSULATION_KEY_LEN],
+        };
+        let inner = ml_kem::key_gen(
+            &alg.params,
+            seed,
+            &mut encapsulation_key.bytes[..alg.encapsulation_key_len()],
+        );
+        Ok(Self {
+            seed: *seed,
+            inner,
+            encapsulation_key,
+        })
+    }
+
+    /// The seed of the key, in the form accepted by `from_seed`.
+    ///
+    /// This exposes the secret key material.
+    pub fn seed_bytes_less_safe(&self) -> &[u8] {
+        &self.seed
+    }
+
+    /// The encapsulation key for this decapsulation key.
+    #[inline]
+    pub fn encapsulation_key(&self) -> &EncapsulationKey {
+        &self.encapsulation_key
+    }
+
+    /// The algorithm for the key.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.encapsulation_key.algorithm
+    }
+}
+
+/// A public key for encapsulating shared secrets.
+#[derive(Clone)]
+pub struct EncapsulationKey {
+    algorithm: &'static Algorithm,
+    bytes: [u8; ml_kem::MAX_ENCAPSULATION_KEY_LEN],
+}
+
+impl AsRef<[u8]> for EncapsulationKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes[..self.algorithm.encapsulation_key_len()]
+    }
+}
+
+impl core::fmt::Debug for EncapsulationKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("EncapsulationKey")
+            .field("algorithm", &self.algorithm)
+            .field("bytes", &debug::HexStr(self.as_ref()))
+            .finish()
+    }
+}
+
+impl EncapsulationKey {
+    /// The algorithm for the key.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+}
+
+/// An unparsed, possibly malformed, encapsulation key.
+#[derive(Clone, Copy)]
+pub struct UnparsedEncapsulationKey<B> {
+    algorithm: &'static Algorithm,
+    bytes: B,
+}
+
+impl<B> AsRef<[u8]> for UnparsedEncapsulationKey<B>
+where
+    B: AsRef<[u8]>,
+{
+    fn as_ref(&self) -> &[u8] {
+        self.bytes.as_ref()
+    }
+}
+
+impl<B: core::fmt::Debug> core::fmt::Debug for UnparsedEncapsulationKey<B>
+where
+    B: AsRef<[u8]>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("UnparsedEncapsulationKey")
+            .field("algorithm", &self.algorithm)
+            .field("bytes", &debug::HexStr(self.bytes.as_ref()))
+            .finish()
+    }
+}
+
+impl<B> UnparsedEncapsulationKey<B> {
+    /// Constructs a new `UnparsedEncapsulationKey`.
+    pub fn new(algorithm: &'static Algorithm, bytes: B) -> Self {
+        Self { algorithm, bytes }
+    }
+
+    /// The algorithm for the key.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+
+    /// The encoded key.
+    #[inline]
+    pub fn bytes(&self) -> &B {
+        &self.bytes
+    }
+}
+
+/// A ciphertext produced by `encapsulate`.
+#[derive(Clone)]
+pub struct Ciphertext {
+    algorithm: &'static Algorithm,
+    bytes: [u8; ml_kem::MAX_CIPHERTEXT_LEN],
+}
+
+impl AsRef<[u8]> for Ciphertext {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes[..self.algorithm.ciphertext_len()]
+    }
+}
+
+impl core::fmt::Debug for Ciphertext {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Ciphertext")
+            .field("algorithm", &self.algorithm)
+            .field("bytes", &debug::HexStr(self.as_ref()))
+            .finish()
+    }
+}
+
+impl Ciphertext {
+    /// The algorithm for the ciphertext.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+}
+
+/// An unparsed, possibly malformed, ciphertext.
+#[derive(Clone, Copy)]
+pub struct UnparsedCiphertext<B> {
+    algorithm: &'static Algorithm,
+    bytes: B,
+}
+
+impl<B> AsRef<[u8]> for UnparsedCiphertext<B>
+where
+    B: AsRef<[u8]>,
+{
+    fn as_ref(&self) -> &[u8] {
+        self.bytes.as_ref()
+    }
+}
+
+impl<B: core::fmt::Debug> core::fmt::Debug for UnparsedCiphertext<B>
+where
+    B: AsRef<[u8]>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("UnparsedCiphertext")
+            .field("algorithm", &self.algorithm)
+            .field("bytes", &debug::HexStr(self.bytes.as_ref()))
+            .finish()
+    }
+}
+
+impl<B> UnparsedCiphertext<B> {
+    /// Constructs a new `UnparsedCiphertext`.
+    pub fn new(algorithm: &'static Algorithm, bytes: B) -> Self {
+        Self { algorithm, bytes }
+    }
+
+    /// The algorithm for the ciphertext.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+
+    /// The encoded ciphertext.
+    #[inline]
+    pub fn bytes(&self) -> &B {
+        &self.bytes
+    }
+}
+
+/// Encapsulates a fresh shared secret to the holder of the decapsulation key
+/// for `encapsulation_key`.
+///
+/// Fails if `encapsulation_key` doesn't have the right length or isn't a
+/// valid encoding, as checked by the modulus check of FIPS 203 Section 7.2.
+///
+/// After the encapsulation is done, `encapsulate` calls `kdf` with the shared
+/// secret and then returns the ciphertext, which must be sent to the peer,
+/// along with what `kdf` returns.
+pub fn encapsulate<B: AsRef<[u8]>, R>(
+    encapsulation_key: &UnparsedEncapsulationKey<B>,
+    rng: &dyn rand::SecureRandom,
+    kdf: impl FnOnce(&[u8]) -> R,
+) -> Result<(Ciphertext, R), error::Unspecified> {
+    let alg = encapsulation_key.algorithm;
+    let mut m = [0u8; 32];
+    rng.fill(&mut m)?;
+    let mut ciphertext = Ciphertext {
+        algorithm: alg,
+        bytes: [0; ml_kem::MAX_CIPHERTEXT_LEN],
+    };
+    let shared_secret = ml_kem::encaps(
+        &alg.params,
+        encapsulation_key.bytes.as_ref(),
+        &m,
+        &mut ciphertext.bytes[..alg.ciphertext_len()],
+    )?;
+    Ok((ciphertext, kdf(&shared_secret)))
+}
+
+/// Decapsulates the shared secret from `ciphertext`.
+///
+/// Fails if `ciphertext` isn't for `decapsulation_key`'s algorithm or if it
+/// doesn't have the right length. Otherwise decapsulation always succeeds:
+/// as specified in FIPS 203, an invalid ciphertext results in a pseudorandom
+/// shared secret that won't match the peer's, instead of an error, so that
+/// the validity of the ciphertext isn't revealed.
+///
+/// After the decapsulation is done, `decapsulate` calls `kdf` with the shared
+/// secret and then returns what `kdf` returns.
+pub fn decapsulate<B: AsRef<[u8]>, R>(
+    decapsulation_key: &DecapsulationKey,
+    ciphertext: &UnparsedCiphertext<B>,
+    kdf: impl FnOnce(&[u8]) -> R,
+) -> Result<R, error::Unspecified> {
+    let alg = decapsulation_key.algorithm();
+    if ciphertext.algorithm != alg {
+        return Err(error::Unspecified);
+    }
+    let shared_secret = ml_kem::decaps(
+        &alg.params,
+        &decapsulation_key.inner,
+        decapsulation_key.encapsulation_key.as_ref(),
+        ciphertext.bytes.as_ref(),
+    )?;
+    Ok(kdf(&shared_secret))
+}
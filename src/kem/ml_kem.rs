@@ -0,0 +1,300 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! ML-KEM, as specified in [FIPS 203].
+//!
+//! [FIPS 203]: https://doi.org/10.6028/NIST.FIPS.203
+
+use self::poly::{Poly, CBD_INPUT_LEN, ENCODED_LEN, MESSAGE_LEN};
+use super::{Algorithm, AlgorithmID};
+use crate::{digest, error, limb};
+
+mod poly;
+
+/// ML-KEM-768, as specified in FIPS 203.
+pub static ML_KEM_768: Algorithm = Algorithm {
+    id: AlgorithmID::ML_KEM_768,
+    params: Params {
+        k: 3,
+        du: 10,
+        dv: 4,
+    },
+};
+
+/// ML-KEM-1024, as specified in FIPS 203.
+pub static ML_KEM_1024: Algorithm = Algorithm {
+    id: AlgorithmID::ML_KEM_1024,
+    params: Params {
+        k: 4,
+        du: 11,
+        dv: 5,
+    },
+};
+
+/// The parameters of an ML-KEM parameter set. Both supported parameter sets
+/// use η1 = η2 = 2.
+#[derive(Clone, Copy)]
+pub(super) struct Params {
+    k: usize,
+    du: usize,
+    dv: usize,
+}
+
+impl Params {
+    pub(super) fn encapsulation_key_len(&self) -> usize {
+        ENCODED_LEN * self.k + SEED_HALF_LEN
+    }
+
+    pub(super) fn ciphertext_len(&self) -> usize {
+        32 * (self.du * self.k + self.dv)
+    }
+}
+
+const MAX_K: usize = 4;
+
+/// The maximum length of an encapsulation key.
+pub(super) const MAX_ENCAPSULATION_KEY_LEN: usize = ENCODED_LEN * MAX_K + SEED_HALF_LEN;
+
+/// The maximum length of a ciphertext.
+pub(super) const MAX_CIPHERTEXT_LEN: usize = 32 * (11 * MAX_K + 5);
+
+/// The length of the seed `d || z` from which a key pair is generated.
+pub(super) const SEED_LEN: usize = 2 * SEED_HALF_LEN;
+
+/// The length of a shared secret.
+pub(super) const SHARED_SECRET_LEN: usize = 32;
+
+const SEED_HALF_LEN: usize = 32;
+
+type Vector = [Poly; MAX_K];
+
+/// The expanded form of a decapsulation key, except for the encapsulation
+/// key, which is stored separately.
+pub(super) struct DecapsulationKey {
+    // The secret vector in the NTT domain.
+    s_hat: Vector,
+    // H(ek).
+    h: [u8; 32],
+    // The implicit rejection value.
+    z: [u8; 32],
+}
+
+/// Generates a key pair deterministically from `seed`
+/// (`ML-KEM.KeyGen_internal`, FIPS 203 Algorithm 16), writing the
+/// encapsulation key to `ek`.
+pub(super) fn key_gen(params: &Params, seed: &[u8; SEED_LEN], ek: &mut [u8]) -> DecapsulationKey {
+    let (d, z) = seed.split_at(SEED_HALF_LEN);
+    let k = params.k;
+
+    // K-PKE.KeyGen (FIPS 203 Algorithm 13).
+    #[allow(clippy::cast_possible_truncation)]
+    let (rho, sigma) = g(&[d, &[k as u8]]);
+    let mut n = 0;
+    let mut s_hat = [Poly::ZERO; MAX_K];
+    for s in &mut s_hat[..k] {
+        *s = sample_poly_cbd(&sigma, &mut n);
+        s.ntt();
+    }
+    let (t_hat_bytes, rho_out) = ek.split_at_mut(ENCODED_LEN * k);
+    for (i, t_hat_bytes) in t_hat_bytes.chunks_exact_mut(ENCODED_LEN).enumerate() {
+        let mut t_hat = sample_poly_cbd(&sigma, &mut n);
+        t_hat.ntt();
+        for (j, s_hat) in s_hat[..k].iter().enumerate() {
+            t_hat.add_product_ntt(&sample_matrix_entry(&rho, i, j), s_hat);
+        }
+        t_hat.encode(t_hat_bytes);
+    }
+    rho_out.copy_from_slice(&rho);
+
+    let mut dk = DecapsulationKey {
+        s_hat,
+        h: h(ek),
+        z: [0; 32],
+    };
+    dk.z.copy_from_slice(z);
+    dk
+}
+
+/// Encapsulates a shared secret to `ek` using the randomness `m`
+/// (`ML-KEM.Encaps_internal`, FIPS 203 Algorithm 17).
+///
+/// Fails if `ek` isn't a valid encapsulation key for `params`.
+pub(super) fn encaps(
+    params: &Params,
+    ek: &[u8],
+    m: &[u8; MESSAGE_LEN],
+    ciphertext: &mut [u8],
+) -> Result<[u8; SHARED_SECRET_LEN], error::Unspecified> {
+    let (shared_secret, r) = g(&[m, &h(ek)]);
+    k_pke_encrypt(params, ek, m, &r, ciphertext)?;
+    Ok(shared_secret)
+}
+
+/// Decapsulates the shared secret from `ciphertext`
+/// (`ML-KEM.Decaps_internal`, FIPS 203 Algorithm 18).
+///
+/// If the ciphertext is invalid then the result is a pseudorandom value
+/// derived from `z` and the ciphertext, which is selected in constant time.
+pub(super) fn decaps(
+    params: &Params,
+    dk: &DecapsulationKey,
+    ek: &[u8],
+    ciphertext: &[u8],
+) -> Result<[u8; SHARED_SECRET_LEN], error::Unspecified> {
+    if ciphertext.len() != params.ciphertext_len() {
+        return Err(error::Unspecified);
+    }
+    let m = k_pke_decrypt(params, &dk.s_hat, ciphertext);
+    let (shared_secret, r) = g(&[&m, &dk.h]);
+    let rejection_secret = j(&dk.z, ciphertext);
+
+    let mut expected = [0u8; MAX_CIPHERTEXT_LEN];
+    let expected = &mut expected[..ciphertext.len()];
+    k_pke_encrypt(params, ek, &m, &r, expected)?;
+
+    let difference = expected
+        .iter()
+        .zip(ciphertext.iter())
+        .fold(0, |acc, (a, b)| acc | (a ^ b));
+    let is_valid =
+        limb::limbs_are_zero_constant_time(&[limb::Limb::from(difference)]) as limb::Limb;
+
+    let mut result = [0u8; SHARED_SECRET_LEN];
+    for ((result, shared_secret), rejection_secret) in result
+        .iter_mut()
+        .zip(shared_secret.iter())
+        .zip(rejection_secret.iter())
+    {
+        let selected = (limb::Limb::from(*shared_secret) & is_valid)
+            | (limb::Limb::from(*rejection_secret) & !is_valid);
+        #[allow(clippy::cast_possible_truncation)]
+        let selected = selected as u8;
+        *result = selected;
+    }
+    Ok(result)
+}
+
+// K-PKE.Encrypt (FIPS 203 Algorithm 14), including the modulus check of the
+// encapsulation key from FIPS 203 Section 7.2.
+fn k_pke_encrypt(
+    params: &Params,
+    ek: &[u8],
+    m: &[u8; MESSAGE_LEN],
+    r: &[u8; 32],
+    ciphertext: &mut [u8],
+) -> Result<(), error::Unspecified> {
+    let k = params.k;
+    if ek.len() != params.encapsulation_key_len() || ciphertext.len() != params.ciphertext_len() {
+        return Err(error::Unspecified);
+    }
+    let (t_hat_bytes, rho) = ek.split_at(ENCODED_LEN * k);
+    let mut t_hat = [Poly::ZERO; MAX_K];
+    for (t_hat, bytes) in t_hat[..k]
+        .iter_mut()
+        .zip(t_hat_bytes.chunks_exact(ENCODED_LEN))
+    {
+        *t_hat = Poly::decode(bytes)?;
+    }
+
+    let mut n = 0;
+    let mut y_hat = [Poly::ZERO; MAX_K];
+    for y in &mut y_hat[..k] {
+        *y = sample_poly_cbd(r, &mut n);
+        y.ntt();
+    }
+
+    let (c1, c2) = ciphertext.split_at_mut(32 * params.du * k);
+    for (i, c1) in c1.chunks_exact_mut(32 * params.du).enumerate() {
+        let mut u = Poly::ZERO;
+        for (j, y_hat) in y_hat[..k].iter().enumerate() {
+            u.add_product_ntt(&sample_matrix_entry(rho, j, i), y_hat);
+        }
+        u.inverse_ntt();
+        let u = u.add(&sample_poly_cbd(r, &mut n));
+        u.compress_and_encode(params.du, c1);
+    }
+
+    let mut v = Poly::ZERO;
+    for (t_hat, y_hat) in t_hat[..k].iter().zip(y_hat[..k].iter()) {
+        v.add_product_ntt(t_hat, y_hat);
+    }
+    v.inverse_ntt();
+    let v = v
+        .add(&sample_poly_cbd(r, &mut n))
+        .add(&Poly::from_message(m));
+    v.compress_and_encode(params.dv, c2);
+
+    Ok(())
+}
+
+// K-PKE.Decrypt (FIPS 203 Algorithm 15). `ciphertext` must have the correct
+// length.
+fn k_pke_decrypt(params: &Params, s_hat: &Vector, ciphertext: &[u8]) -> [u8; MESSAGE_LEN] {
+    let k = params.k;
+    let (c1, c2) = ciphertext.split_at(32 * params.du * k);
+    let mut product = Poly::ZERO;
+    for (s_hat, c1) in s_hat[..k].iter().zip(c1.chunks_exact(32 * params.du)) {
+        let mut u = Poly::decode_and_decompress(params.du, c1);
+        u.ntt();
+        product.add_product_ntt(s_hat, &u);
+    }
+    product.inverse_ntt();
+    let v = Poly::decode_and_decompress(params.dv, c2);
+    v.sub(&product).to_message()
+}
+
+// The entry in row `i` and column `j` of the matrix Â generated from `rho`.
+fn sample_matrix_entry(rho: &[u8], i: usize, j: usize) -> Poly {
+    #[allow(clippy::cast_possible_truncation)]
+    Poly::sample_ntt(rho, [j as u8, i as u8])
+}
+
+// Samples a polynomial from `SamplePolyCBD(PRF(seed, n))` and increments `n`.
+fn sample_poly_cbd(seed: &[u8; 32], n: &mut u8) -> Poly {
+    let mut ctx = digest::XofContext::new(&digest::SHAKE256);
+    ctx.update(seed);
+    ctx.update(&[*n]);
+    *n += 1;
+    let mut input = [0u8; CBD_INPUT_LEN];
+    ctx.finalize().squeeze(&mut input);
+    Poly::sample_poly_cbd(&input)
+}
+
+// G = SHA3-512, split into two 32-byte halves.
+fn g(input: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    let mut ctx = digest::Context::new(&digest::SHA3_512);
+    input.iter().for_each(|input| ctx.update(input));
+    let digest = ctx.finish();
+    let (mut a, mut b) = ([0u8; 32], [0u8; 32]);
+    a.copy_from_slice(&digest.as_ref()[..32]);
+    b.copy_from_slice(&digest.as_ref()[32..]);
+    (a, b)
+}
+
+// H = SHA3-256.
+fn h(input: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(digest::digest(&digest::SHA3_256, input).as_ref());
+    out
+}
+
+// J = SHAKE256 with a 32-byte output.
+fn j(z: &[u8; 32], ciphertext: &[u8]) -> [u8; SHARED_SECRET_LEN] {
+    let mut ctx = digest::XofContext::new(&digest::SHAKE256);
+    ctx.update(z);
+    ctx.update(ciphertext);
+    let mut out = [0u8; SHARED_SECRET_LEN];
+    ctx.finalize().squeeze(&mut out);
+    out
+}
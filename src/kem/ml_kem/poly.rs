@@ -0,0 +1,434 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Polynomials in the ring Z_q[X]/(X^256 + 1) and their NTT representation,
+//! as used by ML-KEM.
+//!
+//! All the arithmetic on coefficients is constant-time. Only `sample_ntt`,
+//! which operates on public data, is variable-time.
+
+use crate::{digest, error};
+
+/// The number of coefficients of a polynomial.
+pub const N: usize = 256;
+
+/// The length of a polynomial encoded with 12 bits per coefficient.
+pub const ENCODED_LEN: usize = N * 12 / 8;
+
+/// The length of a message.
+pub const MESSAGE_LEN: usize = N / 8;
+
+const Q: u16 = 3329;
+
+// ML-KEM-768 and ML-KEM-1024 both use η1 = η2 = 2.
+const ETA: usize = 2;
+
+/// The length of the PRF output needed by `sample_poly_cbd`.
+pub const CBD_INPUT_LEN: usize = 64 * ETA;
+
+// ζ^BitRev7(i) mod q, where ζ = 17 is a primitive 256th root of unity.
+const ZETAS: [u16; 128] = powers_of_zeta(0);
+
+// ζ^(2·BitRev7(i) + 1) mod q, used in `multiply_ntts`.
+const GAMMAS: [u16; 128] = powers_of_zeta(1);
+
+// 128^-1 mod q.
+const N_INV: u16 = 3303;
+
+#[allow(clippy::cast_lossless, clippy::cast_possible_truncation)]
+const fn powers_of_zeta(odd: u32) -> [u16; 128] {
+    let mut r = [0u16; 128];
+    let mut i = 0;
+    while i < 128 {
+        // BitRev7(i).
+        let mut rev = 0;
+        let mut b = 0;
+        while b < 7 {
+            rev |= ((i >> b) & 1) << (6 - b);
+            b += 1;
+        }
+        let exponent = if odd == 1 { 2 * rev + 1 } else { rev };
+        let mut value: u32 = 1;
+        let mut e = 0;
+        while e < exponent {
+            value = (value * 17) % (Q as u32);
+            e += 1;
+        }
+        r[i] = value as u16;
+        i += 1;
+    }
+    r
+}
+
+/// A polynomial with coefficients in [0, q).
+#[derive(Clone, Copy)]
+pub struct Poly([u16; N]);
+
+impl Poly {
+    pub const ZERO: Self = Self([0; N]);
+
+    pub fn add(&self, other: &Self) -> Self {
+        let mut r = *self;
+        r.0.iter_mut()
+            .zip(other.0.iter())
+            .for_each(|(r, b)| *r = field_add(*r, *b));
+        r
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        let mut r = *self;
+        r.0.iter_mut()
+            .zip(other.0.iter())
+            .for_each(|(r, b)| *r = field_sub(*r, *b));
+        r
+    }
+
+    /// Sets `self += a * b`, where all three are in the NTT domain.
+    pub fn add_product_ntt(&mut self, a: &Self, b: &Self) {
+        *self = self.add(&multiply_ntts(a, b));
+    }
+
+    /// Transforms `self` into the NTT domain (FIPS 203 Algorithm 9).
+    pub fn ntt(&mut self) {
+        let f = &mut self.0;
+        let mut i = 1;
+        let mut len = 128;
+        while len >= 2 {
+            for start in (0..N).step_by(2 * len) {
+                let zeta = ZETAS[i];
+                i += 1;
+                let (lo, hi) = f[start..][..2 * len].split_at_mut(len);
+                for (a, b) in lo.iter_mut().zip(hi.iter_mut()) {
+                    let t = field_mul(zeta, *b);
+                    *b = field_sub(*a, t);
+                    *a = field_add(*a, t);
+                }
+            }
+            len /= 2;
+        }
+    }
+
+    /// Transforms `self` out of the NTT domain (FIPS 203 Algorithm 10).
+    pub fn inverse_ntt(&mut self) {
+        let f = &mut self.0;
+        let mut i = 127;
+        let mut len = 2;
+        while len <= 128 {
+            for start in (0..N).step_by(2 * len) {
+                let zeta = ZETAS[i];
+                i -= 1;
+                let (lo, hi) = f[start..][..2 * len].split_at_mut(len);
+                for (a, b) in lo.iter_mut().zip(hi.iter_mut()) {
+                    let t = *a;
+                    *a = field_add(t, *b);
+                    *b = field_mul(zeta, field_sub(*b, t));
+                }
+            }
+            len *= 2;
+        }
+        f.iter_mut().for_each(|a| *a = field_mul(*a, N_INV));
+    }
+
+    /// Samples a polynomial in the NTT domain from `SHAKE128(rho || suffix)`
+    /// using rejection sampling (FIPS 203 Algorithm 7).
+    pub fn sample_ntt(rho: &[u8], suffix: [u8; 2]) -> Self {
+        let mut ctx = digest::XofContext::new(&digest::SHAKE128);
+        ctx.update(rho);
+        ctx.update(&suffix);
+        let mut reader = ctx.finalize();
+
+        let mut r = Self::ZERO;
+        let mut j = 0;
+        let mut block = [0u8; SHAKE128_RATE];
+        while j < N {
+            reader.squeeze(&mut block);
+            for c in block.chunks_exact(3) {
+                let d1 = u16::from(c[0]) | (u16::from(c[1] & 0x0f) << 8);
+                let d2 = u16::from(c[1] >> 4) | (u16::from(c[2]) << 4);
+                for d in [d1, d2] {
+                    if d < Q && j < N {
+                        r.0[j] = d;
+                        j += 1;
+                    }
+                }
+            }
+        }
+        r
+    }
+
+    /// Samples a polynomial from the centered binomial distribution with
+    /// η = 2, using `input` as the source of randomness (FIPS 203
+    /// Algorithm 8).
+    pub fn sample_poly_cbd(input: &[u8; CBD_INPUT_LEN]) -> Self {
+        let bit = |i: usize| u16::from((input[i / 8] >> (i % 8)) & 1);
+        let mut r = Self::ZERO;
+        for (i, f) in r.0.iter_mut().enumerate() {
+            let base = 2 * ETA * i;
+            let x: u16 = (0..ETA).map(|j| bit(base + j)).sum();
+            let y: u16 = (0..ETA).map(|j| bit(base + ETA + j)).sum();
+            *f = field_sub(x, y);
+        }
+        r
+    }
+
+    /// Encodes the coefficients with 12 bits each (`ByteEncode_12`) into
+    /// `out`, which must be `ENCODED_LEN` bytes long.
+    pub fn encode(&self, out: &mut [u8]) {
+        byte_encode(self.0.iter().copied(), 12, out);
+    }
+
+    /// Decodes the output of `encode`, failing if any coefficient isn't
+    /// less than q (`ByteDecode_12` followed by the modulus check of FIPS 203
+    /// Section 7.2).
+    pub fn decode(bytes: &[u8]) -> Result<Self, error::Unspecified> {
+        if bytes.len() != ENCODED_LEN {
+            return Err(error::Unspecified);
+        }
+        let mut r = Self::ZERO;
+        byte_decode(bytes, 12, &mut r.0);
+        if r.0.iter().any(|&a| a >= Q) {
+            return Err(error::Unspecified);
+        }
+        Ok(r)
+    }
+
+    /// Compresses the coefficients to `d` bits and encodes them into `out`,
+    /// which must be `32 * d` bytes long.
+    pub fn compress_and_encode(&self, d: usize, out: &mut [u8]) {
+        byte_encode(self.0.iter().map(|&a| compress(a, d)), d, out);
+    }
+
+    /// Decodes `32 * d` bytes of `d`-bit values and decompresses them.
+    pub fn decode_and_decompress(d: usize, bytes: &[u8]) -> Self {
+        let mut r = Self::ZERO;
+        byte_decode(bytes, d, &mut r.0);
+        r.0.iter_mut().for_each(|a| *a = decompress(*a, d));
+        r
+    }
+
+    /// Maps each bit of the message to 0 or ⌈q/2⌋.
+    pub fn from_message(m: &[u8; MESSAGE_LEN]) -> Self {
+        Self::decode_and_decompress(1, m)
+    }
+
+    /// Maps each coefficient to the nearest of 0 and ⌈q/2⌋ and encodes the
+    /// result as a message.
+    pub fn to_message(self) -> [u8; MESSAGE_LEN] {
+        let mut m = [0u8; MESSAGE_LEN];
+        self.compress_and_encode(1, &mut m);
+        m
+    }
+}
+
+const SHAKE128_RATE: usize = 168;
+
+// Returns `x mod q` for `x < 2q`.
+fn reduce_once(x: u16) -> u16 {
+    debug_assert!(x < 2 * Q);
+    let t = x.wrapping_sub(Q);
+    let mask = 0u16.wrapping_sub(t >> 15);
+    t.wrapping_add(Q & mask)
+}
+
+// Returns `x mod q` for `x < 2^24` using Barrett reduction.
+fn barrett_reduce(x: u32) -> u16 {
+    debug_assert!(x < 1 << 24);
+    // floor(2^24 / q).
+    const M: u64 = 5039;
+    let quotient = (u64::from(x) * M) >> 24;
+    // The quotient is at most one less than floor(x / q), so the remainder is
+    // less than 2q.
+    #[allow(clippy::cast_possible_truncation)]
+    let r = (x - (quotient as u32) * u32::from(Q)) as u16;
+    reduce_once(r)
+}
+
+fn field_add(a: u16, b: u16) -> u16 {
+    reduce_once(a + b)
+}
+
+fn field_sub(a: u16, b: u16) -> u16 {
+    reduce_once(a + Q - b)
+}
+
+fn field_mul(a: u16, b: u16) -> u16 {
+    barrett_reduce(u32::from(a) * u32::from(b))
+}
+
+// FIPS 203 Algorithms 11 and 12.
+fn multiply_ntts(a: &Poly, b: &Poly) -> Poly {
+    let mut r = Poly::ZERO;
+    for (((r, a), b), gamma) in
+        r.0.chunks_exact_mut(2)
+            .zip(a.0.chunks_exact(2))
+            .zip(b.0.chunks_exact(2))
+            .zip(GAMMAS.iter())
+    {
+        r[0] = field_add(
+            field_mul(a[0], b[0]),
+            field_mul(field_mul(a[1], b[1]), *gamma),
+        );
+        r[1] = field_add(field_mul(a[0], b[1]), field_mul(a[1], b[0]));
+    }
+    r
+}
+
+// Returns ⌈(2^d / q) · x⌋ mod 2^d without a (potentially variable-time)
+// division.
+fn compress(x: u16, d: usize) -> u16 {
+    debug_assert!(d <= 11);
+    // ceil(2^35 / q). For numerators less than 2^23, multiplying by this and
+    // shifting is exact division by q.
+    const M: u64 = 10321340;
+    let numerator = (u64::from(x) << d) + u64::from(Q / 2);
+    let quotient = (numerator * M) >> 35;
+    #[allow(clippy::cast_possible_truncation)]
+    let quotient = quotient as u16;
+    quotient & ((1 << d) - 1)
+}
+
+// Returns ⌈(q / 2^d) · y⌋.
+fn decompress(y: u16, d: usize) -> u16 {
+    let r = (u32::from(y) * u32::from(Q) + (1 << (d - 1))) >> d;
+    #[allow(clippy::cast_possible_truncation)]
+    let r = r as u16;
+    r
+}
+
+// `ByteEncode_d`: packs the low `d` bits of each value, least significant bit
+// first.
+fn byte_encode(values: impl Iterator<Item = u16>, d: usize, out: &mut [u8]) {
+    debug_assert_eq!(out.len(), N * d / 8);
+    let mut out = out.iter_mut();
+    let mut acc: u32 = 0;
+    let mut acc_bits = 0;
+    for value in values {
+        acc |= u32::from(value) << acc_bits;
+        acc_bits += d;
+        while acc_bits >= 8 {
+            if let Some(out) = out.next() {
+                #[allow(clippy::cast_possible_truncation)]
+                let byte = acc as u8;
+                *out = byte;
+            }
+            acc >>= 8;
+            acc_bits -= 8;
+        }
+    }
+}
+
+// `ByteDecode_d` without the reduction modulo q for `d == 12`, which is done
+// by `Poly::decode` instead.
+fn byte_decode(bytes: &[u8], d: usize, out: &mut [u16; N]) {
+    debug_assert_eq!(bytes.len(), N * d / 8);
+    let mut bytes = bytes.iter();
+    let mut acc: u32 = 0;
+    let mut acc_bits = 0;
+    for out in out.iter_mut() {
+        while acc_bits < d {
+            acc |= u32::from(bytes.next().copied().unwrap_or(0)) << acc_bits;
+            acc_bits += 8;
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        let value = (acc & ((1 << d) - 1)) as u16;
+        *out = value;
+        acc >>= d;
+        acc_bits -= d;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zetas() {
+        assert_eq!(ZETAS[0], 1);
+        assert_eq!(ZETAS[1], 1729);
+        assert_eq!(ZETAS[127], 2154);
+        assert_eq!(GAMMAS[0], 17);
+        assert_eq!(GAMMAS[1], Q - 17);
+    }
+
+    #[test]
+    fn test_reduction() {
+        for x in (0..u32::from(Q) * u32::from(Q)).step_by(7) {
+            assert_eq!(u32::from(barrett_reduce(x)), x % u32::from(Q));
+        }
+        for x in 0..2 * Q {
+            assert_eq!(reduce_once(x), x % Q);
+        }
+    }
+
+    #[test]
+    fn test_compress() {
+        for d in [1, 4, 5, 10, 11] {
+            for x in 0..Q {
+                let expected = ((u32::from(x) << d) + u32::from(Q / 2)) / u32::from(Q);
+                let expected = expected & ((1 << d) - 1);
+                assert_eq!(u32::from(compress(x, d)), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ntt_round_trip() {
+        let mut f = Poly::ZERO;
+        for (i, a) in (0u32..).zip(f.0.iter_mut()) {
+            *a = barrett_reduce(i * 1009 + 7);
+        }
+        let original = f;
+        f.ntt();
+        f.inverse_ntt();
+        assert_eq!(f.0[..], original.0[..]);
+    }
+
+    #[test]
+    fn test_ntt_multiplication() {
+        // X * X^255 = X^256 = -1.
+        let mut a = Poly::ZERO;
+        a.0[1] = 1;
+        let mut b = Poly::ZERO;
+        b.0[255] = 1;
+        a.ntt();
+        b.ntt();
+        let mut r = Poly::ZERO;
+        r.add_product_ntt(&a, &b);
+        r.inverse_ntt();
+        let mut expected = Poly::ZERO;
+        expected.0[0] = Q - 1;
+        assert_eq!(r.0[..], expected.0[..]);
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let mut f = Poly::ZERO;
+        for (i, a) in (0u32..).zip(f.0.iter_mut()) {
+            *a = barrett_reduce(i * 3301 + 11);
+        }
+        let mut encoded = [0u8; ENCODED_LEN];
+        f.encode(&mut encoded);
+        let decoded = Poly::decode(&encoded).unwrap();
+        assert_eq!(decoded.0[..], f.0[..]);
+
+        // A coefficient equal to q is rejected.
+        encoded[0] = 0x01;
+        encoded[1] = (encoded[1] & 0xf0) | 0x0d;
+        assert!(Poly::decode(&encoded).is_err());
+        assert!(Poly::decode(&encoded[1..]).is_err());
+
+        let m = [0xa5; MESSAGE_LEN];
+        assert_eq!(Poly::from_message(&m).to_message(), m);
+    }
+}
@@ -114,6 +114,8 @@ mod endian;
 pub mod error;
 pub mod hkdf;
 pub mod hmac;
+pub mod hpke;
+pub mod kem;
 mod limb;
 pub mod pbkdf2;
 pub mod pkcs8;
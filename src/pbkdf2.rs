@@ -131,6 +131,15 @@ pub static PBKDF2_HMAC_SHA384: Algorithm = Algorithm(hmac::HMAC_SHA384);
 /// PBKDF2 using HMAC-SHA512.
 pub static PBKDF2_HMAC_SHA512: Algorithm = Algorithm(hmac::HMAC_SHA512);
 
+/// PBKDF2 using HMAC-SHA3-256.
+pub static PBKDF2_HMAC_SHA3_256: Algorithm = Algorithm(hmac::HMAC_SHA3_256);
+
+/// PBKDF2 using HMAC-SHA3-384.
+pub static PBKDF2_HMAC_SHA3_384: Algorithm = Algorithm(hmac::HMAC_SHA3_384);
+
+/// PBKDF2 using HMAC-SHA3-512.
+pub static PBKDF2_HMAC_SHA3_512: Algorithm = Algorithm(hmac::HMAC_SHA3_512);
+
 /// Fills `out` with the key derived using PBKDF2 with the given inputs.
 ///
 /// Do not use `derive` as part of verifying a secret; use `verify` instead, to
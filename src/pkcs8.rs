@@ -18,6 +18,9 @@
 
 use crate::{ec, error, io::der};
 
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
 pub(crate) struct PublicKeyOptions {
     /// Should the wrong public key ASN.1 tagging used by early implementations
     /// of PKCS#8 v2 (including earlier versions of *ring*) be accepted?
@@ -176,30 +179,43 @@ fn unwrap_key__<'a>(
 }
 
 /// A generated PKCS#8 document.
-pub struct Document {
-    bytes: [u8; ec::PKCS8_DOCUMENT_MAX_LEN],
-    len: usize,
+pub struct Document(DocumentBytes);
+
+// Documents for EC keys are small enough to be stored inline, so they can be
+// generated without `alloc`.
+#[allow(clippy::large_enum_variant, variant_size_differences)]
+enum DocumentBytes {
+    Fixed {
+        bytes: [u8; ec::PKCS8_DOCUMENT_MAX_LEN],
+        len: usize,
+    },
+    #[cfg(feature = "alloc")]
+    Boxed(Box<[u8]>),
+}
+
+impl Document {
+    #[cfg(feature = "alloc")]
+    pub(crate) fn from_boxed(bytes: Box<[u8]>) -> Self {
+        Self(DocumentBytes::Boxed(bytes))
+    }
 }
 
 impl AsRef<[u8]> for Document {
     #[inline]
     fn as_ref(&self) -> &[u8] {
-        &self.bytes[..self.len]
+        match &self.0 {
+            DocumentBytes::Fixed { bytes, len } => &bytes[..*len],
+            #[cfg(feature = "alloc")]
+            DocumentBytes::Boxed(bytes) => bytes,
+        }
     }
 }
 
 pub(crate) fn wrap_key(template: &Template, private_key: &[u8], public_key: &[u8]) -> Document {
-    let mut result = Document {
-        bytes: [0; ec::PKCS8_DOCUMENT_MAX_LEN],
-        len: template.bytes.len() + private_key.len() + public_key.len(),
-    };
-    wrap_key_(
-        template,
-        private_key,
-        public_key,
-        &mut result.bytes[..result.len],
-    );
-    result
+    let mut bytes = [0; ec::PKCS8_DOCUMENT_MAX_LEN];
+    let len = template.bytes.len() + private_key.len() + public_key.len();
+    wrap_key_(template, private_key, public_key, &mut bytes[..len]);
+    Document(DocumentBytes::Fixed { bytes, len })
 }
 
 /// Formats a private key "prefix||private_key||middle||public_key" where
@@ -41,6 +41,39 @@ pub struct RsaParameters {
     min_bits: bits::BitLength,
 }
 
+impl RsaParameters {
+    /// Constructs parameters for verifying signatures padded with
+    /// `padding_alg` using RSA keys of `min_bits`-8192 bits.
+    ///
+    /// This is mostly useful for verifying RSA PSS signatures with parameters
+    /// other than the ones used by `RSA_PSS_*`:
+    ///
+    /// ```
+    /// use ring::{digest, signature};
+    ///
+    /// static RSA_PSS_SHA256_MAX_SALT: signature::PSS = signature::PSS::new(
+    ///     &digest::SHA256,
+    ///     &digest::SHA256,
+    ///     signature::PssSaltLength::Maximum,
+    /// );
+    ///
+    /// static RSA_PSS_2048_8192_SHA256_MAX_SALT: signature::RsaParameters =
+    ///     signature::RsaParameters::new(&RSA_PSS_SHA256_MAX_SALT, 2048);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_bits` is less than 1024, the smallest key size that
+    /// *ring* supports for verification.
+    pub const fn new(padding_alg: &'static dyn padding::Verification, min_bits: usize) -> Self {
+        assert!(min_bits >= 1024);
+        Self {
+            padding_alg,
+            min_bits: bits::BitLength::from_usize_bits(min_bits),
+        }
+    }
+}
+
 fn parse_public_key(
     input: untrusted::Input,
 ) -> Result<(io::Positive, io::Positive), error::Unspecified> {
@@ -59,6 +92,8 @@ enum N {}
 
 impl bigint::PublicModulus for N {}
 
+mod blind;
+mod keygen;
 mod keypair;
 mod keypair_components;
 mod public_exponent;
@@ -71,6 +106,14 @@ pub(crate) mod verification;
 use self::{public_exponent::PublicExponent, public_modulus::PublicModulus};
 
 pub use self::{
-    keypair::KeyPair, keypair_components::KeyPairComponents, public_key::PublicKey,
+    blind::{
+        BlindSignatureAlgorithm, BlindedMessage, RSABSSA_SHA384_PSS_DETERMINISTIC,
+        RSABSSA_SHA384_PSS_RANDOMIZED,
+    },
+    keygen::KeySize,
+    keypair::KeyPair,
+    keypair_components::KeyPairComponents,
+    padding::{OaepAlgorithm, RSA_OAEP_SHA256, RSA_OAEP_SHA384, RSA_OAEP_SHA512},
+    public_key::PublicKey,
     public_key_components::PublicKeyComponents,
 };
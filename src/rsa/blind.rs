@@ -0,0 +1,333 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! RSA blind signatures as specified in [RFC 9474].
+//!
+//! The protocol has three parties: the client prepares and blinds a message
+//! using the server's public key, the server signs the blinded message
+//! without learning the message, and the client finalizes the blind
+//! signature into an ordinary RSASSA-PSS signature of the prepared message.
+//!
+//! ```text
+//!     Client                                  Server
+//!     input_msg = alg.prepare(msg, rng)
+//!     blinded = public_key.blind(alg, input_msg, rng)
+//!                         -- blinded.as_ref() -->
+//!                                             key_pair.blind_sign(...)
+//!                         <-- blind_signature --
+//!     public_key.finalize(alg, input_msg, &blinded, blind_signature, ...)
+//! ```
+//!
+//! [RFC 9474]: https://www.rfc-editor.org/rfc/rfc9474
+
+use super::{
+    padding::{Padding, RsaEncoding, Verification, PSS, RSA_PSS_SHA384},
+    KeyPair, PublicKey, N, PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN,
+};
+use crate::{arithmetic::bigint, bits, cpu, digest, error, rand};
+use alloc::{boxed::Box, vec, vec::Vec};
+
+/// An RSA blind signature variant, as described in [RFC 9474 Section 5].
+///
+/// [RFC 9474 Section 5]: https://www.rfc-editor.org/rfc/rfc9474#section-5
+#[derive(Debug)]
+pub struct BlindSignatureAlgorithm {
+    id: AlgorithmID,
+    padding_alg: &'static PSS,
+    randomized: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum AlgorithmID {
+    RSABSSA_SHA384_PSS_RANDOMIZED,
+    RSABSSA_SHA384_PSS_DETERMINISTIC,
+}
+
+impl PartialEq for BlindSignatureAlgorithm {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for BlindSignatureAlgorithm {}
+
+/// RSABSSA-SHA384-PSS-Randomized: SHA-384 with a 48-byte PSS salt and a
+/// randomized message prefix. This is the variant recommended by RFC 9474.
+pub static RSABSSA_SHA384_PSS_RANDOMIZED: BlindSignatureAlgorithm = BlindSignatureAlgorithm {
+    id: AlgorithmID::RSABSSA_SHA384_PSS_RANDOMIZED,
+    padding_alg: &RSA_PSS_SHA384,
+    randomized: true,
+};
+
+/// RSABSSA-SHA384-PSS-Deterministic: SHA-384 with a 48-byte PSS salt and no
+/// message prefix.
+pub static RSABSSA_SHA384_PSS_DETERMINISTIC: BlindSignatureAlgorithm = BlindSignatureAlgorithm {
+    id: AlgorithmID::RSABSSA_SHA384_PSS_DETERMINISTIC,
+    padding_alg: &RSA_PSS_SHA384,
+    randomized: false,
+};
+
+// The length of the random prefix of the randomized variants.
+const MSG_PREFIX_LEN: usize = 32;
+
+impl BlindSignatureAlgorithm {
+    /// Prepares `msg` for blinding, as described in [RFC 9474 Section 4.1].
+    ///
+    /// For the randomized variants, this prepends 32 random bytes to `msg`;
+    /// otherwise it returns a copy of `msg`. The result is the message that
+    /// is blinded, signed, and verified.
+    ///
+    /// [RFC 9474 Section 4.1]: https://www.rfc-editor.org/rfc/rfc9474#section-4.1
+    pub fn prepare(
+        &self,
+        msg: &[u8],
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<Vec<u8>, error::Unspecified> {
+        let prefix_len = if self.randomized { MSG_PREFIX_LEN } else { 0 };
+        let mut input_msg = vec![0u8; prefix_len + msg.len()];
+        let (prefix, rest) = input_msg.split_at_mut(prefix_len);
+        if self.randomized {
+            rng.fill(prefix)?;
+        }
+        rest.copy_from_slice(msg);
+        Ok(input_msg)
+    }
+}
+
+/// A blinded message, along with the secret inverse of the blinding factor
+/// needed to finalize the blind signature of it.
+///
+/// `as_ref()` returns the blinded message that is sent to the signer.
+pub struct BlindedMessage {
+    blinded_msg: Box<[u8]>,
+    inv: Box<[u8]>,
+}
+
+derive_debug_via_field!(BlindedMessage, blinded_msg);
+
+impl AsRef<[u8]> for BlindedMessage {
+    fn as_ref(&self) -> &[u8] {
+        &self.blinded_msg
+    }
+}
+
+impl PublicKey {
+    /// Blinds the prepared message `input_msg`, as described in
+    /// [RFC 9474 Section 4.2].
+    ///
+    /// `input_msg` must be the result of `alg.prepare()`.
+    ///
+    /// [RFC 9474 Section 4.2]: https://www.rfc-editor.org/rfc/rfc9474#section-4.2
+    pub fn blind(
+        &self,
+        alg: &'static BlindSignatureAlgorithm,
+        input_msg: &[u8],
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<BlindedMessage, error::Unspecified> {
+        let cpu_features = cpu::features();
+
+        let n = self.inner().n();
+        let n_bits = n.len_bits();
+        let n_one = n.oneRR();
+        let n = &n.value(cpu_features);
+        let k = self.modulus_len();
+
+        // Steps 1 and 2.
+        let mut encoded_msg = vec![0u8; k];
+        let m_hash = digest::digest(alg.padding_alg.digest_alg(), input_msg);
+        alg.padding_alg
+            .encode(m_hash, &mut encoded_msg, n_bits, rng)?;
+
+        // Step 3.
+        let m = bigint::Elem::from_be_bytes_padded(untrusted::Input::from(&encoded_msg), n)?;
+
+        // Step 5, out of order.
+        let r = random_nonzero_elem(k, n_bits, n, rng)?;
+
+        // Step 4, combined with the computation of `inv` in step 6.
+        //
+        // `r` must remain secret, but the inversion isn't constant-time. So
+        // instead of inverting `r` directly, invert `m * r * b` for a random
+        // `b`, which reveals nothing about `r`, and then multiply the result
+        // by `m * b`. This also fails if `m` isn't invertible, which is the
+        // coprimality check of step 4.
+        let b = random_nonzero_elem(k, n_bits, n, rng)?;
+        let m_b = {
+            let m_mont = bigint::elem_mul(n_one, m.clone(), n);
+            bigint::elem_mul(&m_mont, b, n)
+        };
+        let m_r_b = {
+            let r_mont = bigint::elem_mul(n_one, r.clone(), n);
+            bigint::elem_mul(&r_mont, m_b.clone(), n)
+        };
+        let m_r_b_inv = bigint::elem_inverse_vartime(&m_r_b, n)?;
+        let inv = {
+            let m_b = bigint::elem_mul(n_one, m_b, n);
+            bigint::elem_mul(&m_b, m_r_b_inv, n)
+        };
+
+        // Step 7.
+        let x = self.inner().exponentiate_elem(&r, cpu_features);
+
+        // Step 8.
+        let z = {
+            let x = bigint::elem_mul(n_one, x, n);
+            bigint::elem_mul(&x, m, n)
+        };
+
+        // Step 9.
+        let mut blinded_msg = vec![0u8; k].into_boxed_slice();
+        z.fill_be_bytes(&mut blinded_msg);
+
+        let mut inv_bytes = vec![0u8; k].into_boxed_slice();
+        inv.fill_be_bytes(&mut inv_bytes);
+
+        Ok(BlindedMessage {
+            blinded_msg,
+            inv: inv_bytes,
+        })
+    }
+
+    /// Finalizes the blind signature `blind_signature` of `blinded` into an
+    /// RSASSA-PSS signature of `input_msg`, writing it into `signature`, as
+    /// described in [RFC 9474 Section 4.4].
+    ///
+    /// `input_msg` must be the same prepared message that was passed to
+    /// `blind()`. The resulting signature is verified before it is returned.
+    /// `signature`'s length must be exactly `self.modulus_len()`.
+    ///
+    /// [RFC 9474 Section 4.4]: https://www.rfc-editor.org/rfc/rfc9474#section-4.4
+    pub fn finalize(
+        &self,
+        alg: &'static BlindSignatureAlgorithm,
+        input_msg: &[u8],
+        blinded: &BlindedMessage,
+        blind_signature: &[u8],
+        signature: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        let cpu_features = cpu::features();
+
+        let k = self.modulus_len();
+
+        // Step 1.
+        if blind_signature.len() != k || signature.len() != k {
+            return Err(error::Unspecified);
+        }
+
+        let n = self.inner().n();
+        let n_one = n.oneRR();
+        let n = &n.value(cpu_features);
+
+        // Steps 2 and 3.
+        let z = bigint::Elem::from_be_bytes_padded(untrusted::Input::from(blind_signature), n)?;
+        let inv = bigint::Elem::from_be_bytes_padded(untrusted::Input::from(&blinded.inv), n)?;
+        let s = {
+            let z = bigint::elem_mul(n_one, z, n);
+            bigint::elem_mul(&z, inv, n)
+        };
+
+        // Step 4.
+        s.fill_be_bytes(signature);
+
+        // Steps 5 and 6.
+        self.verify_blind_signature(alg, input_msg, signature)
+    }
+
+    /// Verifies the finalized blind signature `signature` of the prepared
+    /// message `input_msg`.
+    ///
+    /// This is RSASSA-PSS verification using the digest algorithm and salt
+    /// length of `alg`, i.e. `RSA_PSS_SHA384` verification.
+    pub fn verify_blind_signature(
+        &self,
+        alg: &'static BlindSignatureAlgorithm,
+        input_msg: &[u8],
+        signature: &[u8],
+    ) -> Result<(), error::Unspecified> {
+        let cpu_features = cpu::features();
+
+        // RFC 8017 Section 5.2.2: RSAVP1.
+        let mut decoded = [0u8; PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN];
+        let decoded = self.inner().exponentiate(
+            untrusted::Input::from(signature),
+            &mut decoded,
+            cpu_features,
+        )?;
+
+        let m_hash = digest::digest(alg.padding_alg.digest_alg(), input_msg);
+        untrusted::Input::from(decoded).read_all(error::Unspecified, |m| {
+            alg.padding_alg
+                .verify(m_hash, m, self.inner().n().len_bits())
+        })
+    }
+}
+
+impl KeyPair {
+    /// Signs the blinded message `blinded_msg`, writing the blind signature
+    /// into `blind_signature`, as described in [RFC 9474 Section 4.3].
+    ///
+    /// The lengths of `blinded_msg` and `blind_signature` must be exactly
+    /// `self.public().modulus_len()`. The signer learns nothing about the
+    /// message that was blinded.
+    ///
+    /// [RFC 9474 Section 4.3]: https://www.rfc-editor.org/rfc/rfc9474#section-4.3
+    pub fn blind_sign(
+        &self,
+        blinded_msg: &[u8],
+        blind_signature: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        let cpu_features = cpu::features();
+
+        let k = self.public().modulus_len();
+        if blinded_msg.len() != k || blind_signature.len() != k {
+            return Err(error::Unspecified);
+        }
+
+        // Steps 1-4. `private_exponentiate` rejects `m >= n` and verifies
+        // the result using the public key.
+        let s = self.private_exponentiate(blinded_msg, cpu_features)?;
+
+        // Step 5.
+        s.fill_be_bytes(blind_signature);
+
+        Ok(())
+    }
+}
+
+// Returns a random element of [1, n), where `n` is `k` bytes and `n_bits` bits
+// long.
+fn random_nonzero_elem(
+    k: usize,
+    n_bits: bits::BitLength,
+    n: &bigint::Modulus<N>,
+    rng: &dyn rand::SecureRandom,
+) -> Result<bigint::Elem<N>, error::Unspecified> {
+    let mut bytes = [0u8; PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN];
+    let bytes = &mut bytes[..k];
+    for _ in 0..100 {
+        rng.fill(bytes)?;
+
+        // When the modulus's bit length isn't a multiple of 8, clear the
+        // excess high bits so that candidates aren't almost always rejected.
+        let excess_bits = (k * 8) - n_bits.as_bits();
+        bytes[0] &= 0xff >> excess_bits;
+
+        if let Ok(elem) = bigint::Elem::from_be_bytes_padded(untrusted::Input::from(bytes), n) {
+            if !elem.is_zero() {
+                return Ok(elem);
+            }
+        }
+    }
+    Err(error::Unspecified)
+}
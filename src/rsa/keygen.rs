@@ -0,0 +1,725 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! RSA key pair generation.
+//!
+//! The primes are generated as described in [FIPS 186-4] Appendix B.3.3,
+//! except that each candidate is chosen to be 3 (mod 4). That way `w - 1` is
+//! `2 * m` for an odd `m` and each Miller-Rabin round is a single
+//! constant-time exponentiation, so nothing about the final primes is leaked
+//! through the number of squarings done. As Appendix B.3.1 requires, the
+//! private exponent `d` is `e**-1 (mod LCM(p - 1, q - 1))` and is greater
+//! than `2**(nlen/2)`.
+//!
+//! The arithmetic that isn't modular, such as computing `n = p * q`, is done
+//! on big-endian byte strings, one byte at a time. All of it is
+//! constant-time; in particular, reductions modulo small numbers use Barrett
+//! reduction instead of division.
+//!
+//! [FIPS 186-4]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-4.pdf
+
+use crate::{
+    arithmetic::{bigint, montgomery::RR},
+    cpu, error,
+    io::{self, der, der_writer},
+    pkcs8, rand,
+};
+use alloc::{vec, vec::Vec};
+
+/// The length of the public modulus of a generated RSA key pair.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeySize {
+    /// A 2048-bit public modulus.
+    Rsa2048,
+
+    /// A 3072-bit public modulus.
+    Rsa3072,
+
+    /// A 4096-bit public modulus.
+    Rsa4096,
+}
+
+impl KeySize {
+    /// The length of the public modulus, in bits.
+    #[inline]
+    pub fn len_bits(self) -> usize {
+        match self {
+            Self::Rsa2048 => 2048,
+            Self::Rsa3072 => 3072,
+            Self::Rsa4096 => 4096,
+        }
+    }
+}
+
+// The public exponent of all generated keys.
+const E: u32 = 65537;
+
+// Type-level representations of the moduli used during key generation. See
+// `super::bigint`'s module-level documentation.
+enum Candidate {}
+enum P {}
+
+pub(super) fn generate_pkcs8(
+    size: KeySize,
+    rng: &dyn rand::SecureRandom,
+) -> Result<pkcs8::Document, error::Unspecified> {
+    let cpu_features = cpu::features();
+
+    let n_bits = size.len_bits();
+    let prime_len = n_bits / 2 / 8;
+    let small_primes = small_odd_primes();
+
+    // FIPS 186-4 Appendix B.3.1 Step 3 says to start over with new primes if
+    // `d <= 2**(nlen/2)`, which is astronomically unlikely.
+    let mut attempts = 0..100;
+    let (p, q, d) = loop {
+        if attempts.next().is_none() {
+            return Err(error::Unspecified);
+        }
+        let (p, q) = generate_primes(prime_len, &small_primes, rng, cpu_features)?;
+
+        // Both `p` and `q` are odd, so subtracting one just clears the low bit.
+        let d = private_exponent(
+            &with_low_bits_cleared(&p, 0b1),
+            &with_low_bits_cleared(&q, 0b1),
+        )?;
+
+        // `d` is `2 * prime_len` bytes long and odd, so `d > 2**(nlen/2)` iff
+        // any of its upper `prime_len` bytes is nonzero.
+        if d[..prime_len].iter().fold(0, |acc, &b| acc | b) != 0 {
+            break (p, q, d);
+        }
+    };
+
+    let n = mul(&p, &q);
+
+    let p_minus_1 = with_low_bits_cleared(&p, 0b1);
+    let q_minus_1 = with_low_bits_cleared(&q, 0b1);
+
+    let dP = inverse_of_e(&p_minus_1)?;
+    let dQ = inverse_of_e(&q_minus_1)?;
+    let qInv = inverse_mod_p(&q, &p, cpu_features)?;
+
+    let e = E.to_be_bytes();
+
+    encode_pkcs8(&[&n, &e, &d, &p, &q, &dP, &dQ, &qInv])
+}
+
+// Returns the primes `(p, q)`, each `prime_len` bytes long, with `q < p`, as
+// described in FIPS 186-4 Appendix B.3.3 Steps 4 and 5.
+fn generate_primes(
+    prime_len: usize,
+    small_primes: &[SmallModulus],
+    rng: &dyn rand::SecureRandom,
+    cpu_features: cpu::Features,
+) -> Result<(Vec<u8>, Vec<u8>), error::Unspecified> {
+    let p = generate_prime(prime_len, small_primes, rng, cpu_features)?;
+    let q = {
+        let mut attempts = 0..100;
+        loop {
+            if attempts.next().is_none() {
+                return Err(error::Unspecified);
+            }
+            let q = generate_prime(prime_len, small_primes, rng, cpu_features)?;
+
+            // Step 5.4: Reject `q` if |p - q| <= 2**(nlen/2 - 100). This is
+            // extremely unlikely to happen so the variable-time check doesn't
+            // leak anything meaningful.
+            let (larger, smaller) = if less_than_vartime(&p, &q) {
+                (&q, &p)
+            } else {
+                (&p, &q)
+            };
+            let diff = sub(larger, smaller);
+            let leading_zero_bits = diff.iter().take_while(|&&b| b == 0).count() * 8;
+            if leading_zero_bits < 100 - 8 {
+                break q;
+            }
+        }
+    };
+
+    // Like other implementations, order the primes so that `q < p`. Which of
+    // the two is larger isn't secret.
+    if less_than_vartime(&p, &q) {
+        Ok((q, p))
+    } else {
+        Ok((p, q))
+    }
+}
+
+// Returns a random prime `w` of `len` bytes where `w` is 3 (mod 4),
+// `w >= 1.5 * 2**(len * 8 - 1)`, and `GCD(w - 1, E) == 1`.
+fn generate_prime(
+    len: usize,
+    small_primes: &[SmallModulus],
+    rng: &dyn rand::SecureRandom,
+    cpu_features: cpu::Features,
+) -> Result<Vec<u8>, error::Unspecified> {
+    let e = SmallModulus::new(E);
+    let mut w = vec![0u8; len];
+
+    // FIPS 186-4 Appendix B.3.3 Step 4.7.
+    for _ in 0..(5 * len * 8) {
+        // Step 4.2. Setting the two most significant bits satisfies Step 4.4
+        // since 1.5 > √2, and ensures the product of two such primes has
+        // exactly `2 * len * 8` bits.
+        rng.fill(&mut w)?;
+        w[0] |= 0b1100_0000;
+        w[len - 1] |= 0b11;
+
+        // Quickly weed out most composites by trial division.
+        if small_primes.iter().any(|m| m.reduce_bytes(&w) == 0) {
+            continue;
+        }
+
+        // Step 4.5. Since `E` is prime, `GCD(w - 1, E) == 1` unless `E`
+        // divides `w - 1`.
+        if e.reduce_bytes(&with_low_bits_cleared(&w, 0b1)) == 0 {
+            continue;
+        }
+
+        if is_probably_prime(&w, rng, cpu_features)? {
+            return Ok(w);
+        }
+    }
+
+    Err(error::Unspecified)
+}
+
+// Miller-Rabin probabilistic primality test, as described in FIPS 186-4
+// Appendix C.3.1, for `w` that is 3 (mod 4).
+//
+// Since `w - 1 == 2 * m` for odd `m`, i.e. `a == 1` in the notation of
+// Appendix C.3.1, each round reduces to checking `b**m == ±1 (mod w)`.
+fn is_probably_prime(
+    w: &[u8],
+    rng: &dyn rand::SecureRandom,
+    cpu_features: cpu::Features,
+) -> Result<bool, error::Unspecified> {
+    debug_assert_eq!(w[w.len() - 1] & 0b11, 0b11);
+
+    let w_modulus = bigint::OwnedModulus::<Candidate>::from_be_bytes(untrusted::Input::from(w))
+        .map_err(|_: error::KeyRejected| error::Unspecified)?;
+    let m_modulus = &w_modulus.modulus(cpu_features);
+
+    // m = (w - 1) / 2, which is odd and less than `w`.
+    let m = shifted_right_by_1(w);
+    let m = bigint::PrivateExponent::from_be_bytes_padded(untrusted::Input::from(&m), m_modulus)?;
+
+    let one = bigint::Elem::from_be_bytes_padded(untrusted::Input::from(&[1]), m_modulus)?;
+    let minus_one = bigint::Elem::from_be_bytes_padded(
+        untrusted::Input::from(&with_low_bits_cleared(w, 0b1)),
+        m_modulus,
+    )?;
+    let oneRR = bigint::One::<Candidate, RR>::newRR(m_modulus);
+
+    // The number of rounds from FIPS 186-4 Table C.2 for an error probability
+    // of at most 2**-100, for 1024-, 1536-, and 2048-bit primes.
+    let rounds = if w.len() * 8 >= 1536 { 4 } else { 5 };
+
+    let mut b = vec![0u8; w.len()];
+    for _ in 0..rounds {
+        // Step 4.1 and 4.2. `b` is chosen from [0, w) instead of [2, w - 2];
+        // the difference is negligible.
+        let b = {
+            let mut attempts = 0..100;
+            loop {
+                if attempts.next().is_none() {
+                    return Err(error::Unspecified);
+                }
+                rng.fill(&mut b)?;
+                if let Ok(b) =
+                    bigint::Elem::from_be_bytes_padded(untrusted::Input::from(&b), m_modulus)
+                {
+                    break b;
+                }
+            }
+        };
+        let b = bigint::elem_mul(oneRR.as_ref(), b, m_modulus);
+
+        // Steps 4.3 - 4.5.
+        let z = bigint::elem_exp_consttime(b, &m, m_modulus)?;
+        let is_one = bigint::elem_verify_equal_consttime(&z, &one).is_ok();
+        let is_minus_one = bigint::elem_verify_equal_consttime(&z, &minus_one).is_ok();
+        if !(is_one | is_minus_one) {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+// Returns `q**-1 (mod p)` for prime `p` > `q`, as a big-endian value padded to
+// the length of `p`.
+fn inverse_mod_p(
+    q: &[u8],
+    p: &[u8],
+    cpu_features: cpu::Features,
+) -> Result<Vec<u8>, error::Unspecified> {
+    let p_modulus = bigint::OwnedModulus::<P>::from_be_bytes(untrusted::Input::from(p))
+        .map_err(|_: error::KeyRejected| error::Unspecified)?;
+    let p_modulus = &p_modulus.modulus(cpu_features);
+
+    // Since `p` is 3 (mod 4), `p - 2` is odd and is computed by clearing the
+    // second-lowest bit.
+    let p_minus_2 = with_low_bits_cleared(p, 0b10);
+    let p_minus_2 = bigint::PrivateExponent::from_be_bytes_padded(
+        untrusted::Input::from(&p_minus_2),
+        p_modulus,
+    )?;
+
+    let q = bigint::Elem::from_be_bytes_padded(untrusted::Input::from(q), p_modulus)?;
+    let oneRR = bigint::One::<P, RR>::newRR(p_modulus);
+    let q = bigint::elem_mul(oneRR.as_ref(), q, p_modulus);
+
+    // Fermat's Little Theorem.
+    let q_inv = bigint::elem_exp_consttime(q, &p_minus_2, p_modulus)?;
+
+    let mut out = vec![0u8; p.len()];
+    q_inv.fill_be_bytes(&mut out);
+    Ok(out)
+}
+
+// Returns `E**-1 (mod a)` for even `a` where `GCD(a, E) == 1`.
+//
+// Since `E` is small, this is `(k * a + 1) / E` where `k == -(a**-1) (mod E)`,
+// which is the smallest `k` that makes the division exact.
+fn inverse_of_e(a: &[u8]) -> Result<Vec<u8>, error::Unspecified> {
+    let e = SmallModulus::new(E);
+    let a_mod_e = e.reduce_bytes(a);
+    if a_mod_e == 0 {
+        return Err(error::Unspecified);
+    }
+    // Fermat's Little Theorem, since `E` is prime.
+    let a_inv_mod_e = e.pow(a_mod_e, E - 2);
+    let k = E - a_inv_mod_e;
+    let ka_plus_1 = mul_small_add_1(a, k);
+    let mut d = divide_exact(&ka_plus_1, E);
+    // The quotient is at most as long as `a`.
+    let extra = d.len() - a.len();
+    debug_assert!(d[..extra].iter().all(|&b| b == 0));
+    Ok(d.split_off(extra))
+}
+
+// Returns `d = E**-1 (mod λ)` for `λ = LCM(p - 1, q - 1)`, as required by
+// FIPS 186-4 Appendix B.3.1, where `p` and `q` are 3 (mod 4) and of the same
+// length.
+//
+// Since `p - 1 == 2 * a` and `q - 1 == 2 * b` for odd `a` and `b`,
+// `λ == (a / GCD(a, b)) * (q - 1)`.
+fn private_exponent(p_minus_1: &[u8], q_minus_1: &[u8]) -> Result<Vec<u8>, error::Unspecified> {
+    let a = shifted_right_by_1(p_minus_1);
+    let b = shifted_right_by_1(q_minus_1);
+    let gcd = gcd_odd(&a, &b);
+    let lambda = mul(&divide_exact_odd(&a, &gcd), q_minus_1);
+    inverse_of_e(&lambda)
+}
+
+// Encodes the components as an unencrypted PKCS#8 v1 `PrivateKeyInfo`
+// containing a PKCS#1 `RSAPrivateKey`.
+fn encode_pkcs8(components: &[&[u8]]) -> Result<pkcs8::Document, error::Unspecified> {
+    const RSA_ENCRYPTION: &[u8] = include_bytes!("../data/alg-rsa-encryption.der");
+
+    let rsa_private_key = der_writer::write_all(der::Tag::Sequence, &|output| {
+        der_writer::write_tlv(output, der::Tag::Integer, |output| output.write_byte(0))?;
+        components.iter().try_for_each(|value| {
+            // Strip the leading zeros.
+            let leading_zeros = value.iter().take_while(|&&b| b == 0).count();
+            let value =
+                io::Positive::from_be_bytes(untrusted::Input::from(&value[leading_zeros..]))
+                    .map_err(|error::Unspecified| io::TooLongError::new())?;
+            der_writer::write_positive_integer(output, &value)
+        })
+    })
+    .map_err(|_: io::TooLongError| error::Unspecified)?;
+
+    let document = der_writer::write_all(der::Tag::Sequence, &|output| {
+        der_writer::write_tlv(output, der::Tag::Integer, |output| output.write_byte(0))?;
+        der_writer::write_tlv(output, der::Tag::Sequence, |output| {
+            output.write_bytes(RSA_ENCRYPTION)
+        })?;
+        der_writer::write_tlv(output, der::Tag::OctetString, |output| {
+            output.write_bytes(&rsa_private_key)
+        })
+    })
+    .map_err(|_: io::TooLongError| error::Unspecified)?;
+
+    Ok(pkcs8::Document::from_boxed(document))
+}
+
+// A small odd public modulus `d` with precomputed constants for Barrett
+// reduction.
+struct SmallModulus {
+    d: u64,
+    m: u64, // floor(2**BARRETT_SHIFT / d)
+}
+
+const BARRETT_SHIFT: u32 = 40;
+
+impl SmallModulus {
+    fn new(d: u32) -> Self {
+        debug_assert!(d > 2 && d < (1 << 17));
+        let d = u64::from(d);
+        Self {
+            d,
+            m: (1 << BARRETT_SHIFT) / d,
+        }
+    }
+
+    // Returns `x (mod d)` in constant time.
+    //
+    // Since `x < 2**BARRETT_SHIFT`, the estimated quotient is at most one less
+    // than the actual quotient, so at most one subtraction of `d` is needed.
+    // Since `x < d * 2**24`, `x * m` doesn't overflow.
+    fn reduce(&self, x: u64) -> u64 {
+        debug_assert!(x < (1 << BARRETT_SHIFT));
+        debug_assert!(x < (self.d << 24));
+        let q = (x * self.m) >> BARRETT_SHIFT;
+        let r = x - (q * self.d);
+        let r_minus_d = r.wrapping_sub(self.d);
+        // All ones if `r < d`, i.e. if `r - d` borrowed.
+        let mask = (r_minus_d >> 63).wrapping_neg();
+        (r & mask) | (r_minus_d & !mask)
+    }
+
+    // Returns `a (mod d)` for a big-endian value `a`.
+    fn reduce_bytes(&self, a: &[u8]) -> u32 {
+        let r = a
+            .iter()
+            .fold(0, |r, &b| self.reduce((r << 8) | u64::from(b)));
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            r as u32
+        }
+    }
+
+    // Returns `a**exponent (mod d)` for `a < d`, in time independent of `a`.
+    fn pow(&self, a: u32, exponent: u32) -> u32 {
+        let a = u64::from(a);
+        let mut acc = 1;
+        for i in (0..32).rev() {
+            acc = self.reduce(acc * acc);
+            let multiplied = self.reduce(acc * a);
+            // `exponent` is public.
+            if (exponent >> i) & 1 == 1 {
+                acc = multiplied;
+            }
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            acc as u32
+        }
+    }
+}
+
+// The odd primes less than 1024, for trial division.
+fn small_odd_primes() -> Vec<SmallModulus> {
+    (3..1024)
+        .step_by(2)
+        .filter(|&n| {
+            (3..n)
+                .step_by(2)
+                .take_while(|k| k * k <= n)
+                .all(|k| n % k != 0)
+        })
+        .map(SmallModulus::new)
+        .collect()
+}
+
+// Returns `a` with the bits in `bits` cleared from its least significant
+// byte.
+fn with_low_bits_cleared(a: &[u8], bits: u8) -> Vec<u8> {
+    let mut r = a.to_vec();
+    r[a.len() - 1] &= !bits;
+    r
+}
+
+// Returns `a >> 1`.
+fn shifted_right_by_1(a: &[u8]) -> Vec<u8> {
+    let mut r = vec![0u8; a.len()];
+    let mut carry = 0;
+    for (r, &a) in r.iter_mut().zip(a) {
+        *r = carry | (a >> 1);
+        carry = a << 7;
+    }
+    r
+}
+
+// Returns `a - b` for `a >= b` of the same length.
+fn sub(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let (r, borrow) = sub_with_borrow(a, b);
+    debug_assert_eq!(borrow, 0);
+    r
+}
+
+// Returns `a - b (mod 2**(8 * a.len()))` for `a` and `b` of the same length,
+// and a mask that is all ones if `a < b` or zero otherwise.
+fn sub_with_borrow(a: &[u8], b: &[u8]) -> (Vec<u8>, u8) {
+    debug_assert_eq!(a.len(), b.len());
+    let mut r = vec![0u8; a.len()];
+    let mut borrow = 0;
+    for ((r, &a), &b) in r.iter_mut().zip(a).zip(b).rev() {
+        let diff = i32::from(a) - i32::from(b) - borrow;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        {
+            *r = diff as u8;
+        }
+        borrow = (diff >> 8) & 1;
+    }
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let borrow = (borrow as u8).wrapping_neg();
+    (r, borrow)
+}
+
+// Returns `a * b`, with length `a.len() + b.len()`.
+fn mul(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut r = vec![0u8; a.len() + b.len()];
+    for (i, &a) in a.iter().enumerate().rev() {
+        let mut carry = 0u32;
+        for (j, &b) in b.iter().enumerate().rev() {
+            let r = &mut r[i + j + 1];
+            let t = u32::from(*r) + (u32::from(a) * u32::from(b)) + carry;
+            *r = t.to_le_bytes()[0];
+            carry = t >> 8;
+        }
+        r[i] = carry.to_le_bytes()[0];
+    }
+    r
+}
+
+// Returns `k * a + 1` for `k < 2**24`, with length `a.len() + 3`.
+fn mul_small_add_1(a: &[u8], k: u32) -> Vec<u8> {
+    let k = u64::from(k);
+    let mut r = vec![0u8; a.len() + 3];
+    let mut carry = 1u64;
+    for (r, &a) in r[3..].iter_mut().zip(a).rev() {
+        let t = (u64::from(a) * k) + carry;
+        *r = t.to_le_bytes()[0];
+        carry = t >> 8;
+    }
+    for r in r[..3].iter_mut().rev() {
+        *r = carry.to_le_bytes()[0];
+        carry >>= 8;
+    }
+    r
+}
+
+// Returns `a / d` for odd `d < 2**24` that divides `a` exactly.
+//
+// This uses exact division by multiplying by the inverse of `d` modulo 2**8,
+// one byte at a time starting with the least significant byte, which avoids
+// any data-dependent division.
+fn divide_exact(a: &[u8], d: u32) -> Vec<u8> {
+    let d = i64::from(d);
+
+    // Newton's method: each iteration doubles the number of correct bits.
+    let mut d_inv = d; // Correct to 3 bits since `d` is odd.
+    for _ in 0..2 {
+        d_inv = (d_inv * (2 - (d * d_inv))) & 0xff;
+    }
+    debug_assert_eq!((d * d_inv) & 0xff, 1);
+
+    let mut r = vec![0u8; a.len()];
+    let mut carry = 0i64;
+    for (r, &a) in r.iter_mut().zip(a).rev() {
+        let t = i64::from(a) - carry;
+        let q = ((t & 0xff) * d_inv) & 0xff;
+        *r = q.to_le_bytes()[0];
+        // `q * d - t` is a multiple of 2**8.
+        carry = ((q * d) - t) >> 8;
+    }
+    debug_assert_eq!(carry, 0);
+    r
+}
+
+// Returns `a / d` for odd `d` that divides `a` exactly, where `a` and `d` have
+// the same length.
+//
+// Like `divide_exact`, this multiplies by the inverse of `d`, here modulo
+// `2**(8 * a.len())`, which is computed with Newton's method.
+fn divide_exact_odd(a: &[u8], d: &[u8]) -> Vec<u8> {
+    debug_assert_eq!(a.len(), d.len());
+    debug_assert_eq!(d[d.len() - 1] & 1, 1);
+
+    let mut two = vec![0u8; d.len()];
+    two[d.len() - 1] = 2;
+
+    // Correct to 3 bits since `d` is odd.
+    let mut d_inv = d.to_vec();
+    let mut correct_bits = 3;
+    while correct_bits < d.len() * 8 {
+        let (t, _) = sub_with_borrow(&two, &mul_low(d, &d_inv));
+        d_inv = mul_low(&d_inv, &t);
+        correct_bits *= 2;
+    }
+
+    mul_low(a, &d_inv)
+}
+
+// Returns `a * b (mod 2**(8 * a.len()))` for `a` and `b` of the same length.
+fn mul_low(a: &[u8], b: &[u8]) -> Vec<u8> {
+    debug_assert_eq!(a.len(), b.len());
+    mul(a, b).split_off(a.len())
+}
+
+// Returns `GCD(a, b)` for odd `a` and `b` of the same length, using the binary
+// GCD algorithm in constant time.
+//
+// Each iteration reduces the sum of the bit lengths of `a` and `b` by at least
+// one until `b` is zero, after which the iterations don't change anything, so
+// `16 * a.len()` iterations always suffice.
+fn gcd_odd(a: &[u8], b: &[u8]) -> Vec<u8> {
+    debug_assert_eq!(a.len(), b.len());
+    debug_assert_eq!(a[a.len() - 1] & 1, 1);
+
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    for _ in 0..(16 * a.len()) {
+        // If `b` is odd then replace `(a, b)` with `(b, a - b)` if `b < a`,
+        // or with `(a, b - a)` otherwise. Either way `a` stays odd and `b`
+        // becomes even. Then halve `b`.
+        let b_is_odd = (b[b.len() - 1] & 1).wrapping_neg();
+        let (b_minus_a, b_less_than_a) = sub_with_borrow(&b, &a);
+        let (a_minus_b, _) = sub_with_borrow(&a, &b);
+        let swap = b_is_odd & b_less_than_a;
+        for (i, (a, b)) in a.iter_mut().zip(b.iter_mut()).enumerate() {
+            let new_b = select(b_less_than_a, a_minus_b[i], b_minus_a[i]);
+            let new_a = select(swap, *b, *a);
+            *b = select(b_is_odd, new_b, *b);
+            *a = new_a;
+        }
+        b = shifted_right_by_1(&b);
+    }
+    debug_assert!(b.iter().all(|&b| b == 0));
+    a
+}
+
+// Returns `a` if `mask` is all ones or `b` if `mask` is zero.
+fn select(mask: u8, a: u8, b: u8) -> u8 {
+    (a & mask) | (b & !mask)
+}
+
+fn less_than_vartime(a: &[u8], b: &[u8]) -> bool {
+    debug_assert_eq!(a.len(), b.len());
+    a < b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+
+    #[test]
+    fn test_small_modulus() {
+        for d in [3, 5, 1021, E] {
+            let m = SmallModulus::new(d);
+            let d = u64::from(d);
+            for x in [0, 1, d - 1, d, d + 1, (d * d) - 1, (d << 8) - 1, 123_456] {
+                assert_eq!(m.reduce(x), x % d);
+            }
+            let bytes = [0xff, 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde];
+            assert_eq!(
+                u64::from(m.reduce_bytes(&bytes)),
+                u64::from_be_bytes(bytes) % d
+            );
+        }
+        let e = SmallModulus::new(E);
+        assert_eq!(e.pow(2, E - 2) * 2 % E, 1);
+        assert_eq!(e.pow(12345, E - 2) * 12345 % E, 1);
+    }
+
+    #[test]
+    fn test_byte_arithmetic() {
+        let a = 0x1234_5678_9abc_def0u64.to_be_bytes();
+        let b = 0x0fed_cba9_8765_4321u64.to_be_bytes();
+        let product = u128::from(u64::from_be_bytes(a)) * u128::from(u64::from_be_bytes(b));
+        assert_eq!(&mul(&a, &b)[..], &product.to_be_bytes()[..]);
+
+        let difference = u64::from_be_bytes(a) - u64::from_be_bytes(b);
+        assert_eq!(&sub(&a, &b)[..], &difference.to_be_bytes()[..]);
+
+        assert_eq!(
+            &shifted_right_by_1(&a)[..],
+            &(u64::from_be_bytes(a) >> 1).to_be_bytes()[..]
+        );
+
+        let k = 0xfedcba;
+        let ka_plus_1 = mul_small_add_1(&a, k);
+        let expected = (u128::from(u64::from_be_bytes(a)) * u128::from(k)) + 1;
+        assert_eq!(&ka_plus_1[..], &expected.to_be_bytes()[16 - 11..]);
+
+        let quotient = divide_exact(&mul(&a, &E.to_be_bytes()), E);
+        assert_eq!(&quotient[4..], &a[..]);
+    }
+
+    #[test]
+    fn test_gcd_odd_and_divide_exact_odd() {
+        let a = (41u64 * 0x1234_5678_9abd).to_be_bytes();
+        let b = (41u64 * 0x0fed_cba9_8766).to_be_bytes();
+        let gcd = gcd_odd(&a, &b);
+        assert_eq!(u64::from_be_bytes(gcd.clone().try_into().unwrap()), 41);
+        assert_eq!(
+            &divide_exact_odd(&a, &gcd)[..],
+            &0x1234_5678_9abdu64.to_be_bytes()[..]
+        );
+
+        let one = 1u64.to_be_bytes();
+        assert_eq!(&gcd_odd(&one, &a)[..], &one[..]);
+        assert_eq!(&gcd_odd(&a, &[0; 8])[..], &a[..]);
+    }
+
+    #[test]
+    fn test_private_exponent() {
+        // `GCD(p - 1, q - 1) == 82`, so the result differs from
+        // `E**-1 (mod (p - 1) * (q - 1))`.
+        let p: u64 = 0xf000_0157;
+        let q: u64 = 0xf000_00b3;
+        let d = private_exponent(&(p - 1).to_be_bytes(), &(q - 1).to_be_bytes()).unwrap();
+        assert_eq!(&d[..], &0x0090_8e73_c9fa_44abu128.to_be_bytes()[..]);
+
+        let lambda = (p - 1) * (q - 1) / 82;
+        let d = u128::from_be_bytes(d.try_into().unwrap());
+        assert!(d < u128::from(lambda));
+        assert_eq!((d * u128::from(E)) % u128::from(lambda), 1);
+    }
+
+    #[test]
+    fn test_inverse_of_e() {
+        let a = 0x1234_5678_9abc_def0u64;
+        let d = inverse_of_e(&a.to_be_bytes()).unwrap();
+        let d = u64::from_be_bytes(d.try_into().unwrap());
+        assert_eq!((u128::from(d) * u128::from(E)) % u128::from(a), 1);
+
+        // `E` must not divide `a`.
+        let a = u64::from(E) * 2;
+        assert!(inverse_of_e(&a.to_be_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_is_probably_prime() {
+        let rng = crate::rand::SystemRandom::new();
+        let cpu_features = cpu::features();
+
+        test::run(
+            test_file!("keygen_is_probably_prime_tests.txt"),
+            |section, test_case| {
+                assert_eq!(section, "");
+                let w = test_case.consume_bytes("W");
+                let expected = test_case.consume_bool("Prime");
+                assert_eq!(is_probably_prime(&w, &rng, cpu_features).unwrap(), expected);
+                Ok(())
+            },
+        );
+    }
+}
@@ -13,7 +13,10 @@
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
 use super::{
-    padding::RsaEncoding, KeyPairComponents, PublicExponent, PublicKey, PublicKeyComponents, N,
+    keygen,
+    padding::{pkcs1_encryption, OaepAlgorithm, RsaEncoding},
+    KeyPairComponents, KeySize, PublicExponent, PublicKey, PublicKeyComponents, N,
+    PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN,
 };
 
 /// RSA PKCS#1 1.5 signatures.
@@ -34,12 +37,29 @@ pub struct KeyPair {
     p: PrivateCrtPrime<P>,
     q: PrivateCrtPrime<Q>,
     qInv: bigint::Elem<P, R>,
+    implicit_rejection_key: pkcs1_encryption::ImplicitRejectionKey,
     public: PublicKey,
 }
 
 derive_debug_via_field!(KeyPair, stringify!(RsaKeyPair), public);
 
 impl KeyPair {
+    /// Generates a new RSA key pair with public exponent 65537 and returns
+    /// the private key serialized as an unencrypted PKCS#8 document.
+    ///
+    /// The result can be parsed with `KeyPair::from_pkcs8()`, and is
+    /// compatible with the keys generated by the OpenSSL commands shown in
+    /// the documentation for that function.
+    ///
+    /// Generating a key pair takes a long time, especially for larger key
+    /// sizes, since it involves finding two random primes.
+    pub fn generate_pkcs8(
+        size: KeySize,
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<pkcs8::Document, error::Unspecified> {
+        keygen::generate_pkcs8(size, rng)
+    }
+
     /// Parses an unencrypted PKCS#8-encoded RSA private key.
     ///
     /// This will generate a 2048-bit RSA private key of the correct form using
@@ -338,6 +358,7 @@ impl KeyPair {
         // First, validate `2**half_n_bits < d`. Since 2**half_n_bits has a bit
         // length of half_n_bits + 1, this check gives us 2**half_n_bits <= d,
         // and knowing d is odd makes the inequality strict.
+        let d_bytes = d;
         let d = bigint::OwnedModulus::<D>::from_be_bytes(d)
             .map_err(|_| error::KeyRejected::invalid_component())?;
         if !(n_bits.half_rounded_up() < d.len_bits()) {
@@ -374,10 +395,17 @@ impl KeyPair {
         let p = PrivateCrtPrime::new(p, dP, cpu_features)?;
         let q = PrivateCrtPrime::new(q, dQ, cpu_features)?;
 
+        let implicit_rejection_key = pkcs1_encryption::ImplicitRejectionKey::new(
+            d_bytes.as_slice_less_safe(),
+            public_key.modulus_len(),
+        )
+        .map_err(|error::Unspecified| KeyRejected::unexpected_error())?;
+
         Ok(Self {
             p,
             q,
             qInv,
+            implicit_rejection_key,
             public: public_key,
         })
     }
@@ -520,13 +548,32 @@ impl KeyPair {
     /// Many other crypto libraries have signing functions that takes a
     /// precomputed digest as input, instead of the message to digest. This
     /// function does *not* take a precomputed digest; instead, `sign`
-    /// calculates the digest itself.
+    /// calculates the digest itself. Use `sign_digest` to sign a precomputed
+    /// digest.
     pub fn sign(
         &self,
         padding_alg: &'static dyn RsaEncoding,
         rng: &dyn rand::SecureRandom,
         msg: &[u8],
         signature: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        let m_hash = digest::digest(padding_alg.digest_alg(), msg);
+        self.sign_digest(padding_alg, rng, m_hash, signature)
+    }
+
+    /// Computes the signature of the message that was digested to `m_hash`
+    /// and writes it into `signature`.
+    ///
+    /// This is like `sign` except the message was already digested, e.g. by
+    /// a streaming pipeline or a remote client. `m_hash` must have been
+    /// computed using the digest algorithm from `padding_alg`; otherwise an
+    /// error will be returned.
+    pub fn sign_digest(
+        &self,
+        padding_alg: &'static dyn RsaEncoding,
+        rng: &dyn rand::SecureRandom,
+        m_hash: digest::Digest,
+        signature: &mut [u8],
     ) -> Result<(), error::Unspecified> {
         let cpu_features = cpu::features();
 
@@ -534,7 +581,9 @@ impl KeyPair {
             return Err(error::Unspecified);
         }
 
-        let m_hash = digest::digest(padding_alg.digest_alg(), msg);
+        if m_hash.algorithm() != padding_alg.digest_alg() {
+            return Err(error::Unspecified);
+        }
 
         // Use the output buffer as the scratch space for the signature to
         // reduce the required stack space.
@@ -552,6 +601,111 @@ impl KeyPair {
         Ok(())
     }
 
+    /// Decrypts `ciphertext`, which was encrypted using RSA-OAEP with the
+    /// given `label`, and returns the plaintext.
+    ///
+    /// The plaintext is written into the beginning of `plaintext`, which must
+    /// be at least `oaep_alg.max_plaintext_len(self.public().modulus_len())`
+    /// bytes long. `ciphertext`'s length must be exactly
+    /// `self.public().modulus_len()`.
+    ///
+    /// The decryption and the decoding of the padding are done in constant
+    /// time, and the error doesn't indicate which check failed.
+    ///
+    /// See [RFC 8017 Section 7.1.2].
+    ///
+    /// [RFC 8017 Section 7.1.2]: https://tools.ietf.org/html/rfc8017#section-7.1.2
+    pub fn decrypt_oaep<'p>(
+        &self,
+        oaep_alg: &'static OaepAlgorithm,
+        label: &[u8],
+        ciphertext: &[u8],
+        plaintext: &'p mut [u8],
+    ) -> Result<&'p [u8], error::Unspecified> {
+        let cpu_features = cpu::features();
+
+        // Step 1.b.
+        if ciphertext.len() != self.public().modulus_len() {
+            return Err(error::Unspecified);
+        }
+
+        // Steps 2.a and 2.b: RSADP.
+        let m = self.private_exponentiate(ciphertext, cpu_features)?;
+
+        // Step 2.c.
+        let mut em = [0u8; PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN];
+        let em = &mut em[..ciphertext.len()];
+        m.fill_be_bytes(em);
+
+        // Step 3.
+        let msg = oaep_alg.decode(label, em)?;
+        let msg = &em[msg];
+
+        // Step 4.
+        let plaintext = plaintext.get_mut(..msg.len()).ok_or(error::Unspecified)?;
+        plaintext.copy_from_slice(msg);
+        Ok(plaintext)
+    }
+
+    /// Decrypts `ciphertext`, which was encrypted using RSAES-PKCS1-v1_5,
+    /// using the implicit rejection method, and returns the plaintext.
+    ///
+    /// The plaintext is written into the beginning of `plaintext`, which must
+    /// be at least `self.public().modulus_len() - 11` bytes long.
+    /// `ciphertext`'s length must be exactly `self.public().modulus_len()`.
+    ///
+    /// When the padding is invalid, no error is returned. Instead, a synthetic
+    /// plaintext that is derived deterministically from the private key and
+    /// `ciphertext` is returned, in constant time, as described in
+    /// [draft-irtf-cfrg-rsa-guidance]. This prevents Bleichenbacher-style
+    /// padding oracle attacks, but it means the protocol using this must
+    /// detect incorrect plaintexts some other way, e.g. because a key derived
+    /// from the plaintext fails to authenticate subsequent messages. An error
+    /// is returned only for errors that don't depend on the plaintext, such
+    /// as `ciphertext` having the wrong length or not being less than the
+    /// public modulus.
+    ///
+    /// New protocols should use RSA-OAEP ([`Self::decrypt_oaep`]) instead.
+    ///
+    /// See [RFC 8017 Section 7.2.2].
+    ///
+    /// [draft-irtf-cfrg-rsa-guidance]:
+    ///     https://datatracker.ietf.org/doc/draft-irtf-cfrg-rsa-guidance/
+    /// [RFC 8017 Section 7.2.2]: https://tools.ietf.org/html/rfc8017#section-7.2.2
+    pub fn decrypt_pkcs1_implicit_rejection<'p>(
+        &self,
+        ciphertext: &[u8],
+        plaintext: &'p mut [u8],
+    ) -> Result<&'p [u8], error::Unspecified> {
+        let cpu_features = cpu::features();
+
+        // Step 1.
+        if ciphertext.len() != self.public().modulus_len() {
+            return Err(error::Unspecified);
+        }
+
+        // Steps 2.a and 2.b: RSADP.
+        let m = self.private_exponentiate(ciphertext, cpu_features)?;
+
+        // Step 2.c.
+        let mut em = [0u8; PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN];
+        let em = &mut em[..ciphertext.len()];
+        m.fill_be_bytes(em);
+
+        // Step 3.
+        let msg = pkcs1_encryption::decode_with_implicit_rejection(
+            &self.implicit_rejection_key,
+            ciphertext,
+            em,
+        )?;
+        let msg = &em[msg];
+
+        // Step 4.
+        let plaintext = plaintext.get_mut(..msg.len()).ok_or(error::Unspecified)?;
+        plaintext.copy_from_slice(msg);
+        Ok(plaintext)
+    }
+
     /// Returns base**d (mod n).
     ///
     /// This does not return or write any intermediate results into any buffers
@@ -559,7 +713,7 @@ impl KeyPair {
     /// leaked that would endanger the private key.
     ///
     /// Panics if `in_out` is not `self.public().modulus_len()`.
-    fn private_exponentiate(
+    pub(super) fn private_exponentiate(
         &self,
         base: &[u8],
         cpu_features: cpu::Features,
@@ -14,12 +14,15 @@
 
 use crate::{bits, digest, error, rand};
 
+mod oaep;
 mod pkcs1;
+pub(super) mod pkcs1_encryption;
 mod pss;
 
 pub use self::{
+    oaep::{OaepAlgorithm, RSA_OAEP_SHA256, RSA_OAEP_SHA384, RSA_OAEP_SHA512},
     pkcs1::{RSA_PKCS1_SHA256, RSA_PKCS1_SHA384, RSA_PKCS1_SHA512},
-    pss::{RSA_PSS_SHA256, RSA_PSS_SHA384, RSA_PSS_SHA512},
+    pss::{PssSaltLength, PSS, RSA_PSS_SHA256, RSA_PSS_SHA384, RSA_PSS_SHA512},
 };
 pub(super) use pkcs1::RSA_PKCS1_SHA1_FOR_LEGACY_USE_ONLY;
 
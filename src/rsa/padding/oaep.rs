@@ -0,0 +1,243 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::mgf1;
+use crate::{
+    digest, error,
+    limb::{self, Limb, LimbMask},
+    rand,
+};
+
+/// RSA OAEP encryption padding as described in [RFC 8017 Section 7.1].
+///
+/// The same digest algorithm is used for hashing the label and for MGF1.
+///
+/// [RFC 8017 Section 7.1]: https://tools.ietf.org/html/rfc8017#section-7.1
+#[derive(Debug)]
+pub struct OaepAlgorithm {
+    digest_alg: &'static digest::Algorithm,
+}
+
+impl OaepAlgorithm {
+    /// The digest algorithm used for hashing the label and for MGF1.
+    #[inline]
+    pub fn digest_algorithm(&self) -> &'static digest::Algorithm {
+        self.digest_alg
+    }
+
+    /// The maximum length of a plaintext that can be encrypted with a public
+    /// modulus that is `modulus_len` bytes long.
+    pub fn max_plaintext_len(&self, modulus_len: usize) -> Option<usize> {
+        modulus_len.checked_sub(2 * self.digest_alg.output_len() + 2)
+    }
+
+    // RFC 8017 Section 7.1.1, Step 2: EME-OAEP encoding.
+    //
+    // `em` is the big-endian-encoded value of `m` from the specification,
+    // padded to `k` bytes, where `k` is the length in bytes of the public
+    // modulus.
+    pub(in crate::rsa) fn encode(
+        &self,
+        label: &[u8],
+        msg: &[u8],
+        em: &mut [u8],
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<(), error::Unspecified> {
+        let h_len = self.digest_alg.output_len();
+
+        // Step 1.b. (Step 1.a is unnecessary as the length of the label is
+        // always less than the input limitation for the hash function.)
+        let max_msg_len = self.max_plaintext_len(em.len()).ok_or(error::Unspecified)?;
+        if msg.len() > max_msg_len {
+            return Err(error::Unspecified);
+        }
+
+        let (y, em) = em.split_at_mut(1);
+        let (seed, db) = em.split_at_mut(h_len);
+
+        // Step 2.a - 2.c: DB = lHash || PS || 0x01 || M.
+        let l_hash = digest::digest(self.digest_alg, label);
+        let (db_l_hash, rest) = db.split_at_mut(h_len);
+        db_l_hash.copy_from_slice(l_hash.as_ref());
+        let (ps, rest) = rest.split_at_mut(rest.len() - msg.len() - 1);
+        ps.fill(0);
+        rest[0] = 0x01;
+        rest[1..].copy_from_slice(msg);
+
+        // Step 2.d.
+        rng.fill(seed)?;
+
+        // Steps 2.e and 2.f.
+        mgf1(self.digest_alg, seed, db);
+
+        // Steps 2.g and 2.h.
+        mgf1(self.digest_alg, db, seed);
+
+        // Step 2.i.
+        y[0] = 0;
+
+        Ok(())
+    }
+
+    // RFC 8017 Section 7.1.2, Step 3: EME-OAEP decoding.
+    //
+    // `em` is the big-endian-encoded value of `m`, padded to `k` bytes. On
+    // success, returns the range of `em` that contains the message.
+    //
+    // This is constant-time with respect to the contents of `em` (only); in
+    // particular, the reason for any failure isn't revealed, as required to
+    // prevent the attacks in "A Chosen Ciphertext Attack on RSA Optimal
+    // Asymmetric Encryption Padding (OAEP) as Standardized in PKCS #1 v2.0" by
+    // James Manger.
+    pub(in crate::rsa) fn decode(
+        &self,
+        label: &[u8],
+        em: &mut [u8],
+    ) -> Result<core::ops::Range<usize>, error::Unspecified> {
+        let h_len = self.digest_alg.output_len();
+
+        // Step 1.c.
+        if em.len() < (2 * h_len) + 2 {
+            return Err(error::Unspecified);
+        }
+
+        // Step 3.a.
+        let l_hash = digest::digest(self.digest_alg, label);
+
+        // Step 3.b.
+        let (y, em_rest) = em.split_at_mut(1);
+        let (seed, db) = em_rest.split_at_mut(h_len);
+
+        // Steps 3.c and 3.d.
+        mgf1(self.digest_alg, db, seed);
+
+        // Steps 3.e and 3.f.
+        mgf1(self.digest_alg, seed, db);
+
+        // Step 3.g.
+        let (db_l_hash, rest) = db.split_at(h_len);
+
+        let l_hash_diff = db_l_hash
+            .iter()
+            .zip(l_hash.as_ref())
+            .fold(0, |acc, (a, b)| acc | (a ^ b));
+        let mut good = is_zero(y[0]) & is_zero(l_hash_diff);
+
+        // Find the 0x01 separator after the zero padding, without revealing
+        // its position.
+        let mut looking_for_separator = LimbMask::True as Limb;
+        let mut separator_index = 0;
+        for (i, &b) in rest.iter().enumerate() {
+            let is_separator = looking_for_separator & is_one(b);
+            separator_index |= is_separator & (i as Limb);
+            good &= !(looking_for_separator & !is_zero(b) & !is_one(b));
+            looking_for_separator &= !is_separator;
+        }
+        good &= !looking_for_separator;
+
+        if good != LimbMask::True as Limb {
+            return Err(error::Unspecified);
+        }
+
+        // The length of the message isn't secret once the padding has been
+        // found to be valid.
+        #[allow(clippy::cast_possible_truncation)]
+        let msg_start = 1 + (2 * h_len) + (separator_index as usize) + 1;
+        Ok(msg_start..em.len())
+    }
+}
+
+impl crate::sealed::Sealed for OaepAlgorithm {}
+
+fn is_zero(b: u8) -> Limb {
+    limb::limbs_are_zero_constant_time(&[Limb::from(b)]) as Limb
+}
+
+fn is_one(b: u8) -> Limb {
+    limb::limbs_equal_limb_constant_time(&[Limb::from(b)], 1) as Limb
+}
+
+macro_rules! rsa_oaep_algorithm {
+    ( $ALGORITHM:ident, $digest_alg:expr, $doc_str:expr ) => {
+        #[doc=$doc_str]
+        pub static $ALGORITHM: OaepAlgorithm = OaepAlgorithm {
+            digest_alg: $digest_alg,
+        };
+    };
+}
+
+rsa_oaep_algorithm!(
+    RSA_OAEP_SHA256,
+    &digest::SHA256,
+    "OAEP encryption padding using SHA-256 for the label hash and MGF1."
+);
+rsa_oaep_algorithm!(
+    RSA_OAEP_SHA384,
+    &digest::SHA384,
+    "OAEP encryption padding using SHA-384 for the label hash and MGF1."
+);
+rsa_oaep_algorithm!(
+    RSA_OAEP_SHA512,
+    &digest::SHA512,
+    "OAEP encryption padding using SHA-512 for the label hash and MGF1."
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+    use alloc::vec;
+
+    #[test]
+    fn test_oaep_encode_decode() {
+        let rng = crate::rand::SystemRandom::new();
+        for alg in [&RSA_OAEP_SHA256, &RSA_OAEP_SHA384, &RSA_OAEP_SHA512] {
+            for k in [256, 384, 512] {
+                let max_len = alg.max_plaintext_len(k).unwrap();
+                for msg_len in [0, 1, max_len] {
+                    let msg = vec![0x01u8; msg_len];
+                    let mut em = vec![0u8; k];
+                    alg.encode(b"label", &msg, &mut em, &rng).unwrap();
+                    assert_eq!(em[0], 0);
+
+                    let mut decoded = em.clone();
+                    let range = alg.decode(b"label", &mut decoded).unwrap();
+                    assert_eq!(&decoded[range], &msg[..]);
+
+                    let mut decoded = em.clone();
+                    assert!(alg.decode(b"lab3l", &mut decoded).is_err());
+
+                    for i in [0, 1, k - 1] {
+                        let mut decoded = em.clone();
+                        decoded[i] ^= 1;
+                        assert!(alg.decode(b"label", &mut decoded).is_err());
+                    }
+                }
+
+                let msg = vec![0u8; max_len + 1];
+                let mut em = vec![0u8; k];
+                assert!(alg.encode(b"", &msg, &mut em, &rng).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_oaep_encode_too_short() {
+        let rng = test::rand::FixedByteRandom { byte: 0 };
+        let mut em = [0u8; (2 * 64) + 1];
+        assert_eq!(RSA_OAEP_SHA512.max_plaintext_len(em.len()), None);
+        assert!(RSA_OAEP_SHA512.encode(b"", b"", &mut em, &rng).is_err());
+        assert!(RSA_OAEP_SHA512.decode(b"", &mut em).is_err());
+    }
+}
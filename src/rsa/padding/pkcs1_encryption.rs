@@ -0,0 +1,286 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! RSAES-PKCS1-v1_5 encryption padding ([RFC 8017 Section 7.2]), with
+//! decryption using the implicit rejection method of
+//! [draft-irtf-cfrg-rsa-guidance].
+//!
+//! [RFC 8017 Section 7.2]: https://tools.ietf.org/html/rfc8017#section-7.2
+//! [draft-irtf-cfrg-rsa-guidance]:
+//!     https://datatracker.ietf.org/doc/draft-irtf-cfrg-rsa-guidance/
+
+use super::super::PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN;
+use crate::{
+    digest, error, hmac,
+    limb::{self, Limb, LimbMask},
+    rand,
+};
+use core::ops::Range;
+
+// The minimum length of the random nonzero padding string PS.
+const MIN_PS_LEN: usize = 8;
+
+// The number of 16-bit candidate synthetic message lengths.
+const MAX_LEN_GEN_TRIES: usize = 128;
+
+/// The maximum length of a plaintext that can be encrypted with a public
+/// modulus that is `modulus_len` bytes long.
+pub(in crate::rsa) fn max_plaintext_len(modulus_len: usize) -> Option<usize> {
+    modulus_len.checked_sub(3 + MIN_PS_LEN)
+}
+
+// RFC 8017 Section 7.2.1, Step 2: EME-PKCS1-v1_5 encoding.
+//
+// `em` is the big-endian-encoded value of `m` from the specification, padded
+// to `k` bytes, where `k` is the length in bytes of the public modulus.
+pub(in crate::rsa) fn encode(
+    msg: &[u8],
+    em: &mut [u8],
+    rng: &dyn rand::SecureRandom,
+) -> Result<(), error::Unspecified> {
+    // Step 1.
+    let max_msg_len = max_plaintext_len(em.len()).ok_or(error::Unspecified)?;
+    if msg.len() > max_msg_len {
+        return Err(error::Unspecified);
+    }
+
+    // Step 2.b: EM = 0x00 || 0x02 || PS || 0x00 || M.
+    let (header, rest) = em.split_at_mut(2);
+    header[0] = 0x00;
+    header[1] = 0x02;
+    let (ps, rest) = rest.split_at_mut(rest.len() - msg.len() - 1);
+    rest[0] = 0x00;
+    rest[1..].copy_from_slice(msg);
+
+    // Step 2.a: PS consists of pseudo-randomly generated nonzero octets.
+    rng.fill(ps)?;
+    for b in ps.iter_mut() {
+        let mut attempts = 0..100;
+        while *b == 0 {
+            if attempts.next().is_none() {
+                return Err(error::Unspecified);
+            }
+            rng.fill(core::slice::from_mut(b))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The key from which the per-ciphertext key derivation keys of the implicit
+/// rejection method are derived.
+pub(in crate::rsa) struct ImplicitRejectionKey(hmac::Key);
+
+impl ImplicitRejectionKey {
+    // `d` is the big-endian-encoded private exponent, which must be less than
+    // the public modulus, which is `modulus_len` bytes long.
+    pub(in crate::rsa) fn new(d: &[u8], modulus_len: usize) -> Result<Self, error::Unspecified> {
+        let d = match d.iter().position(|&b| b != 0) {
+            Some(start) => &d[start..],
+            None => &[],
+        };
+        let mut d_padded = [0u8; PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN];
+        let d_padded = d_padded.get_mut(..modulus_len).ok_or(error::Unspecified)?;
+        let leading_zeros = modulus_len.checked_sub(d.len()).ok_or(error::Unspecified)?;
+        d_padded[leading_zeros..].copy_from_slice(d);
+
+        let d_hash = digest::digest(&digest::SHA256, d_padded);
+        Ok(Self(hmac::Key::new(hmac::HMAC_SHA256, d_hash.as_ref())))
+    }
+}
+
+// EME-PKCS1-v1_5 decoding with implicit rejection.
+//
+// `ciphertext` is the ciphertext that was decrypted and `em` is the
+// big-endian-encoded result of decrypting it, padded to `k` bytes. Returns the
+// range of `em` that contains the message.
+//
+// When the padding is invalid, `em` is overwritten with a synthetic message
+// that is derived deterministically from `key` and `ciphertext`, and the
+// returned range selects that synthetic message, so that callers can't
+// distinguish valid padding from invalid padding. This is constant-time with
+// respect to the contents of `em`, except for the length of the returned
+// range.
+pub(in crate::rsa) fn decode_with_implicit_rejection(
+    key: &ImplicitRejectionKey,
+    ciphertext: &[u8],
+    em: &mut [u8],
+) -> Result<Range<usize>, error::Unspecified> {
+    let k = em.len();
+    if ciphertext.len() != k || k > PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN {
+        return Err(error::Unspecified);
+    }
+    let max_sep_offset = max_plaintext_len(k).ok_or(error::Unspecified)? + 1;
+
+    // Derive the key derivation key for this ciphertext.
+    let kdk = hmac::sign(&key.0, ciphertext);
+    let kdk = hmac::Key::new(hmac::HMAC_SHA256, kdk.as_ref());
+
+    // Derive the synthetic message and its length.
+    let mut synthetic = [0u8; PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN];
+    let synthetic = &mut synthetic[..k];
+    prf(&kdk, b"message", synthetic);
+
+    let mut candidate_lengths = [0u8; 2 * MAX_LEN_GEN_TRIES];
+    prf(&kdk, b"length", &mut candidate_lengths);
+
+    let len_mask = {
+        let mut mask = max_sep_offset;
+        mask |= mask >> 1;
+        mask |= mask >> 2;
+        mask |= mask >> 4;
+        mask |= mask >> 8;
+        mask as Limb
+    };
+    let mut synthetic_len = 0;
+    for candidate in candidate_lengths.chunks_exact(2) {
+        let candidate = Limb::from(u16::from_be_bytes([candidate[0], candidate[1]])) & len_mask;
+        let is_valid =
+            limb::limbs_less_than_limbs_consttime(&[candidate], &[max_sep_offset as Limb]) as Limb;
+        synthetic_len = select(is_valid, candidate, synthetic_len);
+    }
+    let synthetic_msg_index = (k as Limb) - synthetic_len;
+
+    // Check the padding: EM = 0x00 || 0x02 || PS || 0x00 || M, where PS is at
+    // least eight nonzero bytes long.
+    let mut good =
+        is_zero(em[0]) & limb::limbs_equal_limb_constant_time(&[Limb::from(em[1])], 2) as Limb;
+
+    let mut looking_for_separator = LimbMask::True as Limb;
+    let mut separator_index = 0;
+    for (i, &b) in em.iter().enumerate().skip(2) {
+        let is_separator = looking_for_separator & is_zero(b);
+        separator_index = select(is_separator, i as Limb, separator_index);
+        looking_for_separator &= !is_separator;
+    }
+    good &= !looking_for_separator;
+    good &= !(limb::limbs_less_than_limbs_consttime(&[separator_index], &[(2 + MIN_PS_LEN) as Limb])
+        as Limb);
+
+    // Select the real message or the synthetic one.
+    for (em, synthetic) in em.iter_mut().zip(synthetic.iter()) {
+        #[allow(clippy::cast_possible_truncation)]
+        let selected = select(good, Limb::from(*em), Limb::from(*synthetic)) as u8;
+        *em = selected;
+    }
+    let msg_index = select(good, separator_index + 1, synthetic_msg_index);
+
+    // The length of the (real or synthetic) message isn't secret.
+    #[allow(clippy::cast_possible_truncation)]
+    let msg_index = msg_index as usize;
+    Ok(msg_index..k)
+}
+
+// The pseudo-random function of the implicit rejection method: HMAC-SHA256 in
+// counter mode, where each block is HMAC(key, I || label || bit_length) and
+// both `I` and `bit_length` are 16-bit big-endian integers.
+fn prf(key: &hmac::Key, label: &[u8], out: &mut [u8]) {
+    #[allow(clippy::cast_possible_truncation)]
+    let bit_len = ((out.len() * 8) as u16).to_be_bytes();
+    for (i, out) in out.chunks_mut(digest::SHA256_OUTPUT_LEN).enumerate() {
+        #[allow(clippy::cast_possible_truncation)]
+        let i = (i as u16).to_be_bytes();
+        let mut ctx = hmac::Context::with_key(key);
+        ctx.update(&i);
+        ctx.update(label);
+        ctx.update(&bit_len);
+        let block = ctx.sign();
+        out.copy_from_slice(&block.as_ref()[..out.len()]);
+    }
+}
+
+fn is_zero(b: u8) -> Limb {
+    limb::limbs_are_zero_constant_time(&[Limb::from(b)]) as Limb
+}
+
+fn select(mask: Limb, a: Limb, b: Limb) -> Limb {
+    (a & mask) | (b & !mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+    use alloc::vec;
+
+    #[test]
+    fn test_pkcs1_encryption_encode_decode() {
+        let rng = crate::rand::SystemRandom::new();
+        let key = ImplicitRejectionKey::new(&[0x12; 200], 256).unwrap();
+        for k in [256, 384, 512] {
+            let max_len = max_plaintext_len(k).unwrap();
+            let ciphertext = vec![0x55u8; k];
+            for msg_len in [0, 1, max_len] {
+                let msg = vec![0u8; msg_len];
+                let mut em = vec![0u8; k];
+                encode(&msg, &mut em, &rng).unwrap();
+                assert_eq!(&em[..2], &[0x00, 0x02]);
+                assert!(em[2..(k - msg_len - 1)].iter().all(|&b| b != 0));
+
+                let mut decoded = em.clone();
+                let range =
+                    decode_with_implicit_rejection(&key, &ciphertext, &mut decoded).unwrap();
+                assert_eq!(&decoded[range], &msg[..]);
+
+                // Invalid padding results in the same synthetic message every
+                // time for the same ciphertext.
+                let mut synthetic = em.clone();
+                synthetic[1] = 0x01;
+                let range =
+                    decode_with_implicit_rejection(&key, &ciphertext, &mut synthetic).unwrap();
+                assert!(range.len() <= max_len);
+                let mut synthetic2 = em.clone();
+                synthetic2[0] = 0x01;
+                let range2 =
+                    decode_with_implicit_rejection(&key, &ciphertext, &mut synthetic2).unwrap();
+                assert_eq!(&synthetic[range], &synthetic2[range2]);
+            }
+
+            let msg = vec![0u8; max_len + 1];
+            let mut em = vec![0u8; k];
+            assert!(encode(&msg, &mut em, &rng).is_err());
+
+            // An RNG that only produces zeros doesn't cause an endless loop.
+            let zero_rng = test::rand::FixedByteRandom { byte: 0 };
+            let mut em = vec![0u8; k];
+            assert!(encode(&[], &mut em, &zero_rng).is_err());
+        }
+    }
+
+    #[test]
+    fn test_pkcs1_encryption_short_padding_rejected() {
+        let key = ImplicitRejectionKey::new(&[0x12; 200], 256).unwrap();
+        let ciphertext = [0x55u8; 256];
+
+        // Seven bytes of nonzero padding isn't enough.
+        let mut em = [0xffu8; 256];
+        em[0] = 0x00;
+        em[1] = 0x02;
+        em[2 + 7] = 0x00;
+        let mut synthetic = [0xffu8; 256];
+        synthetic[0] = 0x01;
+        let range = decode_with_implicit_rejection(&key, &ciphertext, &mut em).unwrap();
+        let synthetic_range =
+            decode_with_implicit_rejection(&key, &ciphertext, &mut synthetic).unwrap();
+        assert_eq!(&em[range], &synthetic[synthetic_range]);
+
+        // Eight bytes is.
+        let mut em = [0xffu8; 256];
+        em[0] = 0x00;
+        em[1] = 0x02;
+        em[2 + 8] = 0x00;
+        let range = decode_with_implicit_rejection(&key, &ciphertext, &mut em).unwrap();
+        assert_eq!(range, (2 + 8 + 1)..256);
+    }
+}
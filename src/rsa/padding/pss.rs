@@ -18,13 +18,77 @@ use crate::{bits, digest, error, rand};
 /// RSA PSS padding as described in [RFC 3447 Section 8.1].
 ///
 /// See "`RSA_PSS_*` Details\" in `ring::signature`'s module-level
-/// documentation for more details.
+/// documentation for more details. Padding with other parameters can be
+/// constructed with `PSS::new()`.
 ///
 /// [RFC 3447 Section 8.1]: https://tools.ietf.org/html/rfc3447#section-8.1
 #[allow(clippy::upper_case_acronyms)] // TODO: Until we implement cargo-semver-checks
 #[derive(Debug)]
 pub struct PSS {
     digest_alg: &'static digest::Algorithm,
+    mgf1_digest_alg: &'static digest::Algorithm,
+    salt_len: PssSaltLength,
+}
+
+/// The length of the salt in RSA PSS padding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PssSaltLength {
+    /// The salt is the same length as the message digest. This is what the
+    /// `RSA_PSS_*` algorithms use.
+    DigestLength,
+
+    /// The salt is exactly the given number of bytes long.
+    Exact(usize),
+
+    /// The salt is as long as the public modulus allows: the length of the
+    /// encoded message minus the length of the message digest minus 2.
+    Maximum,
+
+    /// During verification, accept any salt length; the salt length is
+    /// recovered from the encoded message. During signing, this is the same
+    /// as `Maximum`.
+    Auto,
+}
+
+impl PSS {
+    /// Constructs RSA PSS padding using `digest_alg` to digest the message,
+    /// `mgf1_digest_alg` for MGF1, and a salt of length `salt_len`.
+    ///
+    /// Since the signing and verification APIs take `&'static` padding
+    /// algorithms, this is usually used to initialize a `static`:
+    ///
+    /// ```
+    /// use ring::{digest, signature};
+    ///
+    /// static RSA_PSS_SHA256_NO_SALT: signature::PSS = signature::PSS::new(
+    ///     &digest::SHA256,
+    ///     &digest::SHA256,
+    ///     signature::PssSaltLength::Exact(0),
+    /// );
+    /// ```
+    pub const fn new(
+        digest_alg: &'static digest::Algorithm,
+        mgf1_digest_alg: &'static digest::Algorithm,
+        salt_len: PssSaltLength,
+    ) -> Self {
+        Self {
+            digest_alg,
+            mgf1_digest_alg,
+            salt_len,
+        }
+    }
+
+    // Returns the salt length, or `None` if it is to be recovered from the
+    // encoded message.
+    fn s_len(&self, mod_bits: bits::BitLength) -> Result<Option<usize>, error::Unspecified> {
+        let s_len = match self.salt_len {
+            PssSaltLength::DigestLength => self.digest_alg.output_len(),
+            PssSaltLength::Exact(s_len) => s_len,
+            PssSaltLength::Maximum => max_s_len(self.digest_alg, mod_bits)?,
+            PssSaltLength::Auto => return Ok(None),
+        };
+        Ok(Some(s_len))
+    }
 }
 
 impl crate::sealed::Sealed for PSS {}
@@ -45,57 +109,19 @@ impl RsaEncoding for PSS {
         mod_bits: bits::BitLength,
         rng: &dyn rand::SecureRandom,
     ) -> Result<(), error::Unspecified> {
-        let metrics = PSSMetrics::new(self.digest_alg, mod_bits)?;
-
-        // The `m_out` this function fills is the big-endian-encoded value of `m`
-        // from the specification, padded to `k` bytes, where `k` is the length
-        // in bytes of the public modulus. The spec says "Note that emLen will
-        // be one less than k if modBits - 1 is divisible by 8 and equal to k
-        // otherwise." In other words we might need to prefix `em` with a
-        // leading zero byte to form a correct value of `m`.
-        let em = if metrics.top_byte_mask == 0xff {
-            m_out[0] = 0;
-            &mut m_out[1..]
-        } else {
-            m_out
-        };
-        assert_eq!(em.len(), metrics.em_len);
-
-        // Steps 1 and 2 are done by the caller to produce `m_hash`.
-
-        // Step 3 is done by `PSSMetrics::new()` above.
-
-        let (db, digest_terminator) = em.split_at_mut(metrics.db_len);
-
-        let separator_pos = db.len() - 1 - metrics.s_len;
-
-        // Step 4.
-        let salt: &[u8] = {
-            let salt = &mut db[(separator_pos + 1)..];
-            rng.fill(salt)?; // salt
-            salt
+        let s_len = match self.s_len(mod_bits)? {
+            Some(s_len) => s_len,
+            None => max_s_len(self.digest_alg, mod_bits)?,
         };
-
-        // Steps 5 and 6.
-        let h = pss_digest(self.digest_alg, m_hash, salt);
-
-        // Step 7.
-        db[..separator_pos].fill(0); // ps
-
-        // Step 8.
-        db[separator_pos] = 0x01;
-
-        // Steps 9 and 10.
-        mgf1(self.digest_alg, h.as_ref(), db);
-
-        // Step 11.
-        db[0] &= metrics.top_byte_mask;
-
-        // Step 12.
-        digest_terminator[..metrics.h_len].copy_from_slice(h.as_ref());
-        digest_terminator[metrics.h_len] = 0xbc;
-
-        Ok(())
+        encode(
+            self.digest_alg,
+            self.mgf1_digest_alg,
+            s_len,
+            m_hash,
+            m_out,
+            mod_bits,
+            rng,
+        )
     }
 }
 
@@ -108,86 +134,192 @@ impl Verification for PSS {
         m: &mut untrusted::Reader,
         mod_bits: bits::BitLength,
     ) -> Result<(), error::Unspecified> {
-        let metrics = PSSMetrics::new(self.digest_alg, mod_bits)?;
-
-        // RSASSA-PSS-VERIFY Step 2(c). The `m` this function is given is the
-        // big-endian-encoded value of `m` from the specification, padded to
-        // `k` bytes, where `k` is the length in bytes of the public modulus.
-        // The spec. says "Note that emLen will be one less than k if
-        // modBits - 1 is divisible by 8 and equal to k otherwise," where `k`
-        // is the length in octets of the RSA public modulus `n`. In other
-        // words, `em` might have an extra leading zero byte that we need to
-        // strip before we start the PSS decoding steps which is an artifact of
-        // the `Verification` interface.
-        if metrics.top_byte_mask == 0xff {
-            if m.read_byte()? != 0 {
-                return Err(error::Unspecified);
-            }
-        };
-        let em = m;
+        verify(
+            self.digest_alg,
+            self.mgf1_digest_alg,
+            self.s_len(mod_bits)?,
+            m_hash,
+            m,
+            mod_bits,
+        )
+    }
+}
 
-        // The rest of this function is EMSA-PSS-VERIFY from
-        // https://tools.ietf.org/html/rfc3447#section-9.1.2.
+// EMSA-PSS-ENCODE, https://tools.ietf.org/html/rfc3447#section-9.1.1, with a
+// salt of `s_len` bytes.
+fn encode(
+    digest_alg: &'static digest::Algorithm,
+    mgf1_digest_alg: &'static digest::Algorithm,
+    s_len: usize,
+    m_hash: digest::Digest,
+    m_out: &mut [u8],
+    mod_bits: bits::BitLength,
+    rng: &dyn rand::SecureRandom,
+) -> Result<(), error::Unspecified> {
+    let metrics = PSSMetrics::new(digest_alg, s_len, mod_bits)?;
+
+    // The `m_out` this function fills is the big-endian-encoded value of `m`
+    // from the specification, padded to `k` bytes, where `k` is the length
+    // in bytes of the public modulus. The spec says "Note that emLen will
+    // be one less than k if modBits - 1 is divisible by 8 and equal to k
+    // otherwise." In other words we might need to prefix `em` with a
+    // leading zero byte to form a correct value of `m`.
+    let em = if metrics.top_byte_mask == 0xff {
+        m_out[0] = 0;
+        &mut m_out[1..]
+    } else {
+        m_out
+    };
+    assert_eq!(em.len(), metrics.em_len);
 
-        // Steps 1 and 2 are done by the caller to produce `m_hash`.
+    // Steps 1 and 2 are done by the caller to produce `m_hash`.
 
-        // Step 3 is done by `PSSMetrics::new()` above.
+    // Step 3 is done by `PSSMetrics::new()` above.
 
-        // Step 5, out of order.
-        let masked_db = em.read_bytes(metrics.db_len)?;
-        let h_hash = em.read_bytes(metrics.h_len)?;
+    let (db, digest_terminator) = em.split_at_mut(metrics.db_len);
 
-        // Step 4.
-        if em.read_byte()? != 0xbc {
-            return Err(error::Unspecified);
-        }
+    let separator_pos = db.len() - 1 - metrics.s_len;
 
-        // Step 7.
-        let mut db = [0u8; PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN];
-        let db = &mut db[..metrics.db_len];
-
-        mgf1(self.digest_alg, h_hash.as_slice_less_safe(), db);
-
-        masked_db.read_all(error::Unspecified, |masked_bytes| {
-            // Step 6. Check the top bits of first byte are zero.
-            let b = masked_bytes.read_byte()?;
-            if b & !metrics.top_byte_mask != 0 {
-                return Err(error::Unspecified);
-            }
-            db[0] ^= b;
-
-            // Step 8.
-            for db in db[1..].iter_mut() {
-                *db ^= masked_bytes.read_byte()?;
-            }
-            Ok(())
-        })?;
-
-        // Step 9.
-        db[0] &= metrics.top_byte_mask;
-
-        // Step 10.
-        let ps_len = metrics.ps_len;
-        if db[0..ps_len].iter().any(|&db| db != 0) {
-            return Err(error::Unspecified);
-        }
-        if db[metrics.ps_len] != 1 {
+    // Step 4.
+    let salt: &[u8] = {
+        let salt = &mut db[(separator_pos + 1)..];
+        rng.fill(salt)?; // salt
+        salt
+    };
+
+    // Steps 5 and 6.
+    let h = pss_digest(digest_alg, m_hash, salt);
+
+    // Step 7.
+    db[..separator_pos].fill(0); // ps
+
+    // Step 8.
+    db[separator_pos] = 0x01;
+
+    // Steps 9 and 10.
+    mgf1(mgf1_digest_alg, h.as_ref(), db);
+
+    // Step 11.
+    db[0] &= metrics.top_byte_mask;
+
+    // Step 12.
+    digest_terminator[..metrics.h_len].copy_from_slice(h.as_ref());
+    digest_terminator[metrics.h_len] = 0xbc;
+
+    Ok(())
+}
+
+// RSASSA-PSS-VERIFY from https://tools.ietf.org/html/rfc3447#section-8.1.2
+// where steps 1, 2(a), and 2(b) have been done for us, with a salt of `s_len`
+// bytes. If `s_len` is `None` then any salt length is accepted.
+fn verify(
+    digest_alg: &'static digest::Algorithm,
+    mgf1_digest_alg: &'static digest::Algorithm,
+    s_len: Option<usize>,
+    m_hash: digest::Digest,
+    m: &mut untrusted::Reader,
+    mod_bits: bits::BitLength,
+) -> Result<(), error::Unspecified> {
+    // When the salt length is unknown, check the lengths as though the salt
+    // were empty; the actual salt length is determined in step 10.
+    let metrics = PSSMetrics::new(digest_alg, s_len.unwrap_or(0), mod_bits)?;
+
+    // RSASSA-PSS-VERIFY Step 2(c). The `m` this function is given is the
+    // big-endian-encoded value of `m` from the specification, padded to
+    // `k` bytes, where `k` is the length in bytes of the public modulus.
+    // The spec. says "Note that emLen will be one less than k if
+    // modBits - 1 is divisible by 8 and equal to k otherwise," where `k`
+    // is the length in octets of the RSA public modulus `n`. In other
+    // words, `em` might have an extra leading zero byte that we need to
+    // strip before we start the PSS decoding steps which is an artifact of
+    // the `Verification` interface.
+    if metrics.top_byte_mask == 0xff {
+        if m.read_byte()? != 0 {
             return Err(error::Unspecified);
         }
+    };
+    let em = m;
+
+    // The rest of this function is EMSA-PSS-VERIFY from
+    // https://tools.ietf.org/html/rfc3447#section-9.1.2.
+
+    // Steps 1 and 2 are done by the caller to produce `m_hash`.
 
-        // Step 11.
-        let salt = &db[(db.len() - metrics.s_len)..];
+    // Step 3 is done by `PSSMetrics::new()` above.
 
-        // Step 12 and 13.
-        let h_prime = pss_digest(self.digest_alg, m_hash, salt);
+    // Step 5, out of order.
+    let masked_db = em.read_bytes(metrics.db_len)?;
+    let h_hash = em.read_bytes(metrics.h_len)?;
 
-        // Step 14.
-        if h_hash.as_slice_less_safe() != h_prime.as_ref() {
+    // Step 4.
+    if em.read_byte()? != 0xbc {
+        return Err(error::Unspecified);
+    }
+
+    // Step 7.
+    let mut db = [0u8; PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN];
+    let db = &mut db[..metrics.db_len];
+
+    mgf1(mgf1_digest_alg, h_hash.as_slice_less_safe(), db);
+
+    masked_db.read_all(error::Unspecified, |masked_bytes| {
+        // Step 6. Check the top bits of first byte are zero.
+        let b = masked_bytes.read_byte()?;
+        if b & !metrics.top_byte_mask != 0 {
             return Err(error::Unspecified);
         }
+        db[0] ^= b;
 
+        // Step 8.
+        for db in db[1..].iter_mut() {
+            *db ^= masked_bytes.read_byte()?;
+        }
         Ok(())
+    })?;
+
+    // Step 9.
+    db[0] &= metrics.top_byte_mask;
+
+    // Step 10. When the salt length is unknown, the padding ends at the first
+    // nonzero byte.
+    let ps_len = match s_len {
+        Some(_) => metrics.ps_len,
+        None => db[..=metrics.ps_len]
+            .iter()
+            .position(|&db| db != 0)
+            .ok_or(error::Unspecified)?,
+    };
+    if db[0..ps_len].iter().any(|&db| db != 0) {
+        return Err(error::Unspecified);
+    }
+    if db[ps_len] != 1 {
+        return Err(error::Unspecified);
+    }
+
+    // Step 11.
+    let salt = &db[(ps_len + 1)..];
+
+    // Step 12 and 13.
+    let h_prime = pss_digest(digest_alg, m_hash, salt);
+
+    // Step 14.
+    if h_hash.as_slice_less_safe() != h_prime.as_ref() {
+        return Err(error::Unspecified);
     }
+
+    Ok(())
+}
+
+// Returns the maximum salt length for a public modulus of `mod_bits` bits,
+// `emLen - hLen - 2`.
+fn max_s_len(
+    digest_alg: &'static digest::Algorithm,
+    mod_bits: bits::BitLength,
+) -> Result<usize, error::Unspecified> {
+    let em_len = mod_bits.try_sub_1()?.as_usize_bytes_rounded_up();
+    em_len
+        .checked_sub(digest_alg.output_len() + 2)
+        .ok_or(error::Unspecified)
 }
 
 struct PSSMetrics {
@@ -203,6 +335,7 @@ struct PSSMetrics {
 impl PSSMetrics {
     fn new(
         digest_alg: &'static digest::Algorithm,
+        s_len: usize,
         mod_bits: bits::BitLength,
     ) -> Result<Self, error::Unspecified> {
         let em_bits = mod_bits.try_sub_1()?;
@@ -213,9 +346,6 @@ impl PSSMetrics {
 
         let h_len = digest_alg.output_len();
 
-        // We require the salt length to be equal to the digest length.
-        let s_len = h_len;
-
         // Step 3 of both `EMSA-PSS-ENCODE` is `EMSA-PSS-VERIFY` requires that
         // we reject inputs where "emLen < hLen + sLen + 2". The definition of
         // `emBits` in RFC 3447 Sections 9.1.1 and 9.1.2 says `emBits` must be
@@ -223,8 +353,8 @@ impl PSSMetrics {
         // two conditions are equivalent. 9 bits are required as the 0x01
         // before the salt requires 1 bit and the 0xbc after the digest
         // requires 8 bits.
-        let db_len = em_len.checked_sub(1 + s_len).ok_or(error::Unspecified)?;
-        let ps_len = db_len.checked_sub(h_len + 1).ok_or(error::Unspecified)?;
+        let db_len = em_len.checked_sub(1 + h_len).ok_or(error::Unspecified)?;
+        let ps_len = db_len.checked_sub(s_len + 1).ok_or(error::Unspecified)?;
 
         debug_assert!(em_bits.as_bits() >= (8 * h_len) + (8 * s_len) + 9);
 
@@ -260,6 +390,8 @@ macro_rules! rsa_pss_padding {
         #[doc=$doc_str]
         $vis static $PADDING_ALGORITHM: PSS = PSS {
             digest_alg: $digest_alg,
+            mgf1_digest_alg: $digest_alg,
+            salt_len: PssSaltLength::DigestLength,
         };
     };
 }
@@ -287,3 +419,79 @@ rsa_pss_padding!(
                  \"`RSA_PSS_*` Details\" in `ring::signature`'s module-level
                  documentation for more details."
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+    use alloc::vec;
+
+    fn digest_alg(name: &str) -> &'static digest::Algorithm {
+        match name {
+            "SHA256" => &digest::SHA256,
+            "SHA384" => &digest::SHA384,
+            "SHA512" => &digest::SHA512,
+            _ => panic!("Unsupported digest: {}", name),
+        }
+    }
+
+    #[test]
+    fn test_pss_salt_len_verify() {
+        test::run(
+            test_file!("rsa_pss_salt_len_tests.txt"),
+            |section, test_case| {
+                assert_eq!(section, "");
+                let digest_alg = digest_alg(&test_case.consume_string("Digest"));
+                let msg = test_case.consume_bytes("Msg");
+                let salt = test_case.consume_bytes("Salt");
+                let encoded = test_case.consume_bytes("EM");
+                let bit_len = test_case.consume_usize_bits("Len");
+                let is_valid = test_case.consume_string("Result") == "P";
+
+                let m_hash = digest::digest(digest_alg, &msg);
+                let actual_result = untrusted::Input::from(&encoded)
+                    .read_all(error::Unspecified, |m| {
+                        verify(digest_alg, digest_alg, Some(salt.len()), m_hash, m, bit_len)
+                    });
+                assert_eq!(actual_result.is_ok(), is_valid);
+
+                Ok(())
+            },
+        );
+    }
+
+    #[test]
+    fn test_pss_salt_len_encode() {
+        test::run(
+            test_file!("rsa_pss_salt_len_tests.txt"),
+            |section, test_case| {
+                assert_eq!(section, "");
+                let digest_alg = digest_alg(&test_case.consume_string("Digest"));
+                let msg = test_case.consume_bytes("Msg");
+                let salt = test_case.consume_bytes("Salt");
+                let encoded = test_case.consume_bytes("EM");
+                let bit_len = test_case.consume_usize_bits("Len");
+                if test_case.consume_string("Result") != "P" {
+                    return Ok(());
+                }
+
+                let rng = test::rand::FixedSliceRandom { bytes: &salt };
+                let mut m_out = vec![0u8; bit_len.as_usize_bytes_rounded_up()];
+                let m_hash = digest::digest(digest_alg, &msg);
+                encode(
+                    digest_alg,
+                    digest_alg,
+                    salt.len(),
+                    m_hash,
+                    &mut m_out,
+                    bit_len,
+                    &rng,
+                )
+                .unwrap();
+                assert_eq!(m_out, encoded);
+
+                Ok(())
+            },
+        );
+    }
+}
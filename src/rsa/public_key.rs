@@ -12,12 +12,16 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-use super::{PublicExponent, PublicModulus, N, PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN};
+use super::{
+    padding::{pkcs1_encryption, OaepAlgorithm},
+    PublicExponent, PublicModulus, N, PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN,
+};
 use crate::{
     arithmetic::bigint,
     bits, cpu, error,
     io::{self, der, der_writer},
     limb::LIMB_BYTES,
+    rand,
 };
 use alloc::boxed::Box;
 use core::num::NonZeroU64;
@@ -77,6 +81,90 @@ impl PublicKey {
         self.inner.n().len_bits().as_usize_bytes_rounded_up()
     }
 
+    /// Encrypts `plaintext` using RSA-OAEP with the given `label`, writing
+    /// the ciphertext into `ciphertext`.
+    ///
+    /// `ciphertext`'s length must be exactly `self.modulus_len()`, and
+    /// `plaintext` must be no longer than
+    /// `oaep_alg.max_plaintext_len(self.modulus_len())`; otherwise an error
+    /// will be returned. Most applications use an empty `label`.
+    ///
+    /// See [RFC 8017 Section 7.1.1].
+    ///
+    /// [RFC 8017 Section 7.1.1]: https://tools.ietf.org/html/rfc8017#section-7.1.1
+    pub fn encrypt_oaep(
+        &self,
+        oaep_alg: &'static OaepAlgorithm,
+        label: &[u8],
+        plaintext: &[u8],
+        rng: &dyn rand::SecureRandom,
+        ciphertext: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        let cpu_features = cpu::features();
+
+        if ciphertext.len() != self.modulus_len() {
+            return Err(error::Unspecified);
+        }
+
+        // Step 2: EME-OAEP encoding.
+        let mut em = [0u8; PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN];
+        let em = &mut em[..ciphertext.len()];
+        oaep_alg.encode(label, plaintext, em, rng)?;
+
+        // Steps 3.a and 3.b: RSAEP.
+        let mut c = [0u8; PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN];
+        let c = self
+            .inner
+            .exponentiate(untrusted::Input::from(em), &mut c, cpu_features)?;
+
+        // Step 3.c.
+        ciphertext.copy_from_slice(c);
+
+        Ok(())
+    }
+
+    /// Encrypts `plaintext` using RSAES-PKCS1-v1_5, writing the ciphertext
+    /// into `ciphertext`.
+    ///
+    /// `ciphertext`'s length must be exactly `self.modulus_len()`, and
+    /// `plaintext` must be no longer than `self.modulus_len() - 11` bytes;
+    /// otherwise an error will be returned.
+    ///
+    /// This is only for legacy protocols that require it; new protocols
+    /// should use [`Self::encrypt_oaep`] instead.
+    ///
+    /// See [RFC 8017 Section 7.2.1].
+    ///
+    /// [RFC 8017 Section 7.2.1]: https://tools.ietf.org/html/rfc8017#section-7.2.1
+    pub fn encrypt_pkcs1(
+        &self,
+        plaintext: &[u8],
+        rng: &dyn rand::SecureRandom,
+        ciphertext: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        let cpu_features = cpu::features();
+
+        if ciphertext.len() != self.modulus_len() {
+            return Err(error::Unspecified);
+        }
+
+        // Step 2: EME-PKCS1-v1_5 encoding.
+        let mut em = [0u8; PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN];
+        let em = &mut em[..ciphertext.len()];
+        pkcs1_encryption::encode(plaintext, em, rng)?;
+
+        // Steps 3.a and 3.b: RSAEP.
+        let mut c = [0u8; PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN];
+        let c = self
+            .inner
+            .exponentiate(untrusted::Input::from(em), &mut c, cpu_features)?;
+
+        // Step 3.c.
+        ciphertext.copy_from_slice(c);
+
+        Ok(())
+    }
+
     pub(super) fn inner(&self) -> &Inner {
         &self.inner
     }
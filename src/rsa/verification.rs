@@ -28,6 +28,16 @@ impl signature::VerificationAlgorithm for RsaParameters {
         public_key: untrusted::Input,
         msg: untrusted::Input,
         signature: untrusted::Input,
+    ) -> Result<(), error::Unspecified> {
+        let m_hash = digest::digest(self.padding_alg.digest_alg(), msg.as_slice_less_safe());
+        self.verify_digest(public_key, m_hash, signature)
+    }
+
+    fn verify_digest(
+        &self,
+        public_key: untrusted::Input,
+        digest: digest::Digest,
+        signature: untrusted::Input,
     ) -> Result<(), error::Unspecified> {
         let (n, e) = parse_public_key(public_key)?;
         verify_rsa_(
@@ -36,7 +46,7 @@ impl signature::VerificationAlgorithm for RsaParameters {
                 n.big_endian_without_leading_zero_as_input(),
                 e.big_endian_without_leading_zero_as_input(),
             ),
-            msg,
+            digest,
             signature,
             cpu::features(),
         )
@@ -180,13 +190,14 @@ where
         message: &[u8],
         signature: &[u8],
     ) -> Result<(), error::Unspecified> {
+        let m_hash = digest::digest(params.padding_alg.digest_alg(), message);
         verify_rsa_(
             params,
             (
                 untrusted::Input::from(self.n.as_ref()),
                 untrusted::Input::from(self.e.as_ref()),
             ),
-            untrusted::Input::from(message),
+            m_hash,
             untrusted::Input::from(signature),
             cpu::features(),
         )
@@ -196,10 +207,14 @@ where
 pub(crate) fn verify_rsa_(
     params: &RsaParameters,
     (n, e): (untrusted::Input, untrusted::Input),
-    msg: untrusted::Input,
+    m_hash: digest::Digest,
     signature: untrusted::Input,
     cpu_features: cpu::Features,
 ) -> Result<(), error::Unspecified> {
+    if m_hash.algorithm() != params.padding_alg.digest_alg() {
+        return Err(error::Unspecified);
+    }
+
     let max_bits: bits::BitLength =
         bits::BitLength::from_usize_bytes(PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN)?;
 
@@ -221,7 +236,6 @@ pub(crate) fn verify_rsa_(
     let decoded = key.exponentiate(signature, &mut decoded, cpu_features)?;
 
     // Verify the padded message is correct.
-    let m_hash = digest::digest(params.padding_alg.digest_alg(), msg.as_slice_less_safe());
     untrusted::Input::from(decoded).read_all(error::Unspecified, |m| {
         params.padding_alg.verify(m_hash, m, key.n().len_bits())
     })
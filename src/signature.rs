@@ -26,11 +26,12 @@
 //! reduce the risks of algorithm agility and to provide consistency with ECDSA
 //! and EdDSA.
 //!
-//! Currently this module does not support digesting the message to be signed
-//! separately from the public key operation, as it is currently being
-//! optimized for Ed25519 and for the implementation of protocols that do not
-//! requiring signing large messages. An interface for efficiently supporting
-//! larger messages may be added later.
+//! For ECDSA and RSA, the message can be digested separately from the public
+//! key operation, e.g. when it is digested incrementally or by another party.
+//! See `EcdsaKeyPair::sign_digest()`, `RsaKeyPair::sign_digest()`, and
+//! `UnparsedPublicKey::verify_digest()`. The digest must have been computed
+//! with the digest algorithm of the signature algorithm. Ed25519 signs the
+//! message itself, so it doesn't support this.
 //!
 //!
 //! # Algorithm Details
@@ -56,8 +57,9 @@
 //!
 //! The signature is *r*||*s*, where || denotes concatenation, and where both
 //! *r* and *s* are both big-endian-encoded values that are left-padded to the
-//! maximum length. A P-256 signature will be 64 bytes long (two 32-byte
-//! components) and a P-384 signature will be 96 bytes long (two 48-byte
+//! maximum length. A P-256 or secp256k1 signature will be 64 bytes long (two
+//! 32-byte components), a P-384 signature will be 96 bytes long (two 48-byte
+//! components), and a P-521 signature will be 132 bytes long (two 66-byte
 //! components). This is the form of ECDSA signature used PKCS#11 and DNSSEC.
 //!
 //! The public key is encoding in uncompressed form using the
@@ -105,6 +107,11 @@
 //! Additionally, the entire salt is randomly generated separately for each
 //! signature using the secure random number generator passed to `sign()`.
 //!
+//! Signatures that use a different salt length or MGF1 digest algorithm, e.g.
+//! ones from software that uses an empty or maximum-length salt, can be
+//! signed and verified using padding constructed with `PSS::new()` and
+//! verification parameters constructed with `RsaParameters::new()`.
+//!
 //!
 //! [SEC 1: Elliptic Curve Cryptography, Version 2.0]:
 //!     http://www.secg.org/sec1-v2.pdf
@@ -255,33 +262,52 @@
 //! # }
 //! ```
 
-use crate::{cpu, debug, ec, error, sealed};
+use crate::{cpu, debug, digest, ec, error, sealed};
 
 pub use crate::ec::{
     curve25519::ed25519::{
         signing::Ed25519KeyPair,
-        verification::{EdDSAParameters, ED25519},
-        ED25519_PUBLIC_KEY_LEN,
+        verification::{EdDSAParameters, ED25519, ED25519PH},
+        ED25519_MAX_CONTEXT_LEN, ED25519_PUBLIC_KEY_LEN,
+    },
+    curve448::ed448::{
+        signing::Ed448KeyPair,
+        verification::{Ed448Parameters, ED448, ED448PH},
+        ED448_MAX_CONTEXT_LEN, ED448_PUBLIC_KEY_LEN,
     },
     suite_b::ecdsa::{
         signing::{
             EcdsaKeyPair, EcdsaSigningAlgorithm, ECDSA_P256_SHA256_ASN1_SIGNING,
-            ECDSA_P256_SHA256_FIXED_SIGNING, ECDSA_P384_SHA384_ASN1_SIGNING,
-            ECDSA_P384_SHA384_FIXED_SIGNING,
+            ECDSA_P256_SHA256_ASN1_SIGNING_DETERMINISTIC, ECDSA_P256_SHA256_FIXED_SIGNING,
+            ECDSA_P256_SHA256_FIXED_SIGNING_DETERMINISTIC, ECDSA_P384_SHA384_ASN1_SIGNING,
+            ECDSA_P384_SHA384_ASN1_SIGNING_DETERMINISTIC, ECDSA_P384_SHA384_FIXED_SIGNING,
+            ECDSA_P384_SHA384_FIXED_SIGNING_DETERMINISTIC, ECDSA_P521_SHA512_ASN1_SIGNING,
+            ECDSA_P521_SHA512_ASN1_SIGNING_DETERMINISTIC, ECDSA_P521_SHA512_FIXED_SIGNING,
+            ECDSA_P521_SHA512_FIXED_SIGNING_DETERMINISTIC, ECDSA_SECP256K1_SHA256_ASN1_SIGNING,
+            ECDSA_SECP256K1_SHA256_FIXED_SIGNING, ECDSA_SECP256K1_SHA256_LOW_S_ASN1_SIGNING,
+            ECDSA_SECP256K1_SHA256_LOW_S_ASN1_SIGNING_DETERMINISTIC,
+            ECDSA_SECP256K1_SHA256_LOW_S_FIXED_SIGNING,
+            ECDSA_SECP256K1_SHA256_LOW_S_FIXED_SIGNING_DETERMINISTIC,
         },
         verification::{
-            EcdsaVerificationAlgorithm, ECDSA_P256_SHA256_ASN1, ECDSA_P256_SHA256_FIXED,
-            ECDSA_P256_SHA384_ASN1, ECDSA_P384_SHA256_ASN1, ECDSA_P384_SHA384_ASN1,
-            ECDSA_P384_SHA384_FIXED,
+            EcdsaVerificationAlgorithm, ECDSA_P256_SHA224_ASN1, ECDSA_P256_SHA256_ASN1,
+            ECDSA_P256_SHA256_FIXED, ECDSA_P256_SHA384_ASN1, ECDSA_P384_SHA224_ASN1,
+            ECDSA_P384_SHA256_ASN1, ECDSA_P384_SHA384_ASN1, ECDSA_P384_SHA384_FIXED,
+            ECDSA_P521_SHA512_ASN1, ECDSA_P521_SHA512_FIXED, ECDSA_SECP256K1_SHA256_ASN1,
+            ECDSA_SECP256K1_SHA256_FIXED, ECDSA_SECP256K1_SHA256_LOW_S_ASN1,
+            ECDSA_SECP256K1_SHA256_LOW_S_FIXED,
         },
     },
 };
 
+#[cfg(feature = "alloc")]
+pub use crate::ec::curve25519::ed25519::verification::{BatchVerificationError, Ed25519BatchItem};
+
 #[cfg(feature = "alloc")]
 pub use crate::rsa::{
     padding::{
-        RsaEncoding, RSA_PKCS1_SHA256, RSA_PKCS1_SHA384, RSA_PKCS1_SHA512, RSA_PSS_SHA256,
-        RSA_PSS_SHA384, RSA_PSS_SHA512,
+        PssSaltLength, RsaEncoding, PSS, RSA_PKCS1_SHA256, RSA_PKCS1_SHA384, RSA_PKCS1_SHA512,
+        RSA_PSS_SHA256, RSA_PSS_SHA384, RSA_PSS_SHA512,
     },
     verification::{
         RsaPublicKeyComponents, RSA_PKCS1_1024_8192_SHA1_FOR_LEGACY_USE_ONLY,
@@ -335,10 +361,11 @@ pub trait KeyPair: core::fmt::Debug + Send + Sized + Sync {
     fn public_key(&self) -> &Self::PublicKey;
 }
 
-/// The longest signature is an ASN.1 P-384 signature where *r* and *s* are of
-/// maximum length with the leading high bit set on each. Then each component
-/// will have a tag, a one-byte length, and a one-byte “I'm not negative”
-/// prefix, and the outer sequence will have a two-byte length.
+/// The longest signature is an ASN.1 P-521 signature where *r* and *s* are of
+/// maximum length. Then each component will have a tag, a one-byte length, and
+/// (conservatively, since it is only needed when the leading high bit is set)
+/// a one-byte “I'm not negative” prefix, and the outer sequence will have a
+/// two-byte length.
 pub(crate) const MAX_LEN: usize = 1/*tag:SEQUENCE*/ + 2/*len*/ +
     (2 * (1/*tag:INTEGER*/ + 1/*len*/ + 1/*zero*/ + ec::SCALAR_MAX_BYTES));
 
@@ -352,6 +379,19 @@ pub trait VerificationAlgorithm: core::fmt::Debug + Sync + sealed::Sealed {
         msg: untrusted::Input,
         signature: untrusted::Input,
     ) -> Result<(), error::Unspecified>;
+
+    /// Verify the signature `signature` of a message, given its already
+    /// computed digest `digest`, with the public key `public_key`.
+    ///
+    /// Fails if `digest` wasn't computed with the digest algorithm that this
+    /// algorithm uses, or if this algorithm doesn't sign message digests
+    /// (e.g. Ed25519).
+    fn verify_digest(
+        &self,
+        public_key: untrusted::Input,
+        digest: digest::Digest,
+        signature: untrusted::Input,
+    ) -> Result<(), error::Unspecified>;
 }
 
 /// An unparsed, possibly malformed, public key for signature verification.
@@ -406,4 +446,26 @@ impl<B> UnparsedPublicKey<B> {
             untrusted::Input::from(signature),
         )
     }
+
+    /// Parses the public key and verifies `signature` is a valid signature of
+    /// the message that was digested to `digest`.
+    ///
+    /// This is for use when the message was digested elsewhere. `digest` must
+    /// have been computed using the digest algorithm of the verification
+    /// algorithm; otherwise an error is returned.
+    pub fn verify_digest(
+        &self,
+        digest: digest::Digest,
+        signature: &[u8],
+    ) -> Result<(), error::Unspecified>
+    where
+        B: AsRef<[u8]>,
+    {
+        let _ = cpu::features();
+        self.algorithm.verify_digest(
+            untrusted::Input::from(self.bytes.as_ref()),
+            digest,
+            untrusted::Input::from(signature),
+        )
+    }
 }
@@ -171,20 +171,26 @@ impl TestCase {
         }
     }
 
-    /// Maps the strings "SHA1", "SHA256", "SHA384", and "SHA512" to digest
-    /// algorithms, maps "SHA224" to `None`, and panics on other (erroneous)
-    /// inputs. "SHA224" is mapped to None because *ring* intentionally does
-    /// not support SHA224, but we need to consume test vectors from NIST that
-    /// have SHA224 vectors in them.
+    /// Maps the strings "SHA1", "SHA224", "SHA256", "SHA384", "SHA512",
+    /// "SHA512_224", "SHA512_256", "SHA3_256", "SHA3_384", "SHA3_512",
+    /// "BLAKE2B_256", "BLAKE2B_512", and "BLAKE2S_256" to digest algorithms,
+    /// and panics on other (erroneous) inputs.
     pub fn consume_digest_alg(&mut self, key: &str) -> Option<&'static digest::Algorithm> {
         let name = self.consume_string(key);
         match name.as_ref() {
             "SHA1" => Some(&digest::SHA1_FOR_LEGACY_USE_ONLY),
-            "SHA224" => None, // We actively skip SHA-224 support.
+            "SHA224" => Some(&digest::SHA224),
             "SHA256" => Some(&digest::SHA256),
             "SHA384" => Some(&digest::SHA384),
             "SHA512" => Some(&digest::SHA512),
+            "SHA512_224" => Some(&digest::SHA512_224),
             "SHA512_256" => Some(&digest::SHA512_256),
+            "SHA3_256" => Some(&digest::SHA3_256),
+            "SHA3_384" => Some(&digest::SHA3_384),
+            "SHA3_512" => Some(&digest::SHA3_512),
+            "BLAKE2B_256" => Some(&digest::BLAKE2B_256),
+            "BLAKE2B_512" => Some(&digest::BLAKE2B_512),
+            "BLAKE2S_256" => Some(&digest::BLAKE2S_256),
             _ => panic!("Unsupported digest algorithm: {}", name),
         }
     }
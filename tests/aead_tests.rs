@@ -79,8 +79,14 @@ macro_rules! test_aead {
 }
 
 test_aead! {
+    { AES_128_CCM, "aead_aes_128_ccm_tests.txt" },
+    { AES_256_CCM, "aead_aes_256_ccm_tests.txt" },
+    { AES_128_CCM_8, "aead_aes_128_ccm_8_tests.txt" },
+    { AES_256_CCM_8, "aead_aes_256_ccm_8_tests.txt" },
     { AES_128_GCM, "aead_aes_128_gcm_tests.txt" },
     { AES_256_GCM, "aead_aes_256_gcm_tests.txt" },
+    { AES_128_GCM_SIV, "../crypto/cipher_extra/test/aes_128_gcm_siv_tests.txt" },
+    { AES_256_GCM_SIV, "../crypto/cipher_extra/test/aes_256_gcm_siv_tests.txt" },
     { CHACHA20_POLY1305, "aead_chacha20_poly1305_tests.txt" },
 }
 
@@ -116,6 +122,20 @@ fn test_aead(
                 ));
                 return Ok(());
             }
+            Some("WRONG_TAG") => {
+                let key = make_less_safe_key(aead_alg, &key);
+                let mut in_out = [&ct[..], &tag[..]].concat();
+                assert!(key
+                    .open_in_place(
+                        aead::Nonce::try_assume_unique_for_key(&nonce)?,
+                        aead::Aad::from(&aad),
+                        &mut in_out,
+                    )
+                    .is_err());
+                // The plaintext is zeroed when verification fails.
+                assert!(in_out[..ct.len()].iter().all(|&b| b == 0));
+                return Ok(());
+            }
             Some(unexpected) => {
                 unreachable!("unexpected error in test data: {}", unexpected);
             }
@@ -490,6 +510,179 @@ fn aead_chacha20_poly1305_openssh() {
     );
 }
 
+#[test]
+fn aead_xchacha20_poly1305() {
+    use aead::xchacha20_poly1305::{Key, Nonce, KEY_LEN};
+
+    test::run(
+        test_file!("aead_xchacha20_poly1305_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let key_bytes: [u8; KEY_LEN] = test_case.consume_bytes("KEY").try_into().unwrap();
+            let nonce = test_case.consume_bytes("NONCE");
+            let plaintext = test_case.consume_bytes("IN");
+            let aad = test_case.consume_bytes("AD");
+            let ct = test_case.consume_bytes("CT");
+            let tag = test_case.consume_bytes("TAG");
+
+            let key = Key::new(&key_bytes);
+
+            let mut in_out = plaintext.clone();
+            key.seal_in_place_append_tag(
+                Nonce::try_assume_unique_for_key(&nonce)?,
+                aead::Aad::from(&aad),
+                &mut in_out,
+            )?;
+            assert_eq!(&in_out[..ct.len()], &ct[..]);
+            assert_eq!(&in_out[ct.len()..], &tag[..]);
+
+            let mut ciphertext_and_tag = in_out.clone();
+            let opened = key.open_in_place(
+                Nonce::try_assume_unique_for_key(&nonce)?,
+                aead::Aad::from(&aad),
+                &mut ciphertext_and_tag,
+            )?;
+            assert_eq!(opened, &plaintext[..]);
+
+            // Opening with a shift.
+            const PREFIX_LEN: usize = 5;
+            let mut shifted = vec![0xff; PREFIX_LEN];
+            shifted.extend_from_slice(&in_out);
+            let opened = key.open_within(
+                Nonce::try_assume_unique_for_key(&nonce)?,
+                aead::Aad::from(&aad),
+                &mut shifted,
+                PREFIX_LEN..,
+            )?;
+            assert_eq!(opened, &plaintext[..]);
+
+            // Any modification of the tag is detected.
+            let mut tampered = in_out.clone();
+            let last = tampered.len() - 1;
+            tampered[last] ^= 1;
+            assert!(key
+                .open_in_place(
+                    Nonce::try_assume_unique_for_key(&nonce)?,
+                    aead::Aad::from(&aad),
+                    &mut tampered,
+                )
+                .is_err());
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn aead_xchacha20_poly1305_nonce_sizes() {
+    use aead::xchacha20_poly1305::{Nonce, NONCE_LEN};
+
+    let nonce = [0u8; NONCE_LEN + 1];
+    assert!(Nonce::try_assume_unique_for_key(&nonce[..NONCE_LEN]).is_ok());
+    assert!(Nonce::try_assume_unique_for_key(&nonce[..(NONCE_LEN - 1)]).is_err());
+    assert!(Nonce::try_assume_unique_for_key(&nonce).is_err());
+    assert!(Nonce::try_assume_unique_for_key(&nonce[..aead::NONCE_LEN]).is_err());
+    assert!(Nonce::try_assume_unique_for_key(&[]).is_err());
+}
+
+#[test]
+fn aead_gmac() {
+    test::run(test_file!("aead_gmac_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        let key_bytes = test_case.consume_bytes("KEY");
+        let nonce = test_case.consume_bytes("NONCE");
+        let input = test_case.consume_bytes("IN");
+        let expected_tag = test_case.consume_bytes("TAG");
+
+        let algorithm = match key_bytes.len() {
+            16 => &aead::gmac::AES_128,
+            32 => &aead::gmac::AES_256,
+            _ => unreachable!(),
+        };
+        let key = aead::gmac::Key::new(algorithm, &key_bytes)?;
+
+        // Split the input at every possible point (for short inputs) to
+        // exercise the buffering of partial blocks.
+        let split_points = if input.len() <= 64 {
+            (0..=input.len()).collect::<Vec<_>>()
+        } else {
+            vec![0, 1, 15, 16, 17, input.len() - 1, input.len()]
+        };
+        for split in split_points {
+            let (a, b) = input.split_at(split);
+            let nonce = aead::Nonce::try_assume_unique_for_key(&nonce)?;
+            let mut ctx = aead::gmac::Context::with_key(&key, nonce);
+            ctx.update(a)?;
+            ctx.update(b)?;
+            assert_eq!(ctx.finish().as_ref(), &expected_tag[..]);
+        }
+
+        let mut ctx =
+            aead::gmac::Context::with_key(&key, aead::Nonce::try_assume_unique_for_key(&nonce)?);
+        for byte in &input {
+            ctx.update(core::slice::from_ref(byte))?;
+        }
+        assert!(ctx.verify(&expected_tag).is_ok());
+
+        let mut wrong_tag = expected_tag.clone();
+        wrong_tag[0] ^= 1;
+        let mut ctx =
+            aead::gmac::Context::with_key(&key, aead::Nonce::try_assume_unique_for_key(&nonce)?);
+        ctx.update(&input)?;
+        assert!(ctx.verify(&wrong_tag).is_err());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn aead_gmac_key_sizes() {
+    for algorithm in [&aead::gmac::AES_128, &aead::gmac::AES_256] {
+        let key_len = algorithm.key_len();
+        let key_data = vec![0u8; key_len + 1];
+        assert!(aead::gmac::Key::new(algorithm, &key_data[..key_len]).is_ok());
+        assert!(aead::gmac::Key::new(algorithm, &key_data[..(key_len - 1)]).is_err());
+        assert!(aead::gmac::Key::new(algorithm, &key_data[..(key_len + 1)]).is_err());
+    }
+}
+
+#[test]
+fn aead_one_time_poly1305() {
+    use aead::one_time_poly1305::{Context, Key, KEY_LEN};
+
+    // RFC 8439 Section 2.5.2.
+    let key: [u8; KEY_LEN] = [
+        0x85, 0xd6, 0xbe, 0x78, 0x57, 0x55, 0x6d, 0x33, 0x7f, 0x44, 0x52, 0xfe, 0x42, 0xd5, 0x06,
+        0xa8, 0x01, 0x03, 0x80, 0x8a, 0xfb, 0x0d, 0xb2, 0xfd, 0x4a, 0xbf, 0xf6, 0xaf, 0x41, 0x49,
+        0xf5, 0x1b,
+    ];
+    let message = b"Cryptographic Forum Research Group";
+    let expected_tag = [
+        0xa8, 0x06, 0x1d, 0xc1, 0x30, 0x51, 0x36, 0xc6, 0xc2, 0x2b, 0x8b, 0xaf, 0x0c, 0x01, 0x27,
+        0xa9,
+    ];
+
+    for split in 0..=message.len() {
+        let (a, b) = message.split_at(split);
+        let mut ctx = Context::new(Key::new(&key));
+        ctx.update(a);
+        ctx.update(b);
+        assert_eq!(ctx.finish().as_ref(), &expected_tag[..]);
+    }
+
+    let mut ctx = Context::new(Key::new(&key));
+    ctx.update(message);
+    assert!(ctx.verify(&expected_tag).is_ok());
+
+    let mut ctx = Context::new(Key::new(&key));
+    ctx.update(&message[1..]);
+    assert!(ctx.verify(&expected_tag).is_err());
+
+    let mut ctx = Context::new(Key::new(&key));
+    ctx.update(message);
+    assert!(ctx.verify(&expected_tag[..15]).is_err());
+}
+
 #[test]
 fn aead_test_aad_traits() {
     test::compile_time_assert_copy::<aead::Aad<&'_ [u8]>>();
@@ -514,6 +707,13 @@ fn test_tag_traits() {
     let tag = aead::Tag::from([4u8; 16]);
     let _tag_2 = tag; // Cover `Copy`
     assert_eq!(tag.as_ref(), tag.clone().as_ref()); // Cover `Clone`
+
+    // Truncated tags are supported, but empty and overlong ones aren't.
+    let truncated = aead::Tag::try_from(&[4u8; 8][..]).unwrap();
+    assert_eq!(truncated.as_ref(), &[4u8; 8]);
+    assert!(aead::Tag::try_from(&[4u8; 16][..]).is_ok());
+    assert!(aead::Tag::try_from(&[][..]).is_err());
+    assert!(aead::Tag::try_from(&[4u8; 17][..]).is_err());
 }
 
 #[test]
@@ -585,6 +785,16 @@ fn test_aead_lesssafekey_clone_for_algorithm(algorithm: &'static aead::Algorithm
     assert_eq!(buf1, buf2);
 }
 
+#[test]
+fn test_aead_lesssafekey_clone_aes_128_ccm() {
+    test_aead_lesssafekey_clone_for_algorithm(&aead::AES_128_CCM);
+}
+
+#[test]
+fn test_aead_lesssafekey_clone_aes_256_ccm_8() {
+    test_aead_lesssafekey_clone_for_algorithm(&aead::AES_256_CCM_8);
+}
+
 #[test]
 fn test_aead_lesssafekey_clone_aes_128_gcm() {
     test_aead_lesssafekey_clone_for_algorithm(&aead::AES_128_GCM);
@@ -595,11 +805,267 @@ fn test_aead_lesssafekey_clone_aes_256_gcm() {
     test_aead_lesssafekey_clone_for_algorithm(&aead::AES_256_GCM);
 }
 
+#[test]
+fn test_aead_lesssafekey_clone_aes_128_gcm_siv() {
+    test_aead_lesssafekey_clone_for_algorithm(&aead::AES_128_GCM_SIV);
+}
+
+#[test]
+fn test_aead_lesssafekey_clone_aes_256_gcm_siv() {
+    test_aead_lesssafekey_clone_for_algorithm(&aead::AES_256_GCM_SIV);
+}
+
 #[test]
 fn test_aead_lesssafekey_clone_chacha20_poly1305() {
     test_aead_lesssafekey_clone_for_algorithm(&aead::CHACHA20_POLY1305);
 }
 
+static ALL_ALGORITHMS: &[&aead::Algorithm] = &[
+    &aead::AES_128_CCM,
+    &aead::AES_256_CCM,
+    &aead::AES_128_CCM_8,
+    &aead::AES_256_CCM_8,
+    &aead::AES_128_GCM,
+    &aead::AES_256_GCM,
+    &aead::AES_128_GCM_SIV,
+    &aead::AES_256_GCM_SIV,
+    &aead::CHACHA20_POLY1305,
+];
+
+#[test]
+fn test_aead_open_rejects_modified_input() {
+    for &alg in ALL_ALGORITHMS {
+        let key_bytes = vec![1u8; alg.key_len()];
+        let key = make_less_safe_key(alg, &key_bytes);
+        let nonce = [2u8; aead::NONCE_LEN];
+
+        let plaintext: Vec<u8> = (0..33).collect();
+        let mut sealed = plaintext.clone();
+        key.seal_in_place_append_tag(
+            aead::Nonce::assume_unique_for_key(nonce),
+            aead::Aad::from(b"aad"),
+            &mut sealed,
+        )
+        .unwrap();
+
+        // Flip every bit of the ciphertext and the tag.
+        for i in 0..sealed.len() {
+            for bit in 0..8 {
+                let mut in_out = sealed.clone();
+                in_out[i] ^= 1 << bit;
+                assert!(key
+                    .open_in_place(
+                        aead::Nonce::assume_unique_for_key(nonce),
+                        aead::Aad::from(b"aad"),
+                        &mut in_out,
+                    )
+                    .is_err());
+                // The plaintext is zeroed when verification fails.
+                assert!(in_out[..plaintext.len()].iter().all(|&b| b == 0));
+            }
+        }
+
+        let mut in_out = sealed.clone();
+        assert!(key
+            .open_in_place(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::from(b"AAD"),
+                &mut in_out,
+            )
+            .is_err());
+
+        let mut in_out = sealed.clone();
+        let opened = key
+            .open_in_place(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::from(b"aad"),
+                &mut in_out,
+            )
+            .unwrap();
+        assert_eq!(opened, &plaintext[..]);
+    }
+}
+
+fn stream_seal(
+    alg: &'static aead::Algorithm,
+    key_bytes: &[u8],
+    nonce_prefix: [u8; aead::stream::NONCE_PREFIX_LEN],
+    segments: &[&[u8]],
+) -> Vec<Vec<u8>> {
+    let key = aead::UnboundKey::new(alg, key_bytes).unwrap();
+    let mut encryptor = aead::stream::Encryptor::new(key, nonce_prefix);
+    let (last, rest) = segments.split_last().unwrap();
+    let mut sealed = Vec::new();
+    for segment in rest {
+        let mut in_out = segment.to_vec();
+        encryptor
+            .seal_in_place_append_tag(aead::Aad::empty(), &mut in_out)
+            .unwrap();
+        sealed.push(in_out);
+    }
+    let mut in_out = last.to_vec();
+    encryptor
+        .seal_last_in_place_append_tag(aead::Aad::empty(), &mut in_out)
+        .unwrap();
+    sealed.push(in_out);
+    sealed
+}
+
+fn stream_open(
+    alg: &'static aead::Algorithm,
+    key_bytes: &[u8],
+    nonce_prefix: [u8; aead::stream::NONCE_PREFIX_LEN],
+    sealed: &[Vec<u8>],
+) -> Result<Vec<u8>, error::Unspecified> {
+    let key = aead::UnboundKey::new(alg, key_bytes).unwrap();
+    let mut decryptor = aead::stream::Decryptor::new(key, nonce_prefix);
+    let (last, rest) = sealed.split_last().unwrap();
+    let mut plaintext = Vec::new();
+    for segment in rest {
+        let mut in_out = segment.clone();
+        plaintext.extend_from_slice(decryptor.open_in_place(aead::Aad::empty(), &mut in_out)?);
+    }
+    let mut in_out = last.clone();
+    plaintext.extend_from_slice(decryptor.open_last_in_place(aead::Aad::empty(), &mut in_out)?);
+    Ok(plaintext)
+}
+
+#[test]
+fn test_aead_stream() {
+    const NONCE_PREFIX: [u8; aead::stream::NONCE_PREFIX_LEN] = [7; aead::stream::NONCE_PREFIX_LEN];
+
+    let message: Vec<u8> = (0..100u8).collect();
+    let segments: Vec<&[u8]> = vec![
+        &message[..32],
+        &message[32..64],
+        &message[64..96],
+        &message[96..],
+    ];
+
+    for &alg in ALL_ALGORITHMS {
+        let key_bytes = vec![3u8; alg.key_len()];
+        let sealed = stream_seal(alg, &key_bytes, NONCE_PREFIX, &segments);
+
+        // Each segment is sealed with the nonce `prefix || counter || last`.
+        let key = make_less_safe_key(alg, &key_bytes);
+        for (i, (segment, sealed_segment)) in segments.iter().zip(sealed.iter()).enumerate() {
+            let mut nonce = [0u8; aead::NONCE_LEN];
+            nonce[..NONCE_PREFIX.len()].copy_from_slice(&NONCE_PREFIX);
+            nonce[NONCE_PREFIX.len()..][..4].copy_from_slice(&(i as u32).to_be_bytes());
+            nonce[aead::NONCE_LEN - 1] = u8::from(i == segments.len() - 1);
+            let mut expected = segment.to_vec();
+            key.seal_in_place_append_tag(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::empty(),
+                &mut expected,
+            )
+            .unwrap();
+            assert_eq!(sealed_segment, &expected);
+        }
+
+        assert_eq!(
+            stream_open(alg, &key_bytes, NONCE_PREFIX, &sealed).unwrap(),
+            message
+        );
+
+        // A single segment message.
+        let single = stream_seal(alg, &key_bytes, NONCE_PREFIX, &[&message]);
+        assert_eq!(
+            stream_open(alg, &key_bytes, NONCE_PREFIX, &single).unwrap(),
+            message
+        );
+
+        // Truncation.
+        assert!(stream_open(alg, &key_bytes, NONCE_PREFIX, &sealed[..3]).is_err());
+        assert!(stream_open(alg, &key_bytes, NONCE_PREFIX, &sealed[..1]).is_err());
+
+        // Reordering.
+        let mut reordered = sealed.clone();
+        reordered.swap(1, 2);
+        assert!(stream_open(alg, &key_bytes, NONCE_PREFIX, &reordered).is_err());
+
+        // Dropping a segment from the middle.
+        let mut dropped = sealed.clone();
+        let _ = dropped.remove(1);
+        assert!(stream_open(alg, &key_bytes, NONCE_PREFIX, &dropped).is_err());
+
+        // Duplicating a segment.
+        let mut duplicated = sealed.clone();
+        duplicated.insert(1, sealed[1].clone());
+        assert!(stream_open(alg, &key_bytes, NONCE_PREFIX, &duplicated).is_err());
+
+        // Extension after the last segment.
+        let mut extended = sealed.clone();
+        extended.push(sealed[0].clone());
+        assert!(stream_open(alg, &key_bytes, NONCE_PREFIX, &extended).is_err());
+
+        // Wrong nonce prefix.
+        assert!(stream_open(
+            alg,
+            &key_bytes,
+            [8; aead::stream::NONCE_PREFIX_LEN],
+            &sealed
+        )
+        .is_err());
+    }
+}
+
+#[test]
+fn test_aead_stream_decryptor_recovers_after_failure() {
+    let alg = &aead::AES_128_GCM;
+    let key_bytes = [4u8; 16];
+    let prefix = [0u8; aead::stream::NONCE_PREFIX_LEN];
+    let sealed = stream_seal(alg, &key_bytes, prefix, &[b"a", b"b", b"c"]);
+
+    let key = aead::UnboundKey::new(alg, &key_bytes).unwrap();
+    let mut decryptor = aead::stream::Decryptor::new(key, prefix);
+
+    // A failure doesn't advance the segment counter.
+    let mut in_out = sealed[1].clone();
+    assert!(decryptor
+        .open_in_place(aead::Aad::empty(), &mut in_out)
+        .is_err());
+
+    let mut in_out = sealed[0].clone();
+    assert_eq!(
+        decryptor
+            .open_in_place(aead::Aad::empty(), &mut in_out)
+            .unwrap(),
+        b"a"
+    );
+    let mut in_out = sealed[1].clone();
+    assert_eq!(
+        decryptor
+            .open_in_place(aead::Aad::empty(), &mut in_out)
+            .unwrap(),
+        b"b"
+    );
+    let mut in_out = sealed[2].clone();
+    assert_eq!(
+        decryptor
+            .open_last_in_place(aead::Aad::empty(), &mut in_out)
+            .unwrap(),
+        b"c"
+    );
+}
+
+#[test]
+fn test_aead_stream_debug() {
+    let key = aead::UnboundKey::new(&aead::AES_256_GCM, &[0; 32]).unwrap();
+    let encryptor = aead::stream::Encryptor::new(key, [0; aead::stream::NONCE_PREFIX_LEN]);
+    assert_eq!(
+        "Encryptor { algorithm: AES_256_GCM }",
+        format!("{:?}", encryptor)
+    );
+
+    let key = aead::UnboundKey::new(&aead::AES_256_GCM, &[0; 32]).unwrap();
+    let decryptor = aead::stream::Decryptor::new(key, [0; aead::stream::NONCE_PREFIX_LEN]);
+    assert_eq!(
+        "Decryptor { algorithm: AES_256_GCM }",
+        format!("{:?}", decryptor)
+    );
+}
+
 fn make_key<K: aead::BoundKey<OneNonceSequence>>(
     algorithm: &'static aead::Algorithm,
     key: &[u8],
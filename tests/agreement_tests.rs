@@ -40,6 +40,9 @@ fn agreement_traits() {
 
     let public_key = private_key.compute_public_key().unwrap();
 
+    test::compile_time_assert_send::<agreement::StaticPrivateKey>();
+    test::compile_time_assert_sync::<agreement::StaticPrivateKey>();
+
     test::compile_time_assert_clone::<agreement::PublicKey>();
     test::compile_time_assert_send::<agreement::PublicKey>();
     test::compile_time_assert_sync::<agreement::PublicKey>();
@@ -81,14 +84,35 @@ fn agreement_agree_ephemeral() {
 
         match test_case.consume_optional_string("Error") {
             None => {
-                let my_private = test_case.consume_bytes("D");
+                let my_private_bytes = test_case.consume_bytes("D");
                 let my_private = {
-                    let rng = test::rand::FixedSliceRandom { bytes: &my_private };
+                    let rng = test::rand::FixedSliceRandom {
+                        bytes: &my_private_bytes,
+                    };
                     agreement::EphemeralPrivateKey::generate(alg, &rng)?
                 };
                 let my_public = test_case.consume_bytes("MyQ");
                 let output = test_case.consume_bytes("Output");
 
+                // A static key with the same private key agrees on the same
+                // value, any number of times.
+                let my_static =
+                    agreement::StaticPrivateKey::from_private_key_bytes(alg, &my_private_bytes)
+                        .unwrap();
+                assert_eq!(my_static.algorithm(), alg);
+                assert_eq!(my_static.public_key().as_ref(), &my_public[..]);
+                assert_eq!(
+                    my_static.private_key_bytes_less_safe(),
+                    &my_private_bytes[..]
+                );
+                for _ in 0..2 {
+                    let result =
+                        agreement::agree_static(&my_static, &peer_public, |key_material| {
+                            assert_eq!(key_material, &output[..]);
+                        });
+                    assert_eq!(result, Ok(()));
+                }
+
                 assert_eq!(my_private.algorithm(), alg);
 
                 let computed_public = my_private.compute_public_key().unwrap();
@@ -118,6 +142,12 @@ fn agreement_agree_ephemeral() {
                     kdf_not_called
                 )
                 .is_err());
+
+                let dummy_static_key = agreement::StaticPrivateKey::generate(alg, &rng)?;
+                assert!(
+                    agreement::agree_static(&dummy_static_key, &peer_public, kdf_not_called)
+                        .is_err()
+                );
             }
         }
 
@@ -125,6 +155,121 @@ fn agreement_agree_ephemeral() {
     });
 }
 
+#[test]
+fn agreement_static_private_key_pkcs8() {
+    let rng = rand::SystemRandom::new();
+
+    for &alg in &[
+        &agreement::X25519,
+        &agreement::ECDH_P256,
+        &agreement::ECDH_P384,
+    ] {
+        let pkcs8 = agreement::StaticPrivateKey::generate_pkcs8(alg, &rng).unwrap();
+        let my_static = agreement::StaticPrivateKey::from_pkcs8(alg, pkcs8.as_ref()).unwrap();
+        assert_eq!(my_static.to_pkcs8().unwrap().as_ref(), pkcs8.as_ref());
+        check_static_private_key(alg, &my_static, &rng);
+
+        // Keys for one algorithm aren't accepted for another.
+        let other = if alg == &agreement::X25519 {
+            &agreement::ECDH_P256
+        } else {
+            &agreement::X25519
+        };
+        assert!(agreement::StaticPrivateKey::from_pkcs8(other, pkcs8.as_ref()).is_err());
+    }
+}
+
+#[test]
+fn agreement_static_private_key_without_pkcs8() {
+    let rng = rand::SystemRandom::new();
+
+    // Static X448 and P-521 keys, as used by HPKE, don't support PKCS#8.
+    for &alg in &[&agreement::X448, &agreement::ECDH_P521] {
+        assert!(agreement::StaticPrivateKey::generate_pkcs8(alg, &rng).is_err());
+
+        let my_static = agreement::StaticPrivateKey::generate(alg, &rng).unwrap();
+        assert!(my_static.to_pkcs8().is_err());
+        check_static_private_key(alg, &my_static, &rng);
+    }
+
+    let pkcs8 = agreement::StaticPrivateKey::generate_pkcs8(&agreement::ECDH_P256, &rng).unwrap();
+    assert!(
+        agreement::StaticPrivateKey::from_pkcs8(&agreement::ECDH_P521, pkcs8.as_ref()).is_err()
+    );
+}
+
+fn check_static_private_key(
+    alg: &'static agreement::Algorithm,
+    my_static: &agreement::StaticPrivateKey,
+    rng: &dyn rand::SecureRandom,
+) {
+    let from_bytes = agreement::StaticPrivateKey::from_private_key_bytes(
+        alg,
+        my_static.private_key_bytes_less_safe(),
+    )
+    .unwrap();
+    assert_eq!(
+        from_bytes.public_key().as_ref(),
+        my_static.public_key().as_ref()
+    );
+
+    // The static key agrees with several ephemeral peers.
+    for _ in 0..2 {
+        let peer_private = agreement::EphemeralPrivateKey::generate(alg, rng).unwrap();
+        let peer_public = peer_private.compute_public_key().unwrap();
+        let peer_public = agreement::UnparsedPublicKey::new(alg, peer_public.as_ref());
+        let my_public = agreement::UnparsedPublicKey::new(alg, my_static.public_key().as_ref());
+
+        let mine = agreement::agree_static(my_static, &peer_public, |k| k.to_vec()).unwrap();
+        let theirs = agreement::agree_ephemeral(peer_private, &my_public, |k| k.to_vec()).unwrap();
+        assert_eq!(mine, theirs);
+    }
+}
+
+#[test]
+fn agreement_static_private_key_from_openssl_pkcs8() {
+    // `openssl genpkey -algorithm X25519` output, which is PKCS#8 v1, for
+    // Alice's private key from RFC 7748 Section 6.1.
+    let x25519 = h(
+        "302e020100300506032b656e0422042077076d0a7318a57d3c16c17251b26645\
+                    df4c2f87ebc0992ab177fba51db92c2a",
+    );
+    let key = agreement::StaticPrivateKey::from_pkcs8(&agreement::X25519, &x25519).unwrap();
+    assert_eq!(
+        key.public_key().as_ref(),
+        &h("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a")[..]
+    );
+
+    // `openssl genpkey -algorithm EC -pkeyopt ec_paramgen_curve:P-256`
+    // converted to PKCS#8 with `openssl pkcs8 -topk8 -nocrypt`.
+    let p256 = h(
+        "308187020100301306072a8648ce3d020106082a8648ce3d030107046d306b0201\
+                  0104206850104ee710f82feedd4f19e66b1f579afba0b07051e838b9d0f2bdff24\
+                  f1bca1440342000439bcf512eaaebac08f6721d6d8cd27f4043f21c2fa2f78b296\
+                  f07234bc4631879b9e0d011837446a4b572cb244f3678e32542886e121614dbec8\
+                  0342427fff4b",
+    );
+    let key = agreement::StaticPrivateKey::from_pkcs8(&agreement::ECDH_P256, &p256).unwrap();
+    assert_eq!(
+        key.public_key().as_ref(),
+        &h(
+            "0439bcf512eaaebac08f6721d6d8cd27f4043f21c2fa2f78b296f07234bc4631\
+            879b9e0d011837446a4b572cb244f3678e32542886e121614dbec80342427fff4b"
+        )[..]
+    );
+    assert!(agreement::StaticPrivateKey::from_pkcs8(&agreement::ECDH_P384, &p256).is_err());
+
+    // An inconsistent public key is rejected.
+    let mut x25519_v2 = agreement::StaticPrivateKey::from_pkcs8(&agreement::X25519, &x25519)
+        .unwrap()
+        .to_pkcs8()
+        .unwrap()
+        .as_ref()
+        .to_vec();
+    *x25519_v2.last_mut().unwrap() ^= 1;
+    assert!(agreement::StaticPrivateKey::from_pkcs8(&agreement::X25519, &x25519_v2).is_err());
+}
+
 #[test]
 fn test_agreement_ecdh_x25519_rfc_iterated() {
     let mut k = h("0900000000000000000000000000000000000000000000000000000000000000");
@@ -177,14 +322,70 @@ fn test_agreement_ecdh_x25519_rfc_iterated() {
     }
 }
 
+#[test]
+fn test_agreement_ecdh_x448_rfc_iterated() {
+    let mut k = h(
+        "0500000000000000000000000000000000000000000000000000000000000000\
+                   000000000000000000000000000000000000000000000000",
+    );
+    let mut u = k.clone();
+
+    fn expect_iterated_x448(
+        expected_result: &str,
+        range: core::ops::Range<usize>,
+        k: &mut Vec<u8>,
+        u: &mut Vec<u8>,
+    ) {
+        for _ in range {
+            let new_k = x448(k, u);
+            *u = k.clone();
+            *k = new_k;
+        }
+        assert_eq!(&h(expected_result), k);
+    }
+
+    expect_iterated_x448(
+        "3f482c8a9f19b01e6c46ee9711d9dc14fd4bf67af30765c2ae2b846a4d23a8cd\
+         0db897086239492caf350b51f833868b9bc2b3bca9cf4113",
+        0..1,
+        &mut k,
+        &mut u,
+    );
+    expect_iterated_x448(
+        "aa3b4749d55b9daf1e5b00288826c467274ce3ebbdd5c17b975e09d4af6c67cf\
+         10d087202db88286e2b79fceea3ec353ef54faa26e219f38",
+        1..1_000,
+        &mut k,
+        &mut u,
+    );
+
+    if cfg!(feature = "slow_tests") {
+        expect_iterated_x448(
+            "077f453681caca3693198420bbe515cae0002472519b3e67661a7e89cab94695\
+             c8f4bcd66e61b9b9c946da8d524de3d69bd9d9d66b997e37",
+            1_000..1_000_000,
+            &mut k,
+            &mut u,
+        );
+    }
+}
+
 fn x25519(private_key: &[u8], public_key: &[u8]) -> Vec<u8> {
-    x25519_(private_key, public_key).unwrap()
+    agree_(&agreement::X25519, private_key, public_key).unwrap()
+}
+
+fn x448(private_key: &[u8], public_key: &[u8]) -> Vec<u8> {
+    agree_(&agreement::X448, private_key, public_key).unwrap()
 }
 
-fn x25519_(private_key: &[u8], public_key: &[u8]) -> Result<Vec<u8>, error::Unspecified> {
+fn agree_(
+    alg: &'static agreement::Algorithm,
+    private_key: &[u8],
+    public_key: &[u8],
+) -> Result<Vec<u8>, error::Unspecified> {
     let rng = test::rand::FixedSliceRandom { bytes: private_key };
-    let private_key = agreement::EphemeralPrivateKey::generate(&agreement::X25519, &rng)?;
-    let public_key = agreement::UnparsedPublicKey::new(&agreement::X25519, public_key);
+    let private_key = agreement::EphemeralPrivateKey::generate(alg, &rng)?;
+    let public_key = agreement::UnparsedPublicKey::new(alg, public_key);
     agreement::agree_ephemeral(private_key, &public_key, |agreed_value| {
         Vec::from(agreed_value)
     })
@@ -204,8 +405,12 @@ fn alg_from_curve_name(curve_name: &str) -> &'static agreement::Algorithm {
         &agreement::ECDH_P256
     } else if curve_name == "P-384" {
         &agreement::ECDH_P384
+    } else if curve_name == "P-521" {
+        &agreement::ECDH_P521
     } else if curve_name == "X25519" {
         &agreement::X25519
+    } else if curve_name == "X448" {
+        &agreement::X448
     } else {
         panic!("Unsupported curve: {}", curve_name);
     }
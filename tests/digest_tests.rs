@@ -20,6 +20,22 @@ use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
 wasm_bindgen_test_configure!(run_in_browser);
 
+const ALL_ALGORITHMS: &[&digest::Algorithm] = &[
+    &digest::SHA1_FOR_LEGACY_USE_ONLY,
+    &digest::SHA224,
+    &digest::SHA256,
+    &digest::SHA384,
+    &digest::SHA512,
+    &digest::SHA512_224,
+    &digest::SHA512_256,
+    &digest::SHA3_256,
+    &digest::SHA3_384,
+    &digest::SHA3_512,
+    &digest::BLAKE2B_256,
+    &digest::BLAKE2B_512,
+    &digest::BLAKE2S_256,
+];
+
 /// Test vectors from BoringSSL, Go, and other sources.
 #[test]
 fn digest_misc() {
@@ -46,6 +62,199 @@ fn digest_misc() {
     });
 }
 
+#[test]
+fn digest_export_state() {
+    let input: Vec<u8> = (0..=255).cycle().take(300).collect();
+
+    for &alg in ALL_ALGORITHMS {
+        let expected = digest::digest(alg, &input);
+        for split in 0..=input.len() {
+            let (first, second) = input.split_at(split);
+            let mut ctx = digest::Context::new(alg);
+            ctx.update(first);
+            let state = ctx.export_state();
+
+            let mut ctx = digest::Context::restore(alg, state.as_ref()).unwrap();
+            ctx.update(second);
+            assert_eq!(expected.as_ref(), ctx.finish().as_ref());
+        }
+    }
+
+    let mut ctx = digest::Context::new_keyed(&digest::BLAKE2S_256, b"key").unwrap();
+    ctx.update(b"hello");
+    let state = ctx.export_state();
+    let mut ctx = digest::Context::restore(&digest::BLAKE2S_256, state.as_ref()).unwrap();
+    ctx.update(b", world");
+    let mut expected = digest::Context::new_keyed(&digest::BLAKE2S_256, b"key").unwrap();
+    expected.update(b"hello, world");
+    assert_eq!(expected.finish().as_ref(), ctx.finish().as_ref());
+}
+
+#[test]
+fn digest_restore_malformed_state() {
+    for &alg in ALL_ALGORITHMS {
+        let mut ctx = digest::Context::new(alg);
+        ctx.update(b"hello");
+        let state = ctx.export_state();
+        let state = state.as_ref();
+        assert!(digest::Context::restore(alg, state).is_ok());
+
+        // Truncated or extended.
+        assert!(digest::Context::restore(alg, &[]).is_err());
+        assert!(digest::Context::restore(alg, &state[..state.len() - 1]).is_err());
+        let mut extended = state.to_vec();
+        extended.push(0);
+        assert!(digest::Context::restore(alg, &extended).is_err());
+
+        // Too many pending bytes.
+        let mut state = digest::Context::new(alg).export_state().as_ref().to_vec();
+        state[8..10].copy_from_slice(&u16::to_be_bytes(alg.block_len() as u16 + 1));
+        state.extend(vec![0; alg.block_len() + 1]);
+        assert!(digest::Context::restore(alg, &state).is_err());
+
+        // Too much input for the length in bits to fit in a `u64`.
+        let block_len = alg.block_len() as u64;
+        let max_blocks = u64::MAX / 8 / block_len;
+        let mut state = digest::Context::new(alg).export_state().as_ref().to_vec();
+        state[..8].copy_from_slice(&u64::to_be_bytes(max_blocks));
+        assert!(digest::Context::restore(alg, &state).is_ok());
+        for &blocks in &[max_blocks + 1, u64::MAX / block_len, u64::MAX] {
+            state[..8].copy_from_slice(&u64::to_be_bytes(blocks));
+            assert!(digest::Context::restore(alg, &state).is_err());
+        }
+    }
+}
+
+#[test]
+fn digest_sha256_batch() {
+    for &len in &[0, 1, 55, 56, 63, 64, 65, 119, 120, 128, 200] {
+        for &count in &[0, 1, 3, 4, 5, 8, 9, 17] {
+            let messages: Vec<Vec<u8>> = (0..count)
+                .map(|i| (0..len).map(|j| (i * 31 + j) as u8).collect())
+                .collect();
+            let messages: Vec<&[u8]> = messages.iter().map(|m| &m[..]).collect();
+            let mut digests = vec![[0u8; digest::SHA256_OUTPUT_LEN]; count];
+            digest::sha256_batch(&messages, &mut digests).unwrap();
+            for (message, actual) in messages.iter().zip(digests.iter()) {
+                let expected = digest::digest(&digest::SHA256, message);
+                assert_eq!(expected.as_ref(), &actual[..]);
+            }
+        }
+    }
+}
+
+#[test]
+fn digest_sha256_batch_rejects_mismatched_lengths() {
+    let mut digests = [[0u8; digest::SHA256_OUTPUT_LEN]; 2];
+    assert!(digest::sha256_batch(&[b"abc", b"abcd"], &mut digests).is_err());
+    assert!(digest::sha256_batch(&[b"abc"], &mut digests).is_err());
+    assert!(digest::sha256_batch(&[b"abc", b"def", b"ghi"], &mut digests).is_err());
+    assert!(digest::sha256_batch(&[b"abc", b"def"], &mut digests).is_ok());
+}
+
+#[test]
+fn digest_blake2_keyed() {
+    test::run(
+        test_file!("digest_blake2_keyed_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let digest_alg = test_case.consume_digest_alg("Hash").unwrap();
+            let key = test_case.consume_bytes("Key");
+            let input = test_case.consume_bytes("Input");
+            let expected = test_case.consume_bytes("Output");
+
+            let mut ctx = digest::Context::new_keyed(digest_alg, &key).unwrap();
+            ctx.update(&input);
+            assert_eq!(&expected, &ctx.finish().as_ref());
+
+            let mut ctx = digest::Context::new_keyed(digest_alg, &key).unwrap();
+            for b in &input {
+                ctx.update(core::slice::from_ref(b));
+            }
+            assert_eq!(&expected, &ctx.finish().as_ref());
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn digest_keyed_key_len() {
+    for (alg, max_key_len) in [
+        (&digest::BLAKE2B_256, 64),
+        (&digest::BLAKE2B_512, 64),
+        (&digest::BLAKE2S_256, 32),
+    ] {
+        assert!(digest::Context::new_keyed(alg, &[]).is_err());
+        assert!(digest::Context::new_keyed(alg, &[0; 1]).is_ok());
+        assert!(digest::Context::new_keyed(alg, &vec![0; max_key_len]).is_ok());
+        assert!(digest::Context::new_keyed(alg, &vec![0; max_key_len + 1]).is_err());
+    }
+
+    for alg in [&digest::SHA256, &digest::SHA512, &digest::SHA3_256] {
+        assert!(digest::Context::new_keyed(alg, &[0; 16]).is_err());
+    }
+}
+
+#[test]
+fn digest_shake() {
+    test::run(
+        test_file!("digest_shake_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let algorithm = match test_case.consume_string("Hash").as_str() {
+                "SHAKE128" => &digest::SHAKE128,
+                "SHAKE256" => &digest::SHAKE256,
+                name => panic!("Unsupported XOF algorithm: {}", name),
+            };
+            let input = test_case.consume_bytes("Input");
+            let expected = test_case.consume_bytes("Output");
+
+            // One update and one squeeze.
+            {
+                let mut ctx = digest::XofContext::new(algorithm);
+                ctx.update(&input);
+                let mut actual = vec![0u8; expected.len()];
+                ctx.finalize().squeeze(&mut actual);
+                assert_eq!(actual, expected);
+            }
+
+            // Byte-by-byte updates and squeezes.
+            {
+                let mut ctx = digest::XofContext::new(algorithm);
+                for b in &input {
+                    ctx.update(core::slice::from_ref(b));
+                }
+                let mut reader = ctx.finalize();
+                let mut actual = vec![0u8; expected.len()];
+                for b in actual.iter_mut() {
+                    reader.squeeze(core::slice::from_mut(b));
+                }
+                assert_eq!(actual, expected);
+            }
+
+            // A shorter output is a prefix of a longer one.
+            {
+                let mut ctx = digest::XofContext::new(algorithm);
+                ctx.update(&input);
+                let mut actual = vec![0u8; expected.len() / 2];
+                ctx.finalize().squeeze(&mut actual);
+                assert_eq!(actual, &expected[..actual.len()]);
+            }
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn digest_xof_fmt() {
+    let ctx = digest::XofContext::new(&digest::SHAKE256);
+    assert_eq!("XofContext { algorithm: SHAKE256 }", format!("{:?}", ctx));
+    let reader = ctx.finalize();
+    assert_eq!("XofReader { algorithm: SHAKE256 }", format!("{:?}", reader));
+}
+
 /// Test some ways in which `Context::update` and/or `Context::finish`
 /// could go wrong by testing every combination of updating three inputs
 /// that vary from zero bytes to one byte larger than the block length.
@@ -56,8 +265,8 @@ macro_rules! test_i_u_f {
         #[cfg(not(debug_assertions))]
         #[test]
         fn $test_name() {
-            let mut input = [0; (digest::MAX_BLOCK_LEN + 1) * 3];
             let max = $alg.block_len() + 1;
+            let mut input = vec![0; max * 3];
             for i in 0..(max * 3) {
                 input[i] = (i & 0xff) as u8;
             }
@@ -85,9 +294,17 @@ macro_rules! test_i_u_f {
     };
 }
 test_i_u_f!(digest_test_i_u_f_sha1, digest::SHA1_FOR_LEGACY_USE_ONLY);
+test_i_u_f!(digest_test_i_u_f_sha224, digest::SHA224);
 test_i_u_f!(digest_test_i_u_f_sha256, digest::SHA256);
 test_i_u_f!(digest_test_i_u_f_sha384, digest::SHA384);
 test_i_u_f!(digest_test_i_u_f_sha512, digest::SHA512);
+test_i_u_f!(digest_test_i_u_f_sha512_224, digest::SHA512_224);
+test_i_u_f!(digest_test_i_u_f_sha3_256, digest::SHA3_256);
+test_i_u_f!(digest_test_i_u_f_sha3_384, digest::SHA3_384);
+test_i_u_f!(digest_test_i_u_f_sha3_512, digest::SHA3_512);
+test_i_u_f!(digest_test_i_u_f_blake2b_256, digest::BLAKE2B_256);
+test_i_u_f!(digest_test_i_u_f_blake2b_512, digest::BLAKE2B_512);
+test_i_u_f!(digest_test_i_u_f_blake2s_256, digest::BLAKE2S_256);
 
 /// See https://bugzilla.mozilla.org/show_bug.cgi?id=610162. This tests the
 /// calculation of 8GB of the byte 123.
@@ -186,10 +403,20 @@ test_large_digest!(
 #[test]
 fn test_fmt_algorithm() {
     assert_eq!("SHA1", &format!("{:?}", digest::SHA1_FOR_LEGACY_USE_ONLY));
+    assert_eq!("SHA224", &format!("{:?}", digest::SHA224));
     assert_eq!("SHA256", &format!("{:?}", digest::SHA256));
     assert_eq!("SHA384", &format!("{:?}", digest::SHA384));
     assert_eq!("SHA512", &format!("{:?}", digest::SHA512));
+    assert_eq!("SHA512_224", &format!("{:?}", digest::SHA512_224));
     assert_eq!("SHA512_256", &format!("{:?}", digest::SHA512_256));
+    assert_eq!("SHA3_256", &format!("{:?}", digest::SHA3_256));
+    assert_eq!("SHA3_384", &format!("{:?}", digest::SHA3_384));
+    assert_eq!("SHA3_512", &format!("{:?}", digest::SHA3_512));
+    assert_eq!("BLAKE2B_256", &format!("{:?}", digest::BLAKE2B_256));
+    assert_eq!("BLAKE2B_512", &format!("{:?}", digest::BLAKE2B_512));
+    assert_eq!("BLAKE2S_256", &format!("{:?}", digest::BLAKE2S_256));
+    assert_eq!("SHAKE128", &format!("{:?}", digest::SHAKE128));
+    assert_eq!("SHAKE256", &format!("{:?}", digest::SHAKE256));
 }
 
 #[test]
@@ -201,6 +428,11 @@ fn digest_test_fmt() {
             digest::digest(&digest::SHA1_FOR_LEGACY_USE_ONLY, b"hello, world")
         )
     );
+    assert_eq!(
+        "SHA224:6e1a93e32fb44081a401f3db3ef2e6e108b7bb\
+         eeb5705afdaf01fb27",
+        &format!("{:?}", digest::digest(&digest::SHA224, b"hello, world"))
+    );
     assert_eq!(
         "SHA256:09ca7e4eaa6e8ae9c7d261167129184883644d\
          07dfba7cbfbc4c8a2e08360d5b",
@@ -219,6 +451,11 @@ fn digest_test_fmt() {
         &format!("{:?}", digest::digest(&digest::SHA512, b"hello, world"))
     );
 
+    assert_eq!(
+        "SHA512_224:7cbabb02ab4083f5b270bdd94705137aa3\
+         aaa6260a8e041b0f8f3046",
+        &format!("{:?}", digest::digest(&digest::SHA512_224, b"hello, world"))
+    );
     assert_eq!(
         "SHA512_256:11f2c88c04f0a9c3d0970894ad2472505e\
          0bc6e8c7ec46b5211cd1fa3e253e62",
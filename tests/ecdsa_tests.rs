@@ -13,7 +13,7 @@
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
 use ring::{
-    rand,
+    digest, rand,
     signature::{self, KeyPair},
     test, test_file,
 };
@@ -51,6 +51,26 @@ fn ecdsa_from_pkcs8_test() {
                         &signature::ECDSA_P256_SHA256_ASN1_SIGNING,
                     ),
                 ),
+                "P-521" => (
+                    (
+                        &signature::ECDSA_P521_SHA512_FIXED_SIGNING,
+                        &signature::ECDSA_P521_SHA512_ASN1_SIGNING,
+                    ),
+                    (
+                        &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+                        &signature::ECDSA_P384_SHA384_ASN1_SIGNING,
+                    ),
+                ),
+                "secp256k1" => (
+                    (
+                        &signature::ECDSA_SECP256K1_SHA256_FIXED_SIGNING,
+                        &signature::ECDSA_SECP256K1_SHA256_ASN1_SIGNING,
+                    ),
+                    (
+                        &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+                        &signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+                    ),
+                ),
                 _ => unreachable!(),
             };
 
@@ -96,6 +116,12 @@ fn ecdsa_generate_pkcs8_test() {
         &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
         &signature::ECDSA_P384_SHA384_ASN1_SIGNING,
         &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+        &signature::ECDSA_P521_SHA512_ASN1_SIGNING,
+        &signature::ECDSA_P521_SHA512_FIXED_SIGNING,
+        &signature::ECDSA_SECP256K1_SHA256_ASN1_SIGNING,
+        &signature::ECDSA_SECP256K1_SHA256_FIXED_SIGNING,
+        &signature::ECDSA_SECP256K1_SHA256_LOW_S_ASN1_SIGNING,
+        &signature::ECDSA_SECP256K1_SHA256_LOW_S_FIXED_SIGNING,
     ] {
         let pkcs8 = signature::EcdsaKeyPair::generate_pkcs8(alg, &rng).unwrap();
         println!();
@@ -125,19 +151,25 @@ fn signature_ecdsa_verify_asn1_test() {
             let is_valid = test_case.consume_string("Result") == "P (0 )";
 
             let alg = match (curve_name.as_str(), digest_name.as_str()) {
+                ("P-256", "SHA224") => &signature::ECDSA_P256_SHA224_ASN1,
                 ("P-256", "SHA256") => &signature::ECDSA_P256_SHA256_ASN1,
                 ("P-256", "SHA384") => &signature::ECDSA_P256_SHA384_ASN1,
+                ("P-384", "SHA224") => &signature::ECDSA_P384_SHA224_ASN1,
                 ("P-384", "SHA256") => &signature::ECDSA_P384_SHA256_ASN1,
                 ("P-384", "SHA384") => &signature::ECDSA_P384_SHA384_ASN1,
+                ("P-521", "SHA512") => &signature::ECDSA_P521_SHA512_ASN1,
+                ("secp256k1", "SHA256") => &signature::ECDSA_SECP256K1_SHA256_ASN1,
                 _ => {
                     panic!("Unsupported curve+digest: {}+{}", curve_name, digest_name);
                 }
             };
 
-            let actual_result =
-                signature::UnparsedPublicKey::new(alg, &public_key).verify(&msg, &sig);
+            let public_key = signature::UnparsedPublicKey::new(alg, &public_key);
+            let actual_result = public_key.verify(&msg, &sig);
             assert_eq!(actual_result.is_ok(), is_valid);
 
+            check_verify_digest(&public_key, &digest_name, &msg, &sig, actual_result);
+
             Ok(())
         },
     );
@@ -161,6 +193,8 @@ fn signature_ecdsa_verify_fixed_test() {
             let alg = match (curve_name.as_str(), digest_name.as_str()) {
                 ("P-256", "SHA256") => &signature::ECDSA_P256_SHA256_FIXED,
                 ("P-384", "SHA384") => &signature::ECDSA_P384_SHA384_FIXED,
+                ("P-521", "SHA512") => &signature::ECDSA_P521_SHA512_FIXED,
+                ("secp256k1", "SHA256") => &signature::ECDSA_SECP256K1_SHA256_FIXED,
                 _ => {
                     panic!("Unsupported curve+digest: {}+{}", curve_name, digest_name);
                 }
@@ -168,10 +202,12 @@ fn signature_ecdsa_verify_fixed_test() {
 
             let is_valid = expected_result == "P (0 )";
 
-            let actual_result =
-                signature::UnparsedPublicKey::new(alg, &public_key).verify(&msg, &sig);
+            let public_key = signature::UnparsedPublicKey::new(alg, &public_key);
+            let actual_result = public_key.verify(&msg, &sig);
             assert_eq!(actual_result.is_ok(), is_valid);
 
+            check_verify_digest(&public_key, &digest_name, &msg, &sig, actual_result);
+
             Ok(())
         },
     );
@@ -244,6 +280,14 @@ fn signature_ecdsa_sign_fixed_sign_and_verify_test() {
                     &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
                     &signature::ECDSA_P384_SHA384_FIXED,
                 ),
+                ("P-521", "SHA512") => (
+                    &signature::ECDSA_P521_SHA512_FIXED_SIGNING,
+                    &signature::ECDSA_P521_SHA512_FIXED,
+                ),
+                ("secp256k1", "SHA256") => (
+                    &signature::ECDSA_SECP256K1_SHA256_FIXED_SIGNING,
+                    &signature::ECDSA_SECP256K1_SHA256_FIXED,
+                ),
                 _ => {
                     panic!("Unsupported curve+digest: {}+{}", curve_name, digest_name);
                 }
@@ -258,6 +302,16 @@ fn signature_ecdsa_sign_fixed_sign_and_verify_test() {
             let public_key = signature::UnparsedPublicKey::new(verification_alg, q);
             assert_eq!(public_key.verify(&msg, signature.as_ref()), Ok(()));
 
+            // Sign the digest instead of the message.
+            let h = digest::digest(digest_alg(&digest_name), &msg);
+            let signature = private_key.sign_digest(&rng, h).unwrap();
+            assert_eq!(public_key.verify(&msg, signature.as_ref()), Ok(()));
+            assert_eq!(public_key.verify_digest(h, signature.as_ref()), Ok(()));
+
+            // The digest algorithm must match the signing algorithm.
+            let wrong_h = digest::digest(&digest::SHA1_FOR_LEGACY_USE_ONLY, &msg);
+            assert!(private_key.sign_digest(&rng, wrong_h).is_err());
+
             Ok(())
         },
     );
@@ -298,6 +352,14 @@ fn signature_ecdsa_sign_asn1_test() {
                     &signature::ECDSA_P384_SHA384_ASN1_SIGNING,
                     &signature::ECDSA_P384_SHA384_ASN1,
                 ),
+                ("P-521", "SHA512") => (
+                    &signature::ECDSA_P521_SHA512_ASN1_SIGNING,
+                    &signature::ECDSA_P521_SHA512_ASN1,
+                ),
+                ("secp256k1", "SHA256") => (
+                    &signature::ECDSA_SECP256K1_SHA256_ASN1_SIGNING,
+                    &signature::ECDSA_SECP256K1_SHA256_ASN1,
+                ),
                 _ => {
                     panic!("Unsupported curve+digest: {}+{}", curve_name, digest_name);
                 }
@@ -316,3 +378,125 @@ fn signature_ecdsa_sign_asn1_test() {
         },
     );
 }
+
+#[test]
+fn signature_ecdsa_secp256k1_low_s_test() {
+    // n - 1, big-endian.
+    const N_MINUS_1: [u8; 32] = [
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xfe, 0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36,
+        0x41, 0x40,
+    ];
+
+    // Returns `n - s` given a big-endian `s` in [1, n).
+    fn negated(s: &[u8]) -> [u8; 32] {
+        // n - s == (n - 1 - s) + 1, and (n - 1 - s) doesn't borrow.
+        let mut r = [0u8; 32];
+        let mut borrow = 0u8;
+        for i in (0..32).rev() {
+            let (d, b1) = N_MINUS_1[i].overflowing_sub(s[i]);
+            let (d, b2) = d.overflowing_sub(borrow);
+            r[i] = d;
+            borrow = u8::from(b1 | b2);
+        }
+        assert_eq!(borrow, 0);
+        for b in r.iter_mut().rev() {
+            let (sum, carry) = b.overflowing_add(1);
+            *b = sum;
+            if !carry {
+                break;
+            }
+        }
+        r
+    }
+
+    fn is_low(s: &[u8]) -> bool {
+        // (n - 1) / 2, big-endian.
+        let mut half = N_MINUS_1;
+        let mut carry = 0;
+        for b in half.iter_mut() {
+            let new_carry = *b & 1;
+            *b = (*b >> 1) | (carry << 7);
+            carry = new_carry;
+        }
+        s <= &half[..]
+    }
+
+    let rng = rand::SystemRandom::new();
+
+    test::run(
+        test_file!("../src/ec/suite_b/ecdsa/ecdsa_sign_fixed_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let curve_name = test_case.consume_string("Curve");
+            let _digest_name = test_case.consume_string("Digest");
+            let msg = test_case.consume_bytes("Msg");
+            let d = test_case.consume_bytes("d");
+            let q = test_case.consume_bytes("Q");
+            let _k = test_case.consume_bytes("k");
+            let _expected_result = test_case.consume_bytes("Sig");
+
+            if curve_name != "secp256k1" {
+                return Ok(());
+            }
+
+            let key_pair = signature::EcdsaKeyPair::from_private_key_and_public_key(
+                &signature::ECDSA_SECP256K1_SHA256_LOW_S_FIXED_SIGNING,
+                &d,
+                &q,
+                &rng,
+            )
+            .unwrap();
+            let public_key = signature::UnparsedPublicKey::new(
+                &signature::ECDSA_SECP256K1_SHA256_LOW_S_FIXED,
+                &q,
+            );
+            let lenient_public_key =
+                signature::UnparsedPublicKey::new(&signature::ECDSA_SECP256K1_SHA256_FIXED, &q);
+
+            for _ in 0..8 {
+                let sig = key_pair.sign(&rng, &msg).unwrap();
+                let (r, s) = sig.as_ref().split_at(32);
+                assert!(is_low(s));
+                assert_eq!(public_key.verify(&msg, sig.as_ref()), Ok(()));
+                assert_eq!(lenient_public_key.verify(&msg, sig.as_ref()), Ok(()));
+
+                // The high-S form of the same signature is valid, but it is
+                // rejected by the low-S verification algorithm.
+                let mut high_s_sig = r.to_vec();
+                high_s_sig.extend_from_slice(&negated(s));
+                assert!(public_key.verify(&msg, &high_s_sig).is_err());
+                assert_eq!(lenient_public_key.verify(&msg, &high_s_sig), Ok(()));
+            }
+
+            Ok(())
+        },
+    );
+}
+
+fn digest_alg(name: &str) -> &'static digest::Algorithm {
+    match name {
+        "SHA224" => &digest::SHA224,
+        "SHA256" => &digest::SHA256,
+        "SHA384" => &digest::SHA384,
+        "SHA512" => &digest::SHA512,
+        _ => panic!("Unsupported digest: {}", name),
+    }
+}
+
+// Checks that verifying the digest of `msg` gives the same result as verifying
+// `msg`, and that a digest computed with the wrong algorithm is rejected.
+fn check_verify_digest(
+    public_key: &signature::UnparsedPublicKey<&Vec<u8>>,
+    digest_name: &str,
+    msg: &[u8],
+    sig: &[u8],
+    expected_result: Result<(), ring::error::Unspecified>,
+) {
+    let h = digest::digest(digest_alg(digest_name), msg);
+    assert_eq!(public_key.verify_digest(h, sig), expected_result);
+
+    let wrong_h = digest::digest(&digest::SHA1_FOR_LEGACY_USE_ONLY, msg);
+    assert!(public_key.verify_digest(wrong_h, sig).is_err());
+}
@@ -13,7 +13,7 @@
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
 use ring::{
-    error, rand,
+    digest, error, rand,
     signature::{self, Ed25519KeyPair, KeyPair},
     test, test_file,
 };
@@ -83,12 +83,249 @@ fn test_signature_ed25519_verify() {
                 "F" => Err(error::Unspecified),
                 s => panic!("{:?} is not a valid result", s),
             };
+            // Only used by `test_signature_ed25519_verify_batch`.
+            let _ = test_case.consume_optional_string("CofactoredResult");
             test_signature_verification(&public_key, &msg, &sig, expected_result);
             Ok(())
         },
     );
 }
 
+#[test]
+fn test_signature_ed25519_verify_batch() {
+    struct Item {
+        public_key: Vec<u8>,
+        msg: Vec<u8>,
+        sig: Vec<u8>,
+        valid: bool,
+    }
+
+    let mut items = Vec::new();
+    test::run(test_file!("ed25519_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        let _ = test_case.consume_bytes("SEED");
+        let public_key = test_case.consume_bytes("PUB");
+        let msg = test_case.consume_bytes("MESSAGE");
+        let sig = test_case.consume_bytes("SIG");
+        items.push(Item {
+            public_key,
+            msg,
+            sig,
+            valid: true,
+        });
+        Ok(())
+    });
+    test::run(
+        test_file!("ed25519_verify_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let public_key = test_case.consume_bytes("PUB");
+            let msg = test_case.consume_bytes("MESSAGE");
+            let sig = test_case.consume_bytes("SIG");
+            let result = test_case.consume_string("Result");
+            let valid = test_case
+                .consume_optional_string("CofactoredResult")
+                .unwrap_or(result)
+                == "P";
+            items.push(Item {
+                public_key,
+                msg,
+                sig,
+                valid,
+            });
+            Ok(())
+        },
+    );
+
+    let rng = rand::SystemRandom::new();
+    fn batch(items: &[Item]) -> Vec<signature::Ed25519BatchItem<'_>> {
+        items
+            .iter()
+            .map(|item| signature::Ed25519BatchItem {
+                public_key: &item.public_key,
+                msg: &item.msg,
+                signature: &item.sig,
+            })
+            .collect()
+    }
+
+    // All the valid signatures.
+    let valid: Vec<Item> = items
+        .iter()
+        .filter(|item| item.valid)
+        .map(|item| Item {
+            public_key: item.public_key.clone(),
+            msg: item.msg.clone(),
+            sig: item.sig.clone(),
+            valid: true,
+        })
+        .collect();
+    assert_eq!(
+        signature::ED25519.verify_batch(&batch(&valid), &rng),
+        Ok(())
+    );
+    assert_eq!(signature::ED25519.verify_batch(&[], &rng), Ok(()));
+
+    // The invalid signatures are identified.
+    let expected_invalid: Vec<usize> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| !item.valid)
+        .map(|(i, _)| i)
+        .collect();
+    assert!(!expected_invalid.is_empty());
+    let err = signature::ED25519
+        .verify_batch(&batch(&items), &rng)
+        .unwrap_err();
+    assert_eq!(err.invalid_indices(), &expected_invalid[..]);
+
+    // Each invalid signature is identified on its own.
+    for &i in &expected_invalid {
+        let mut contaminated = batch(&valid);
+        contaminated.push(signature::Ed25519BatchItem {
+            public_key: &items[i].public_key,
+            msg: &items[i].msg,
+            signature: &items[i].sig,
+        });
+        let err = signature::ED25519
+            .verify_batch(&contaminated, &rng)
+            .unwrap_err();
+        assert_eq!(err.invalid_indices(), &[valid.len()]);
+    }
+
+    // Tampering with any one of a valid batch is detected.
+    for i in [0, valid.len() / 2, valid.len() - 1] {
+        let mut tampered = batch(&valid);
+        let mut sig = valid[i].sig.clone();
+        sig[32] ^= 1; // Change S.
+        tampered[i].signature = &sig;
+        let err = signature::ED25519
+            .verify_batch(&tampered, &rng)
+            .unwrap_err();
+        assert_eq!(err.invalid_indices(), &[i]);
+    }
+}
+
+/// Test vectors from RFC 8032 Sections 7.2 and 7.3.
+#[test]
+fn test_signature_ed25519_ctx_ph() {
+    test::run(
+        test_file!("ed25519_ctx_ph_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let variant = test_case.consume_string("Variant");
+            let seed = test_case.consume_bytes("SEED");
+            let public_key = test_case.consume_bytes("PUB");
+            let msg = test_case.consume_bytes("MESSAGE");
+            let context = test_case.consume_bytes("CONTEXT");
+            let expected_sig = test_case.consume_bytes("SIG");
+
+            let key_pair = Ed25519KeyPair::from_seed_and_public_key(&seed, &public_key).unwrap();
+            let h = digest::digest(&digest::SHA512, &msg);
+
+            type VerifyWithContext =
+                fn(&[u8], &[u8], &[u8], &[u8]) -> Result<(), error::Unspecified>;
+            let (verify_with_context, actual_sig): (VerifyWithContext, _) = match variant.as_str() {
+                "Ed25519ctx" => {
+                    // The context must not be empty.
+                    assert!(key_pair.sign_ctx(&[], &msg).is_err());
+                    (
+                        |public_key, context, msg, sig| {
+                            signature::ED25519.verify_with_context(public_key, context, msg, sig)
+                        },
+                        key_pair.sign_ctx(&context, &msg).unwrap(),
+                    )
+                }
+                "Ed25519ph" => {
+                    let actual_sig = key_pair.sign_ph(&context, &msg).unwrap();
+                    let digest_sig = key_pair.sign_ph_digest(&context, h).unwrap();
+                    assert_eq!(actual_sig.as_ref(), digest_sig.as_ref());
+                    assert!(key_pair
+                        .sign_ph_digest(&context, digest::digest(&digest::SHA256, &msg))
+                        .is_err());
+                    assert_eq!(
+                        signature::ED25519PH.verify_digest_with_context(
+                            &public_key,
+                            &context,
+                            h,
+                            &expected_sig
+                        ),
+                        Ok(())
+                    );
+                    if context.is_empty() {
+                        let public_key =
+                            signature::UnparsedPublicKey::new(&signature::ED25519PH, &public_key);
+                        assert_eq!(public_key.verify(&msg, &expected_sig), Ok(()));
+                        assert_eq!(public_key.verify_digest(h, &expected_sig), Ok(()));
+                    }
+                    (
+                        |public_key, context, msg, sig| {
+                            signature::ED25519PH.verify_with_context(public_key, context, msg, sig)
+                        },
+                        actual_sig,
+                    )
+                }
+                variant => panic!("Unsupported variant: {}", variant),
+            };
+            assert_eq!(&expected_sig[..], actual_sig.as_ref());
+
+            assert_eq!(
+                verify_with_context(&public_key, &context, &msg, &expected_sig),
+                Ok(())
+            );
+
+            // The signature is bound to the variant and to the context.
+            let mut wrong_context = context.clone();
+            wrong_context.push(0);
+            assert!(verify_with_context(&public_key, &wrong_context, &msg, &expected_sig).is_err());
+            assert!(
+                signature::UnparsedPublicKey::new(&signature::ED25519, &public_key)
+                    .verify(&msg, &expected_sig)
+                    .is_err()
+            );
+            let mut tampered_msg = msg.clone();
+            tampered_msg.push(0);
+            assert!(
+                verify_with_context(&public_key, &context, &tampered_msg, &expected_sig).is_err()
+            );
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn test_ed25519_context_too_long() {
+    let rng = rand::SystemRandom::new();
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+    let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+    let public_key = key_pair.public_key().as_ref();
+
+    let context = vec![0x5a; signature::ED25519_MAX_CONTEXT_LEN + 1];
+    let context = &context[..];
+    let max_context = &context[..signature::ED25519_MAX_CONTEXT_LEN];
+
+    let sig = key_pair.sign_ctx(max_context, b"msg").unwrap();
+    assert_eq!(
+        signature::ED25519.verify_with_context(public_key, max_context, b"msg", sig.as_ref()),
+        Ok(())
+    );
+    assert!(key_pair.sign_ctx(context, b"msg").is_err());
+    assert!(signature::ED25519
+        .verify_with_context(public_key, context, b"msg", sig.as_ref())
+        .is_err());
+
+    let sig = key_pair.sign_ph(max_context, b"msg").unwrap();
+    assert_eq!(
+        signature::ED25519PH.verify_with_context(public_key, max_context, b"msg", sig.as_ref()),
+        Ok(())
+    );
+    assert!(key_pair.sign_ph(context, b"msg").is_err());
+    assert!(signature::ED25519PH
+        .verify_with_context(public_key, context, b"msg", sig.as_ref())
+        .is_err());
+}
+
 fn test_signature_verification(
     public_key: &[u8],
     msg: &[u8],
@@ -99,6 +336,15 @@ fn test_signature_verification(
         expected_result,
         signature::UnparsedPublicKey::new(&signature::ED25519, public_key).verify(msg, sig)
     );
+
+    // Ed25519 signs the message itself, not its digest, so verifying a digest
+    // always fails.
+    let h = digest::digest(&digest::SHA512, msg);
+    assert!(
+        signature::UnparsedPublicKey::new(&signature::ED25519, public_key)
+            .verify_digest(h, sig)
+            .is_err()
+    );
 }
 
 #[test]
@@ -0,0 +1,270 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use ring::{
+    digest, error, rand,
+    signature::{self, Ed448KeyPair, KeyPair},
+    test, test_file,
+};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Test vectors from RFC 8032 Section 7.4, and ones generated with OpenSSL.
+#[test]
+fn test_signature_ed448() {
+    test::run(test_file!("ed448_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        let seed = test_case.consume_bytes("SEED");
+        assert_eq!(57, seed.len());
+
+        let public_key = test_case.consume_bytes("PUB");
+        assert_eq!(signature::ED448_PUBLIC_KEY_LEN, public_key.len());
+
+        let msg = test_case.consume_bytes("MESSAGE");
+
+        let expected_sig = test_case.consume_bytes("SIG");
+
+        {
+            let key_pair = Ed448KeyPair::from_seed_and_public_key(&seed, &public_key).unwrap();
+            let actual_sig = key_pair.sign(&msg);
+            assert_eq!(&expected_sig[..], actual_sig.as_ref());
+        }
+
+        // Test PKCS#8 generation, parsing, and private-to-public calculations.
+        let rng = test::rand::FixedSliceRandom { bytes: &seed };
+        let pkcs8 = Ed448KeyPair::generate_pkcs8(&rng).unwrap();
+        let key_pair = Ed448KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        assert_eq!(public_key, key_pair.public_key().as_ref());
+
+        // Test Signature generation.
+        let actual_sig = key_pair.sign(&msg);
+        assert_eq!(&expected_sig[..], actual_sig.as_ref());
+
+        // An empty context is the same as no context.
+        let actual_sig = key_pair.sign_ctx(&[], &msg).unwrap();
+        assert_eq!(&expected_sig[..], actual_sig.as_ref());
+
+        // Test Signature verification.
+        test_signature_verification(&public_key, &msg, &expected_sig, Ok(()));
+        assert_eq!(
+            signature::ED448.verify_with_context(&public_key, &[], &msg, &expected_sig),
+            Ok(())
+        );
+
+        let mut tampered_sig = expected_sig;
+        tampered_sig[0] ^= 1;
+
+        test_signature_verification(&public_key, &msg, &tampered_sig, Err(error::Unspecified));
+
+        Ok(())
+    });
+}
+
+/// Test vectors from RFC 8032 Sections 7.4 and 7.5, and ones generated with
+/// OpenSSL.
+#[test]
+fn test_signature_ed448_ctx_ph() {
+    test::run(
+        test_file!("ed448_ctx_ph_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let variant = test_case.consume_string("Variant");
+            let seed = test_case.consume_bytes("SEED");
+            let public_key = test_case.consume_bytes("PUB");
+            let msg = test_case.consume_bytes("MESSAGE");
+            let context = test_case.consume_bytes("CONTEXT");
+            let expected_sig = test_case.consume_bytes("SIG");
+
+            let key_pair = Ed448KeyPair::from_seed_and_public_key(&seed, &public_key).unwrap();
+
+            type VerifyWithContext =
+                fn(&[u8], &[u8], &[u8], &[u8]) -> Result<(), error::Unspecified>;
+            let (verify_with_context, actual_sig): (VerifyWithContext, _) = match variant.as_str() {
+                "Ed448" => (
+                    |public_key, context, msg, sig| {
+                        signature::ED448.verify_with_context(public_key, context, msg, sig)
+                    },
+                    key_pair.sign_ctx(&context, &msg).unwrap(),
+                ),
+                "Ed448ph" => {
+                    if context.is_empty() {
+                        let public_key =
+                            signature::UnparsedPublicKey::new(&signature::ED448PH, &public_key);
+                        assert_eq!(public_key.verify(&msg, &expected_sig), Ok(()));
+                    }
+                    (
+                        |public_key, context, msg, sig| {
+                            signature::ED448PH.verify_with_context(public_key, context, msg, sig)
+                        },
+                        key_pair.sign_ph(&context, &msg).unwrap(),
+                    )
+                }
+                variant => panic!("Unsupported variant: {}", variant),
+            };
+            assert_eq!(&expected_sig[..], actual_sig.as_ref());
+
+            assert_eq!(
+                verify_with_context(&public_key, &context, &msg, &expected_sig),
+                Ok(())
+            );
+
+            // The signature is bound to the variant and to the context.
+            let mut wrong_context = context.clone();
+            wrong_context.push(0);
+            assert!(verify_with_context(&public_key, &wrong_context, &msg, &expected_sig).is_err());
+            let other_variant: VerifyWithContext = if variant == "Ed448" {
+                |public_key, context, msg, sig| {
+                    signature::ED448PH.verify_with_context(public_key, context, msg, sig)
+                }
+            } else {
+                |public_key, context, msg, sig| {
+                    signature::ED448.verify_with_context(public_key, context, msg, sig)
+                }
+            };
+            assert!(other_variant(&public_key, &context, &msg, &expected_sig).is_err());
+            let mut tampered_msg = msg.clone();
+            tampered_msg.push(0);
+            assert!(
+                verify_with_context(&public_key, &context, &tampered_msg, &expected_sig).is_err()
+            );
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn test_ed448_context_too_long() {
+    let rng = rand::SystemRandom::new();
+    let pkcs8 = Ed448KeyPair::generate_pkcs8(&rng).unwrap();
+    let key_pair = Ed448KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+    let public_key = key_pair.public_key().as_ref();
+
+    let context = vec![0x5a; signature::ED448_MAX_CONTEXT_LEN + 1];
+    let context = &context[..];
+    let max_context = &context[..signature::ED448_MAX_CONTEXT_LEN];
+
+    let sig = key_pair.sign_ctx(max_context, b"msg").unwrap();
+    assert_eq!(
+        signature::ED448.verify_with_context(public_key, max_context, b"msg", sig.as_ref()),
+        Ok(())
+    );
+    assert!(key_pair.sign_ctx(context, b"msg").is_err());
+    assert!(signature::ED448
+        .verify_with_context(public_key, context, b"msg", sig.as_ref())
+        .is_err());
+
+    let sig = key_pair.sign_ph(max_context, b"msg").unwrap();
+    assert_eq!(
+        signature::ED448PH.verify_with_context(public_key, max_context, b"msg", sig.as_ref()),
+        Ok(())
+    );
+    assert!(key_pair.sign_ph(context, b"msg").is_err());
+    assert!(signature::ED448PH
+        .verify_with_context(public_key, context, b"msg", sig.as_ref())
+        .is_err());
+}
+
+#[test]
+fn test_signature_ed448_verify() {
+    test::run(
+        test_file!("ed448_verify_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let public_key = test_case.consume_bytes("PUB");
+            let msg = test_case.consume_bytes("MESSAGE");
+            let sig = test_case.consume_bytes("SIG");
+            let expected_result = match test_case.consume_string("Result").as_str() {
+                "P" => Ok(()),
+                "F" => Err(error::Unspecified),
+                s => panic!("{:?} is not a valid result", s),
+            };
+            test_signature_verification(&public_key, &msg, &sig, expected_result);
+            Ok(())
+        },
+    );
+}
+
+fn test_signature_verification(
+    public_key: &[u8],
+    msg: &[u8],
+    sig: &[u8],
+    expected_result: Result<(), error::Unspecified>,
+) {
+    assert_eq!(
+        expected_result,
+        signature::UnparsedPublicKey::new(&signature::ED448, public_key).verify(msg, sig)
+    );
+
+    // Ed448 signs the message itself, not its digest, so verifying a digest
+    // always fails.
+    let h = digest::digest(&digest::SHA512, msg);
+    assert!(
+        signature::UnparsedPublicKey::new(&signature::ED448, public_key)
+            .verify_digest(h, sig)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_ed448_from_pkcs8_v1() {
+    // The PKCS#8 v1 encoding of the first RFC 8032 test key, as generated by
+    // OpenSSL.
+    const PKCS8_V1: &str = "3047020100300506032b6571043b04396c82a562cb808d10d632be89c8513ebf6c\
+                            929f34ddfa8c9f63c9960ef6e348a3528c8a3fcc2f044e39a3fc5b94492f8f032e\
+                            7549a20098f95b";
+    const PUBLIC_KEY: &str = "5fd7449b59b461fd2ce787ec616ad46a1da1342485a70e1f8a0ea75d80e96778\
+                              edf124769b46c7061bd6783df1e50f6cd1fa1abeafe8256180";
+    let pkcs8 = test::from_hex(PKCS8_V1).unwrap();
+
+    assert!(Ed448KeyPair::from_pkcs8(&pkcs8).is_err());
+
+    let key_pair = Ed448KeyPair::from_pkcs8_maybe_unchecked(&pkcs8).unwrap();
+    assert_eq!(
+        key_pair.public_key().as_ref(),
+        &test::from_hex(PUBLIC_KEY).unwrap()[..]
+    );
+}
+
+#[test]
+fn ed448_test_generate_pkcs8() {
+    let rng = rand::SystemRandom::new();
+    let generated = Ed448KeyPair::generate_pkcs8(&rng).unwrap();
+    let generated = generated.as_ref();
+
+    let key_pair = Ed448KeyPair::from_pkcs8(generated).unwrap();
+    let _ = Ed448KeyPair::from_pkcs8_maybe_unchecked(generated).unwrap();
+
+    assert_eq!(generated.len(), 20 + 57 + 57);
+    assert_eq!(&generated[..3], &[0x30, 0x81, 0x83]);
+
+    // Ed25519 keys aren't accepted as Ed448 keys.
+    let ed25519 = signature::Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+    assert!(Ed448KeyPair::from_pkcs8(ed25519.as_ref()).is_err());
+
+    // A generated key can sign and verify.
+    const MESSAGE: &[u8] = b"test message";
+    let sig = key_pair.sign(MESSAGE);
+    test_signature_verification(
+        key_pair.public_key().as_ref(),
+        MESSAGE,
+        sig.as_ref(),
+        Ok(()),
+    );
+}
@@ -28,8 +28,18 @@ fn hkdf_tests() {
             let digest_alg = test_case
                 .consume_digest_alg("Hash")
                 .ok_or(error::Unspecified)?;
-            if digest_alg == &digest::SHA256 {
+            if digest_alg == &digest::SHA224 {
+                hkdf::HKDF_SHA224
+            } else if digest_alg == &digest::SHA256 {
                 hkdf::HKDF_SHA256
+            } else if digest_alg == &digest::SHA512_224 {
+                hkdf::HKDF_SHA512_224
+            } else if digest_alg == &digest::SHA3_256 {
+                hkdf::HKDF_SHA3_256
+            } else if digest_alg == &digest::SHA3_384 {
+                hkdf::HKDF_SHA3_384
+            } else if digest_alg == &digest::SHA3_512 {
+                hkdf::HKDF_SHA3_512
             } else {
                 // TODO: add test vectors for other algorithms
                 panic!("unsupported algorithm: {:?}", digest_alg);
@@ -57,7 +67,15 @@ fn hkdf_tests() {
 
 #[test]
 fn hkdf_output_len_tests() {
-    for &alg in &[hkdf::HKDF_SHA256, hkdf::HKDF_SHA384, hkdf::HKDF_SHA512] {
+    for &alg in &[
+        hkdf::HKDF_SHA224,
+        hkdf::HKDF_SHA256,
+        hkdf::HKDF_SHA384,
+        hkdf::HKDF_SHA512,
+        hkdf::HKDF_SHA512_224,
+        hkdf::HKDF_SHA3_256,
+        hkdf::HKDF_SHA3_512,
+    ] {
         const MAX_BLOCKS: usize = 255;
 
         let salt = hkdf::Salt::new(alg, &[]);
@@ -38,12 +38,22 @@ fn hmac_tests() {
             };
             if digest_alg == &digest::SHA1_FOR_LEGACY_USE_ONLY {
                 hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY
+            } else if digest_alg == &digest::SHA224 {
+                hmac::HMAC_SHA224
             } else if digest_alg == &digest::SHA256 {
                 hmac::HMAC_SHA256
             } else if digest_alg == &digest::SHA384 {
                 hmac::HMAC_SHA384
             } else if digest_alg == &digest::SHA512 {
                 hmac::HMAC_SHA512
+            } else if digest_alg == &digest::SHA512_224 {
+                hmac::HMAC_SHA512_224
+            } else if digest_alg == &digest::SHA3_256 {
+                hmac::HMAC_SHA3_256
+            } else if digest_alg == &digest::SHA3_384 {
+                hmac::HMAC_SHA3_384
+            } else if digest_alg == &digest::SHA3_512 {
+                hmac::HMAC_SHA3_512
             } else {
                 unreachable!()
             }
@@ -97,6 +107,18 @@ fn hmac_test_case_inner(
         let signature = ctx.sign();
         assert_eq!(is_ok, signature.as_ref() == output);
     }
+
+    // Multi-part API, with the state exported and restored half-way.
+    {
+        let (first, second) = input.split_at(input.len() / 2);
+        let mut ctx = hmac::Context::with_key(&key);
+        ctx.update(first);
+        let state = ctx.export_state();
+        let mut ctx = hmac::Context::restore(&key, state.as_ref()).unwrap();
+        ctx.update(second);
+        let signature = ctx.sign();
+        assert_eq!(is_ok, signature.as_ref() == output);
+    }
 }
 
 #[test]
@@ -0,0 +1,235 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+use ring::{aead::Aad, agreement, hpke, rand, test, test_file};
+
+#[test]
+fn hpke_traits() {
+    test::compile_time_assert_send::<hpke::SenderContext>();
+    test::compile_time_assert_sync::<hpke::SenderContext>();
+    test::compile_time_assert_send::<hpke::ReceiverContext>();
+    test::compile_time_assert_sync::<hpke::ReceiverContext>();
+    test::compile_time_assert_copy::<hpke::Suite>();
+
+    assert_eq!(
+        format!("{:?}", hpke::DHKEM_X25519_HKDF_SHA256),
+        "DHKEM_X25519_HKDF_SHA256"
+    );
+    assert_eq!(hpke::DHKEM_X25519_HKDF_SHA256.id(), 0x0020);
+    assert_eq!(hpke::HKDF_SHA384.id(), 0x0002);
+    assert_eq!(hpke::EXPORT_ONLY.id(), 0xffff);
+}
+
+#[test]
+fn hpke_test_vectors() {
+    test::run(test_file!("hpke_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+
+        let suite = hpke::Suite::new(
+            kem_from_name(&test_case.consume_string("KEM")),
+            kdf_from_name(&test_case.consume_string("KDF")),
+            aead_from_name(&test_case.consume_string("AEAD")),
+        );
+        let mode = test_case.consume_string("Mode");
+        let info = test_case.consume_bytes("Info");
+        let ikm_r = test_case.consume_bytes("IkmR");
+        let sk_r = test_case.consume_bytes("SkR");
+        let pk_r = test_case.consume_bytes("PkR");
+        let ikm_e = test_case.consume_bytes("IkmE");
+        let expected_enc = test_case.consume_bytes("Enc");
+
+        let recipient_key = suite.kem().derive_key_pair(&ikm_r)?;
+        assert_eq!(recipient_key.private_key_bytes_less_safe(), &sk_r[..]);
+        assert_eq!(recipient_key.public_key().as_ref(), &pk_r[..]);
+
+        let rng = test::rand::FixedSliceRandom { bytes: &ikm_e };
+        let (enc, mut sender, mut receiver) = match mode.as_str() {
+            "Base" => {
+                let (enc, sender) = hpke::setup_base_sender(&suite, &pk_r, &info, &rng)?;
+                let receiver =
+                    hpke::setup_base_receiver(&suite, enc.as_ref(), &recipient_key, &info)?;
+                (enc, sender, receiver)
+            }
+            "PSK" => {
+                let psk = test_case.consume_bytes("PSK");
+                let psk_id = test_case.consume_bytes("PSKID");
+                let (enc, sender) =
+                    hpke::setup_psk_sender(&suite, &pk_r, &info, &psk, &psk_id, &rng)?;
+                let receiver = hpke::setup_psk_receiver(
+                    &suite,
+                    enc.as_ref(),
+                    &recipient_key,
+                    &info,
+                    &psk,
+                    &psk_id,
+                )?;
+                (enc, sender, receiver)
+            }
+            _ => unreachable!(),
+        };
+        assert_eq!(enc.as_ref(), &expected_enc[..]);
+
+        match test_case.consume_optional_bytes("Pt") {
+            Some(pt) => {
+                let expected = [
+                    (0, test_case.consume_bytes("Ct0")),
+                    (1, test_case.consume_bytes("Ct1")),
+                    (256, test_case.consume_bytes("Ct256")),
+                ];
+                for seq in 0..=256 {
+                    let aad = format!("Count-{}", seq);
+                    let mut in_out = pt.clone();
+                    sender.seal_in_place_append_tag(Aad::from(&aad), &mut in_out)?;
+                    if let Some((_, ct)) = expected.iter().find(|(n, _)| *n == seq) {
+                        assert_eq!(in_out, *ct);
+                    }
+                    let opened = receiver.open_in_place(Aad::from(&aad), &mut in_out)?;
+                    assert_eq!(opened, &pt[..]);
+                }
+            }
+            None => {
+                let mut in_out = vec![0u8; 16];
+                assert!(sender
+                    .seal_in_place_append_tag(Aad::empty(), &mut in_out)
+                    .is_err());
+                assert!(receiver.open_in_place(Aad::empty(), &mut in_out).is_err());
+            }
+        }
+
+        for (name, exporter_context) in [("Export0", &b""[..]), ("Export1", b"TestContext")] {
+            let expected = test_case.consume_bytes(name);
+            let mut sender_exported = vec![0u8; expected.len()];
+            sender.export(exporter_context, &mut sender_exported)?;
+            assert_eq!(sender_exported, expected);
+            let mut receiver_exported = vec![0u8; expected.len()];
+            receiver.export(exporter_context, &mut receiver_exported)?;
+            assert_eq!(receiver_exported, expected);
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+fn hpke_errors() {
+    let rng = rand::SystemRandom::new();
+    let suite = hpke::Suite::new(
+        &hpke::DHKEM_X25519_HKDF_SHA256,
+        &hpke::HKDF_SHA256,
+        &hpke::CHACHA20_POLY1305,
+    );
+    let recipient_key = agreement::StaticPrivateKey::generate(&agreement::X25519, &rng).unwrap();
+    let pk_r = recipient_key.public_key().as_ref();
+    let psk = [0x42; 32];
+    let psk_id = b"psk id";
+
+    // The PSK and its identifier are required in PSK mode.
+    assert!(hpke::setup_psk_sender(&suite, pk_r, b"", &[], psk_id, &rng).is_err());
+    assert!(hpke::setup_psk_sender(&suite, pk_r, b"", &psk, &[], &rng).is_err());
+
+    // The recipient's key must be for the suite's KEM.
+    let p256_key = agreement::StaticPrivateKey::generate(&agreement::ECDH_P256, &rng).unwrap();
+    assert!(hpke::setup_base_sender(&suite, p256_key.public_key().as_ref(), b"", &rng).is_err());
+    let (enc, mut sender) =
+        hpke::setup_psk_sender(&suite, pk_r, b"info", &psk, psk_id, &rng).unwrap();
+    assert!(hpke::setup_base_receiver(&suite, enc.as_ref(), &p256_key, b"info").is_err());
+
+    // An invalid encapsulated key is rejected.
+    assert!(hpke::setup_base_receiver(&suite, &[0; 32], &recipient_key, b"info").is_err());
+
+    let mut ciphertext = b"message".to_vec();
+    sender
+        .seal_in_place_append_tag(Aad::empty(), &mut ciphertext)
+        .unwrap();
+
+    // A receiver with a different mode, PSK, or info can't open the message.
+    let receivers = [
+        hpke::setup_base_receiver(&suite, enc.as_ref(), &recipient_key, b"info"),
+        hpke::setup_psk_receiver(
+            &suite,
+            enc.as_ref(),
+            &recipient_key,
+            b"info",
+            &[0x43; 32],
+            psk_id,
+        ),
+        hpke::setup_psk_receiver(&suite, enc.as_ref(), &recipient_key, b"", &psk, psk_id),
+    ];
+    for receiver in receivers {
+        let mut in_out = ciphertext.clone();
+        assert!(receiver
+            .unwrap()
+            .open_in_place(Aad::empty(), &mut in_out)
+            .is_err());
+    }
+
+    // A failed open doesn't advance the sequence number.
+    let mut receiver =
+        hpke::setup_psk_receiver(&suite, enc.as_ref(), &recipient_key, b"info", &psk, psk_id)
+            .unwrap();
+    let mut in_out = ciphertext.clone();
+    assert!(receiver
+        .open_in_place(Aad::from(b"wrong"), &mut in_out)
+        .is_err());
+    let mut in_out = ciphertext.clone();
+    let plaintext = receiver.open_in_place(Aad::empty(), &mut in_out).unwrap();
+    assert_eq!(plaintext, b"message");
+
+    // Exports are limited to 255 times the KDF output length.
+    let mut out = vec![0u8; 255 * 32 + 1];
+    assert!(receiver.export(b"", &mut out).is_err());
+    assert!(receiver.export(b"", &mut out[..255 * 32]).is_ok());
+
+    // `derive_key_pair` requires at least `private_key_len()` bytes of input.
+    let kem = &hpke::DHKEM_P521_HKDF_SHA512;
+    assert_eq!(kem.private_key_len(), 66);
+    assert!(kem.derive_key_pair(&[0; 65]).is_err());
+    assert!(kem.derive_key_pair(&[0; 66]).is_ok());
+}
+
+fn kem_from_name(name: &str) -> &'static hpke::Kem {
+    match name {
+        "DHKEM_P256_HKDF_SHA256" => &hpke::DHKEM_P256_HKDF_SHA256,
+        "DHKEM_P384_HKDF_SHA384" => &hpke::DHKEM_P384_HKDF_SHA384,
+        "DHKEM_P521_HKDF_SHA512" => &hpke::DHKEM_P521_HKDF_SHA512,
+        "DHKEM_X25519_HKDF_SHA256" => &hpke::DHKEM_X25519_HKDF_SHA256,
+        "DHKEM_X448_HKDF_SHA512" => &hpke::DHKEM_X448_HKDF_SHA512,
+        _ => panic!("Unsupported KEM: {}", name),
+    }
+}
+
+fn kdf_from_name(name: &str) -> &'static hpke::Kdf {
+    match name {
+        "HKDF_SHA256" => &hpke::HKDF_SHA256,
+        "HKDF_SHA384" => &hpke::HKDF_SHA384,
+        "HKDF_SHA512" => &hpke::HKDF_SHA512,
+        _ => panic!("Unsupported KDF: {}", name),
+    }
+}
+
+fn aead_from_name(name: &str) -> &'static hpke::Aead {
+    match name {
+        "AES_128_GCM" => &hpke::AES_128_GCM,
+        "AES_256_GCM" => &hpke::AES_256_GCM,
+        "CHACHA20_POLY1305" => &hpke::CHACHA20_POLY1305,
+        "EXPORT_ONLY" => &hpke::EXPORT_ONLY,
+        _ => panic!("Unsupported AEAD: {}", name),
+    }
+}
@@ -0,0 +1,248 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+use ring::{digest, error, kem, rand, test, test_file};
+
+#[test]
+fn kem_traits() {
+    test::compile_time_assert_send::<kem::DecapsulationKey>();
+    test::compile_time_assert_sync::<kem::DecapsulationKey>();
+
+    test::compile_time_assert_clone::<kem::EncapsulationKey>();
+    test::compile_time_assert_send::<kem::EncapsulationKey>();
+    test::compile_time_assert_sync::<kem::EncapsulationKey>();
+
+    test::compile_time_assert_clone::<kem::Ciphertext>();
+    test::compile_time_assert_send::<kem::Ciphertext>();
+    test::compile_time_assert_sync::<kem::Ciphertext>();
+
+    test::compile_time_assert_copy::<kem::UnparsedEncapsulationKey<&[u8]>>();
+    test::compile_time_assert_sync::<kem::UnparsedEncapsulationKey<&[u8]>>();
+    test::compile_time_assert_copy::<kem::UnparsedCiphertext<&[u8]>>();
+    test::compile_time_assert_sync::<kem::UnparsedCiphertext<&[u8]>>();
+
+    assert_eq!(format!("{:?}", kem::ML_KEM_768), "ML_KEM_768");
+
+    let ciphertext = kem::UnparsedCiphertext::new(&kem::ML_KEM_1024, &[0x01, 0x02, 0x03]);
+    assert_eq!(
+        format!("{:?}", ciphertext),
+        r#"UnparsedCiphertext { algorithm: ML_KEM_1024, bytes: "010203" }"#
+    );
+    assert_eq!(ciphertext.as_ref(), &[0x01, 0x02, 0x03]);
+}
+
+#[test]
+fn kem_lengths() {
+    assert_eq!(kem::ML_KEM_768.encapsulation_key_len(), 1184);
+    assert_eq!(kem::ML_KEM_768.ciphertext_len(), 1088);
+    assert_eq!(kem::ML_KEM_768.shared_secret_len(), 32);
+    assert_eq!(kem::ML_KEM_1024.encapsulation_key_len(), 1568);
+    assert_eq!(kem::ML_KEM_1024.ciphertext_len(), 1568);
+    assert_eq!(kem::ML_KEM_1024.shared_secret_len(), 32);
+}
+
+#[test]
+fn kem_test_vectors() {
+    test::run(test_file!("kem_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+
+        let alg = alg_from_name(&test_case.consume_string("Algorithm"));
+        let seed = test_case.consume_bytes("Seed");
+        let ct = test_case.consume_bytes("Ct");
+        let expected_shared_secret = test_case.consume_bytes("SharedSecret");
+
+        let decapsulation_key = kem::DecapsulationKey::from_seed(alg, &seed).unwrap();
+        assert_eq!(decapsulation_key.algorithm(), alg);
+        assert_eq!(decapsulation_key.seed_bytes_less_safe(), &seed[..]);
+
+        if let Some(ek) = test_case.consume_optional_bytes("Ek") {
+            let m = test_case.consume_bytes("M");
+            assert_eq!(decapsulation_key.encapsulation_key().as_ref(), &ek[..]);
+
+            let rng = test::rand::FixedSliceRandom { bytes: &m };
+            let encapsulation_key = kem::UnparsedEncapsulationKey::new(alg, &ek);
+            let (ciphertext, ()) = kem::encapsulate(&encapsulation_key, &rng, |shared_secret| {
+                assert_eq!(shared_secret, &expected_shared_secret[..]);
+            })?;
+            assert_eq!(ciphertext.algorithm(), alg);
+            assert_eq!(ciphertext.as_ref(), &ct[..]);
+        }
+
+        let ciphertext = kem::UnparsedCiphertext::new(alg, &ct);
+        kem::decapsulate(&decapsulation_key, &ciphertext, |shared_secret| {
+            assert_eq!(shared_secret, &expected_shared_secret[..]);
+        })?;
+
+        Ok(())
+    });
+}
+
+// The "accumulated" test of C2SP CCTV and Go's `TestAccumulated`: keys,
+// encapsulation randomness, and invalid ciphertexts are drawn from the
+// SHAKE-128 output for the empty input, and the encapsulation keys,
+// ciphertexts and shared secrets are hashed with SHAKE-128, to cover many
+// random vectors without checking them in.
+//
+// The ML-KEM-768 result for 100 iterations matches the one in Go's
+// `TestAccumulated`. The other results were computed with an independent
+// implementation of FIPS 203 that was checked against the ML-KEM
+// implementation of pyca/cryptography.
+#[test]
+fn kem_accumulated() {
+    fn accumulated(alg: &'static kem::Algorithm, iterations: usize, expected: &str) {
+        let mut s = digest::XofContext::new(&digest::SHAKE128).finalize();
+        let mut o = digest::XofContext::new(&digest::SHAKE128);
+        let mut seed = [0u8; kem::SEED_LEN];
+        let mut m = [0u8; 32];
+        let mut ct1 = vec![0u8; alg.ciphertext_len()];
+
+        for _ in 0..iterations {
+            s.squeeze(&mut seed);
+            let decapsulation_key = kem::DecapsulationKey::from_seed(alg, &seed).unwrap();
+            let ek = decapsulation_key.encapsulation_key();
+            o.update(ek.as_ref());
+
+            s.squeeze(&mut m);
+            let rng = test::rand::FixedSliceRandom { bytes: &m };
+            let ek = kem::UnparsedEncapsulationKey::new(alg, ek.as_ref());
+            let (ct, k) = kem::encapsulate(&ek, &rng, |k| k.to_vec()).unwrap();
+            o.update(ct.as_ref());
+            o.update(&k);
+
+            let ct = kem::UnparsedCiphertext::new(alg, ct.as_ref());
+            let kk = kem::decapsulate(&decapsulation_key, &ct, |k| k.to_vec()).unwrap();
+            assert_eq!(kk, k);
+
+            s.squeeze(&mut ct1);
+            let ct1 = kem::UnparsedCiphertext::new(alg, &ct1);
+            kem::decapsulate(&decapsulation_key, &ct1, |k1| o.update(k1)).unwrap();
+        }
+
+        let mut actual = [0u8; 32];
+        o.finalize().squeeze(&mut actual);
+        assert_eq!(&actual[..], &test::from_hex(expected).unwrap()[..]);
+    }
+
+    accumulated(
+        &kem::ML_KEM_768,
+        100,
+        "1114b1b6699ed191734fa339376afa7e285c9e6acf6ff0177d346696ce564415",
+    );
+    accumulated(
+        &kem::ML_KEM_1024,
+        100,
+        "800018fec3e2723f73f1d657fe239b4d5d8782efaade297e8cd448e54cc2ac00",
+    );
+
+    // Go's test does 10,000 iterations unless run with `-short`, but that
+    // takes too long to do by default right now.
+    if cfg!(feature = "slow_tests") {
+        accumulated(
+            &kem::ML_KEM_768,
+            10_000,
+            "8a518cc63da366322a8e7a818c7a0d63483cb3528d34a4cf42f35d5ad73f22fc",
+        );
+        accumulated(
+            &kem::ML_KEM_1024,
+            10_000,
+            "f1a3925c9cf8538bb104c56efb2f5ecb74cc3df25087460b73f6c873e96bcb6a",
+        );
+    }
+}
+
+#[test]
+fn kem_round_trip() -> Result<(), error::Unspecified> {
+    let rng = rand::SystemRandom::new();
+    for alg in [&kem::ML_KEM_768, &kem::ML_KEM_1024] {
+        let decapsulation_key = kem::DecapsulationKey::generate(alg, &rng)?;
+        let encapsulation_key = decapsulation_key.encapsulation_key();
+        assert_eq!(encapsulation_key.algorithm(), alg);
+        assert_eq!(
+            encapsulation_key.as_ref().len(),
+            alg.encapsulation_key_len()
+        );
+
+        let encapsulation_key = kem::UnparsedEncapsulationKey::new(alg, encapsulation_key.as_ref());
+        let (ciphertext, sender_secret) =
+            kem::encapsulate(&encapsulation_key, &rng, |s| s.to_vec())?;
+        assert_eq!(ciphertext.as_ref().len(), alg.ciphertext_len());
+
+        let ciphertext = kem::UnparsedCiphertext::new(alg, ciphertext.as_ref());
+        let receiver_secret = kem::decapsulate(&decapsulation_key, &ciphertext, |s| s.to_vec())?;
+        assert_eq!(sender_secret, receiver_secret);
+
+        // The key can be restored from its seed.
+        let restored =
+            kem::DecapsulationKey::from_seed(alg, decapsulation_key.seed_bytes_less_safe())
+                .unwrap();
+        let restored_secret = kem::decapsulate(&restored, &ciphertext, |s| s.to_vec())?;
+        assert_eq!(sender_secret, restored_secret);
+    }
+    Ok(())
+}
+
+#[test]
+fn kem_errors() {
+    let rng = rand::SystemRandom::new();
+    let alg = &kem::ML_KEM_768;
+    let decapsulation_key = kem::DecapsulationKey::generate(alg, &rng).unwrap();
+    let ek = decapsulation_key.encapsulation_key().as_ref();
+
+    // Seeds must have exactly `SEED_LEN` bytes.
+    assert_eq!(kem::SEED_LEN, 64);
+    assert!(kem::DecapsulationKey::from_seed(alg, &[0; 63]).is_err());
+    assert!(kem::DecapsulationKey::from_seed(alg, &[0; 65]).is_err());
+
+    // The encapsulation key must have the right length.
+    for ek in [&ek[1..], &[ek, &[0]].concat()[..]] {
+        let ek = kem::UnparsedEncapsulationKey::new(alg, ek);
+        assert!(kem::encapsulate(&ek, &rng, |_| ()).is_err());
+    }
+    let ek_1024 = kem::UnparsedEncapsulationKey::new(&kem::ML_KEM_1024, ek);
+    assert!(kem::encapsulate(&ek_1024, &rng, |_| ()).is_err());
+
+    // The encapsulation key must pass the modulus check: a coefficient equal
+    // to q = 3329 = 0xd01 is rejected.
+    let mut bad_ek = ek.to_vec();
+    bad_ek[0] = 0x01;
+    bad_ek[1] = (bad_ek[1] & 0xf0) | 0x0d;
+    let bad_ek = kem::UnparsedEncapsulationKey::new(alg, &bad_ek);
+    assert!(kem::encapsulate(&bad_ek, &rng, |_| ()).is_err());
+
+    let ek = kem::UnparsedEncapsulationKey::new(alg, ek);
+    let (ciphertext, _) = kem::encapsulate(&ek, &rng, |_| ()).unwrap();
+    let ct = ciphertext.as_ref();
+
+    // The ciphertext must have the right length and algorithm.
+    for ct in [&ct[1..], &[ct, &[0]].concat()[..]] {
+        let ct = kem::UnparsedCiphertext::new(alg, ct);
+        assert!(kem::decapsulate(&decapsulation_key, &ct, |_| ()).is_err());
+    }
+    let ct_1024 = kem::UnparsedCiphertext::new(&kem::ML_KEM_1024, ct);
+    assert!(kem::decapsulate(&decapsulation_key, &ct_1024, |_| ()).is_err());
+}
+
+fn alg_from_name(name: &str) -> &'static kem::Algorithm {
+    match name {
+        "ML_KEM_768" => &kem::ML_KEM_768,
+        "ML_KEM_1024" => &kem::ML_KEM_1024,
+        _ => panic!("Unsupported algorithm: {}", name),
+    }
+}
@@ -36,6 +36,12 @@ pub fn pbkdf2_tests() {
                 pbkdf2::PBKDF2_HMAC_SHA384
             } else if digest_alg == &digest::SHA512 {
                 pbkdf2::PBKDF2_HMAC_SHA512
+            } else if digest_alg == &digest::SHA3_256 {
+                pbkdf2::PBKDF2_HMAC_SHA3_256
+            } else if digest_alg == &digest::SHA3_384 {
+                pbkdf2::PBKDF2_HMAC_SHA3_384
+            } else if digest_alg == &digest::SHA3_512 {
+                pbkdf2::PBKDF2_HMAC_SHA3_512
             } else {
                 unreachable!()
             }
@@ -15,7 +15,7 @@
 #![cfg(feature = "alloc")]
 
 use ring::{
-    error,
+    digest, error,
     io::der,
     rand, rsa,
     signature::{self, KeyPair},
@@ -50,6 +50,38 @@ fn rsa_from_pkcs8_test() {
     );
 }
 
+#[test]
+fn rsa_generate_pkcs8_test() {
+    let rng = rand::SystemRandom::new();
+
+    for (size, len_bits) in [
+        (rsa::KeySize::Rsa2048, 2048),
+        (rsa::KeySize::Rsa3072, 3072),
+        (rsa::KeySize::Rsa4096, 4096),
+    ] {
+        assert_eq!(size.len_bits(), len_bits);
+
+        let pkcs8 = rsa::KeyPair::generate_pkcs8(size, &rng).unwrap();
+        let key_pair = rsa::KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        assert_eq!(key_pair.public().modulus_len(), len_bits / 8);
+
+        let components = rsa::PublicKeyComponents::<Vec<_>>::from(key_pair.public());
+        assert_eq!(&components.e, &[0x01, 0x00, 0x01]);
+
+        const MESSAGE: &[u8] = b"hello, world";
+        let mut signature = vec![0; key_pair.public().modulus_len()];
+        key_pair
+            .sign(&signature::RSA_PSS_SHA256, &rng, MESSAGE, &mut signature)
+            .unwrap();
+        signature::UnparsedPublicKey::new(
+            &signature::RSA_PSS_2048_8192_SHA256,
+            key_pair.public_key().as_ref(),
+        )
+        .verify(MESSAGE, &signature)
+        .unwrap();
+    }
+}
+
 #[cfg(feature = "alloc")]
 #[test]
 fn test_signature_rsa_pkcs1_sign() {
@@ -86,6 +118,20 @@ fn test_signature_rsa_pkcs1_sign() {
                 .sign(alg, &rng, &msg, actual.as_mut_slice())
                 .unwrap();
             assert_eq!(actual.as_slice() == &expected[..], result == "Pass");
+
+            // Signing the digest gives the same (deterministic) signature.
+            let mut actual_from_digest = vec![0u8; key_pair.public().modulus_len()];
+            let h = digest::digest(digest_alg(&digest_name), &msg);
+            key_pair
+                .sign_digest(alg, &rng, h, actual_from_digest.as_mut_slice())
+                .unwrap();
+            assert_eq!(actual_from_digest, actual);
+
+            // The digest algorithm must match the padding algorithm.
+            let wrong_h = digest::digest(&digest::SHA1_FOR_LEGACY_USE_ONLY, &msg);
+            assert!(key_pair
+                .sign_digest(alg, &rng, wrong_h, actual_from_digest.as_mut_slice())
+                .is_err());
             Ok(())
         },
     );
@@ -225,11 +271,13 @@ fn test_signature_rsa_pkcs1_verify() {
             let msg = test_case.consume_bytes("Msg");
             let sig = test_case.consume_bytes("Sig");
             let is_valid = test_case.consume_string("Result") == "P";
+            let h = digest::digest(digest_alg(&digest_name), &msg);
             for &(alg, min_bits) in params {
                 let width_ok = key_bits >= min_bits;
-                let actual_result =
-                    signature::UnparsedPublicKey::new(alg, &public_key).verify(&msg, &sig);
+                let public_key = signature::UnparsedPublicKey::new(alg, &public_key);
+                let actual_result = public_key.verify(&msg, &sig);
                 assert_eq!(actual_result.is_ok(), is_valid && width_ok);
+                assert_eq!(public_key.verify_digest(h, &sig), actual_result);
             }
 
             Ok(())
@@ -275,10 +323,17 @@ fn test_signature_rsa_pss_verify() {
             let sig = test_case.consume_bytes("Sig");
             let is_valid = test_case.consume_string("Result") == "P";
 
-            let actual_result =
-                signature::UnparsedPublicKey::new(alg, &public_key).verify(&msg, &sig);
+            let public_key = signature::UnparsedPublicKey::new(alg, &public_key);
+            let actual_result = public_key.verify(&msg, &sig);
             assert_eq!(actual_result.is_ok(), is_valid);
 
+            let h = digest::digest(digest_alg(&digest_name), &msg);
+            assert_eq!(public_key.verify_digest(h, &sig), actual_result);
+
+            // The digest algorithm must match the padding algorithm.
+            let wrong_h = digest::digest(&digest::SHA1_FOR_LEGACY_USE_ONLY, &msg);
+            assert!(public_key.verify_digest(wrong_h, &sig).is_err());
+
             Ok(())
         },
     );
@@ -306,6 +361,377 @@ fn test_signature_rsa_primitive_verification() {
     )
 }
 
+#[test]
+fn test_signature_rsa_pss_salt_len_and_mgf1_verify() {
+    const PUBLIC_KEY: &[u8] = include_bytes!("rsa_test_public_key_2048.der");
+
+    test::run(
+        test_file!("rsa_pss_salt_mgf1_verify_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let message_digest_alg = digest_alg(&test_case.consume_string("Digest"));
+            let mgf1_digest_alg = digest_alg(&test_case.consume_string("MGF1Digest"));
+            let _ = test_case.consume_usize("SaltLen");
+            let salt_len = match test_case.consume_string("VerifySaltLen").as_str() {
+                "digest" => signature::PssSaltLength::DigestLength,
+                "max" => signature::PssSaltLength::Maximum,
+                "auto" => signature::PssSaltLength::Auto,
+                salt_len => signature::PssSaltLength::Exact(salt_len.parse().unwrap()),
+            };
+            let msg = test_case.consume_bytes("Msg");
+            let sig = test_case.consume_bytes("Sig");
+            let is_valid = test_case.consume_string("Result") == "P";
+
+            let padding_alg: &'static signature::PSS = Box::leak(Box::new(signature::PSS::new(
+                message_digest_alg,
+                mgf1_digest_alg,
+                salt_len,
+            )));
+            let params: &'static signature::RsaParameters =
+                Box::leak(Box::new(signature::RsaParameters::new(padding_alg, 2048)));
+
+            let actual_result =
+                signature::UnparsedPublicKey::new(params, PUBLIC_KEY).verify(&msg, &sig);
+            assert_eq!(actual_result.is_ok(), is_valid);
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_rsa_parameters_min_bits_too_small() {
+    let _ = signature::RsaParameters::new(&signature::RSA_PSS_SHA256, 1023);
+}
+
+#[test]
+fn test_signature_rsa_pss_salt_len_and_mgf1_sign() {
+    static RSA_PSS_SHA256_MGF1_SHA1_NO_SALT: signature::PSS = signature::PSS::new(
+        &digest::SHA256,
+        &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        signature::PssSaltLength::Exact(0),
+    );
+    static RSA_PSS_SHA256_MGF1_SHA1_AUTO: signature::PSS = signature::PSS::new(
+        &digest::SHA256,
+        &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        signature::PssSaltLength::Auto,
+    );
+    static RSA_PSS_SHA256_MAX_SALT: signature::PSS = signature::PSS::new(
+        &digest::SHA256,
+        &digest::SHA256,
+        signature::PssSaltLength::Maximum,
+    );
+    static RSA_PSS_SHA256_AUTO: signature::PSS = signature::PSS::new(
+        &digest::SHA256,
+        &digest::SHA256,
+        signature::PssSaltLength::Auto,
+    );
+
+    const PRIVATE_KEY: &[u8] = include_bytes!("rsa_test_private_key_2048.p8");
+    let key_pair = rsa::KeyPair::from_pkcs8(PRIVATE_KEY).unwrap();
+    let rng = rand::SystemRandom::new();
+    const MESSAGE: &[u8] = b"hello, world";
+
+    let cases: &[(&'static signature::PSS, &'static signature::PSS, bool)] = &[
+        (
+            &RSA_PSS_SHA256_MGF1_SHA1_NO_SALT,
+            &RSA_PSS_SHA256_MGF1_SHA1_NO_SALT,
+            true,
+        ),
+        (
+            &RSA_PSS_SHA256_MGF1_SHA1_NO_SALT,
+            &RSA_PSS_SHA256_MGF1_SHA1_AUTO,
+            true,
+        ),
+        (
+            &RSA_PSS_SHA256_MGF1_SHA1_NO_SALT,
+            &RSA_PSS_SHA256_AUTO,
+            false,
+        ),
+        (&RSA_PSS_SHA256_MAX_SALT, &RSA_PSS_SHA256_MAX_SALT, true),
+        (&RSA_PSS_SHA256_MAX_SALT, &RSA_PSS_SHA256_AUTO, true),
+        // `Auto` signs with the maximum salt length.
+        (&RSA_PSS_SHA256_AUTO, &RSA_PSS_SHA256_MAX_SALT, true),
+        (&RSA_PSS_SHA256_MAX_SALT, &signature::RSA_PSS_SHA256, false),
+        (&signature::RSA_PSS_SHA256, &RSA_PSS_SHA256_AUTO, true),
+    ];
+    for &(sign_alg, verify_alg, expected) in cases {
+        let mut sig = vec![0; key_pair.public().modulus_len()];
+        key_pair.sign(sign_alg, &rng, MESSAGE, &mut sig).unwrap();
+
+        let params: &'static signature::RsaParameters =
+            Box::leak(Box::new(signature::RsaParameters::new(verify_alg, 2048)));
+        let actual_result =
+            signature::UnparsedPublicKey::new(params, key_pair.public_key()).verify(MESSAGE, &sig);
+        assert_eq!(actual_result.is_ok(), expected);
+    }
+}
+
+#[test]
+fn test_rsa_oaep_decrypt() {
+    const PRIVATE_KEY: &[u8] = include_bytes!("rsa_test_private_key_2048.p8");
+    let key_pair = rsa::KeyPair::from_pkcs8(PRIVATE_KEY).unwrap();
+
+    test::run(
+        test_file!("rsa_oaep_decrypt_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let oaep_alg = oaep_alg(&test_case.consume_string("Digest"));
+            let label = test_case.consume_bytes("Label");
+            let msg = test_case.consume_bytes("Msg");
+            let ciphertext = test_case.consume_bytes("Ciphertext");
+            let is_valid = test_case.consume_string("Result") == "P";
+
+            let mut plaintext = vec![0; key_pair.public().modulus_len()];
+            let actual = key_pair.decrypt_oaep(oaep_alg, &label, &ciphertext, &mut plaintext);
+            match actual {
+                Ok(plaintext) => {
+                    assert!(is_valid);
+                    assert_eq!(plaintext, &msg[..]);
+                }
+                Err(error::Unspecified) => assert!(!is_valid),
+            }
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn test_rsa_oaep_encrypt_decrypt() {
+    const PRIVATE_KEY: &[u8] = include_bytes!("rsa_test_private_key_2048.p8");
+    let key_pair = rsa::KeyPair::from_pkcs8(PRIVATE_KEY).unwrap();
+    let public_key = key_pair.public();
+    let rng = rand::SystemRandom::new();
+
+    for oaep_alg in [
+        &rsa::RSA_OAEP_SHA256,
+        &rsa::RSA_OAEP_SHA384,
+        &rsa::RSA_OAEP_SHA512,
+    ] {
+        let max_len = oaep_alg
+            .max_plaintext_len(public_key.modulus_len())
+            .unwrap();
+        for msg_len in [0, 1, 32, max_len] {
+            let msg = vec![0xa5; msg_len];
+            let mut ciphertext = vec![0; public_key.modulus_len()];
+            public_key
+                .encrypt_oaep(oaep_alg, b"label", &msg, &rng, &mut ciphertext)
+                .unwrap();
+
+            let mut plaintext = vec![0; max_len];
+            let decrypted = key_pair
+                .decrypt_oaep(oaep_alg, b"label", &ciphertext, &mut plaintext)
+                .unwrap();
+            assert_eq!(decrypted, &msg[..]);
+
+            // The plaintext buffer must be large enough.
+            if msg_len > 0 {
+                let mut plaintext = vec![0; msg_len - 1];
+                assert!(key_pair
+                    .decrypt_oaep(oaep_alg, b"label", &ciphertext, &mut plaintext)
+                    .is_err());
+            }
+        }
+
+        // The plaintext is too long.
+        let msg = vec![0xa5; max_len + 1];
+        let mut ciphertext = vec![0; public_key.modulus_len()];
+        assert!(public_key
+            .encrypt_oaep(oaep_alg, b"", &msg, &rng, &mut ciphertext)
+            .is_err());
+
+        // The ciphertext buffer is the wrong length.
+        let mut ciphertext = vec![0; public_key.modulus_len() + 1];
+        assert!(public_key
+            .encrypt_oaep(oaep_alg, b"", b"", &rng, &mut ciphertext)
+            .is_err());
+    }
+}
+
+#[test]
+fn test_rsa_pkcs1_decrypt_implicit_rejection() {
+    const PRIVATE_KEY: &[u8] = include_bytes!("rsa_test_private_key_2048.p8");
+    let key_pair = rsa::KeyPair::from_pkcs8(PRIVATE_KEY).unwrap();
+
+    test::run(
+        test_file!("rsa_pkcs1_decrypt_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let ciphertext = test_case.consume_bytes("Ciphertext");
+            let msg = test_case.consume_bytes("Msg");
+
+            // Invalid ciphertexts decrypt to a synthetic message instead of
+            // failing.
+            let mut plaintext = vec![0; key_pair.public().modulus_len()];
+            let actual = key_pair
+                .decrypt_pkcs1_implicit_rejection(&ciphertext, &mut plaintext)
+                .unwrap();
+            assert_eq!(actual, &msg[..]);
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn test_rsa_pkcs1_encrypt_decrypt() {
+    const PRIVATE_KEY: &[u8] = include_bytes!("rsa_test_private_key_2048.p8");
+    let key_pair = rsa::KeyPair::from_pkcs8(PRIVATE_KEY).unwrap();
+    let public_key = key_pair.public();
+    let rng = rand::SystemRandom::new();
+
+    let max_len = public_key.modulus_len() - 11;
+    for msg_len in [0, 1, 48, max_len] {
+        let msg = vec![0xa5; msg_len];
+        let mut ciphertext = vec![0; public_key.modulus_len()];
+        public_key
+            .encrypt_pkcs1(&msg, &rng, &mut ciphertext)
+            .unwrap();
+
+        let mut plaintext = vec![0; max_len];
+        let decrypted = key_pair
+            .decrypt_pkcs1_implicit_rejection(&ciphertext, &mut plaintext)
+            .unwrap();
+        assert_eq!(decrypted, &msg[..]);
+    }
+
+    // The plaintext is too long.
+    let msg = vec![0xa5; max_len + 1];
+    let mut ciphertext = vec![0; public_key.modulus_len()];
+    assert!(public_key
+        .encrypt_pkcs1(&msg, &rng, &mut ciphertext)
+        .is_err());
+
+    // The ciphertext has the wrong length, or isn't less than the modulus.
+    let mut plaintext = vec![0; max_len];
+    for ciphertext in [
+        vec![0; public_key.modulus_len() - 1],
+        vec![0xff; public_key.modulus_len()],
+    ] {
+        assert!(key_pair
+            .decrypt_pkcs1_implicit_rejection(&ciphertext, &mut plaintext)
+            .is_err());
+    }
+}
+
+#[test]
+fn test_rsa_blind_signature() {
+    const PRIVATE_KEY: &[u8] = include_bytes!("rsa_test_private_key_2048.p8");
+    let key_pair = rsa::KeyPair::from_pkcs8(PRIVATE_KEY).unwrap();
+    let public_key = key_pair.public();
+
+    test::run(
+        test_file!("rsa_blind_signature_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let alg = match test_case.consume_string("Variant").as_str() {
+                "RSABSSA-SHA384-PSS-Randomized" => &rsa::RSABSSA_SHA384_PSS_RANDOMIZED,
+                "RSABSSA-SHA384-PSS-Deterministic" => &rsa::RSABSSA_SHA384_PSS_DETERMINISTIC,
+                variant => panic!("Unsupported variant: {}", variant),
+            };
+            let msg = test_case.consume_bytes("Msg");
+            let msg_prefix = test_case.consume_optional_bytes("MsgPrefix");
+            let salt = test_case.consume_bytes("Salt");
+            let r = test_case.consume_bytes("R");
+            let b = test_case.consume_bytes("B");
+            let _ = test_case.consume_bytes("Inv");
+            let expected_blinded_msg = test_case.consume_bytes("BlindedMsg");
+            let expected_blind_sig = test_case.consume_bytes("BlindSig");
+            let expected_sig = test_case.consume_bytes("Sig");
+
+            let input_msg = match &msg_prefix {
+                Some(msg_prefix) => {
+                    let rng = test::rand::FixedSliceSequenceRandom {
+                        bytes: &[msg_prefix],
+                        current: core::cell::UnsafeCell::new(0),
+                    };
+                    alg.prepare(&msg, &rng).unwrap()
+                }
+                None => alg.prepare(&msg, &rand::SystemRandom::new()).unwrap(),
+            };
+            assert_eq!(
+                input_msg,
+                [msg_prefix.as_deref().unwrap_or(&[]), &msg[..]].concat()
+            );
+
+            let rng = test::rand::FixedSliceSequenceRandom {
+                bytes: &[&salt, &r, &b],
+                current: core::cell::UnsafeCell::new(0),
+            };
+            let blinded = public_key.blind(alg, &input_msg, &rng).unwrap();
+            assert_eq!(blinded.as_ref(), &expected_blinded_msg[..]);
+
+            let mut blind_sig = vec![0; public_key.modulus_len()];
+            key_pair
+                .blind_sign(blinded.as_ref(), &mut blind_sig)
+                .unwrap();
+            assert_eq!(blind_sig, expected_blind_sig);
+
+            let mut sig = vec![0; public_key.modulus_len()];
+            public_key
+                .finalize(alg, &input_msg, &blinded, &blind_sig, &mut sig)
+                .unwrap();
+            assert_eq!(sig, expected_sig);
+
+            public_key
+                .verify_blind_signature(alg, &input_msg, &sig)
+                .unwrap();
+            assert!(public_key
+                .verify_blind_signature(alg, &[&input_msg[..], b"x"].concat(), &sig)
+                .is_err());
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn test_rsa_blind_signature_round_trip() {
+    // The modulus of the 3071-bit key isn't a whole number of bytes long.
+    for private_key in [
+        &include_bytes!("rsa_test_private_key_2048.p8")[..],
+        &include_bytes!("rsa_test_private_key_3071.p8")[..],
+    ] {
+        let key_pair = rsa::KeyPair::from_pkcs8(private_key).unwrap();
+        let public_key = key_pair.public();
+        let rng = rand::SystemRandom::new();
+
+        let alg = &rsa::RSABSSA_SHA384_PSS_RANDOMIZED;
+        let input_msg = alg.prepare(b"hello, world", &rng).unwrap();
+        let blinded = public_key.blind(alg, &input_msg, &rng).unwrap();
+
+        let mut blind_sig = vec![0; public_key.modulus_len()];
+        key_pair
+            .blind_sign(blinded.as_ref(), &mut blind_sig)
+            .unwrap();
+
+        // A blind signature of a different blinded message doesn't finalize.
+        let other = public_key.blind(alg, &input_msg, &rng).unwrap();
+        let mut sig = vec![0; public_key.modulus_len()];
+        assert!(public_key
+            .finalize(alg, &input_msg, &other, &blind_sig, &mut sig)
+            .is_err());
+
+        public_key
+            .finalize(alg, &input_msg, &blinded, &blind_sig, &mut sig)
+            .unwrap();
+
+        // The finalized signature is an ordinary RSASSA-PSS signature.
+        signature::UnparsedPublicKey::new(
+            &signature::RSA_PSS_2048_8192_SHA384,
+            public_key.as_ref(),
+        )
+        .verify(&input_msg, &sig)
+        .unwrap();
+    }
+}
+
 #[cfg(feature = "alloc")]
 #[test]
 fn rsa_test_keypair_coverage() {
@@ -343,3 +769,22 @@ fn test_public_key_coverage(key: &rsa::PublicKey) {
     const _65537: &[u8] = &[0x01, 0x00, 0x01];
     assert_eq!(_65537, &components.e);
 }
+
+fn oaep_alg(name: &str) -> &'static rsa::OaepAlgorithm {
+    match name {
+        "SHA256" => &rsa::RSA_OAEP_SHA256,
+        "SHA384" => &rsa::RSA_OAEP_SHA384,
+        "SHA512" => &rsa::RSA_OAEP_SHA512,
+        _ => panic!("Unsupported digest: {}", name),
+    }
+}
+
+fn digest_alg(name: &str) -> &'static digest::Algorithm {
+    match name {
+        "SHA1" => &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        "SHA256" => &digest::SHA256,
+        "SHA384" => &digest::SHA384,
+        "SHA512" => &digest::SHA512,
+        _ => panic!("Unsupported digest: {}", name),
+    }
+}